@@ -0,0 +1,74 @@
+//! Throughput benchmarks.
+//!
+//! The offline benchmarks measure the client-side cost of handling a stream.
+//! The end-to-end benchmark runs against a live server and is skipped unless
+//! `OLLIE_BENCH_SERVER` is set, e.g.:
+//!
+//! ```text
+//! OLLIE_BENCH_SERVER=127.0.0.1:11434 OLLIE_BENCH_MODEL=gemma3:1b cargo bench
+//! ```
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use ollie_rs::{Benchmark, StopSequenceFilter, TextAccumulator};
+use std::hint::black_box;
+
+/// The number of chunks in a simulated stream.
+const CHUNKS: usize = 10_000;
+
+fn chunks() -> Vec<String> {
+    (0..CHUNKS).map(|i| format!(" token{i}")).collect()
+}
+
+fn bench_text_accumulator(c: &mut Criterion) {
+    let chunks = chunks();
+    let mut group = c.benchmark_group("stream");
+    group.throughput(Throughput::Elements(CHUNKS as u64));
+
+    group.bench_function("text_accumulator", |b| {
+        b.iter(|| {
+            let mut text = TextAccumulator::new();
+            chunks.iter().for_each(|chunk| text.push(chunk));
+            black_box(text.into_string())
+        })
+    });
+
+    group.bench_function("stop_sequence_filter", |b| {
+        b.iter(|| {
+            let mut filter = StopSequenceFilter::new(&["<|end|>", "\n\nUser:"]);
+            let mut text = TextAccumulator::new();
+            for chunk in &chunks {
+                text.push(&filter.push(chunk));
+            }
+            text.push(&filter.finish());
+            black_box(text.into_string())
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_end_to_end(c: &mut Criterion) {
+    let Ok(server) = std::env::var("OLLIE_BENCH_SERVER") else {
+        return;
+    };
+    let model = std::env::var("OLLIE_BENCH_MODEL").unwrap_or_else(|_| "gemma3:1b".to_string());
+
+    let mut bench = Benchmark::new(&server, &model);
+    bench.set_iterations(1).set_warmup(0);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    // Load the model before measuring.
+    let report = runtime.block_on(bench.run()).unwrap();
+    println!("{report}");
+
+    let mut group = c.benchmark_group("end_to_end");
+    group.sample_size(10);
+    group.bench_function(&model, |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(bench.run().await.unwrap()) })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_text_accumulator, bench_end_to_end);
+criterion_main!(benches);
@@ -1,6 +1,4 @@
-use ollie_rs::Gemini;
-use ollie_rs::GeminiPromptUser;
-use ollie_rs::GeminiRequest;
+use ollie_rs::prelude::*;
 use std::env;
 
 /// This example demonstrates how to have a multi-turn conversation with Gemini models
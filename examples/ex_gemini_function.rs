@@ -1,7 +1,4 @@
-use ollie_rs::{
-    Gemini, GeminiFunctionCall, GeminiFunctionDeclaration, GeminiFunctionResponse,
-    GeminiPromptUser, GeminiRequest, GeminiToolDeclaration,
-};
+use ollie_rs::prelude::*;
 use rand::Rng;
 use schemars::{JsonSchema, schema_for};
 use serde::{Deserialize, Serialize};
@@ -1,4 +1,4 @@
-use ollie_rs::Gemini;
+use ollie_rs::prelude::*;
 use std::env;
 
 #[tokio::main]
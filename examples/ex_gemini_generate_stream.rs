@@ -1,4 +1,4 @@
-use ollie_rs::{Gemini, GeminiRequest};
+use ollie_rs::prelude::*;
 use std::env;
 use std::io::Write;
 
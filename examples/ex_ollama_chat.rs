@@ -1,4 +1,4 @@
-use ollie_rs::{Ollama, OllamaMessage, OllamaOptions, OllamaRequest};
+use ollie_rs::prelude::*;
 use std::io::Write;
 
 #[tokio::main]
@@ -7,12 +7,12 @@ async fn main() {
     let question = "Why is the sky blue?";
 
     let control = OllamaMessage::new()
-        .set_role("control")
+        .set_role(OllamaRole::Control)
         .set_content("thinking")
         .to_json();
 
     let user = OllamaMessage::new()
-        .set_role("user")
+        .set_role(OllamaRole::User)
         .set_content(question)
         .to_json();
 
@@ -25,7 +25,7 @@ async fn main() {
         // .model("gemma3:12b")
         // .set_model("gemma3:4b")
         .set_model("gemma3:1b")
-        .set_options(&options.to_json())
+        .set_options(options.to_json().unwrap())
         .add_message(control)
         .add_message(user);
 
@@ -55,12 +55,12 @@ async fn main() {
 
     // Print the response statistics.
     let response = result.unwrap();
-    response.print_stats();
+    println!("\n\n-> stats:\n{}", response.stats());
 
     // Ask a follow-up question based on the previous response.
     let question = "Can you summarize your previous answer in 2 sentences?";
     let user = OllamaMessage::new()
-        .set_role("user")
+        .set_role(OllamaRole::User)
         .set_content(question)
         .to_json();
 
@@ -96,5 +96,5 @@ async fn main() {
 
     // Print the response statistics.
     let response = result.unwrap();
-    response.print_stats();
+    println!("\n\n-> stats:\n{}", response.stats());
 }
@@ -1,15 +1,14 @@
-use ollie_rs::OllamaSession;
+use ollie_rs::prelude::*;
 use std::io::{self, Write};
-// Uncomment to add delays between agent responses
-// use tokio::time::{Duration, sleep};
 
 /// This example demonstrates how to create two AI agents that have a conversation with each other
-/// using Ollama sessions. Each agent responds to the other's messages in an infinite loop.
+/// using a `Dialogue`. Each agent responds to the other's messages for a fixed number of turns.
 #[tokio::main]
 async fn main() {
     // === Configuration ===
     const MODEL: &str = "gemma3:4b"; // Model to use for both agents
     const CONTEXT_WINDOW_SIZE: u32 = 24 * 1024; // Context window size
+    const MAX_TURNS: usize = 20; // Total number of responses across both agents
 
     // === Initialize Two Ollama Sessions ===
     // Create two separate Ollama sessions (agents)
@@ -20,69 +19,36 @@ async fn main() {
     agent1.set_context_window_size(CONTEXT_WINDOW_SIZE);
     agent2.set_context_window_size(CONTEXT_WINDOW_SIZE);
 
-    // === Conversation Variables ===
-    let mut agent1_response = String::new();
-    // Initialize with a greeting to start the conversation
-    let mut agent2_response = "Hello there! How are you doing today?".to_string();
+    // === Set Up the Dialogue ===
+    let mut dialogue = Dialogue::new();
 
-    // === Begin Conversation Loop ===
-    loop {
-        // === Agent 1's Turn ("BOB") ===
-        println!("\n\n=== AGENT 1 (BOB) ===\n");
-
-        // Clear previous response and send Agent 2's message to Agent 1
-        agent1_response.clear();
-        agent1.user(&agent2_response);
+    // Stream each agent's response as it arrives
+    dialogue.add_participant("BOB", agent1).on_chunk(|chunk| {
+        print!("{}", chunk);
+        io::stdout().flush().unwrap();
+    });
+    dialogue.add_participant("FRED", agent2).on_chunk(|chunk| {
+        print!("{}", chunk);
+        io::stdout().flush().unwrap();
+    });
 
-        // Stream Agent 1's response and collect it
-        let stats = agent1
-            .update(|chunk| {
-                // Print each chunk as it arrives
-                print!("{}", chunk);
-                io::stdout().flush().unwrap();
-                // Store the complete response
-                agent1_response.push_str(&chunk);
-            })
-            .await
-            .unwrap();
+    // Initialize with a greeting from FRED to start the conversation
+    dialogue.say("FRED", "Hello there! How are you doing today?");
 
-        // Display token usage statistics
-        println!(
-            "\n\n[Stats] Tokens used: {} of {}",
-            stats.tokens_used(),
-            agent1.context_window_size()
-        );
-
-        // Optional: Add delay between responses
-        // sleep(Duration::from_secs(10)).await;
-
-        // === Agent 2's Turn ("FRED") ===
-        println!("\n\n=== AGENT 2 (FRED) ===\n");
-
-        // Clear previous response and send Agent 1's message to Agent 2
-        agent2_response.clear();
-        agent2.user(&agent1_response);
+    // === Begin Conversation Loop ===
+    for _ in 0..MAX_TURNS {
+        // Turns alternate, so BOB answers odd-length transcripts and FRED even ones
+        let speaker = dialogue.participants()[(dialogue.transcript().len() + 1) % 2]
+            .name()
+            .to_string();
+        println!("\n\n=== {} ===\n", speaker);
 
-        // Stream Agent 2's response and collect it
-        let stats = agent2
-            .update(|chunk| {
-                // Print each chunk as it arrives
-                print!("{}", chunk);
-                io::stdout().flush().unwrap();
-                // Store the complete response
-                agent2_response.push_str(&chunk);
-            })
-            .await
-            .unwrap();
+        let turn = dialogue.step().await.unwrap();
 
         // Display token usage statistics
         println!(
             "\n\n[Stats] Tokens used: {} of {}",
-            stats.tokens_used(),
-            agent2.context_window_size()
+            turn.tokens_used, CONTEXT_WINDOW_SIZE
         );
-
-        // Optional: Add delay between responses
-        // sleep(Duration::from_secs(10)).await;
     }
 }
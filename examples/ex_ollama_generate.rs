@@ -1,4 +1,4 @@
-use ollie_rs::{Ollama, OllamaRequest};
+use ollie_rs::prelude::*;
 use std::io::Write;
 
 #[tokio::main]
@@ -31,5 +31,5 @@ async fn main() {
         return;
     }
 
-    result.unwrap().print_stats();
+    println!("\n\n-> stats:\n{}", result.unwrap().stats());
 }
@@ -1,4 +1,4 @@
-use ollie_rs::OllamaSession;
+use ollie_rs::prelude::*;
 use std::io::{self, Write};
 
 #[tokio::main]
@@ -0,0 +1,335 @@
+//! Procedural macros for `ollie-rs`.
+//!
+//! These macros are re-exported by `ollie-rs` when its `macros` feature is enabled,
+//! and should be used through that crate rather than depending on this one directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, LitStr, Meta, Pat,
+    PathArguments, ReturnType, Type, parse_macro_input,
+};
+
+/// Turns an async function into an `ollie_rs::Tool`.
+///
+/// The macro keeps the function as written and generates a companion
+/// `<function>_tool()` constructor returning an `ollie_rs::Tool` whose
+/// declaration is derived from the function signature and doc comment:
+///
+/// * The tool name is the function name (override with `#[ollie_tool(name = "...")]`).
+/// * The tool description is the doc comment text before the first heading.
+/// * Parameter descriptions come from the `# Arguments` list (`` * `name` - description ``).
+/// * Parameter schemas come from each type's `schemars::JsonSchema` implementation;
+///   `Option<T>` parameters are declared as optional.
+///
+/// The function must be a free `async fn` taking owned, deserializable arguments.
+/// Its return value must be serializable, either directly or wrapped in a `Result`
+/// whose error converts into `Box<dyn Error + Send + Sync>`.
+///
+/// # Example
+///
+/// ```ignore
+/// use ollie_rs::{ToolRegistry, ollie_tool};
+///
+/// /// Gets the current weather for a location.
+/// ///
+/// /// # Arguments
+/// ///
+/// /// * `location` - The city to get the weather for.
+/// /// * `unit` - Either "celsius" or "fahrenheit".
+/// #[ollie_tool]
+/// async fn get_current_weather(location: String, unit: Option<String>) -> String {
+///     format!("It is sunny in {location}")
+/// }
+///
+/// let mut registry = ToolRegistry::new();
+/// registry.register(get_current_weather_tool());
+/// ```
+#[proc_macro_attribute]
+pub fn ollie_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut name: Option<LitStr> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported ollie_tool argument, expected `name = \"...\"`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+
+    let function = parse_macro_input!(item as ItemFn);
+    match expand(name, function) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// A documented parameter of the annotated function.
+struct Parameter {
+    ident: syn::Ident,
+    ty: Type,
+    schema_ty: Type,
+    required: bool,
+    description: String,
+}
+
+fn expand(name: Option<LitStr>, function: ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &function.sig;
+
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "#[ollie_tool] can only be applied to an `async fn`",
+        ));
+    }
+
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "#[ollie_tool] functions cannot be generic",
+        ));
+    }
+
+    let docs = doc_lines(&function.attrs);
+    let description = description(&docs);
+    let arg_docs = argument_docs(&docs);
+
+    let mut parameters = Vec::new();
+    for input in &sig.inputs {
+        let typed = match input {
+            FnArg::Typed(typed) => typed,
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "#[ollie_tool] functions cannot take `self`",
+                ));
+            }
+        };
+
+        let ident = match typed.pat.as_ref() {
+            Pat::Ident(pat) => pat.ident.clone(),
+            pat => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    "#[ollie_tool] parameters must be plain identifiers",
+                ));
+            }
+        };
+
+        let ty = typed.ty.as_ref().clone();
+        let (schema_ty, required) = match option_inner(&ty) {
+            Some(inner) => (inner.clone(), false),
+            None => (ty.clone(), true),
+        };
+
+        let key = ident.to_string();
+        let description = arg_docs
+            .iter()
+            .find(|(arg, _)| *arg == key)
+            .map(|(_, text)| text.clone())
+            .unwrap_or_default();
+
+        parameters.push(Parameter {
+            ident,
+            ty,
+            schema_ty,
+            required,
+            description,
+        });
+    }
+
+    let fn_ident = &sig.ident;
+    let vis = &function.vis;
+    let tool_fn = format_ident!("{}_tool", fn_ident);
+    let tool_name = name
+        .map(|lit| lit.value())
+        .unwrap_or_else(|| fn_ident.to_string());
+    let invoke_fn = format_ident!("__{}_invoke", fn_ident);
+
+    let schema_entries = parameters.iter().map(|param| {
+        let key = param.ident.to_string();
+        let schema_ty = &param.schema_ty;
+        let description = &param.description;
+        let describe = if description.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                if let Some(object) = schema.as_object_mut() {
+                    object.insert("description".to_string(), #description.into());
+                }
+            }
+        };
+        let push_required = if param.required {
+            quote! { required.push(#key.into()); }
+        } else {
+            quote! {}
+        };
+
+        quote! {
+            #[allow(unused_mut)]
+            let mut schema = ::ollie_rs::Tool::schema_of::<#schema_ty>();
+            #describe
+            properties.insert(#key.to_string(), schema);
+            #push_required
+        }
+    });
+
+    let arg_bindings = parameters.iter().map(|param| {
+        let ident = &param.ident;
+        let ty = &param.ty;
+        let key = ident.to_string();
+
+        quote! {
+            let #ident: #ty = ::ollie_rs::__private::serde_json::from_value(
+                args.get(#key)
+                    .cloned()
+                    .unwrap_or(::ollie_rs::__private::serde_json::Value::Null),
+            )
+            .map_err(|err| format!("invalid argument '{}': {}", #key, err))?;
+        }
+    });
+
+    let arg_idents = parameters.iter().map(|param| &param.ident);
+    let call = if returns_result(&sig.output) {
+        quote! { #fn_ident(#(#arg_idents),*).await? }
+    } else {
+        quote! { #fn_ident(#(#arg_idents),*).await }
+    };
+
+    Ok(quote! {
+        #function
+
+        #[doc = concat!("Returns the `", #tool_name, "` tool generated from [`", stringify!(#fn_ident), "`].")]
+        #vis fn #tool_fn() -> ::ollie_rs::Tool {
+            async fn #invoke_fn(
+                args: ::ollie_rs::__private::serde_json::Value,
+            ) -> ::ollie_rs::ToolResult {
+                #(#arg_bindings)*
+                let output = #call;
+                Ok(::ollie_rs::__private::serde_json::to_value(output)?)
+            }
+
+            #[allow(unused_mut)]
+            let mut properties = ::ollie_rs::__private::serde_json::Map::new();
+            #[allow(unused_mut)]
+            let mut required: Vec<::ollie_rs::__private::serde_json::Value> = Vec::new();
+            #(#schema_entries)*
+
+            let parameters = ::ollie_rs::__private::serde_json::json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            });
+
+            ::ollie_rs::Tool::new(#tool_name, #description, parameters, #invoke_fn)
+        }
+    })
+}
+
+/// Collects the doc comment lines of an item, with the leading space removed.
+fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect()
+}
+
+/// Returns the doc comment text before the first heading, joined into one line.
+fn description(docs: &[String]) -> String {
+    docs.iter()
+        .take_while(|line| !line.trim_start().starts_with('#'))
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses the `# Arguments` section into `(name, description)` pairs.
+fn argument_docs(docs: &[String]) -> Vec<(String, String)> {
+    let mut args: Vec<(String, String)> = Vec::new();
+    let mut in_arguments = false;
+
+    for line in docs {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('#') {
+            in_arguments = trimmed.trim_start_matches('#').trim() == "Arguments";
+            continue;
+        }
+
+        if !in_arguments || trimmed.is_empty() {
+            continue;
+        }
+
+        let item = trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "));
+
+        match item {
+            Some(item) => {
+                let Some(rest) = item.strip_prefix('`') else {
+                    continue;
+                };
+                let Some((name, text)) = rest.split_once('`') else {
+                    continue;
+                };
+                let text = text.trim_start().trim_start_matches(['-', ':']).trim();
+                args.push((name.to_string(), text.to_string()));
+            }
+            None => {
+                // Continuation line of the previous argument description.
+                if let Some((_, text)) = args.last_mut() {
+                    text.push(' ');
+                    text.push_str(trimmed);
+                }
+            }
+        }
+    }
+
+    args
+}
+
+/// Returns the `T` of an `Option<T>` type.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the function's declared return type is a `Result`.
+fn returns_result(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, ty) => match ty.as_ref() {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Result"),
+            _ => false,
+        },
+    }
+}
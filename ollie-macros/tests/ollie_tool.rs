@@ -0,0 +1,94 @@
+use ollie_rs::{OllamaToolCall, ToolRegistry, ollie_tool};
+use serde_json::json;
+
+/// Gets the current weather for a location.
+///
+/// # Arguments
+///
+/// * `location` - The city to get the weather for.
+/// * `unit` - Either "celsius" or "fahrenheit",
+///   defaults to "celsius".
+#[ollie_tool]
+async fn get_current_weather(location: String, unit: Option<String>) -> String {
+    let unit = unit.unwrap_or_else(|| "celsius".to_string());
+    format!("It is 20 degrees {unit} in {location}")
+}
+
+/// Divides two numbers.
+///
+/// # Arguments
+///
+/// * `a` - The dividend.
+/// * `b` - The divisor.
+#[ollie_tool(name = "divide_numbers")]
+async fn divide(a: f64, b: f64) -> Result<f64, String> {
+    if b == 0.0 {
+        return Err("division by zero".to_string());
+    }
+    Ok(a / b)
+}
+
+#[test]
+fn test_generated_declaration() {
+    let tool = get_current_weather_tool();
+    assert_eq!(tool.name(), "get_current_weather");
+    assert_eq!(
+        tool.description(),
+        "Gets the current weather for a location."
+    );
+    assert_eq!(
+        tool.parameters(),
+        &json!({
+            "type": "object",
+            "properties": {
+                "location": {
+                    "type": "string",
+                    "description": "The city to get the weather for."
+                },
+                "unit": {
+                    "type": "string",
+                    "description": "Either \"celsius\" or \"fahrenheit\", defaults to \"celsius\"."
+                }
+            },
+            "required": ["location"]
+        })
+    );
+}
+
+#[test]
+fn test_name_override() {
+    let tool = divide_tool();
+    assert_eq!(tool.name(), "divide_numbers");
+    assert_eq!(tool.parameters()["required"], json!(["a", "b"]));
+}
+
+#[tokio::test]
+async fn test_generated_dispatcher() {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(get_current_weather_tool())
+        .register(divide_tool());
+
+    let result = registry
+        .call("get_current_weather", json!({ "location": "Paris" }))
+        .await
+        .unwrap();
+    assert_eq!(result, json!("It is 20 degrees celsius in Paris"));
+
+    let tool_call = OllamaToolCall::from(&json!({
+        "function": { "name": "divide_numbers", "arguments": { "a": 9.0, "b": 3.0 } }
+    }));
+    assert_eq!(registry.dispatch(&tool_call).await.unwrap(), json!(3.0));
+
+    let err = registry
+        .call("divide_numbers", json!({ "a": 1.0, "b": 0.0 }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "division by zero");
+
+    let err = registry
+        .call("divide_numbers", json!({ "a": "one", "b": 2.0 }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid argument 'a'"));
+}
@@ -0,0 +1,909 @@
+use crate::{OllamaHistory, OllamaResponse, OllamaSession, OllamaTools};
+use crate::{TextChunker, ToolError, ToolErrorKind, ToolRegistry, ToolResultTruncation};
+use serde_json::Value as JsonValue;
+use std::error::Error;
+
+/// The chunk size, in tokens, used to summarize a tool result that is too long.
+const SUMMARY_CHUNK_TOKENS: u32 = 2000;
+
+/// A callback invoked after every agent step.
+pub type AgentHook = Box<dyn FnMut(&AgentStep) + Send>;
+
+/// A predicate deciding whether the agent should stop after a step.
+pub type AgentStopCondition = Box<dyn FnMut(&AgentStep) -> bool + Send>;
+
+// ===
+// STRUCT: AgentToolCall
+// ===
+
+/// A tool call made by the model during an agent step, with its result.
+#[derive(Debug, Clone)]
+pub struct AgentToolCall {
+    /// The name of the called tool.
+    pub name: String,
+    /// The arguments the model passed to the tool.
+    pub arguments: JsonValue,
+    /// The tool output, or the error message if the call failed.
+    pub result: Result<JsonValue, String>,
+}
+
+// ===
+// STRUCT: AgentStep
+// ===
+
+/// One model round-trip of an agent run.
+pub struct AgentStep {
+    /// The 1-based index of this step within the run.
+    pub turn: usize,
+    /// The final response received from the model for this step.
+    pub response: OllamaResponse,
+    /// The tool calls requested by the model and their results.
+    pub tool_calls: Vec<AgentToolCall>,
+    /// The total tokens used by the run so far, including this step.
+    pub tokens_used: u32,
+}
+
+impl AgentStep {
+    /// Returns the text generated by the model in this step.
+    pub fn text(&self) -> &str {
+        self.response.text().unwrap_or_default()
+    }
+}
+
+// ===
+// ENUM: AgentStopReason
+// ===
+
+/// The reason an agent run ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgentStopReason {
+    /// The model answered without requesting any tool calls.
+    Finished,
+    /// The maximum number of turns was reached.
+    MaxTurns,
+    /// The token budget was exhausted.
+    TokenBudget,
+    /// The custom stop condition returned `true`.
+    StopCondition,
+}
+
+// ===
+// ENUM: ToolChoice
+// ===
+
+/// Which tool calls the model may make during an agent run.
+///
+/// Ollama has no `tool_choice` parameter, so the agent steers the model with a
+/// system message and checks each response. A response that ignores the choice
+/// is not acted on; the model is reminded and asked again, like a call with
+/// invalid arguments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ToolChoice {
+    /// The model decides whether to call tools.
+    #[default]
+    Auto,
+    /// The model must call the named tool before anything else. Only that tool is
+    /// declared until it has been called; the run then continues as `Auto`.
+    Required(String),
+    /// The model must answer without calling tools, which are not declared.
+    None,
+}
+
+impl ToolChoice {
+    /// Returns the system message steering the model toward this choice.
+    fn steering_prompt(&self) -> Option<String> {
+        match self {
+            ToolChoice::Auto => None,
+            ToolChoice::Required(name) => Some(format!(
+                "You must call the `{name}` tool now. Respond with a call to `{name}`, not with text."
+            )),
+            ToolChoice::None => {
+                Some("Do not call any tools. Answer the user directly with text.".to_string())
+            }
+        }
+    }
+}
+
+// ===
+// STRUCT: AgentOutcome
+// ===
+
+/// The result of an agent run.
+pub struct AgentOutcome {
+    /// Why the run ended.
+    pub reason: AgentStopReason,
+    /// The number of model round-trips performed.
+    pub turns: usize,
+    /// The total tokens used across all turns.
+    pub tokens_used: u32,
+    /// The last response received from the model.
+    pub response: OllamaResponse,
+}
+
+impl AgentOutcome {
+    /// Returns the text of the last model response.
+    pub fn text(&self) -> &str {
+        self.response.text().unwrap_or_default()
+    }
+}
+
+// ===
+// STRUCT: Agent
+// ===
+
+/// A tool-using agent built on top of an [`OllamaSession`].
+///
+/// The agent sends the conversation to the model, executes any tool calls it
+/// requests through its [`ToolRegistry`], feeds the results back and repeats until
+/// the model answers without calling a tool or a stop condition is met.
+///
+/// Tool call arguments are checked against the tool's parameter schema first. A
+/// call that fails is not executed; the model gets a tool error naming the
+/// problems and is asked again, which small local models often need.
+pub struct Agent {
+    session: OllamaSession,
+    registry: ToolRegistry,
+    system_prompt: Option<String>,
+    max_turns: usize,
+    max_validation_retries: usize,
+    tool_choice: ToolChoice,
+    token_budget: Option<u32>,
+    stop_condition: Option<AgentStopCondition>,
+    hooks: Vec<AgentHook>,
+}
+
+impl Agent {
+    /// The default maximum number of turns per run.
+    pub const DEFAULT_MAX_TURNS: usize = 10;
+
+    /// The default number of times the model is asked again after invalid tool arguments.
+    pub const DEFAULT_MAX_VALIDATION_RETRIES: usize = 2;
+
+    /// Creates a new agent driving the given session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session used to talk to the model.
+    ///
+    /// # Returns
+    ///
+    /// A new `Agent` with no tools, no system prompt and the default turn limit.
+    pub fn new(session: OllamaSession) -> Self {
+        Self {
+            session,
+            registry: ToolRegistry::new(),
+            system_prompt: None,
+            max_turns: Self::DEFAULT_MAX_TURNS,
+            max_validation_retries: Self::DEFAULT_MAX_VALIDATION_RETRIES,
+            tool_choice: ToolChoice::Auto,
+            token_budget: None,
+            stop_condition: None,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the underlying session.
+    pub fn session(&self) -> &OllamaSession {
+        &self.session
+    }
+
+    /// Returns a mutable reference to the underlying session.
+    pub fn session_mut(&mut self) -> &mut OllamaSession {
+        &mut self.session
+    }
+
+    /// Returns the tools available to the agent.
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.registry
+    }
+
+    /// Sets the tools available to the agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - The registry the agent dispatches tool calls to.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_tools(&mut self, registry: ToolRegistry) -> &mut Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Sets the system prompt added to the conversation before the first run.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The system prompt describing the agent's behavior.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_system_prompt(&mut self, prompt: &str) -> &mut Self {
+        self.system_prompt = Some(prompt.to_string());
+        self
+    }
+
+    /// Sets the maximum number of model round-trips per run.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_turns` - The turn limit; a run always performs at least one turn.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_max_turns(&mut self, max_turns: usize) -> &mut Self {
+        self.max_turns = max_turns;
+        self
+    }
+
+    /// Sets how many turns in a row may make tool calls with invalid arguments.
+    ///
+    /// After each such turn the model is told what was wrong and asked again; once
+    /// the retries are used up, the run fails with the validation error.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - The number of corrective turns; 0 fails on the first invalid call.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_max_validation_retries(&mut self, retries: usize) -> &mut Self {
+        self.max_validation_retries = retries;
+        self
+    }
+
+    /// Sets which tool calls the model may make during each run.
+    ///
+    /// Responses that ignore the choice count against the validation retries set
+    /// with `set_max_validation_retries`.
+    ///
+    /// # Arguments
+    ///
+    /// * `choice` - The tool choice; `ToolChoice::Auto` by default.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_tool_choice(&mut self, choice: ToolChoice) -> &mut Self {
+        self.tool_choice = choice;
+        self
+    }
+
+    /// Sets the maximum number of tokens a run may use across all turns.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The token budget; the run stops once it is reached.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_token_budget(&mut self, budget: u32) -> &mut Self {
+        self.token_budget = Some(budget);
+        self
+    }
+
+    /// Sets a custom predicate checked after every step.
+    ///
+    /// # Arguments
+    ///
+    /// * `condition` - Returns `true` to stop the run after the given step.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_stop_condition<F>(&mut self, condition: F) -> &mut Self
+    where
+        F: FnMut(&AgentStep) -> bool + Send + 'static,
+    {
+        self.stop_condition = Some(Box::new(condition));
+        self
+    }
+
+    /// Adds a hook invoked after every step, e.g. for logging or UI updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The callback receiving each completed step.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn on_step<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: FnMut(&AgentStep) + Send + 'static,
+    {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Runs the agent on a user prompt until it finishes or a stop condition is met.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The user message starting this run.
+    /// * `callback` - A function called with each chunk of generated text.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(AgentOutcome)` - The final response and why the run stopped.
+    /// * `Err(Box<dyn Error>)` - Any error that occurred while talking to the model, or
+    ///   the validation error of tool arguments still invalid after the allowed retries,
+    ///   or of a tool choice the model still ignores.
+    pub async fn run<F>(
+        &mut self,
+        prompt: &str,
+        mut callback: F,
+    ) -> Result<AgentOutcome, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        if let Some(system_prompt) = self.system_prompt.take() {
+            self.session.system(&system_prompt);
+        }
+
+        // The tool still required by the tool choice, if any.
+        let mut required = match &self.tool_choice {
+            ToolChoice::Required(name) => match self.registry.get(name) {
+                Some(tool) => Some(tool.clone()),
+                None => return Err(format!("tool choice requires unknown tool '{name}'").into()),
+            },
+            _ => None,
+        };
+
+        let tools = match (&self.tool_choice, &required) {
+            (ToolChoice::None, _) => Some(OllamaTools::new()),
+            (_, Some(tool)) => {
+                let mut tools = OllamaTools::new();
+                tools.push_function(tool.to_ollama_function());
+                Some(tools)
+            }
+            _ if !self.registry.is_empty() => Some(self.registry.to_ollama_tools()),
+            _ => None,
+        };
+        if let Some(tools) = tools {
+            self.session.set_tools(&tools);
+        }
+
+        self.session.user(prompt);
+
+        let mut tokens_used = 0;
+        let mut turn = 0;
+        let mut validation_retries = 0;
+
+        loop {
+            turn += 1;
+            // Steer only while the choice applies, without keeping the reminder
+            // in the history.
+            let steering = match (&self.tool_choice, &required) {
+                (ToolChoice::Required(_), None) => None,
+                (choice, _) => choice.steering_prompt(),
+            };
+            self.session.set_steering(steering);
+            let checkpoint = self.session.checkpoint();
+            let response = self.session.update(&mut callback).await;
+            self.session.set_steering(None);
+            let response = response?;
+            tokens_used += response.tokens_used();
+
+            // A response that ignores the tool choice is not acted on.
+            let required_name = required.as_ref().map(|tool| tool.name());
+            if let Some(problem) = self.tool_choice_problem(&response, required_name) {
+                // The rejected answer is not kept, so retries are not sent a dangling
+                // tool call or a text answer the model was told not to give.
+                self.session.restore(checkpoint);
+                let step = AgentStep {
+                    turn,
+                    response,
+                    tool_calls: Vec::new(),
+                    tokens_used,
+                };
+                for hook in &mut self.hooks {
+                    hook(&step);
+                }
+
+                if validation_retries >= self.max_validation_retries {
+                    return Err(format!(
+                        "{problem} (still ignored after {validation_retries} retries)"
+                    )
+                    .into());
+                }
+                validation_retries += 1;
+
+                if turn >= self.max_turns {
+                    return Ok(AgentOutcome {
+                        reason: AgentStopReason::MaxTurns,
+                        turns: turn,
+                        tokens_used,
+                        response: step.response,
+                    });
+                }
+                continue;
+            }
+
+            // Once the required tool has been called, every tool is available again.
+            if required.take().is_some() {
+                self.session.set_tools(&self.registry.to_ollama_tools());
+            }
+
+            // Execute the requested tools and feed their results back to the model.
+            let mut tool_calls = Vec::new();
+            let mut invalid = None;
+            if let Some(calls) = response.message().and_then(|m| m.tool_calls()) {
+                for index in 0..calls.len() {
+                    let Some(call) = calls.tool_call(index) else {
+                        continue;
+                    };
+
+                    let name = call.name().unwrap_or_default().to_string();
+                    let result = match self.registry.validate(&call) {
+                        Ok(()) => self
+                            .registry
+                            .dispatch(&call)
+                            .await
+                            .map_err(|err| ToolError::from_error(&name, err)),
+                        Err(problems) => {
+                            invalid = Some(format!("tool call '{name}': {problems}"));
+                            Err(ToolError::new(&name, ToolErrorKind::InvalidArgs, &problems))
+                        }
+                    };
+
+                    let content = match &result {
+                        Ok(value) => self.limit_result(value).await,
+                        Err(err) => err.to_json().to_string(),
+                    };
+                    self.session.tool_result(&name, &content);
+                    let result = result.map_err(|err| err.to_string());
+
+                    tool_calls.push(AgentToolCall {
+                        name,
+                        arguments: call.arguments().cloned().unwrap_or(JsonValue::Null),
+                        result,
+                    });
+                }
+            }
+
+            let step = AgentStep {
+                turn,
+                response,
+                tool_calls,
+                tokens_used,
+            };
+
+            for hook in &mut self.hooks {
+                hook(&step);
+            }
+
+            match invalid {
+                Some(problem) if validation_retries >= self.max_validation_retries => {
+                    return Err(format!(
+                        "{problem} (still invalid after {validation_retries} retries)"
+                    )
+                    .into());
+                }
+                Some(_) => validation_retries += 1,
+                None => validation_retries = 0,
+            }
+
+            let reason = if step.tool_calls.is_empty() {
+                Some(AgentStopReason::Finished)
+            } else if self.stop_condition.as_mut().is_some_and(|stop| stop(&step)) {
+                Some(AgentStopReason::StopCondition)
+            } else if self
+                .token_budget
+                .is_some_and(|budget| tokens_used >= budget)
+            {
+                Some(AgentStopReason::TokenBudget)
+            } else if turn >= self.max_turns {
+                Some(AgentStopReason::MaxTurns)
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                return Ok(AgentOutcome {
+                    reason,
+                    turns: turn,
+                    tokens_used,
+                    response: step.response,
+                });
+            }
+        }
+    }
+
+    /// Describes how a response ignores the tool choice, if it does.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The model's response.
+    /// * `required` - The tool the model must still call, if any.
+    fn tool_choice_problem(
+        &self,
+        response: &OllamaResponse,
+        required: Option<&str>,
+    ) -> Option<String> {
+        let called: Vec<String> = response
+            .message()
+            .and_then(|message| message.tool_calls())
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter_map(|call| call.name().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        match (&self.tool_choice, required) {
+            (ToolChoice::None, _) if !called.is_empty() => Some(format!(
+                "tool choice is none, but the model called '{}'",
+                called.join("', '")
+            )),
+            (_, Some(name)) if !called.iter().any(|called| called == name) => Some(format!(
+                "tool choice requires '{name}', but the model did not call it"
+            )),
+            _ => None,
+        }
+    }
+
+    /// Serializes a tool result, shortened to the registry's result limit.
+    async fn limit_result(&mut self, value: &JsonValue) -> String {
+        let content = value.to_string();
+        let Some((max_chars, truncation)) = self.registry.result_limit() else {
+            return content;
+        };
+        if truncation != ToolResultTruncation::Summarize || content.chars().count() <= max_chars {
+            return self.registry.truncate_result(&content);
+        }
+
+        // The summary is written on a fork without the conversation or the tools,
+        // which only matter to the run itself.
+        let mut summarizer = self.session.fork();
+        summarizer.restore(OllamaHistory::default());
+        summarizer.set_tools(&OllamaTools::new());
+
+        // Pretty-printing puts whitespace between values, so the chunker can split
+        // a compact JSON blob.
+        let document = serde_json::to_string_pretty(value).unwrap_or(content.clone());
+        let chunker = TextChunker::new(SUMMARY_CHUNK_TOKENS);
+        match crate::pipeline::summarize(&document, &chunker, &mut summarizer, |_| {}).await {
+            Ok(summary) if summary.chars().count() <= max_chars => {
+                format!(
+                    "[summary of a {} character result] {summary}",
+                    content.chars().count()
+                )
+            }
+            _ => self.registry.truncate_result(&content),
+        }
+    }
+}
+
+// ===
+// TESTS: Agent
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tool;
+    use crate::mock_server::{MockServer, chat_body, ndjson};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    fn tool_call_body(name: &str, arguments: JsonValue) -> String {
+        ndjson(&[
+            json!({
+                "model": "mock",
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{ "function": { "name": name, "arguments": arguments } }]
+                },
+                "done": false
+            }),
+            json!({
+                "model": "mock",
+                "message": { "role": "assistant", "content": "" },
+                "done": true,
+                "eval_count": 5,
+                "prompt_eval_count": 15
+            }),
+        ])
+    }
+
+    fn add_tool() -> Tool {
+        Tool::new(
+            "add",
+            "Adds two numbers.",
+            json!({ "type": "object", "properties": {}, "required": [] }),
+            |args| async move {
+                let a = args["a"].as_f64().unwrap_or(0.0);
+                let b = args["b"].as_f64().unwrap_or(0.0);
+                Ok(json!(a + b))
+            },
+        )
+    }
+
+    fn calculator_agent(server: &MockServer) -> Agent {
+        let mut registry = ToolRegistry::new();
+        registry.register(add_tool());
+
+        let mut agent = Agent::new(OllamaSession::remote("mock", &server.addr()));
+        agent
+            .set_tools(registry)
+            .set_system_prompt("You are a calculator.");
+        agent
+    }
+
+    #[tokio::test]
+    async fn test_run_executes_tools_until_finished() {
+        let server = MockServer::start(vec![
+            tool_call_body("add", json!({ "a": 2, "b": 3 })),
+            chat_body(&["The answer ", "is 5."]),
+        ])
+        .await;
+
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let recorded = steps.clone();
+
+        let mut agent = calculator_agent(&server);
+        agent.on_step(move |step| recorded.lock().unwrap().push(step.tool_calls.clone()));
+
+        let mut streamed = String::new();
+        let outcome = agent
+            .run("What is 2 + 3?", |chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.reason, AgentStopReason::Finished);
+        assert_eq!(outcome.turns, 2);
+        assert_eq!(outcome.tokens_used, 50);
+        assert_eq!(outcome.text(), "The answer is 5.");
+        assert_eq!(streamed, "The answer is 5.");
+
+        let steps = steps.lock().unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0][0].name, "add");
+        assert_eq!(steps[0][0].result, Ok(json!(5.0)));
+
+        // The second request carries the system prompt, tools, tool call and tool result.
+        let requests = server.requests();
+        let messages = requests[1]["messages"].as_array().unwrap();
+        assert_eq!(requests[1]["tools"][0]["function"]["name"], "add");
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[2]["tool_calls"][0]["function"]["name"], "add");
+        assert_eq!(messages[3]["role"], "tool");
+        assert_eq!(messages[3]["tool_name"], "add");
+        assert_eq!(messages[3]["content"], "5.0");
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_at_max_turns() {
+        let server = MockServer::start(vec![
+            tool_call_body("add", json!({ "a": 1, "b": 1 })),
+            tool_call_body("missing", json!({})),
+        ])
+        .await;
+
+        let mut agent = calculator_agent(&server);
+        agent.set_max_turns(2);
+
+        let outcome = agent.run("Loop forever", |_| {}).await.unwrap();
+        assert_eq!(outcome.reason, AgentStopReason::MaxTurns);
+        assert_eq!(outcome.turns, 2);
+
+        // Unknown tools are reported back to the model as errors.
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_on_budget_and_condition() {
+        let server = MockServer::start(vec![tool_call_body("add", json!({}))]).await;
+        let mut agent = calculator_agent(&server);
+        agent.set_token_budget(10);
+        let outcome = agent.run("Go", |_| {}).await.unwrap();
+        assert_eq!(outcome.reason, AgentStopReason::TokenBudget);
+
+        let server = MockServer::start(vec![tool_call_body("add", json!({}))]).await;
+        let mut agent = calculator_agent(&server);
+        agent.set_stop_condition(|step| step.tool_calls.iter().any(|call| call.name == "add"));
+        let outcome = agent.run("Go", |_| {}).await.unwrap();
+        assert_eq!(outcome.reason, AgentStopReason::StopCondition);
+        assert_eq!(outcome.turns, 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_reasks_on_invalid_arguments() {
+        let server = MockServer::start(vec![
+            tool_call_body("add", json!({ "a": "2" })),
+            tool_call_body("add", json!({ "a": 2, "b": 3 })),
+            chat_body(&["5"]),
+            tool_call_body("add", json!({ "a": 1 })),
+            tool_call_body("add", json!({ "b": 1 })),
+        ])
+        .await;
+
+        let schema = json!({
+            "type": "object",
+            "properties": { "a": { "type": "number" }, "b": { "type": "number" } },
+            "required": ["a", "b"]
+        });
+        let mut registry = ToolRegistry::new();
+        registry.register(Tool::new(
+            "add",
+            "Adds two numbers.",
+            schema,
+            |args| async move {
+                Ok(json!(
+                    args["a"].as_f64().unwrap() + args["b"].as_f64().unwrap()
+                ))
+            },
+        ));
+        let mut agent = Agent::new(OllamaSession::remote("mock", &server.addr()));
+        agent.set_tools(registry);
+
+        let outcome = agent.run("What is 2 + 3?", |_| {}).await.unwrap();
+        assert_eq!(outcome.reason, AgentStopReason::Finished);
+        assert_eq!(outcome.turns, 3);
+
+        let requests = server.requests();
+        let correction = requests[1]["messages"][2]["content"].as_str().unwrap();
+        assert!(correction.contains("arguments failed validation"));
+        assert!(correction.contains("$.a: expected number, got string"));
+        assert_eq!(requests[2]["messages"][4]["content"], "5.0");
+
+        agent.set_max_validation_retries(1);
+        let error = agent.run("What is 1 + 1?", |_| {}).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "tool call 'add': $: missing required property 'a' (still invalid after 1 retries)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_steers_tool_choice() {
+        let server = MockServer::start(vec![
+            chat_body(&["It is 5."]),
+            tool_call_body("add", json!({ "a": 2, "b": 3 })),
+            chat_body(&["The answer is 5."]),
+            tool_call_body("add", json!({ "a": 1, "b": 1 })),
+        ])
+        .await;
+
+        let mut agent = calculator_agent(&server);
+        let mut registry = agent.tools().clone();
+        registry.register(Tool::new(
+            "noop",
+            "Does nothing.",
+            json!({ "type": "object" }),
+            |_| async move { Ok(JsonValue::Null) },
+        ));
+        agent
+            .set_tools(registry)
+            .set_tool_choice(ToolChoice::Required("add".to_string()));
+
+        let outcome = agent.run("What is 2 + 3?", |_| {}).await.unwrap();
+        assert_eq!(outcome.reason, AgentStopReason::Finished);
+        assert_eq!(outcome.turns, 3);
+
+        // Only the required tool is declared until the model calls it.
+        let requests = server.requests();
+        assert_eq!(requests[0]["tools"].as_array().unwrap().len(), 1);
+        assert_eq!(requests[1]["tools"].as_array().unwrap().len(), 1);
+        assert_eq!(requests[2]["tools"].as_array().unwrap().len(), 2);
+        assert_eq!(requests[0]["messages"][2]["role"], "system");
+        let reminder = requests[1]["messages"][2]["content"].as_str().unwrap();
+        // The ignored text answer is not sent with the retry.
+        assert_eq!(requests[1]["messages"].as_array().unwrap().len(), 3);
+        assert!(reminder.contains("You must call the `add` tool"));
+
+        // The reminder is sent with each request until the tool is called, but
+        // never kept in the history.
+        let steered = |request: &JsonValue| {
+            request["messages"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|message| message["content"].as_str() == Some(reminder))
+                .count()
+        };
+        assert_eq!(steered(&requests[1]), 1);
+        assert_eq!(steered(&requests[2]), 0);
+        assert!(
+            agent
+                .session()
+                .messages()
+                .iter()
+                .all(|m| m["role"] != "system" || m["content"] == "You are a calculator.")
+        );
+
+        agent
+            .set_tool_choice(ToolChoice::None)
+            .set_max_validation_retries(0);
+        let error = agent.run("What is 1 + 1?", |_| {}).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "tool choice is none, but the model called 'add' (still ignored after 0 retries)"
+        );
+        assert_eq!(server.requests()[3]["tools"], json!([]));
+        let last = agent.session().messages().last().unwrap().clone();
+        assert_eq!(last, json!({ "role": "user", "content": "What is 1 + 1?" }));
+
+        agent.set_tool_choice(ToolChoice::Required("missing".to_string()));
+        assert!(agent.run("Hi", |_| {}).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_without_the_rejected_tool_call() {
+        let server = MockServer::start(vec![
+            tool_call_body("add", json!({ "a": 2, "b": 3 })),
+            chat_body(&["It is 5."]),
+        ])
+        .await;
+
+        let mut agent = calculator_agent(&server);
+        agent.set_tool_choice(ToolChoice::None);
+        let outcome = agent.run("What is 2 + 3?", |_| {}).await.unwrap();
+        assert_eq!(outcome.text(), "It is 5.");
+
+        // The retry carries no assistant turn, so no tool call is left unanswered.
+        let requests = server.requests();
+        let roles: Vec<_> = requests[1]["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, ["system", "user", "system"]);
+
+        let history: Vec<_> = agent.session().messages().iter().cloned().collect();
+        assert_eq!(history.len(), 3);
+        assert!(
+            history
+                .iter()
+                .all(|message| message.get("tool_calls").is_none())
+        );
+        assert_eq!(history[2]["content"], "It is 5.");
+    }
+
+    #[tokio::test]
+    async fn test_run_summarizes_long_tool_results() {
+        let server = MockServer::start(vec![
+            tool_call_body("listing", json!({})),
+            chat_body(&["Three files."]),
+            chat_body(&["Done."]),
+        ])
+        .await;
+
+        let mut registry = ToolRegistry::new();
+        registry
+            .register(Tool::new(
+                "listing",
+                "Lists files.",
+                json!({ "type": "object" }),
+                |_| async move { Ok(json!(["a.txt", "b.txt", "c.txt"])) },
+            ))
+            .set_result_limit(20, ToolResultTruncation::Summarize);
+
+        let mut agent = Agent::new(OllamaSession::remote("mock", &server.addr()));
+        agent.set_tools(registry);
+        let outcome = agent.run("List the files.", |_| {}).await.unwrap();
+        assert_eq!(outcome.text(), "Done.");
+
+        // The summary is requested without the conversation or the tools.
+        let requests = server.requests();
+        assert_eq!(requests[1]["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(requests[1]["tools"], json!([]));
+
+        let messages = requests[2]["messages"].as_array().unwrap();
+        assert_eq!(messages[2]["role"], "tool");
+        assert_eq!(
+            messages[2]["content"],
+            "[summary of a 25 character result] Three files."
+        );
+    }
+}
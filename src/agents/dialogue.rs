@@ -0,0 +1,483 @@
+use crate::OllamaSession;
+use rand::Rng;
+use std::error::Error;
+
+/// A callback receiving each chunk of text streamed by a participant.
+pub type DialogueChunkCallback = Box<dyn FnMut(&str) + Send>;
+
+/// A function choosing the index of the next speaker from the transcript so far.
+pub type DialogueTurnSelector = Box<dyn FnMut(&[DialogueTurn], usize) -> usize + Send>;
+
+/// A predicate deciding whether the dialogue should end after a turn.
+pub type DialogueStopCondition = Box<dyn FnMut(&DialogueTurn) -> bool + Send>;
+
+// ===
+// STRUCT: DialogueTurn
+// ===
+
+/// A single message in the shared dialogue transcript.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialogueTurn {
+    /// The name of the participant who spoke, or the name given to [`Dialogue::say`].
+    pub speaker: String,
+    /// The text of the message.
+    pub text: String,
+    /// The tokens used by the model to produce this message, zero for injected messages.
+    pub tokens_used: u32,
+}
+
+// ===
+// ENUM: TurnPolicy
+// ===
+
+/// Decides which participant speaks next.
+pub enum TurnPolicy {
+    /// Participants speak in the order they were added.
+    RoundRobin,
+    /// A random participant other than the last speaker is picked each turn.
+    Random,
+    /// A custom selector receives the transcript and the participant count
+    /// and returns the index of the next speaker.
+    Custom(DialogueTurnSelector),
+}
+
+// ===
+// ENUM: DialogueStopReason
+// ===
+
+/// The reason a dialogue run ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DialogueStopReason {
+    /// The maximum number of turns was reached.
+    MaxTurns,
+    /// The stop condition returned `true`.
+    StopCondition,
+}
+
+// ===
+// STRUCT: DialogueParticipant
+// ===
+
+/// A named participant of a [`Dialogue`], backed by its own session.
+pub struct DialogueParticipant {
+    name: String,
+    session: OllamaSession,
+    on_chunk: Option<DialogueChunkCallback>,
+    seen: usize,
+}
+
+impl DialogueParticipant {
+    /// Returns the participant's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a reference to the participant's session.
+    pub fn session(&self) -> &OllamaSession {
+        &self.session
+    }
+
+    /// Returns a mutable reference to the participant's session.
+    pub fn session_mut(&mut self) -> &mut OllamaSession {
+        &mut self.session
+    }
+
+    /// Adds a system prompt to the participant's session.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The instructions describing this participant's persona.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_system_prompt(&mut self, prompt: &str) -> &mut Self {
+        self.session.system(prompt);
+        self
+    }
+
+    /// Sets the callback receiving the text streamed while this participant speaks.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function called with each chunk of generated text.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn on_chunk<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        self.on_chunk = Some(Box::new(callback));
+        self
+    }
+}
+
+// ===
+// STRUCT: Dialogue
+// ===
+
+/// A conversation between several model-backed participants.
+///
+/// Every participant keeps its own session. Before a participant speaks, the
+/// messages it has not yet seen from the shared transcript are added to its
+/// session as user messages. With more than two participants each message is
+/// prefixed with its speaker's name so the models can tell them apart.
+pub struct Dialogue {
+    participants: Vec<DialogueParticipant>,
+    transcript: Vec<DialogueTurn>,
+    policy: TurnPolicy,
+    next: usize,
+    max_turns: usize,
+    stop_condition: Option<DialogueStopCondition>,
+}
+
+impl Dialogue {
+    /// The default maximum number of turns per run.
+    pub const DEFAULT_MAX_TURNS: usize = 10;
+
+    /// Creates a new dialogue with no participants and round-robin turns.
+    pub fn new() -> Self {
+        Self {
+            participants: Vec::new(),
+            transcript: Vec::new(),
+            policy: TurnPolicy::RoundRobin,
+            next: 0,
+            max_turns: Self::DEFAULT_MAX_TURNS,
+            stop_condition: None,
+        }
+    }
+
+    /// Adds a participant to the dialogue.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name identifying the participant in the transcript.
+    /// * `session` - The session the participant speaks through.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the new participant for further configuration.
+    pub fn add_participant(
+        &mut self,
+        name: &str,
+        session: OllamaSession,
+    ) -> &mut DialogueParticipant {
+        self.participants.push(DialogueParticipant {
+            name: name.to_string(),
+            session,
+            on_chunk: None,
+            seen: 0,
+        });
+        self.participants.last_mut().unwrap()
+    }
+
+    /// Returns the participants of the dialogue.
+    pub fn participants(&self) -> &[DialogueParticipant] {
+        &self.participants
+    }
+
+    /// Returns the participant with the given name, if any.
+    pub fn participant_mut(&mut self, name: &str) -> Option<&mut DialogueParticipant> {
+        self.participants.iter_mut().find(|p| p.name == name)
+    }
+
+    /// Returns the shared transcript of the dialogue.
+    pub fn transcript(&self) -> &[DialogueTurn] {
+        &self.transcript
+    }
+
+    /// Sets the policy deciding who speaks next.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The turn-order policy.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_turn_policy(&mut self, policy: TurnPolicy) -> &mut Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets the maximum number of turns taken by [`run`](Self::run).
+    ///
+    /// # Arguments
+    ///
+    /// * `max_turns` - The turn limit.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_max_turns(&mut self, max_turns: usize) -> &mut Self {
+        self.max_turns = max_turns;
+        self
+    }
+
+    /// Sets a predicate checked after every turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `condition` - Returns `true` to end the dialogue after the given turn.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_stop_condition<F>(&mut self, condition: F) -> &mut Self
+    where
+        F: FnMut(&DialogueTurn) -> bool + Send + 'static,
+    {
+        self.stop_condition = Some(Box::new(condition));
+        self
+    }
+
+    /// Adds a message to the transcript without asking a model.
+    ///
+    /// The message is delivered to every participant other than `speaker`
+    /// the next time they speak.
+    ///
+    /// # Arguments
+    ///
+    /// * `speaker` - The name of the speaker; may be a participant or an outside party.
+    /// * `text` - The message text.
+    pub fn say(&mut self, speaker: &str, text: &str) {
+        self.transcript.push(DialogueTurn {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            tokens_used: 0,
+        });
+    }
+
+    /// Lets the next participant, as chosen by the turn policy, speak once.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&DialogueTurn)` - The turn that was added to the transcript.
+    /// * `Err(Box<dyn Error>)` - If there are no participants or the model request failed.
+    ///   A failed step leaves the participant's session as it was, so it can be retried.
+    pub async fn step(&mut self) -> Result<&DialogueTurn, Box<dyn Error>> {
+        if self.participants.is_empty() {
+            return Err("dialogue has no participants".into());
+        }
+
+        let index = self.next_speaker();
+        let labelled = self.participants.len() > 2;
+        let participant = &mut self.participants[index];
+
+        // Deliver everything said by others since this participant last spoke. If the
+        // request fails, the turns are taken back so a retry delivers them once.
+        let checkpoint = participant.session.checkpoint();
+        for turn in &self.transcript[participant.seen..] {
+            if turn.speaker == participant.name {
+                continue;
+            }
+            if labelled {
+                participant
+                    .session
+                    .user(&format!("{}: {}", turn.speaker, turn.text));
+            } else {
+                participant.session.user(&turn.text);
+            }
+        }
+
+        let mut text = String::new();
+        let on_chunk = &mut participant.on_chunk;
+        let result = participant
+            .session
+            .update(|chunk| {
+                text.push_str(chunk);
+                if let Some(callback) = on_chunk {
+                    callback(chunk);
+                }
+            })
+            .await;
+        let response = match result {
+            Ok(response) => response,
+            Err(error) => {
+                participant.session.restore(checkpoint);
+                return Err(error);
+            }
+        };
+
+        self.transcript.push(DialogueTurn {
+            speaker: participant.name.clone(),
+            text,
+            tokens_used: response.tokens_used(),
+        });
+        participant.seen = self.transcript.len();
+        self.next = (index + 1) % self.participants.len();
+
+        Ok(self.transcript.last().unwrap())
+    }
+
+    /// Runs the dialogue until the turn limit or stop condition is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `opening` - An optional message from an outside "user" that starts the dialogue.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DialogueStopReason)` - Why the dialogue ended; see [`transcript`](Self::transcript).
+    /// * `Err(Box<dyn Error>)` - Any error that occurred while talking to a model.
+    pub async fn run(
+        &mut self,
+        opening: Option<&str>,
+    ) -> Result<DialogueStopReason, Box<dyn Error>> {
+        if let Some(opening) = opening {
+            self.say("user", opening);
+        }
+
+        for _ in 0..self.max_turns {
+            self.step().await?;
+
+            let turn = self.transcript.last().unwrap();
+            if let Some(stop) = self.stop_condition.as_mut()
+                && stop(turn)
+            {
+                return Ok(DialogueStopReason::StopCondition);
+            }
+        }
+
+        Ok(DialogueStopReason::MaxTurns)
+    }
+
+    fn next_speaker(&mut self) -> usize {
+        let count = self.participants.len();
+        match &mut self.policy {
+            TurnPolicy::RoundRobin => self.next % count,
+            TurnPolicy::Random => {
+                let last = self.transcript.last().and_then(|turn| {
+                    self.participants
+                        .iter()
+                        .position(|p| p.name == turn.speaker)
+                });
+                match last {
+                    Some(last) if count > 1 => {
+                        let pick = rand::rng().random_range(0..count - 1);
+                        if pick >= last { pick + 1 } else { pick }
+                    }
+                    _ => rand::rng().random_range(0..count),
+                }
+            }
+            TurnPolicy::Custom(select) => select(&self.transcript, count).min(count - 1),
+        }
+    }
+}
+
+// ===
+// TRAIT: Default for Dialogue
+// ===
+
+impl Default for Dialogue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ===
+// TESTS: Dialogue
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body, error_body};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_round_robin_shares_transcript() {
+        let bob =
+            MockServer::start(vec![chat_body(&["Hi, ", "Fred."]), chat_body(&["Bye."])]).await;
+        let fred = MockServer::start(vec![chat_body(&["Hello, Bob."])]).await;
+
+        let streamed = Arc::new(Mutex::new(String::new()));
+        let sink = streamed.clone();
+
+        let mut dialogue = Dialogue::new();
+        dialogue
+            .add_participant("bob", OllamaSession::remote("mock", &bob.addr()))
+            .set_system_prompt("You are Bob.")
+            .on_chunk(move |chunk| sink.lock().unwrap().push_str(chunk));
+        dialogue.add_participant("fred", OllamaSession::remote("mock", &fred.addr()));
+        dialogue.set_max_turns(3);
+
+        let reason = dialogue.run(Some("Introduce yourselves.")).await.unwrap();
+        assert_eq!(reason, DialogueStopReason::MaxTurns);
+
+        let speakers: Vec<_> = dialogue
+            .transcript()
+            .iter()
+            .map(|t| t.speaker.as_str())
+            .collect();
+        assert_eq!(speakers, ["user", "bob", "fred", "bob"]);
+        assert_eq!(dialogue.transcript()[1].text, "Hi, Fred.");
+        assert_eq!(dialogue.transcript()[1].tokens_used, 30);
+        assert_eq!(*streamed.lock().unwrap(), "Hi, Fred.Bye.");
+
+        // Fred hears both the opening and Bob's reply; Bob hears Fred's reply next.
+        let fred_messages = fred.requests()[0]["messages"].clone();
+        assert_eq!(fred_messages[0]["content"], "Introduce yourselves.");
+        assert_eq!(fred_messages[1]["content"], "Hi, Fred.");
+
+        let bob_messages = bob.requests()[1]["messages"].clone();
+        assert_eq!(bob_messages[0]["role"], "system");
+        assert_eq!(bob_messages[2]["role"], "assistant");
+        assert_eq!(bob_messages[3]["content"], "Hello, Bob.");
+    }
+
+    #[tokio::test]
+    async fn test_custom_policy_and_stop_condition() {
+        let a = MockServer::start(vec![]).await;
+        let b = MockServer::start(vec![]).await;
+        let c = MockServer::start(vec![chat_body(&["Done."])]).await;
+
+        let mut dialogue = Dialogue::new();
+        dialogue.add_participant("a", OllamaSession::remote("mock", &a.addr()));
+        dialogue.add_participant("b", OllamaSession::remote("mock", &b.addr()));
+        dialogue.add_participant("c", OllamaSession::remote("mock", &c.addr()));
+        dialogue
+            .set_turn_policy(TurnPolicy::Custom(Box::new(|_, _| 2)))
+            .set_stop_condition(|turn| turn.text.contains("Done"));
+
+        dialogue.say("a", "Over to you.");
+        let reason = dialogue.run(None).await.unwrap();
+        assert_eq!(reason, DialogueStopReason::StopCondition);
+        assert_eq!(dialogue.transcript().len(), 2);
+
+        // With three participants messages are labelled with their speaker.
+        assert_eq!(c.requests()[0]["messages"][0]["content"], "a: Over to you.");
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_can_be_retried() {
+        let bob =
+            MockServer::start(vec![error_body(502, "Bad Gateway"), chat_body(&["Hello."])]).await;
+
+        let mut dialogue = Dialogue::new();
+        dialogue.add_participant("bob", OllamaSession::remote("mock", &bob.addr()));
+        dialogue.say("user", "Hi, Bob.");
+
+        assert!(dialogue.step().await.is_err());
+        assert_eq!(dialogue.transcript().len(), 1);
+        dialogue.step().await.unwrap();
+
+        let requests = bob.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1]["messages"], requests[0]["messages"]);
+        assert_eq!(
+            requests[1]["messages"],
+            json!([{ "role": "user", "content": "Hi, Bob." }])
+        );
+        assert_eq!(dialogue.participants[0].session.messages().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_step_without_participants_fails() {
+        let mut dialogue = Dialogue::new();
+        assert!(dialogue.step().await.is_err());
+    }
+}
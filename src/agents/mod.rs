@@ -0,0 +1,9 @@
+#[doc(hidden)]
+pub mod agent;
+#[doc(inline)]
+pub use agent::*;
+
+#[doc(hidden)]
+pub mod dialogue;
+#[doc(inline)]
+pub use dialogue::*;
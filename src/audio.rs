@@ -0,0 +1,147 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::io;
+use std::path::Path;
+
+// ===
+// STRUCT: AudioClip
+// ===
+
+/// Audio data together with its mime type, ready to attach to a prompt.
+///
+/// Attach a clip to a Gemini request with `GeminiContent::add_audio`, or to an
+/// Ollama message with `OllamaMessage::add_audio` for models that accept audio.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioClip {
+    mime_type: String,
+    data: Vec<u8>,
+}
+
+impl AudioClip {
+    /// Creates a clip from raw bytes in the given format.
+    ///
+    /// # Arguments
+    /// * `mime_type` - The audio format, e.g. "audio/wav"
+    /// * `data` - The encoded audio bytes
+    ///
+    /// # Returns
+    /// * A new AudioClip instance
+    pub fn new(mime_type: &str, data: Vec<u8>) -> Self {
+        Self {
+            mime_type: mime_type.to_string(),
+            data,
+        }
+    }
+
+    /// Loads an audio file, detecting its format from the file contents or extension.
+    ///
+    /// WAV, MP3, FLAC, OGG, AAC and AIFF files are recognized.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the audio file
+    ///
+    /// # Returns
+    /// * `Ok(AudioClip)` with the file contents
+    /// * `Err(io::Error)` if the file cannot be read or its format is not recognized
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let mime_type = Self::sniff_mime_type(&data)
+            .or_else(|| Self::mime_type_for_extension(path))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unrecognized audio format: {}", path.display()),
+                )
+            })?;
+
+        Ok(Self::new(mime_type, data))
+    }
+
+    /// Returns the audio format, e.g. "audio/mp3".
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// Returns the encoded audio bytes.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the audio bytes encoded as base64, as the provider APIs expect them.
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(&self.data)
+    }
+
+    /// Detects the audio format from the leading bytes of the data.
+    fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+        let magic =
+            |offset: usize, bytes: &[u8]| data.get(offset..offset + bytes.len()) == Some(bytes);
+
+        if magic(0, b"RIFF") && magic(8, b"WAVE") {
+            Some("audio/wav")
+        } else if magic(0, b"ID3") {
+            Some("audio/mp3")
+        } else if let [0xFF, second, ..] = data
+            && second & 0xE0 == 0xE0
+            && second & 0x06 != 0
+        {
+            // An MPEG audio frame sync, as found at the start of MP3 files without ID3 tags.
+            Some("audio/mp3")
+        } else if magic(0, b"fLaC") {
+            Some("audio/flac")
+        } else if magic(0, b"OggS") {
+            Some("audio/ogg")
+        } else if magic(0, b"FORM") && magic(8, b"AIF") {
+            Some("audio/aiff")
+        } else {
+            None
+        }
+    }
+
+    /// Maps a file extension to an audio format.
+    fn mime_type_for_extension(path: &Path) -> Option<&'static str> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "wav" => Some("audio/wav"),
+            "mp3" => Some("audio/mp3"),
+            "flac" => Some("audio/flac"),
+            "ogg" | "oga" => Some("audio/ogg"),
+            "aac" => Some("audio/aac"),
+            "aif" | "aiff" => Some("audio/aiff"),
+            _ => None,
+        }
+    }
+}
+
+// ===
+// TESTS: AudioClip
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_file_detects_format() {
+        let dir = std::env::temp_dir();
+
+        let wav = dir.join("ollie_audio_test.bin");
+        std::fs::write(&wav, b"RIFF\x24\0\0\0WAVEfmt ").unwrap();
+        assert_eq!(AudioClip::from_file(&wav).unwrap().mime_type(), "audio/wav");
+
+        let mp3 = dir.join("ollie_audio_test.mp3");
+        std::fs::write(&mp3, b"\0\0\0\0").unwrap();
+        let clip = AudioClip::from_file(&mp3).unwrap();
+        assert_eq!(clip.mime_type(), "audio/mp3");
+        assert_eq!(clip.to_base64(), "AAAAAA==");
+
+        let text = dir.join("ollie_audio_test.txt");
+        std::fs::write(&text, b"hello").unwrap();
+        assert!(AudioClip::from_file(&text).is_err());
+
+        for path in [wav, mp3, text] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,488 @@
+//! Structured audit logging of every exchange with a model server.
+//!
+//! Enabled with the `audit` feature. Attach an [`AuditLog`] to an `Ollama` or
+//! `Gemini` client and each request is written as one JSON line holding the model,
+//! a record id, the request and response, durations and token counts. Redactors
+//! run on every record before it is written, so prompts and outputs containing
+//! sensitive data can be masked or dropped.
+
+use crate::{OllamaRequest, OllamaResponse};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The fields holding prompt or generated text, visited by `AuditRecord::redact_text`.
+const TEXT_FIELDS: &[&str] = &[
+    "content", "prompt", "response", "system", "text", "thinking",
+];
+
+/// A function that edits a record before it is written.
+pub type AuditRedactor = Arc<dyn Fn(&mut AuditRecord) + Send + Sync>;
+
+// ===
+// STRUCT: AuditRecord
+// ===
+
+/// One exchange with a model server, as written to the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditRecord {
+    /// A random id unique to this record.
+    pub id: String,
+    /// When the request was sent, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// The provider, "ollama" or "gemini".
+    pub provider: String,
+    /// The requested model, if known.
+    pub model: Option<String>,
+    /// The endpoint called, e.g. "chat" or "generateContent".
+    pub endpoint: String,
+    /// The request body.
+    pub request: JsonValue,
+    /// The response body, if one was received.
+    pub response: Option<JsonValue>,
+    /// The error message, if the request failed.
+    pub error: Option<String>,
+    /// The wall time of the exchange in milliseconds.
+    pub duration_ms: u64,
+    /// The processing time reported by the server in milliseconds, if any.
+    pub server_duration_ms: Option<u64>,
+    /// The number of prompt tokens, if reported.
+    pub prompt_tokens: Option<u32>,
+    /// The number of generated tokens, if reported.
+    pub completion_tokens: Option<u32>,
+    /// The application metadata of the request, e.g. a correlation id.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl AuditRecord {
+    /// Creates a record of a request, with no response yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider, e.g. "ollama".
+    /// * `endpoint` - The endpoint called.
+    /// * `request` - The request body.
+    /// * `duration` - The wall time of the exchange.
+    ///
+    /// # Returns
+    ///
+    /// A new `AuditRecord` with a fresh id and the current time.
+    pub fn new(provider: &str, endpoint: &str, request: JsonValue, duration: Duration) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let model = request
+            .get("model")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string);
+
+        Self {
+            id: format!("{:016x}", rand::random::<u64>()),
+            timestamp_ms: now.saturating_sub(duration).as_millis() as u64,
+            provider: provider.to_string(),
+            model,
+            endpoint: endpoint.to_string(),
+            request,
+            response: None,
+            error: None,
+            duration_ms: duration.as_millis() as u64,
+            server_duration_ms: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a record of an exchange with an Ollama server.
+    pub(crate) fn ollama(
+        url: &str,
+        request: &OllamaRequest,
+        result: &Result<OllamaResponse, Box<dyn Error>>,
+        duration: Duration,
+    ) -> Self {
+        let endpoint = url.rsplit('/').next().unwrap_or_default();
+        let request_json = serde_json::to_value(request).unwrap_or_default();
+        let mut record = Self::new("ollama", endpoint, request_json, duration);
+        record.metadata = request.metadata().clone();
+
+        match result {
+            Ok(response) => {
+                record.response = Some(response.clone().to_json());
+                record.error = response.error().map(str::to_string);
+                record.server_duration_ms = response
+                    .total_duration()
+                    .map(|duration| duration.as_millis() as u64);
+                record.prompt_tokens = response.prompt_eval_count().copied();
+                record.completion_tokens = response.eval_count().copied();
+            }
+            Err(error) => record.error = Some(error.to_string()),
+        }
+
+        record
+    }
+
+    /// Creates a record of an exchange with the Gemini API.
+    pub(crate) fn gemini(
+        model: &str,
+        request: &JsonValue,
+        result: &Result<JsonValue, Box<dyn Error>>,
+        duration: Duration,
+    ) -> Self {
+        let mut record = Self::new("gemini", "generateContent", request.clone(), duration);
+        record.model = Some(model.to_string());
+
+        match result {
+            Ok(response) => {
+                let usage = response.get("usageMetadata");
+                let count = |field: &str| {
+                    usage
+                        .and_then(|usage| usage.get(field))
+                        .and_then(JsonValue::as_u64)
+                        .map(|count| count as u32)
+                };
+                record.prompt_tokens = count("promptTokenCount");
+                record.completion_tokens = count("candidatesTokenCount");
+                record.error = response
+                    .get("error")
+                    .and_then(|error| error.get("message"))
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string);
+                record.response = Some(response.clone());
+            }
+            Err(error) => record.error = Some(error.to_string()),
+        }
+
+        record
+    }
+
+    /// Rewrites every prompt and response text in the record.
+    ///
+    /// The text fields ("content", "prompt", "response", "system", "text" and
+    /// "thinking") are visited at any depth of the request and response.
+    ///
+    /// # Arguments
+    ///
+    /// * `redact` - Maps each text to its redacted form.
+    pub fn redact_text(&mut self, redact: impl Fn(&str) -> String) {
+        redact_json(&mut self.request, &redact);
+        if let Some(response) = &mut self.response {
+            redact_json(response, &redact);
+        }
+    }
+}
+
+/// Applies `redact` to the string values of text fields in `json`.
+fn redact_json(json: &mut JsonValue, redact: &impl Fn(&str) -> String) {
+    match json {
+        JsonValue::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    JsonValue::String(text) if TEXT_FIELDS.contains(&key.as_str()) => {
+                        *text = redact(text);
+                    }
+                    _ => redact_json(value, redact),
+                }
+            }
+        }
+        JsonValue::Array(values) => values.iter_mut().for_each(|v| redact_json(v, redact)),
+        _ => {}
+    }
+}
+
+// ===
+// STRUCT: AuditLog
+// ===
+
+/// Writes audit records as JSON lines to a shared writer.
+///
+/// Cloning the log shares the writer, so one log can be attached to several clients.
+#[derive(Clone)]
+pub struct AuditLog {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    redactors: Vec<AuditRedactor>,
+}
+
+impl AuditLog {
+    /// Creates a log that writes to the given writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the JSON lines, e.g. a file or `io::stderr()`.
+    ///
+    /// # Returns
+    ///
+    /// A new `AuditLog` with no redactors.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+            redactors: Vec::new(),
+        }
+    }
+
+    /// Creates a log that appends to a file, creating it if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the log file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(AuditLog)` - The log.
+    /// * `Err(io::Error)` - If the file could not be opened.
+    pub fn to_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(file))
+    }
+
+    /// Adds a redactor, run on every record before it is written.
+    ///
+    /// Redactors run in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `redactor` - Edits the record, e.g. with `AuditRecord::redact_text`.
+    pub fn add_redactor(
+        &mut self,
+        redactor: impl Fn(&mut AuditRecord) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.redactors.push(Arc::new(redactor));
+        self
+    }
+
+    /// Redacts a record and writes it as one JSON line.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record to write.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the record was written.
+    /// * `Err(io::Error)` - If serializing or writing failed.
+    pub fn record(&self, mut record: AuditRecord) -> io::Result<()> {
+        for redactor in &self.redactors {
+            redactor(&mut record);
+        }
+
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.write_all(&line)?;
+        writer.flush()
+    }
+}
+
+// ===
+// TESTS: AuditLog
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body};
+    use crate::{Gemini, GeminiRequest, Ollama};
+    use serde_json::json;
+
+    /// A writer whose output can be read back by the test.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn records(&self) -> Vec<JsonValue> {
+            let bytes = self.0.lock().unwrap();
+            String::from_utf8_lossy(&bytes)
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ollama_exchange_is_logged_and_redacted() {
+        let server = MockServer::start(vec![chat_body(&["My SSN is ", "123-45-6789"])]).await;
+        let buffer = SharedBuffer::default();
+        let mut audit_log = AuditLog::new(buffer.clone());
+        audit_log.add_redactor(|record| {
+            record.redact_text(|text| text.replace("123-45-6789", "[redacted]"));
+        });
+
+        let mut ollama = Ollama::new(&server.addr());
+        ollama.set_audit_log(audit_log);
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .set_metadata("request_id", "req-7")
+            .add_message(json!({"role": "user", "content": "Mine is 123-45-6789"}));
+        let response = ollama.chat(&request, |_| {}).await.unwrap();
+        assert_eq!(response.text(), Some("My SSN is 123-45-6789"));
+        assert_eq!(response.metadata()["request_id"], "req-7");
+        assert!(server.requests()[0].get("metadata").is_none());
+
+        let records = buffer.records();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record["provider"], "ollama");
+        assert_eq!(record["endpoint"], "chat");
+        assert_eq!(record["model"], "mock");
+        assert_eq!(record["prompt_tokens"], 20);
+        assert_eq!(record["completion_tokens"], 10);
+        assert_eq!(record["id"].as_str().unwrap().len(), 16);
+        assert_eq!(record["metadata"], json!({ "request_id": "req-7" }));
+        assert_eq!(
+            record["request"]["messages"][0]["content"],
+            "Mine is [redacted]"
+        );
+        assert_eq!(
+            record["response"]["message"]["content"],
+            "My SSN is [redacted]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streamed_exchanges_are_logged_when_they_end() {
+        let server = MockServer::start(vec![
+            chat_body(&["Hello", " there"]),
+            chat_body(&["Cut", " short"]),
+        ])
+        .await;
+        let buffer = SharedBuffer::default();
+        let mut ollama = Ollama::new(&server.addr());
+        ollama.set_audit_log(AuditLog::new(buffer.clone()));
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .add_message(json!({"role": "user", "content": "Hi"}));
+
+        let mut stream = ollama.chat_stream(&request).await.unwrap();
+        while stream.read().await.unwrap().is_some() {}
+        assert_eq!(buffer.records().len(), 1);
+        drop(stream);
+
+        let mut stream = ollama.chat_stream(&request).await.unwrap();
+        stream.read().await.unwrap();
+        drop(stream);
+
+        let records = buffer.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["endpoint"], "chat");
+        assert_eq!(records[0]["response"]["message"]["content"], "Hello there");
+        assert_eq!(records[0]["completion_tokens"], 10);
+        assert!(records[0]["error"].is_null());
+        assert_eq!(records[1]["response"]["message"]["content"], "Cut");
+        assert_eq!(
+            records[1]["error"],
+            "the stream was dropped before it completed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gemini_stream_is_logged() {
+        let event = json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [{ "text": "Hi!" }] },
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": { "promptTokenCount": 2, "candidatesTokenCount": 1 }
+        });
+        let server = MockServer::start(vec![format!("data: {event}\n\n")]).await;
+        let buffer = SharedBuffer::default();
+        let mut gemini = Gemini::builder("dummy_api_key")
+            .model("mock")
+            .base_url(&format!("http://{}", server.addr()))
+            .build()
+            .unwrap();
+        gemini.set_audit_log(AuditLog::new(buffer.clone()));
+
+        let request = GeminiRequest::from_str("Hello.");
+        let mut stream = gemini.generate_stream(&request).await.unwrap();
+        while stream.read().await.is_some() {}
+
+        let records = buffer.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["endpoint"], "streamGenerateContent");
+        assert_eq!(records[0]["model"], "mock");
+        assert_eq!(records[0]["completion_tokens"], 1);
+        assert_eq!(
+            records[0]["response"]["candidates"][0]["content"]["parts"][0]["text"],
+            "Hi!"
+        );
+        assert!(records[0]["error"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_dry_runs_are_not_logged() {
+        let buffer = SharedBuffer::default();
+        let mut ollama = Ollama::new("127.0.0.1:9");
+        ollama
+            .set_dry_run(true)
+            .set_audit_log(AuditLog::new(buffer.clone()));
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .add_message(json!({"role": "user", "content": "Hi"}));
+        ollama.chat(&request, |_| {}).await.unwrap();
+        let mut stream = ollama.chat_stream(&request).await.unwrap();
+        while stream.read().await.unwrap().is_some() {}
+        drop(stream);
+
+        let mut gemini = Gemini::builder("dummy_api_key")
+            .model("mock")
+            .base_url("http://127.0.0.1:9")
+            .dry_run(true)
+            .build()
+            .unwrap();
+        gemini.set_audit_log(AuditLog::new(buffer.clone()));
+        let request = GeminiRequest::from_str("Hello.");
+        gemini.generate(&request).await.unwrap();
+        let mut stream = gemini.generate_stream(&request).await.unwrap();
+        while stream.read().await.is_some() {}
+        drop(stream);
+
+        assert!(buffer.records().is_empty());
+    }
+
+    #[test]
+    fn test_gemini_record_usage_and_error() {
+        let request = json!({ "contents": [{ "parts": [{ "text": "Hi" }] }] });
+        let response = json!({
+            "candidates": [],
+            "usageMetadata": { "promptTokenCount": 3, "candidatesTokenCount": 7 }
+        });
+
+        let record = AuditRecord::gemini(
+            "gemini-2.0-flash",
+            &request,
+            &Ok(response),
+            Duration::from_millis(5),
+        );
+        assert_eq!(record.model.as_deref(), Some("gemini-2.0-flash"));
+        assert_eq!(record.prompt_tokens, Some(3));
+        assert_eq!(record.completion_tokens, Some(7));
+        assert_eq!(record.duration_ms, 5);
+
+        let failed = AuditRecord::gemini("m", &request, &Err("offline".into()), Duration::ZERO);
+        assert_eq!(failed.error.as_deref(), Some("offline"));
+        assert_eq!(failed.response, None);
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{Ollama, OllamaOptions, OllamaRequest};
+use std::error::Error;
+use std::time::Duration;
+
+/// The prompt used when none is set.
+const DEFAULT_PROMPT: &str = "Write a short paragraph about the sea.";
+
+// ===
+// STRUCT: Benchmark
+// ===
+
+/// Measures generation throughput and latency of a model on an Ollama server.
+///
+/// Each iteration sends the same streamed generate request and records the time
+/// to the first token, the total wall time, and the token counts and durations
+/// reported by the server, so models and option sets can be compared from code.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::Benchmark;
+///
+/// let mut bench = Benchmark::new("127.0.0.1:11434", "gemma3:1b");
+/// bench.set_iterations(5);
+/// let report = bench.run().await?;
+/// println!("{report}");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Benchmark {
+    ollama: Ollama,
+    model: String,
+    prompt: String,
+    options: OllamaOptions,
+    iterations: u32,
+    warmup: u32,
+}
+
+impl Benchmark {
+    /// Creates a benchmark of a model on the given server.
+    ///
+    /// The benchmark runs 3 measured iterations after 1 warmup iteration,
+    /// which absorbs the time to load the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_addr` - The server address, e.g. "127.0.0.1:11434".
+    /// * `model` - The model to measure.
+    ///
+    /// # Returns
+    ///
+    /// A new `Benchmark`.
+    pub fn new(server_addr: &str, model: &str) -> Self {
+        Self {
+            ollama: Ollama::new(server_addr),
+            model: model.to_string(),
+            prompt: DEFAULT_PROMPT.to_string(),
+            options: OllamaOptions::new(),
+            iterations: 3,
+            warmup: 1,
+        }
+    }
+
+    /// Sets the prompt sent on every iteration.
+    pub fn set_prompt(&mut self, prompt: &str) -> &mut Self {
+        self.prompt = prompt.to_string();
+        self
+    }
+
+    /// Sets the options sent on every iteration, e.g. a preset's options.
+    pub fn set_options(&mut self, options: OllamaOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the number of measured iterations.
+    pub fn set_iterations(&mut self, iterations: u32) -> &mut Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the number of unmeasured iterations run first.
+    pub fn set_warmup(&mut self, warmup: u32) -> &mut Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Runs the benchmark.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BenchmarkReport)` - The measurements of every measured iteration.
+    /// * `Err(Box<dyn Error>)` - If a request fails.
+    pub async fn run(&self) -> Result<BenchmarkReport, Box<dyn Error>> {
+        for _ in 0..self.warmup {
+            self.run_once().await?;
+        }
+
+        let mut runs = Vec::with_capacity(self.iterations as usize);
+        for _ in 0..self.iterations {
+            runs.push(self.run_once().await?);
+        }
+
+        Ok(BenchmarkReport {
+            model: self.model.clone(),
+            runs,
+        })
+    }
+
+    /// Sends one request and measures it.
+    async fn run_once(&self) -> Result<BenchmarkRun, Box<dyn Error>> {
+        let mut request = OllamaRequest::new();
+        request
+            .set_model(&self.model)
+            .set_prompt(&self.prompt)
+            .set_options(self.options.to_json()?)
+            .set_stream(true);
+
+        let response = self.ollama.generate(&request, |_| {}).await?;
+        if let Some(error) = response.error() {
+            return Err(error.into());
+        }
+
+        let metrics = response.metrics().cloned().unwrap_or_default();
+        let total_time = metrics.total_time();
+
+        Ok(BenchmarkRun {
+            time_to_first_token: metrics.time_to_first_token().unwrap_or(total_time),
+            total_time,
+            eval_count: response.eval_count().copied().unwrap_or(0),
+            prompt_eval_count: response.prompt_eval_count().copied().unwrap_or(0),
+            eval_duration: response.eval_duration(),
+            server_duration: response.total_duration(),
+        })
+    }
+}
+
+// ===
+// STRUCT: BenchmarkRun
+// ===
+
+/// The measurements of one benchmark iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkRun {
+    /// The wall time until the first non-empty text chunk arrived.
+    pub time_to_first_token: Duration,
+    /// The wall time until the stream ended.
+    pub total_time: Duration,
+    /// The number of generated tokens reported by the server.
+    pub eval_count: u32,
+    /// The number of prompt tokens reported by the server.
+    pub prompt_eval_count: u32,
+    /// The time the server spent generating, if reported.
+    pub eval_duration: Option<Duration>,
+    /// The total time the server spent on the request, if reported.
+    pub server_duration: Option<Duration>,
+}
+
+impl BenchmarkRun {
+    /// Returns the end-to-end generation rate: generated tokens per second of wall time.
+    pub fn tokens_per_second(&self) -> f64 {
+        rate(self.eval_count, self.total_time)
+    }
+
+    /// Returns the generation rate reported by the server, excluding network and queueing.
+    pub fn server_tokens_per_second(&self) -> Option<f64> {
+        self.eval_duration
+            .map(|duration| rate(self.eval_count, duration))
+    }
+
+    /// Returns the wall time not accounted for by the server, e.g. network and client overhead.
+    pub fn overhead(&self) -> Option<Duration> {
+        self.server_duration
+            .map(|server| self.total_time.saturating_sub(server))
+    }
+}
+
+// ===
+// STRUCT: BenchmarkReport
+// ===
+
+/// The results of a benchmark.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    /// The benchmarked model.
+    pub model: String,
+    /// The measured iterations, in order.
+    pub runs: Vec<BenchmarkRun>,
+}
+
+impl BenchmarkReport {
+    /// Returns the mean end-to-end tokens per second.
+    pub fn mean_tokens_per_second(&self) -> f64 {
+        mean(self.runs.iter().map(BenchmarkRun::tokens_per_second))
+    }
+
+    /// Returns the mean tokens per second reported by the server.
+    pub fn mean_server_tokens_per_second(&self) -> Option<f64> {
+        let rates: Vec<f64> = self
+            .runs
+            .iter()
+            .filter_map(BenchmarkRun::server_tokens_per_second)
+            .collect();
+        (!rates.is_empty()).then(|| mean(rates.into_iter()))
+    }
+
+    /// Returns the median time to first token.
+    pub fn median_time_to_first_token(&self) -> Duration {
+        median(self.runs.iter().map(|run| run.time_to_first_token))
+    }
+
+    /// Returns the median total wall time.
+    pub fn median_total_time(&self) -> Duration {
+        median(self.runs.iter().map(|run| run.total_time))
+    }
+
+    /// Returns the median request overhead, if the server reported its durations.
+    pub fn median_overhead(&self) -> Option<Duration> {
+        let overheads: Vec<Duration> = self
+            .runs
+            .iter()
+            .filter_map(BenchmarkRun::overhead)
+            .collect();
+        (!overheads.is_empty()).then(|| median(overheads.into_iter()))
+    }
+}
+
+// ===
+// TRAIT: Display for BenchmarkReport
+// ===
+
+impl std::fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "model: {} ({} runs)", self.model, self.runs.len())?;
+        writeln!(f, "tokens/sec: {:.1}", self.mean_tokens_per_second())?;
+        if let Some(rate) = self.mean_server_tokens_per_second() {
+            writeln!(f, "server tokens/sec: {rate:.1}")?;
+        }
+        writeln!(
+            f,
+            "time to first token: {:?}",
+            self.median_time_to_first_token()
+        )?;
+        write!(f, "total time: {:?}", self.median_total_time())?;
+        if let Some(overhead) = self.median_overhead() {
+            write!(f, "\nrequest overhead: {overhead:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `count` per second of `duration`, or 0 for an empty duration.
+fn rate(count: u32, duration: Duration) -> f64 {
+    let seconds = duration.as_secs_f64();
+    if seconds > 0.0 {
+        f64::from(count) / seconds
+    } else {
+        0.0
+    }
+}
+
+/// Returns the mean of the values, or 0 if there are none.
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count > 0 { sum / count as f64 } else { 0.0 }
+}
+
+/// Returns the median of the durations, or zero if there are none.
+fn median(values: impl Iterator<Item = Duration>) -> Duration {
+    let mut values: Vec<Duration> = values.collect();
+    values.sort_unstable();
+    values.get(values.len() / 2).copied().unwrap_or_default()
+}
+
+// ===
+// TESTS: Benchmark
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, ndjson};
+    use serde_json::json;
+
+    fn generate_body() -> String {
+        ndjson(&[
+            json!({ "model": "mock", "response": "Waves", "done": false }),
+            json!({ "model": "mock", "response": " roll.", "done": false }),
+            json!({
+                "model": "mock",
+                "response": "",
+                "done": true,
+                "eval_count": 50,
+                "prompt_eval_count": 8,
+                "eval_duration": 500_000_000u64,
+                "total_duration": 1_000u64
+            }),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_run_measures_each_iteration() {
+        let server = MockServer::start(vec![generate_body(); 3]).await;
+
+        let mut bench = Benchmark::new(&server.addr(), "mock");
+        bench.set_iterations(2).set_prompt("Describe the sea.");
+        let report = bench.run().await.unwrap();
+
+        assert_eq!(server.requests().len(), 3);
+        assert_eq!(server.requests()[0]["prompt"], "Describe the sea.");
+        assert_eq!(report.runs.len(), 2);
+
+        let run = &report.runs[0];
+        assert_eq!(run.eval_count, 50);
+        assert_eq!(run.prompt_eval_count, 8);
+        assert!(run.time_to_first_token <= run.total_time);
+        assert_eq!(run.server_tokens_per_second(), Some(100.0));
+        assert!(run.overhead().unwrap() > Duration::ZERO);
+        assert_eq!(report.mean_server_tokens_per_second(), Some(100.0));
+        assert!(report.to_string().starts_with("model: mock (2 runs)\n"));
+    }
+
+    #[test]
+    fn test_statistics() {
+        let run = |ttft_ms, total_ms| BenchmarkRun {
+            time_to_first_token: Duration::from_millis(ttft_ms),
+            total_time: Duration::from_millis(total_ms),
+            eval_count: 100,
+            prompt_eval_count: 0,
+            eval_duration: None,
+            server_duration: None,
+        };
+        let report = BenchmarkReport {
+            model: "m".to_string(),
+            runs: vec![run(30, 1000), run(10, 2000), run(20, 4000)],
+        };
+
+        assert_eq!(
+            report.median_time_to_first_token(),
+            Duration::from_millis(20)
+        );
+        assert_eq!(report.median_total_time(), Duration::from_millis(2000));
+        assert!((report.mean_tokens_per_second() - 175.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.mean_server_tokens_per_second(), None);
+        assert_eq!(report.median_overhead(), None);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::extract_json;
+use serde_json::{Value as JsonValue, json};
+
+// ===
+// STRUCT: Classification
+// ===
+
+/// The label a model picked for a text, from a fixed set of labels.
+///
+/// Returned by `OllamaSession::classify`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    /// The chosen label, exactly as it appears in the label set.
+    pub label: String,
+    /// The confidence the model reported, from 0.0 to 1.0, if any.
+    pub confidence: Option<f64>,
+}
+
+impl Classification {
+    /// Builds the JSON schema that restricts a response to one of the labels.
+    ///
+    /// # Arguments
+    /// * `labels` - The allowed labels
+    ///
+    /// # Returns
+    /// * A schema for an object with an enum `label` and an optional `confidence`
+    pub fn schema(labels: &[&str]) -> JsonValue {
+        json!({
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "enum": labels },
+                "confidence": { "type": "number", "minimum": 0, "maximum": 1 }
+            },
+            "required": ["label"]
+        })
+    }
+
+    /// Builds the instruction asking the model to classify a text.
+    ///
+    /// # Arguments
+    /// * `text` - The text to classify
+    /// * `labels` - The allowed labels
+    ///
+    /// # Returns
+    /// * The prompt to send as a user message
+    pub fn prompt(text: &str, labels: &[&str]) -> String {
+        format!(
+            "Classify the text below with exactly one of these labels: {}.\n\
+             Answer with JSON like {{\"label\": \"...\", \"confidence\": 0.9}}, where \
+             confidence is between 0 and 1.\n\nText:\n{text}",
+            labels.join(", ")
+        )
+    }
+
+    /// Parses and validates a model's answer.
+    ///
+    /// The label is matched case-insensitively and returned as spelled in `labels`.
+    /// A bare label without JSON is accepted too, for models that ignore the format.
+    ///
+    /// # Arguments
+    /// * `output` - The text the model generated
+    /// * `labels` - The allowed labels
+    ///
+    /// # Returns
+    /// * The classification, or a description of what was wrong with the answer
+    pub fn parse(output: &str, labels: &[&str]) -> Result<Self, String> {
+        let (answer, confidence) = match extract_json(output) {
+            Ok(json) => (
+                json["label"].as_str().unwrap_or_default().to_string(),
+                json["confidence"].as_f64().map(|c| c.clamp(0.0, 1.0)),
+            ),
+            Err(_) => (output.trim().trim_matches(['"', '.']).to_string(), None),
+        };
+
+        let label = labels
+            .iter()
+            .find(|label| label.eq_ignore_ascii_case(answer.trim()))
+            .ok_or_else(|| format!("\"{answer}\" is not one of: {}", labels.join(", ")))?;
+
+        Ok(Self {
+            label: label.to_string(),
+            confidence,
+        })
+    }
+}
+
+// ===
+// TESTS: Classification
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABELS: [&str; 3] = ["positive", "negative", "neutral"];
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Classification::parse(r#"{"label": "Negative", "confidence": 0.8}"#, &LABELS),
+            Ok(Classification {
+                label: "negative".to_string(),
+                confidence: Some(0.8),
+            })
+        );
+        assert_eq!(
+            Classification::parse("Neutral.", &LABELS).unwrap().label,
+            "neutral"
+        );
+        assert!(Classification::parse(r#"{"label": "angry"}"#, &LABELS).is_err());
+        assert_eq!(
+            Classification::schema(&LABELS)["properties"]["label"]["enum"],
+            json!(LABELS)
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use crate::OllamaOptions;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+// ===
+// ENUM: ProviderKind
+// ===
+
+/// The kind of API a provider speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// An Ollama server.
+    Ollama,
+    /// The Google Gemini API.
+    Gemini,
+}
+
+// ===
+// STRUCT: ProviderConfig
+// ===
+
+/// A configured model provider, e.g. a local Ollama server or the Gemini API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderConfig {
+    /// The kind of API this provider speaks.
+    #[serde(rename = "type")]
+    pub kind: ProviderKind,
+
+    /// The server address for Ollama ("host:port") or the base URL for Gemini.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// The model used by presets that don't name one.
+    #[serde(default)]
+    pub default_model: Option<String>,
+
+    /// The API key; prefer `api_key_env` to keep keys out of config files.
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    /// The name of the environment variable holding the API key.
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+}
+
+impl ProviderConfig {
+    /// Returns the API key, read from `api_key_env` if set, otherwise from `api_key`.
+    pub fn resolve_api_key(&self) -> Option<String> {
+        self.api_key_env
+            .as_ref()
+            .and_then(|name| std::env::var(name).ok())
+            .or_else(|| self.api_key.clone())
+    }
+}
+
+// ===
+// STRUCT: PresetConfig
+// ===
+
+/// A named session setup: which provider and model to use, with what options and prompt.
+#[derive(Debug, Deserialize)]
+pub struct PresetConfig {
+    /// The provider name; may be omitted when only one provider is configured.
+    #[serde(default)]
+    pub provider: Option<String>,
+
+    /// The model; defaults to the provider's `default_model`.
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// The name of an entry in `[prompts]` used as the system prompt.
+    #[serde(default)]
+    pub prompt: Option<String>,
+
+    /// The model options applied to sessions created from this preset.
+    #[serde(default)]
+    pub options: Option<OllamaOptions>,
+}
+
+// ===
+// STRUCT: OllieConfig
+// ===
+
+/// Client and session configuration loaded from a TOML file.
+///
+/// ```toml
+/// [providers.local]
+/// type = "ollama"
+/// endpoint = "127.0.0.1:11434"
+/// default_model = "gemma3:4b"
+///
+/// [prompts]
+/// coder = "You are a careful Rust programmer."
+///
+/// [presets.code]
+/// provider = "local"
+/// prompt = "coder"
+/// options = { temperature = 0.1, num_ctx = 16384 }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct OllieConfig {
+    /// The configured providers, by name.
+    #[serde(default)]
+    pub providers: HashMap<String, ProviderConfig>,
+
+    /// The configured session presets, by name.
+    #[serde(default)]
+    pub presets: HashMap<String, PresetConfig>,
+
+    /// Named system prompts that presets can refer to.
+    #[serde(default)]
+    pub prompts: HashMap<String, String>,
+}
+
+impl OllieConfig {
+    /// Loads a configuration from a TOML file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, e.g. "ollie.toml".
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OllieConfig)` - The parsed configuration.
+    /// * `Err(Box<dyn Error>)` - If the file cannot be read or is not valid.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read '{}': {err}", path.display()))?;
+        Self::from_toml(&text)
+            .map_err(|err| format!("invalid config '{}': {err}", path.display()).into())
+    }
+
+    /// Parses a configuration from TOML text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The TOML document.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OllieConfig)` - The parsed configuration.
+    /// * `Err(Box<dyn Error>)` - If the text is not valid TOML or references unknown entries.
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Returns the provider with the given name, if configured.
+    pub fn provider(&self, name: &str) -> Option<&ProviderConfig> {
+        self.providers.get(name)
+    }
+
+    /// Returns the preset with the given name, if configured.
+    pub fn preset(&self, name: &str) -> Option<&PresetConfig> {
+        self.presets.get(name)
+    }
+
+    /// Returns the system prompt with the given name, if configured.
+    pub fn prompt(&self, name: &str) -> Option<&str> {
+        self.prompts.get(name).map(String::as_str)
+    }
+
+    /// Resolves everything needed to create a session from a preset.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The name of the preset.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ResolvedPreset)` - The provider, model, options and system prompt of the preset.
+    /// * `Err(Box<dyn Error>)` - If the preset is unknown or its model cannot be determined.
+    pub fn resolve(&self, preset: &str) -> Result<ResolvedPreset<'_>, Box<dyn Error>> {
+        let config = self
+            .preset(preset)
+            .ok_or_else(|| format!("unknown preset '{preset}'"))?;
+
+        let provider = match &config.provider {
+            Some(name) => self
+                .provider(name)
+                .ok_or_else(|| format!("preset '{preset}' uses unknown provider '{name}'"))?,
+            None if self.providers.len() == 1 => self.providers.values().next().unwrap(),
+            None => return Err(format!("preset '{preset}' must name its provider").into()),
+        };
+
+        let model = config
+            .model
+            .as_deref()
+            .or(provider.default_model.as_deref())
+            .ok_or_else(|| format!("preset '{preset}' has no model"))?;
+
+        let system_prompt = config.prompt.as_deref().and_then(|name| self.prompt(name));
+
+        Ok(ResolvedPreset {
+            provider,
+            model,
+            options: config.options.as_ref(),
+            system_prompt,
+        })
+    }
+
+    /// Checks that presets only refer to configured providers and prompts.
+    fn validate(&self) -> Result<(), String> {
+        for (name, preset) in &self.presets {
+            if let Some(provider) = &preset.provider
+                && !self.providers.contains_key(provider)
+            {
+                return Err(format!(
+                    "preset '{name}' uses unknown provider '{provider}'"
+                ));
+            }
+            if let Some(prompt) = &preset.prompt
+                && !self.prompts.contains_key(prompt)
+            {
+                return Err(format!("preset '{name}' uses unknown prompt '{prompt}'"));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ===
+// STRUCT: ResolvedPreset
+// ===
+
+/// A preset with its provider, model and prompt looked up in the configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvedPreset<'a> {
+    /// The provider the preset uses.
+    pub provider: &'a ProviderConfig,
+    /// The model the preset uses.
+    pub model: &'a str,
+    /// The options the preset applies, if any.
+    pub options: Option<&'a OllamaOptions>,
+    /// The system prompt text, if the preset names one.
+    pub system_prompt: Option<&'a str>,
+}
+
+// ===
+// TESTS: OllieConfig
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gemini, OllamaSession};
+
+    const CONFIG: &str = r#"
+        [providers.local]
+        type = "ollama"
+        endpoint = "127.0.0.1:11434"
+        default_model = "gemma3:4b"
+
+        [providers.cloud]
+        type = "gemini"
+        api_key = "secret"
+        default_model = "gemini-2.0-flash"
+
+        [prompts]
+        coder = "You are a careful Rust programmer."
+
+        [presets.code]
+        provider = "local"
+        model = "qwen2.5-coder"
+        prompt = "coder"
+        options = { temperature = 0.1, num_ctx = 16384 }
+
+        [presets.chat]
+        provider = "cloud"
+    "#;
+
+    #[test]
+    fn test_resolve_preset() {
+        let config = OllieConfig::from_toml(CONFIG).unwrap();
+
+        let code = config.resolve("code").unwrap();
+        assert_eq!(code.provider.kind, ProviderKind::Ollama);
+        assert_eq!(code.model, "qwen2.5-coder");
+        assert_eq!(
+            code.system_prompt,
+            Some("You are a careful Rust programmer.")
+        );
+        assert_eq!(code.options.unwrap().num_ctx(), Some(16384));
+
+        let chat = config.resolve("chat").unwrap();
+        assert_eq!(chat.model, "gemini-2.0-flash");
+        assert_eq!(chat.provider.resolve_api_key().as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn test_clients_from_config() {
+        let config = OllieConfig::from_toml(CONFIG).unwrap();
+
+        let session = OllamaSession::from_config(&config, "code").unwrap();
+        assert_eq!(session.model(), Some("qwen2.5-coder"));
+        assert_eq!(session.context_window_size(), 16384);
+        assert_eq!(session.messages()[0]["role"], "system");
+        assert!(OllamaSession::from_config(&config, "chat").is_err());
+
+        let mut session = OllamaSession::from_config(&config, "code").unwrap();
+        session.apply_preset("creative").unwrap();
+        assert_eq!(session.options().temperature(), Some(1.1));
+        session.apply_preset("code").unwrap();
+        assert_eq!(session.options().temperature(), Some(0.1));
+        assert_eq!(session.context_window_size(), 16384);
+        assert!(session.apply_preset("missing").is_err());
+
+        assert!(Gemini::from_config(&config, "chat").is_ok());
+        assert!(Gemini::from_config(&config, "code").is_err());
+    }
+
+    #[test]
+    fn test_invalid_references() {
+        let error = OllieConfig::from_toml("[presets.a]\nprompt = \"missing\"").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "preset 'a' uses unknown prompt 'missing'"
+        );
+
+        let config = OllieConfig::from_toml(CONFIG).unwrap();
+        assert_eq!(
+            config.resolve("nope").unwrap_err().to_string(),
+            "unknown preset 'nope'"
+        );
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("ollie-config-{}.toml", std::process::id()));
+        std::fs::write(&path, CONFIG).unwrap();
+        let config = OllieConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.providers.len(), 2);
+
+        assert!(OllieConfig::from_file(&path).is_err());
+    }
+}
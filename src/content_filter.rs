@@ -0,0 +1,343 @@
+#[cfg(feature = "pattern-filter")]
+use regex::Regex;
+#[cfg(feature = "pattern-filter")]
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "transport")]
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Common English profanity matched by `PatternFilter::profanity`.
+#[cfg(feature = "pattern-filter")]
+const PROFANITY: &[&str] = &[
+    "asshole",
+    "bastard",
+    "bitch",
+    "bullshit",
+    "cunt",
+    "dickhead",
+    "fuck",
+    "fucked",
+    "fucker",
+    "fucking",
+    "motherfucker",
+    "shit",
+    "shitty",
+];
+
+/// A content filter shared by a session and its forks.
+#[cfg(feature = "transport")]
+pub type SharedContentFilter = Arc<Mutex<dyn ContentFilter>>;
+
+// ===
+// ENUM: FilterVerdict
+// ===
+
+/// What a `ContentFilter` lets through for a chunk of streamed text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterVerdict {
+    /// The text to pass on, possibly changed, shortened or empty while held back.
+    Pass(String),
+    /// The text to pass on before a violation; generation stops after it.
+    Halt(String),
+}
+
+// ===
+// TRAIT: ContentFilter
+// ===
+
+/// Moderates streamed output before it reaches the callback of a generation call.
+///
+/// A filter sees each chunk in order and may hold text back, e.g. a partial word
+/// that could turn out to be a match once the next chunk arrives. Set one with
+/// `OllamaSession::set_content_filter`; the history holds the filtered text.
+pub trait ContentFilter: Send + fmt::Debug {
+    /// Filters the next chunk of streamed text.
+    fn filter(&mut self, chunk: &str) -> FilterVerdict;
+
+    /// Releases the text still held back once the stream ends, and resets the filter.
+    fn finish(&mut self) -> FilterVerdict;
+}
+
+// ===
+// ENUM: FilterMode
+// ===
+
+/// What a `PatternFilter` does when the text matches.
+#[cfg(feature = "pattern-filter")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMode {
+    /// Replaces each character of a match with `*`.
+    #[default]
+    Mask,
+    /// Stops generation before the match.
+    Halt,
+}
+
+// ===
+// STRUCT: PatternFilter
+// ===
+
+/// A `ContentFilter` matching a word list and regular expressions.
+///
+/// Needs the `pattern-filter` feature, enabled by default.
+///
+/// Words match whole and case-insensitively. Text is held back until the next
+/// whitespace, so a word split across chunks is still caught; a pattern spanning
+/// several words may be missed when a chunk ends inside it.
+///
+/// ```
+/// use ollie_rs::{ContentFilter, FilterMode, FilterVerdict, PatternFilter};
+///
+/// let mut filter = PatternFilter::new(FilterMode::Mask);
+/// filter.add_words(&["darn"]);
+/// filter.add_pattern(r"\b\d{3}-\d{4}\b").unwrap();
+///
+/// assert_eq!(filter.filter("Oh DA"), FilterVerdict::Pass("Oh ".to_string()));
+/// assert_eq!(filter.filter("RN, call 555-0199"), FilterVerdict::Pass("****, call ".to_string()));
+/// assert_eq!(filter.finish(), FilterVerdict::Pass("********".to_string()));
+/// ```
+#[cfg(feature = "pattern-filter")]
+#[derive(Debug, Clone)]
+pub struct PatternFilter {
+    mode: FilterMode,
+    words: Vec<String>,
+    word_pattern: Option<Regex>,
+    patterns: Vec<Regex>,
+    pending: String,
+}
+
+#[cfg(feature = "pattern-filter")]
+impl PatternFilter {
+    /// Creates a filter that matches nothing until words or patterns are added.
+    pub fn new(mode: FilterMode) -> Self {
+        Self {
+            mode,
+            words: Vec::new(),
+            word_pattern: None,
+            patterns: Vec::new(),
+            pending: String::new(),
+        }
+    }
+
+    /// Creates a filter for common English profanity.
+    pub fn profanity(mode: FilterMode) -> Self {
+        let mut filter = Self::new(mode);
+        filter.add_words(PROFANITY);
+        filter
+    }
+
+    /// Returns what the filter does when the text matches.
+    pub fn mode(&self) -> FilterMode {
+        self.mode
+    }
+
+    /// Adds words to match whole and case-insensitively.
+    pub fn add_words<S: AsRef<str>>(&mut self, words: &[S]) -> &mut Self {
+        self.words.extend(
+            words
+                .iter()
+                .map(|word| word.as_ref().trim().to_string())
+                .filter(|word| !word.is_empty()),
+        );
+        if !self.words.is_empty() {
+            let alternatives: Vec<String> =
+                self.words.iter().map(|word| regex::escape(word)).collect();
+            let pattern = format!(r"(?i)\b(?:{})\b", alternatives.join("|"));
+            self.word_pattern = Some(Regex::new(&pattern).expect("escaped words are valid"));
+        }
+        self
+    }
+
+    /// Adds a regular expression to match.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The expression, in the syntax of the `regex` crate.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&mut Self)` - The filter, for chaining.
+    /// * `Err(Box<dyn Error>)` - If the expression is invalid.
+    pub fn add_pattern(&mut self, pattern: &str) -> Result<&mut Self, Box<dyn Error>> {
+        self.patterns.push(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Applies the mode to text that is ready to pass on.
+    fn check(&mut self, mut text: String) -> FilterVerdict {
+        for pattern in self.word_pattern.iter().chain(&self.patterns) {
+            match self.mode {
+                FilterMode::Halt => {
+                    if let Some(found) = pattern.find(&text) {
+                        text.truncate(found.start());
+                        self.pending.clear();
+                        return FilterVerdict::Halt(text);
+                    }
+                }
+                FilterMode::Mask => {
+                    text = pattern
+                        .replace_all(&text, |captures: &regex::Captures| {
+                            "*".repeat(captures[0].chars().count())
+                        })
+                        .into_owned();
+                }
+            }
+        }
+        FilterVerdict::Pass(text)
+    }
+}
+
+#[cfg(feature = "pattern-filter")]
+impl ContentFilter for PatternFilter {
+    fn filter(&mut self, chunk: &str) -> FilterVerdict {
+        self.pending.push_str(chunk);
+        // Hold back the last word, which the next chunk may extend.
+        let last_space = self
+            .pending
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace());
+        let ready = match last_space {
+            Some((index, space)) => self.pending.drain(..index + space.len_utf8()).collect(),
+            None => String::new(),
+        };
+        self.check(ready)
+    }
+
+    fn finish(&mut self) -> FilterVerdict {
+        let rest = std::mem::take(&mut self.pending);
+        self.check(rest)
+    }
+}
+
+// ===
+// STRUCT: FilteredStream
+// ===
+
+/// Runs the chunks of one response through a content filter.
+#[cfg(feature = "transport")]
+pub(crate) struct FilteredStream {
+    filter: SharedContentFilter,
+    text: String,
+    halted: bool,
+}
+
+#[cfg(feature = "transport")]
+impl FilteredStream {
+    /// Starts filtering a response.
+    pub(crate) fn new(filter: SharedContentFilter) -> Self {
+        Self {
+            filter,
+            text: String::new(),
+            halted: false,
+        }
+    }
+
+    /// Filters a chunk and passes what the filter lets through to `output`.
+    ///
+    /// Returns `false` once the filter has halted generation.
+    pub(crate) fn push<F: FnMut(&str)>(&mut self, chunk: &str, output: &mut F) -> bool {
+        if self.halted {
+            return false;
+        }
+        let verdict = self.lock().filter(chunk);
+        self.emit(verdict, output)
+    }
+
+    /// Passes the text the filter still holds back to `output`.
+    pub(crate) fn finish<F: FnMut(&str)>(&mut self, output: &mut F) {
+        let verdict = self.lock().finish();
+        // After a halt, finishing only resets the filter.
+        if !self.halted {
+            self.emit(verdict, output);
+        }
+    }
+
+    /// Returns the text passed on so far.
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns `true` if the filter halted generation.
+    pub(crate) fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Locks the filter, ignoring poisoning: a filter that panicked mid-chunk
+    /// still works on the next one, at worst holding back text it lost.
+    fn lock(&self) -> MutexGuard<'_, dyn ContentFilter + 'static> {
+        self.filter
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn emit<F: FnMut(&str)>(&mut self, verdict: FilterVerdict, output: &mut F) -> bool {
+        let (text, halted) = match verdict {
+            FilterVerdict::Pass(text) => (text, false),
+            FilterVerdict::Halt(text) => (text, true),
+        };
+        if !text.is_empty() {
+            output(&text);
+            self.text.push_str(&text);
+        }
+        self.halted = halted;
+        !halted
+    }
+}
+
+// ===
+// TESTS: PatternFilter
+// ===
+
+#[cfg(all(test, feature = "pattern-filter"))]
+mod tests {
+    use super::*;
+
+    fn run(filter: &mut PatternFilter, chunks: &[&str]) -> Vec<FilterVerdict> {
+        let mut verdicts: Vec<FilterVerdict> =
+            chunks.iter().map(|chunk| filter.filter(chunk)).collect();
+        verdicts.push(filter.finish());
+        verdicts
+    }
+
+    #[test]
+    fn test_masks_words_split_across_chunks() {
+        let mut filter = PatternFilter::profanity(FilterMode::Mask);
+        let verdicts = run(
+            &mut filter,
+            &["What the Fu", "ck! Shitake ", "is fine. shit"],
+        );
+        let text: String = verdicts
+            .into_iter()
+            .map(|verdict| match verdict {
+                FilterVerdict::Pass(text) => text,
+                FilterVerdict::Halt(_) => panic!("mask mode never halts"),
+            })
+            .collect();
+        assert_eq!(text, "What the ****! Shitake is fine. ****");
+    }
+
+    #[test]
+    fn test_halts_before_a_match() {
+        let mut filter = PatternFilter::new(FilterMode::Halt);
+        filter.add_pattern(r"(?i)\bpassword=\S+").unwrap();
+        assert!(filter.add_pattern("(unclosed").is_err());
+
+        assert_eq!(
+            filter.filter("Log in with pass"),
+            FilterVerdict::Pass("Log in with ".to_string())
+        );
+        assert_eq!(
+            filter.filter("word=hunter2 now"),
+            FilterVerdict::Halt(String::new())
+        );
+        assert_eq!(filter.finish(), FilterVerdict::Pass(String::new()));
+
+        // A match within the text released together halts after the text before it.
+        assert_eq!(
+            filter.filter("Set Password=x1 "),
+            FilterVerdict::Halt("Set ".to_string())
+        );
+        assert_eq!(filter.mode(), FilterMode::Halt);
+    }
+}
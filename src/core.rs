@@ -0,0 +1,27 @@
+//! The provider data types, usable without the HTTP transport.
+//!
+//! Requests, responses, messages and content parts depend only on `serde`, so
+//! they can be shared with environments that cannot run `reqwest` or `tokio`,
+//! e.g. a wasm frontend talking to a server built on this crate. Build with
+//! `default-features = false` to leave out the clients, sessions and streams,
+//! which need the `transport` feature.
+//!
+//! Everything here is also exported from the crate root.
+
+#[doc(inline)]
+pub use crate::gemini::{
+    gemini_content::*, gemini_function::*, gemini_generation_config::*, gemini_part::*,
+    gemini_prompt::*, gemini_request::*, gemini_response::*, gemini_response_error::*,
+};
+#[doc(inline)]
+pub use crate::ollama::{
+    ollama_chat_template::*, ollama_create_request::*, ollama_grammar::*, ollama_history::*,
+    ollama_message::*, ollama_options::*, ollama_progress::*, ollama_push_request::*,
+    ollama_request::*, ollama_request_report::*, ollama_response::*, ollama_role::*,
+    ollama_stream_error::*, tool::*,
+};
+#[doc(inline)]
+pub use crate::{
+    AudioClip, Classification, GenerationStats, ImageData, JsonAnswer, JsonAttempt,
+    JsonExtractError, LanguageCode, StreamMetrics, TokenBreakdown, extract_json,
+};
@@ -0,0 +1,310 @@
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::Path;
+
+/// The field names accepted for the prompt of an item, in order of preference.
+const PROMPT_FIELDS: [&str; 3] = ["prompt", "input", "question"];
+
+/// The field names accepted for the expected output of an item.
+const EXPECTED_FIELDS: [&str; 3] = ["expected", "output", "answer"];
+
+// ===
+// STRUCT: DatasetItem
+// ===
+
+/// One prompt of a dataset, with the output it should produce.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetItem {
+    /// The identifier of the item, used to match results when resuming a run.
+    pub id: String,
+    /// The prompt sent to the model.
+    pub prompt: String,
+    /// The reference output, if the dataset has one.
+    pub expected: Option<String>,
+    /// The other fields of the record, e.g. a category or difficulty.
+    pub fields: JsonMap<String, JsonValue>,
+}
+
+impl DatasetItem {
+    /// Creates an item from a record, taking the id, prompt and expected output
+    /// from their fields and keeping the rest.
+    fn from_record(
+        mut record: JsonMap<String, JsonValue>,
+        index: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let id = match record.remove("id") {
+            Some(JsonValue::String(id)) => id,
+            Some(JsonValue::Null) | None => index.to_string(),
+            Some(id) => id.to_string(),
+        };
+
+        let prompt = take_text(&mut record, &PROMPT_FIELDS)
+            .ok_or_else(|| format!("record {index} has no prompt field"))?;
+        let expected = take_text(&mut record, &EXPECTED_FIELDS);
+
+        Ok(Self {
+            id,
+            prompt,
+            expected,
+            fields: record,
+        })
+    }
+}
+
+/// Removes the first of the given fields that holds a string.
+fn take_text(record: &mut JsonMap<String, JsonValue>, names: &[&str]) -> Option<String> {
+    let name = names
+        .iter()
+        .find(|name| record.get(**name).is_some_and(JsonValue::is_string))?;
+    record.remove(*name)?.as_str().map(str::to_string)
+}
+
+// ===
+// STRUCT: Dataset
+// ===
+
+/// A list of prompts to evaluate a model on, loaded from JSONL or CSV.
+///
+/// Each record needs a prompt, in a "prompt", "input" or "question" field, and
+/// may have an "id" and a reference output in an "expected", "output" or
+/// "answer" field. Records without an id are numbered from 0. Ids must be
+/// unique, as results are matched to items by id when resuming a run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dataset {
+    items: Vec<DatasetItem>,
+    ids: HashSet<String>,
+}
+
+impl Dataset {
+    /// Creates an empty dataset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a dataset with one JSON object per line.
+    ///
+    /// # Arguments
+    /// * `text` - The JSONL text; blank lines are skipped
+    ///
+    /// # Returns
+    /// * The dataset, or an error naming the first line that could not be parsed
+    ///   or repeats an earlier id
+    pub fn from_jsonl(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut dataset = Self::new();
+        for (line_number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = match serde_json::from_str(line) {
+                Ok(JsonValue::Object(record)) => record,
+                Ok(_) => {
+                    return Err(format!("line {} is not a JSON object", line_number + 1).into());
+                }
+                Err(error) => return Err(format!("line {}: {error}", line_number + 1).into()),
+            };
+            let index = dataset.len();
+            dataset
+                .push(DatasetItem::from_record(record, index)?)
+                .map_err(|error| format!("line {}: {error}", line_number + 1))?;
+        }
+        Ok(dataset)
+    }
+
+    /// Parses a CSV dataset whose first row names the columns.
+    ///
+    /// Fields may be quoted with `"`, which allows commas, line breaks and `""`
+    /// escaped quotes in them.
+    ///
+    /// # Arguments
+    /// * `text` - The CSV text
+    ///
+    /// # Returns
+    /// * The dataset, or an error if a row has the wrong number of fields or
+    ///   repeats an earlier id
+    pub fn from_csv(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut rows = parse_csv(text)?.into_iter();
+        let Some(header) = rows.next() else {
+            return Ok(Self::new());
+        };
+
+        let mut dataset = Self::new();
+        for (row_number, row) in rows.enumerate() {
+            if row.len() != header.len() {
+                return Err(format!(
+                    "row {} has {} fields, expected {}",
+                    row_number + 2,
+                    row.len(),
+                    header.len()
+                )
+                .into());
+            }
+
+            // Empty cells count as missing, e.g. an item without an expected output.
+            let record = header
+                .iter()
+                .cloned()
+                .zip(row)
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(name, value)| (name, JsonValue::String(value)))
+                .collect();
+            let index = dataset.len();
+            dataset
+                .push(DatasetItem::from_record(record, index)?)
+                .map_err(|error| format!("row {}: {error}", row_number + 2))?;
+        }
+        Ok(dataset)
+    }
+
+    /// Loads a dataset from a `.jsonl` or `.csv` file.
+    ///
+    /// # Arguments
+    /// * `path` - The file; its extension selects the format
+    ///
+    /// # Returns
+    /// * The dataset, or an error if the file cannot be read or parsed
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match extension.as_str() {
+            "jsonl" | "ndjson" => Self::from_jsonl(&text),
+            "csv" => Self::from_csv(&text),
+            _ => Err(format!("unknown dataset format: {}", path.display()).into()),
+        }
+    }
+
+    /// Adds an item to the dataset.
+    ///
+    /// # Arguments
+    /// * `item` - The item to add
+    ///
+    /// # Returns
+    /// * The dataset for chaining, or an error if an item with the same id was added before
+    pub fn push(&mut self, item: DatasetItem) -> Result<&mut Self, Box<dyn Error>> {
+        if !self.ids.insert(item.id.clone()) {
+            return Err(format!("duplicate item id \"{}\"", item.id).into());
+        }
+        self.items.push(item);
+        Ok(self)
+    }
+
+    /// Returns the items of the dataset.
+    pub fn items(&self) -> &[DatasetItem] {
+        &self.items
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the dataset has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// Splits CSV text into rows of fields.
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|field| !field.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("unterminated quoted field in CSV".into());
+    }
+    row.push(field);
+    if row.iter().any(|field| !field.is_empty()) {
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// ===
+// TESTS: Dataset
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_jsonl() {
+        let dataset = Dataset::from_jsonl(
+            r#"{"id": "q1", "prompt": "2 + 2?", "expected": "4", "level": "easy"}
+
+{"input": "Capital of France?", "answer": "Paris"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.items()[0].id, "q1");
+        assert_eq!(dataset.items()[0].fields["level"], "easy");
+        assert_eq!(dataset.items()[1].id, "1");
+        assert_eq!(dataset.items()[1].prompt, "Capital of France?");
+        assert_eq!(dataset.items()[1].expected.as_deref(), Some("Paris"));
+
+        assert!(Dataset::from_jsonl(r#"{"expected": "4"}"#).is_err());
+        assert!(Dataset::from_jsonl("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_duplicate_ids_are_rejected() {
+        let error = Dataset::from_jsonl(
+            r#"{"id": "q1", "prompt": "One?"}
+{"id": "q1", "prompt": "Again?"}"#,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "line 2: duplicate item id \"q1\"");
+
+        // An explicit id may collide with the number given to a record without one.
+        let error = Dataset::from_csv("id,prompt\n,Zero?\n0,Also zero?\n").unwrap_err();
+        assert_eq!(error.to_string(), "row 3: duplicate item id \"0\"");
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let dataset = Dataset::from_csv(
+            "id,prompt,expected\r\n\
+             a,\"Say \"\"hi\"\", twice\",hi hi\r\n\
+             b,\"Two\nlines\",\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.items()[0].prompt, "Say \"hi\", twice");
+        assert_eq!(dataset.items()[0].expected.as_deref(), Some("hi hi"));
+        assert_eq!(dataset.items()[1].prompt, "Two\nlines");
+
+        assert!(Dataset::from_csv("prompt,expected\nonly one\n").is_err());
+    }
+}
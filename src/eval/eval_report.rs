@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+// ===
+// STRUCT: EvalResult
+// ===
+
+/// The outcome of running one dataset item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalResult {
+    /// The id of the dataset item.
+    pub id: String,
+    /// The text the model generated, if the request succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// The reference output of the item, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// The wall time of the request, in milliseconds.
+    pub latency_ms: u64,
+    /// The number of prompt tokens reported by the server.
+    #[serde(default)]
+    pub prompt_tokens: u32,
+    /// The number of generated tokens reported by the server.
+    #[serde(default)]
+    pub completion_tokens: u32,
+    /// The error that ended the request, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl EvalResult {
+    /// Returns the wall time of the request.
+    pub fn latency(&self) -> Duration {
+        Duration::from_millis(self.latency_ms)
+    }
+
+    /// Returns true if the request failed.
+    pub fn is_failure(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+// ===
+// STRUCT: EvalReport
+// ===
+
+/// The results of running a dataset, in dataset order.
+///
+/// Save a report with `to_jsonl` and load it back with `from_jsonl` to resume
+/// an interrupted or partly failed run with `eval::resume`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalReport {
+    results: Vec<EvalResult>,
+}
+
+impl EvalReport {
+    /// Creates a report from results.
+    pub fn new(results: Vec<EvalResult>) -> Self {
+        Self { results }
+    }
+
+    /// Parses a report saved with `to_jsonl`.
+    ///
+    /// # Arguments
+    /// * `text` - One result per line; blank lines are skipped
+    ///
+    /// # Returns
+    /// * The report, or an error naming the first line that could not be parsed
+    pub fn from_jsonl(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut results = Vec::new();
+        for (line_number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let result = serde_json::from_str(line)
+                .map_err(|error| format!("line {}: {error}", line_number + 1))?;
+            results.push(result);
+        }
+        Ok(Self { results })
+    }
+
+    /// Serializes the report with one result per line.
+    pub fn to_jsonl(&self) -> String {
+        self.results
+            .iter()
+            .filter_map(|result| serde_json::to_string(result).ok())
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// Returns the results.
+    pub fn results(&self) -> &[EvalResult] {
+        &self.results
+    }
+
+    /// Returns the results of the requests that failed.
+    pub fn failures(&self) -> Vec<&EvalResult> {
+        self.results
+            .iter()
+            .filter(|result| result.is_failure())
+            .collect()
+    }
+
+    /// Returns the ids of the items that completed successfully.
+    pub fn completed_ids(&self) -> HashSet<&str> {
+        self.results
+            .iter()
+            .filter(|result| !result.is_failure())
+            .map(|result| result.id.as_str())
+            .collect()
+    }
+
+    /// Returns the total number of prompt and generated tokens.
+    pub fn total_tokens(&self) -> u64 {
+        self.results
+            .iter()
+            .map(|result| u64::from(result.prompt_tokens) + u64::from(result.completion_tokens))
+            .sum()
+    }
+
+    /// Returns the mean latency of the successful requests.
+    pub fn mean_latency(&self) -> Duration {
+        let latencies: Vec<u64> = self
+            .results
+            .iter()
+            .filter(|result| !result.is_failure())
+            .map(|result| result.latency_ms)
+            .collect();
+        if latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(latencies.iter().sum::<u64>() / latencies.len() as u64)
+    }
+}
+
+// ===
+// TRAIT: EvalReport (fmt::Display)
+// ===
+
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} items, {} failed, mean latency {} ms, {} tokens",
+            self.results.len(),
+            self.failures().len(),
+            self.mean_latency().as_millis(),
+            self.total_tokens()
+        )
+    }
+}
+
+// ===
+// TESTS: EvalReport
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, latency_ms: u64, error: Option<&str>) -> EvalResult {
+        EvalResult {
+            id: id.to_string(),
+            output: error.is_none().then(|| "ok".to_string()),
+            expected: None,
+            latency_ms,
+            prompt_tokens: 5,
+            completion_tokens: 3,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_report_round_trip() {
+        let report = EvalReport::new(vec![
+            result("a", 100, None),
+            result("b", 300, None),
+            result("c", 50, Some("connection refused")),
+        ]);
+
+        assert_eq!(report.failures().len(), 1);
+        assert_eq!(report.completed_ids(), HashSet::from(["a", "b"]));
+        assert_eq!(report.mean_latency(), Duration::from_millis(200));
+        assert_eq!(report.total_tokens(), 24);
+        assert_eq!(
+            report.to_string(),
+            "3 items, 1 failed, mean latency 200 ms, 24 tokens"
+        );
+        assert_eq!(EvalReport::from_jsonl(&report.to_jsonl()).unwrap(), report);
+    }
+}
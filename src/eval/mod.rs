@@ -0,0 +1,21 @@
+#[doc(hidden)]
+pub mod dataset;
+#[doc(inline)]
+pub use dataset::*;
+
+#[doc(hidden)]
+pub mod eval_report;
+#[doc(inline)]
+pub use eval_report::*;
+
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod runner;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use runner::*;
+
+#[doc(hidden)]
+pub mod text_diff;
+#[doc(inline)]
+pub use text_diff::*;
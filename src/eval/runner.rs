@@ -0,0 +1,316 @@
+use crate::OllamaSession;
+use crate::eval::{Dataset, DatasetItem, EvalReport, EvalResult};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{Semaphore, mpsc};
+use tokio::task::LocalSet;
+
+/// Runs every item of a dataset and collects the outputs, latencies and token usage.
+///
+/// Each item gets a fresh session from `session_factory`, which sets the model,
+/// options and any system prompt; the item's prompt is then sent as a user
+/// message. Up to `concurrency` requests are in flight at once. A failed request
+/// does not stop the run; it is recorded in the report and can be retried with
+/// `resume`.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::OllamaSession;
+/// use ollie_rs::eval::{self, Dataset};
+///
+/// let dataset = Dataset::load("questions.jsonl")?;
+/// let report = eval::run(&dataset, |_| OllamaSession::new("gemma3:1b"), 4).await;
+/// std::fs::write("report.jsonl", report.to_jsonl())?;
+/// println!("{report}");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Arguments
+/// * `dataset` - The items to run
+/// * `session_factory` - Creates the session an item is sent with
+/// * `concurrency` - The maximum number of requests in flight, at least 1
+///
+/// # Returns
+/// * A report with one result per item, in dataset order
+pub async fn run<F>(dataset: &Dataset, session_factory: F, concurrency: usize) -> EvalReport
+where
+    F: Fn(&DatasetItem) -> OllamaSession,
+{
+    resume(
+        dataset,
+        &EvalReport::default(),
+        session_factory,
+        concurrency,
+    )
+    .await
+}
+
+/// Continues a run, sending only the items that did not complete before.
+///
+/// Successful results of `previous` are kept; items that failed or are missing
+/// from it are run again.
+///
+/// # Arguments
+/// * `dataset` - The items to run
+/// * `previous` - The report of the earlier run, e.g. loaded with `EvalReport::from_jsonl`
+/// * `session_factory` - Creates the session an item is sent with
+/// * `concurrency` - The maximum number of requests in flight, at least 1
+///
+/// # Returns
+/// * A report with one result per item, in dataset order
+pub async fn resume<F>(
+    dataset: &Dataset,
+    previous: &EvalReport,
+    session_factory: F,
+    concurrency: usize,
+) -> EvalReport
+where
+    F: Fn(&DatasetItem) -> OllamaSession,
+{
+    resume_with_callback(dataset, previous, session_factory, concurrency, |_| {}).await
+}
+
+/// Runs every item of a dataset like `run`, passing each result to a callback
+/// as soon as its request completes.
+///
+/// Write the results out from the callback, e.g. as JSONL lines, so a run that
+/// is killed part way can still be resumed from what it finished.
+///
+/// # Arguments
+/// * `dataset` - The items to run
+/// * `session_factory` - Creates the session an item is sent with
+/// * `concurrency` - The maximum number of requests in flight, at least 1
+/// * `on_result` - Called with each result, in the order the requests complete
+///
+/// # Returns
+/// * A report with one result per item, in dataset order
+pub async fn run_with_callback<F, C>(
+    dataset: &Dataset,
+    session_factory: F,
+    concurrency: usize,
+    on_result: C,
+) -> EvalReport
+where
+    F: Fn(&DatasetItem) -> OllamaSession,
+    C: FnMut(&EvalResult),
+{
+    resume_with_callback(
+        dataset,
+        &EvalReport::default(),
+        session_factory,
+        concurrency,
+        on_result,
+    )
+    .await
+}
+
+/// Continues a run like `resume`, passing each new result to a callback as soon
+/// as its request completes.
+///
+/// The results kept from `previous` are not passed to the callback.
+///
+/// # Arguments
+/// * `dataset` - The items to run
+/// * `previous` - The report of the earlier run, e.g. loaded with `EvalReport::from_jsonl`
+/// * `session_factory` - Creates the session an item is sent with
+/// * `concurrency` - The maximum number of requests in flight, at least 1
+/// * `on_result` - Called with each new result, in the order the requests complete
+///
+/// # Returns
+/// * A report with one result per item, in dataset order
+pub async fn resume_with_callback<F, C>(
+    dataset: &Dataset,
+    previous: &EvalReport,
+    session_factory: F,
+    concurrency: usize,
+    mut on_result: C,
+) -> EvalReport
+where
+    F: Fn(&DatasetItem) -> OllamaSession,
+    C: FnMut(&EvalResult),
+{
+    // Dataset ids are unique, so each successful result matches at most one item.
+    let completed: HashMap<&str, &EvalResult> = previous
+        .results()
+        .iter()
+        .filter(|result| !result.is_failure())
+        .map(|result| (result.id.as_str(), result))
+        .collect();
+    let mut results: Vec<Option<EvalResult>> = dataset
+        .items()
+        .iter()
+        .map(|item| {
+            completed
+                .get(item.id.as_str())
+                .map(|result| (*result).clone())
+        })
+        .collect();
+    let pending: Vec<usize> = (0..results.len())
+        .filter(|index| results[*index].is_none())
+        .collect();
+
+    // Sessions are not `Send`, so the items run as local tasks on this thread.
+    let local = LocalSet::new();
+    local
+        .run_until(async {
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+            let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+
+            // The channel closes once every task has sent its result or panicked.
+            let send_items = async move {
+                for index in pending {
+                    let permit = semaphore
+                        .clone()
+                        .acquire_owned()
+                        .await
+                        .expect("the semaphore is never closed");
+                    let item = dataset.items()[index].clone();
+                    let session = session_factory(&item);
+                    let sender = sender.clone();
+                    tokio::task::spawn_local(async move {
+                        let result = run_item(session, &item).await;
+                        drop(permit);
+                        let _ = sender.send((index, result));
+                    });
+                }
+            };
+
+            let receive_results = async {
+                while let Some((index, result)) = receiver.recv().await {
+                    on_result(&result);
+                    results[index] = Some(result);
+                }
+            };
+
+            tokio::join!(send_items, receive_results);
+        })
+        .await;
+
+    let results = dataset
+        .items()
+        .iter()
+        .zip(results)
+        .map(|(item, result)| {
+            result.unwrap_or_else(|| EvalResult {
+                id: item.id.clone(),
+                output: None,
+                expected: item.expected.clone(),
+                latency_ms: 0,
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                error: Some("the request panicked".to_string()),
+            })
+        })
+        .collect();
+
+    EvalReport::new(results)
+}
+
+/// Sends one item's prompt and records the outcome.
+async fn run_item(mut session: OllamaSession, item: &DatasetItem) -> EvalResult {
+    session.user(&item.prompt);
+
+    let start = Instant::now();
+    let outcome = session.update(|_| {}).await;
+    let latency_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    let mut result = EvalResult {
+        id: item.id.clone(),
+        output: None,
+        expected: item.expected.clone(),
+        latency_ms,
+        prompt_tokens: 0,
+        completion_tokens: 0,
+        error: None,
+    };
+
+    match outcome {
+        Ok(response) => {
+            result.output = Some(response.text().unwrap_or_default().to_string());
+            result.prompt_tokens = response.prompt_eval_count().copied().unwrap_or(0);
+            result.completion_tokens = response.eval_count().copied().unwrap_or(0);
+        }
+        Err(error) => result.error = Some(error.to_string()),
+    }
+    result
+}
+
+// ===
+// TESTS: run
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body, interrupted_chat_body};
+
+    fn dataset() -> Dataset {
+        Dataset::from_jsonl(
+            r#"{"id": "a", "prompt": "One?", "expected": "1"}
+{"id": "b", "prompt": "Two?", "expected": "2"}
+{"id": "c", "prompt": "Three?", "expected": "3"}"#,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_and_resume() {
+        let server = MockServer::start(vec![
+            chat_body(&["1"]),
+            interrupted_chat_body(&["2"]),
+            chat_body(&["3"]),
+        ])
+        .await;
+        let addr = server.addr();
+
+        let report = run(&dataset(), |_| OllamaSession::remote("mock", &addr), 1).await;
+        assert_eq!(report.results()[0].output.as_deref(), Some("1"));
+        assert_eq!(report.results()[0].completion_tokens, 10);
+        assert!(report.results()[1].is_failure());
+        assert_eq!(report.results()[2].expected.as_deref(), Some("3"));
+
+        let retry = MockServer::start(vec![chat_body(&["2"])]).await;
+        let addr = retry.addr();
+        let saved = EvalReport::from_jsonl(&report.to_jsonl()).unwrap();
+
+        let mut emitted = Vec::new();
+        let resumed = resume_with_callback(
+            &dataset(),
+            &saved,
+            |_| OllamaSession::remote("mock", &addr),
+            2,
+            |result| emitted.push(result.clone()),
+        )
+        .await;
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].id, "b");
+        assert_eq!(retry.requests().len(), 1);
+        assert_eq!(retry.requests()[0]["messages"][0]["content"], "Two?");
+        assert!(resumed.failures().is_empty());
+        assert_eq!(resumed.results()[1].output.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_run_concurrently() {
+        let server = MockServer::start(vec![chat_body(&["ok"]); 3]).await;
+        let addr = server.addr();
+
+        let mut emitted = Vec::new();
+        let report = run_with_callback(
+            &dataset(),
+            |_| OllamaSession::remote("mock", &addr),
+            3,
+            |result| emitted.push(result.id.clone()),
+        )
+        .await;
+        assert_eq!(server.requests().len(), 3);
+        let ids: Vec<&str> = report.results().iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+
+        // Results are emitted in completion order, which may differ.
+        emitted.sort();
+        assert_eq!(emitted, ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+use std::fmt;
+
+// ===
+// ENUM: DiffOp
+// ===
+
+/// One step of a diff between an expected and an actual text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffOp {
+    /// A unit present in both texts.
+    Equal(String),
+    /// A unit only in the expected text.
+    Delete(String),
+    /// A unit only in the actual text.
+    Insert(String),
+}
+
+// ===
+// STRUCT: TextDiff
+// ===
+
+/// The difference between an expected and an actual model output.
+///
+/// Built by `diff` (word by word) or `diff_lines` (line by line). Besides the
+/// edit steps it scores how alike the texts are, so regression tests can assert
+/// approximate equality instead of an exact match:
+///
+/// ```
+/// use ollie_rs::eval;
+///
+/// let diff = eval::diff("The capital of France is Paris.", "The capital of France is Paris!");
+/// assert!(diff.similarity() > 0.8);
+/// assert_eq!(diff.distance(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextDiff {
+    ops: Vec<DiffOp>,
+    distance: usize,
+    jaccard: f64,
+    units: usize,
+}
+
+impl TextDiff {
+    /// Returns the edit steps that turn the expected text into the actual text.
+    pub fn ops(&self) -> &[DiffOp] {
+        &self.ops
+    }
+
+    /// Returns the Levenshtein distance between the texts, counted in units.
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    /// Returns the normalized Levenshtein similarity, from 0.0 to 1.0.
+    ///
+    /// Two empty texts have a similarity of 1.0.
+    pub fn similarity(&self) -> f64 {
+        if self.units == 0 {
+            return 1.0;
+        }
+        1.0 - self.distance as f64 / self.units as f64
+    }
+
+    /// Returns the Jaccard similarity of the sets of units, from 0.0 to 1.0.
+    ///
+    /// Unlike `similarity`, this ignores the order of the units.
+    pub fn jaccard(&self) -> f64 {
+        self.jaccard
+    }
+
+    /// Returns true if the texts are identical.
+    pub fn is_equal(&self) -> bool {
+        self.distance == 0
+    }
+
+    /// Returns true if the similarity is at least `threshold`.
+    ///
+    /// # Arguments
+    /// * `threshold` - The minimum similarity, e.g. 0.9
+    pub fn is_similar(&self, threshold: f64) -> bool {
+        self.similarity() >= threshold
+    }
+}
+
+// ===
+// TRAIT: TextDiff (fmt::Display)
+// ===
+
+/// Shows each unit on its own line, prefixed with " ", "-" or "+".
+impl fmt::Display for TextDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, op) in self.ops.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match op {
+                DiffOp::Equal(unit) => write!(f, "  {unit}")?,
+                DiffOp::Delete(unit) => write!(f, "- {unit}")?,
+                DiffOp::Insert(unit) => write!(f, "+ {unit}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Diffs two texts word by word.
+///
+/// Words are separated by whitespace, so differences in spacing and line breaks
+/// are ignored.
+///
+/// # Arguments
+/// * `expected` - The reference output
+/// * `actual` - The output to check
+///
+/// # Returns
+/// * The diff, with similarity scores over words
+pub fn diff(expected: &str, actual: &str) -> TextDiff {
+    diff_units(
+        &expected.split_whitespace().collect::<Vec<_>>(),
+        &actual.split_whitespace().collect::<Vec<_>>(),
+    )
+}
+
+/// Diffs two texts line by line.
+///
+/// # Arguments
+/// * `expected` - The reference output
+/// * `actual` - The output to check
+///
+/// # Returns
+/// * The diff, with similarity scores over lines
+pub fn diff_lines(expected: &str, actual: &str) -> TextDiff {
+    diff_units(
+        &expected.lines().collect::<Vec<_>>(),
+        &actual.lines().collect::<Vec<_>>(),
+    )
+}
+
+/// Computes the edit steps and scores of two sequences of units.
+fn diff_units(expected: &[&str], actual: &[&str]) -> TextDiff {
+    let (n, m) = (expected.len(), actual.len());
+
+    // distances[i][j] is the edit distance between expected[i..] and actual[j..].
+    let mut distances = vec![vec![0; m + 1]; n + 1];
+    for i in (0..=n).rev() {
+        for j in (0..=m).rev() {
+            distances[i][j] = if i == n {
+                m - j
+            } else if j == m {
+                n - i
+            } else if expected[i] == actual[j] {
+                distances[i + 1][j + 1]
+            } else {
+                1 + distances[i + 1][j + 1]
+                    .min(distances[i + 1][j])
+                    .min(distances[i][j + 1])
+            };
+        }
+    }
+
+    // Walk the table, preferring matches so the steps read naturally. A
+    // substitution is shown as a delete followed by an insert.
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            ops.push(DiffOp::Equal(expected[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < n && j < m && distances[i][j] == distances[i + 1][j + 1] + 1 {
+            ops.push(DiffOp::Delete(expected[i].to_string()));
+            ops.push(DiffOp::Insert(actual[j].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || distances[i][j] == distances[i + 1][j] + 1) {
+            ops.push(DiffOp::Delete(expected[i].to_string()));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(actual[j].to_string()));
+            j += 1;
+        }
+    }
+
+    let expected_set: HashSet<&str> = expected.iter().copied().collect();
+    let actual_set: HashSet<&str> = actual.iter().copied().collect();
+    let union = expected_set.union(&actual_set).count();
+    let jaccard = if union == 0 {
+        1.0
+    } else {
+        expected_set.intersection(&actual_set).count() as f64 / union as f64
+    };
+
+    TextDiff {
+        ops,
+        distance: distances[0][0],
+        jaccard,
+        units: n.max(m),
+    }
+}
+
+// ===
+// TESTS: TextDiff
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_words() {
+        let diff = diff("the quick brown fox", "the slow brown fox jumps");
+        assert_eq!(diff.distance(), 2);
+        assert_eq!(
+            diff.ops(),
+            [
+                DiffOp::Equal("the".to_string()),
+                DiffOp::Delete("quick".to_string()),
+                DiffOp::Insert("slow".to_string()),
+                DiffOp::Equal("brown".to_string()),
+                DiffOp::Equal("fox".to_string()),
+                DiffOp::Insert("jumps".to_string()),
+            ]
+        );
+        assert!((diff.similarity() - 0.6).abs() < 1e-9);
+        assert!((diff.jaccard() - 0.5).abs() < 1e-9);
+        assert!(diff.is_similar(0.5));
+        assert!(!diff.is_similar(0.9));
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nc");
+        assert_eq!(diff.distance(), 1);
+        assert_eq!(diff.to_string(), "  a\n- b\n  c");
+
+        assert!(super::diff("same  text", "same text").is_equal());
+        assert_eq!(super::diff("", "").similarity(), 1.0);
+    }
+}
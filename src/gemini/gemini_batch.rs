@@ -0,0 +1,224 @@
+use crate::GeminiResponse;
+use serde_json::Value as JsonValue;
+use std::error::Error;
+
+// ===
+// ENUM: GeminiBatchState
+// ===
+
+/// The state of a Gemini batch job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeminiBatchState {
+    /// The job is queued.
+    Pending,
+    /// The job is being processed.
+    Running,
+    /// The job has finished and its results are available.
+    Succeeded,
+    /// The job failed; see `GeminiBatchJob::error`.
+    Failed,
+    /// The job was cancelled, possibly after some requests were processed.
+    Cancelled,
+    /// The job did not finish in time and was stopped.
+    Expired,
+    /// The API reported no state, or one this crate does not know.
+    Unspecified,
+}
+
+impl GeminiBatchState {
+    /// Parses a state as reported by the API, e.g. "BATCH_STATE_RUNNING".
+    pub fn from_api(state: &str) -> Self {
+        match state
+            .trim_start_matches("BATCH_STATE_")
+            .trim_start_matches("JOB_STATE_")
+        {
+            "PENDING" | "QUEUED" => Self::Pending,
+            "RUNNING" => Self::Running,
+            "SUCCEEDED" => Self::Succeeded,
+            "FAILED" => Self::Failed,
+            "CANCELLED" => Self::Cancelled,
+            "EXPIRED" => Self::Expired,
+            _ => Self::Unspecified,
+        }
+    }
+
+    /// Returns `true` once the job will not change any more.
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self,
+            Self::Succeeded | Self::Failed | Self::Cancelled | Self::Expired
+        )
+    }
+}
+
+// ===
+// STRUCT: GeminiBatchResult
+// ===
+
+/// The outcome of one request of a batch job.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeminiBatchResult {
+    /// The position of the request in the list the job was created from, if known.
+    pub index: Option<usize>,
+
+    /// The response; a failed request has its `error` set, so `response_error`
+    /// explains what went wrong.
+    pub response: GeminiResponse,
+}
+
+// ===
+// STRUCT: GeminiBatchJob
+// ===
+
+/// A batch job created by `Gemini::create_batch`.
+///
+/// Batch jobs run for up to a day at a lower price than single requests. The
+/// job's `name` is all that is needed to follow it, so it can be stored and the
+/// job picked up again later, even from another process, with `Gemini::batch`
+/// or `Gemini::wait_for_batch`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeminiBatchJob {
+    name: String,
+    state: GeminiBatchState,
+    error: Option<String>,
+    results: Vec<GeminiBatchResult>,
+}
+
+impl GeminiBatchJob {
+    /// Reads a job from the long-running operation, or batch, returned by the API.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The operation or batch JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(GeminiBatchJob)` - The job.
+    /// * `Err(Box<dyn Error>)` - If the JSON is an API error or has no job name.
+    pub fn from_json(json: &JsonValue) -> Result<Self, Box<dyn Error>> {
+        let Some(name) = json["name"].as_str() else {
+            let message = json["error"]["message"].as_str().unwrap_or("no job name");
+            return Err(format!("invalid batch job: {message}").into());
+        };
+
+        // An operation wraps the batch in its metadata; a batch stands alone.
+        let batch = json.get("metadata").unwrap_or(json);
+        let mut state = batch["state"]
+            .as_str()
+            .map(GeminiBatchState::from_api)
+            .unwrap_or(GeminiBatchState::Unspecified);
+        let error = json["error"]["message"].as_str().map(str::to_string);
+        if error.is_some() && !state.is_done() {
+            state = GeminiBatchState::Failed;
+        }
+
+        let output = json.get("response").unwrap_or(&batch["output"]);
+        let results = output["inlinedResponses"]["inlinedResponses"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(batch_result)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            name: name.to_string(),
+            state,
+            error,
+            results,
+        })
+    }
+
+    /// Returns the job's name, e.g. "batches/123".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the job's state.
+    pub fn state(&self) -> GeminiBatchState {
+        self.state
+    }
+
+    /// Returns `true` once the job will not change any more.
+    pub fn is_done(&self) -> bool {
+        self.state.is_done()
+    }
+
+    /// Returns why the job failed, if it did.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns the results, in request order, once the job has succeeded.
+    pub fn results(&self) -> &[GeminiBatchResult] {
+        &self.results
+    }
+}
+
+/// Reads one inlined response, which holds either a response or an error.
+fn batch_result(inlined: &JsonValue) -> Result<GeminiBatchResult, Box<dyn Error>> {
+    let index = inlined["metadata"]["key"]
+        .as_str()
+        .and_then(|key| key.parse().ok());
+    let response = match inlined.get("response") {
+        Some(response) => serde_json::from_value(response.clone())?,
+        None => serde_json::from_value(serde_json::json!({ "error": inlined["error"] }))?,
+    };
+    Ok(GeminiBatchResult { index, response })
+}
+
+// ===
+// TESTS: GeminiBatchJob
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_job_from_operation() {
+        let running = json!({
+            "name": "batches/123",
+            "metadata": { "state": "BATCH_STATE_RUNNING" },
+            "done": false
+        });
+        let job = GeminiBatchJob::from_json(&running).unwrap();
+        assert_eq!(job.name(), "batches/123");
+        assert_eq!(job.state(), GeminiBatchState::Running);
+        assert!(!job.is_done());
+        assert!(job.results().is_empty());
+
+        let finished = json!({
+            "name": "batches/123",
+            "metadata": { "state": "BATCH_STATE_SUCCEEDED" },
+            "done": true,
+            "response": {
+                "inlinedResponses": { "inlinedResponses": [
+                    {
+                        "response": { "candidates": [{
+                            "content": { "role": "model", "parts": [{ "text": "Paris" }] },
+                            "finishReason": "STOP"
+                        }] },
+                        "metadata": { "key": "0" }
+                    },
+                    { "error": { "code": 400, "message": "bad request" }, "metadata": { "key": "1" } }
+                ] }
+            }
+        });
+        let job = GeminiBatchJob::from_json(&finished).unwrap();
+        assert!(job.is_done());
+        let results = job.results();
+        assert_eq!(results[0].index, Some(0));
+        assert_eq!(results[0].response.text(), Some("Paris"));
+        assert_eq!(results[1].index, Some(1));
+        assert!(results[1].response.response_error().is_some());
+
+        let failed = json!({ "name": "batches/9", "done": true, "error": { "message": "quota" } });
+        let job = GeminiBatchJob::from_json(&failed).unwrap();
+        assert_eq!(job.state(), GeminiBatchState::Failed);
+        assert_eq!(job.error(), Some("quota"));
+
+        let api_error = json!({ "error": { "code": 404, "message": "not found" } });
+        assert!(GeminiBatchJob::from_json(&api_error).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use crate::gemini::GeminiRole;
-use crate::{GeminiPart, GeminiPartCode, GeminiPartText};
+use crate::{AudioClip, GeminiPart, GeminiPartCode, GeminiPartText, GeminiPrompt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::ops::Index;
 
 // ===
 // STRUCT: GeminiContent
@@ -11,7 +12,7 @@ use serde_json::Value as JsonValue;
 ///
 /// This struct holds the parts that make up a message to be sent to the Gemini API,
 /// with an optional role field to identify the speaker (e.g., "user" or "model").
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiContent {
     /// The role of the message sender (e.g., "user" or "model").
     /// When None, the role is determined by the API based on context.
@@ -39,6 +40,47 @@ impl GeminiContent {
         }
     }
 
+    /// Creates a user turn containing the given text.
+    ///
+    /// # Parameters
+    /// * `text` - The text of the user's message
+    ///
+    /// # Returns
+    /// A new `GeminiContent` with the user role and a single text part.
+    pub fn user(text: &str) -> Self {
+        let mut content = Self::new();
+        content.set_role(GeminiRole::User).add_text(text);
+        content
+    }
+
+    /// Creates a model turn containing the given text.
+    ///
+    /// Useful for replaying earlier model answers when building a conversation history.
+    ///
+    /// # Parameters
+    /// * `text` - The text of the model's answer
+    ///
+    /// # Returns
+    /// A new `GeminiContent` with the model role and a single text part.
+    pub fn model(text: &str) -> Self {
+        let mut content = Self::new();
+        content.set_role(GeminiRole::Model).add_text(text);
+        content
+    }
+
+    /// Creates a tool turn containing the given part, typically a function response.
+    ///
+    /// # Parameters
+    /// * `part` - The part to send, e.g. `GeminiPart::FunctionResponse`
+    ///
+    /// # Returns
+    /// A new `GeminiContent` with the tool role and the given part.
+    pub fn tool(part: GeminiPart) -> Self {
+        let mut content = Self::new();
+        content.set_role(GeminiRole::Tool).add_part(part);
+        content
+    }
+
     /// Converts the GeminiContent instance to a JSON value.
     ///
     /// # Returns
@@ -61,6 +103,17 @@ impl GeminiContent {
         self.add_part(part)
     }
 
+    /// Adds an audio clip to the content's parts, e.g. a voice note to transcribe.
+    ///
+    /// # Parameters
+    /// * `clip` - The audio to send, e.g. loaded with `AudioClip::from_file`
+    ///
+    /// # Returns
+    /// A mutable reference to self for method chaining
+    pub fn add_audio(&mut self, clip: &AudioClip) -> &mut Self {
+        self.add_part(GeminiPart::InlineData(clip.into()))
+    }
+
     /// Adds a part to the content's parts vector.
     ///
     /// # Parameters
@@ -108,3 +161,131 @@ impl GeminiContent {
         self.add_part(part)
     }
 }
+
+// ===
+// TRAIT: Default for GeminiContent
+// ===
+
+impl Default for GeminiContent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ===
+// TRAIT: GeminiContent (From<&str>)
+// ===
+
+/// Creates content with a single text part and no role, leaving the role to the API.
+impl From<&str> for GeminiContent {
+    fn from(text: &str) -> Self {
+        let mut content = Self::new();
+        content.add_text(text);
+        content
+    }
+}
+
+// ===
+// TRAIT: GeminiContent (From<&GeminiPrompt>)
+// ===
+
+/// Creates content with the prompt's text and role, if it has one.
+impl From<&GeminiPrompt> for GeminiContent {
+    fn from(prompt: &GeminiPrompt) -> Self {
+        let mut content = Self::from(prompt.text.as_str());
+        if let Some(role) = prompt.role {
+            content.set_role(role);
+        }
+        content
+    }
+}
+
+// ===
+// TRAIT: GeminiContent (collection of parts)
+// ===
+
+/// Creates content with the given parts and no role.
+impl FromIterator<GeminiPart> for GeminiContent {
+    fn from_iter<I: IntoIterator<Item = GeminiPart>>(iter: I) -> Self {
+        GeminiContent {
+            role: None,
+            parts: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<GeminiPart> for GeminiContent {
+    fn extend<I: IntoIterator<Item = GeminiPart>>(&mut self, iter: I) {
+        self.parts.extend(iter);
+    }
+}
+
+impl<'a> IntoIterator for &'a GeminiContent {
+    type Item = &'a GeminiPart;
+    type IntoIter = std::slice::Iter<'a, GeminiPart>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.parts.iter()
+    }
+}
+
+impl IntoIterator for GeminiContent {
+    type Item = GeminiPart;
+    type IntoIter = std::vec::IntoIter<GeminiPart>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.parts.into_iter()
+    }
+}
+
+impl Index<usize> for GeminiContent {
+    type Output = GeminiPart;
+
+    fn index(&self, index: usize) -> &GeminiPart {
+        &self.parts[index]
+    }
+}
+
+// ===
+// TESTS: GeminiContent
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeminiFunctionResponse, GeminiPromptSystem};
+    use serde_json::json;
+
+    #[test]
+    fn test_role_constructors() {
+        assert_eq!(
+            GeminiContent::user("Hi").to_json(),
+            json!({ "role": "user", "parts": [{ "text": "Hi" }] })
+        );
+        assert_eq!(
+            GeminiContent::model("Hello!").to_json(),
+            json!({ "role": "model", "parts": [{ "text": "Hello!" }] })
+        );
+        assert_eq!(
+            GeminiContent::model("Hello!").role(),
+            Some(GeminiRole::Model)
+        );
+
+        let response = GeminiFunctionResponse::new("get_time", json!({ "time": "12:00" }));
+        let tool = GeminiContent::tool(GeminiPart::FunctionResponse(response));
+        assert_eq!(tool.role(), Some(GeminiRole::Tool));
+        assert!(matches!(tool.parts[0], GeminiPart::FunctionResponse(_)));
+    }
+
+    #[test]
+    fn test_from_conversions() {
+        let content = GeminiContent::from("Hi");
+        assert_eq!(content.to_json(), json!({ "parts": [{ "text": "Hi" }] }));
+
+        let content = GeminiContent::from(&GeminiPromptSystem::new("Be brief."));
+        assert_eq!(
+            content.to_json(),
+            json!({ "role": "system", "parts": [{ "text": "Be brief." }] })
+        );
+    }
+}
@@ -6,7 +6,7 @@ use serde_json::Value as JsonValue;
 // STRUCT: GeminiToolDeclaration
 // ===
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GeminiToolDeclaration {
     #[serde(rename = "functionDeclarations", skip_serializing_if = "Vec::is_empty")]
     function_declarations: Vec<GeminiFunctionDeclaration>,
@@ -33,11 +33,21 @@ impl GeminiToolDeclaration {
     }
 }
 
+// ===
+// TRAIT: Default for GeminiToolDeclaration
+// ===
+
+impl Default for GeminiToolDeclaration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ===
 // STRUCT: GeminiFunctionParameters
 // ===
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GeminiFunctionDeclaration {
     pub name: String,
     pub description: String,
@@ -50,7 +60,7 @@ pub struct GeminiFunctionDeclaration {
 
 impl GeminiFunctionDeclaration {
     pub fn new(name: &str, description: &str, parameters: RootSchema) -> Self {
-        let parameters = serde_json::to_value(parameters).unwrap();
+        let parameters = schema_to_json(parameters);
 
         Self {
             name: name.to_string(),
@@ -78,11 +88,81 @@ impl GeminiFunctionDeclaration {
     }
 
     pub fn parameters(mut self, parameters: RootSchema) -> Self {
-        self.parameters = serde_json::to_value(parameters).unwrap();
+        self.parameters = schema_to_json(parameters);
         self
     }
 }
 
+/// Converts a schema to JSON, which cannot fail: a schema holds only strings,
+/// numbers, flags and maps with string keys.
+fn schema_to_json(schema: RootSchema) -> JsonValue {
+    serde_json::to_value(schema).expect("a JSON schema always serializes to JSON")
+}
+
+// ===
+// ENUM: GeminiFunctionCallingMode
+// ===
+
+/// Whether a Gemini model may, must or must not call functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum GeminiFunctionCallingMode {
+    /// The model decides between text and a function call.
+    #[default]
+    Auto,
+    /// The model must call a function, e.g. for deterministic extraction.
+    Any,
+    /// The model must not call functions, even though tools are declared.
+    None,
+}
+
+// ===
+// STRUCT: GeminiToolConfig
+// ===
+
+/// The `toolConfig` of a Gemini request, which controls function calling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeminiToolConfig {
+    #[serde(rename = "functionCallingConfig")]
+    pub function_calling_config: GeminiFunctionCallingConfig,
+}
+
+impl GeminiToolConfig {
+    /// Creates a tool config with the given function calling mode.
+    ///
+    /// # Arguments
+    /// * `mode` - Whether the model may, must or must not call functions
+    /// * `allowed_function_names` - The functions the model may call in `Any` mode;
+    ///   empty allows all declared functions
+    pub fn new(mode: GeminiFunctionCallingMode, allowed_function_names: &[&str]) -> Self {
+        Self {
+            function_calling_config: GeminiFunctionCallingConfig {
+                mode,
+                allowed_function_names: allowed_function_names
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            },
+        }
+    }
+}
+
+// ===
+// STRUCT: GeminiFunctionCallingConfig
+// ===
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeminiFunctionCallingConfig {
+    pub mode: GeminiFunctionCallingMode,
+
+    #[serde(
+        rename = "allowedFunctionNames",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub allowed_function_names: Vec<String>,
+}
+
 // ===
 // TESTS: GeminiFunctionDeclaration
 // ===
@@ -145,6 +225,26 @@ mod tests {
         println!("{}", pretty);
     }
 
+    #[test]
+    fn test_gemini_tool_config() {
+        let config = GeminiToolConfig::new(GeminiFunctionCallingMode::Any, &["schedule_meeting"]);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::json!({
+                "functionCallingConfig": {
+                    "mode": "ANY",
+                    "allowedFunctionNames": ["schedule_meeting"]
+                }
+            })
+        );
+
+        let config = GeminiToolConfig::new(GeminiFunctionCallingMode::None, &[]);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::json!({ "functionCallingConfig": { "mode": "NONE" } })
+        );
+    }
+
     #[tokio::test]
     async fn test_gemini_request_with_tools() {
         // Create the function declaration.
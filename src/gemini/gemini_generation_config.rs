@@ -0,0 +1,154 @@
+use crate::OllamaOptions;
+use serde::{Deserialize, Serialize};
+
+// ===
+// STRUCT: GeminiGenerationConfig
+// ===
+
+/// Configuration options for content generation in a Gemini request.
+///
+/// Serialized as the `generationConfig` field of the request; unset options are omitted.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiGenerationConfig {
+    /// Sequences that stop generation when the model produces them.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub stop_sequences: Vec<String>,
+
+    /// The sampling temperature.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub temperature: Option<f32>,
+
+    /// The cumulative probability of the tokens sampled from at each step.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub top_p: Option<f32>,
+
+    /// The number of most likely tokens sampled from at each step.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub top_k: Option<u32>,
+
+    /// The maximum number of tokens to generate.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_output_tokens: Option<u32>,
+
+    /// The random seed used for decoding, for reproducible output.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub seed: Option<i32>,
+
+    /// The number of candidate responses to generate.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub candidate_count: Option<u32>,
+
+    /// The kinds of output the model may return, e.g. "TEXT" and "IMAGE".
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub response_modalities: Vec<String>,
+}
+
+// ===
+// PUBLIC: GeminiGenerationConfig
+// ===
+
+impl GeminiGenerationConfig {
+    /// Creates a new GeminiGenerationConfig with no options set.
+    ///
+    /// # Returns
+    /// * A new, empty GeminiGenerationConfig
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the stop sequences.
+    ///
+    /// The stop sequences are also enforced on streamed output by the client.
+    ///
+    /// # Arguments
+    /// * `stop_sequences` - The sequences that end generation
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_stop_sequences(&mut self, stop_sequences: &[&str]) -> &mut Self {
+        self.stop_sequences = stop_sequences.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Sets the number of candidate responses to generate.
+    ///
+    /// # Arguments
+    /// * `count` - The number of candidates; read them with `GeminiResponse::texts`
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_candidate_count(&mut self, count: u32) -> &mut Self {
+        self.candidate_count = Some(count);
+        self
+    }
+
+    /// Sets the kinds of output the model may return.
+    ///
+    /// # Arguments
+    /// * `modalities` - The modalities, e.g. `&["TEXT", "IMAGE"]` for image generation models
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_response_modalities(&mut self, modalities: &[&str]) -> &mut Self {
+        self.response_modalities = modalities.iter().map(|s| s.to_string()).collect();
+        self
+    }
+}
+
+// ===
+// TRAIT: GeminiGenerationConfig (From<&OllamaOptions>)
+// ===
+
+/// Converts the sampling options shared by both APIs, so one preset can drive either.
+impl From<&OllamaOptions> for GeminiGenerationConfig {
+    fn from(options: &OllamaOptions) -> Self {
+        Self {
+            stop_sequences: options.stop().map(<[String]>::to_vec).unwrap_or_default(),
+            temperature: options.temperature(),
+            top_p: options.top_p(),
+            top_k: options.top_k(),
+            max_output_tokens: options.num_predict().and_then(|n| u32::try_from(n).ok()),
+            seed: options.seed(),
+            candidate_count: None,
+            response_modalities: Vec::new(),
+        }
+    }
+}
+
+// ===
+// TESTS: GeminiGenerationConfig
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_stop_sequences() {
+        let mut config = GeminiGenerationConfig::new();
+        assert_eq!(serde_json::to_value(&config).unwrap(), json!({}));
+
+        config.set_stop_sequences(&["END"]).set_candidate_count(2);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            json!({ "stopSequences": ["END"], "candidateCount": 2 })
+        );
+    }
+
+    #[test]
+    fn test_from_ollama_options() {
+        let mut options = OllamaOptions::new();
+        options
+            .set_temperature(0.5)
+            .set_top_k(20)
+            .set_num_predict(-1);
+
+        let config = GeminiGenerationConfig::from(&options);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            json!({ "temperature": 0.5, "topK": 20 })
+        );
+    }
+}
@@ -0,0 +1,696 @@
+use crate::GeminiUsageMetadata;
+#[cfg(feature = "audit")]
+use crate::{AuditLog, AuditRecord};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::error::Error;
+#[cfg(feature = "audit")]
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+const GEMINI_LIVE_URL: &str = "wss://generativelanguage.googleapis.com/ws/google.ai.generativelanguage.v1beta.GenerativeService.BidiGenerateContent";
+
+// ===
+// STRUCT: GeminiLiveConfig
+// ===
+
+/// The setup of a Gemini Live session, sent once when the session connects.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeminiLiveConfig {
+    model: String,
+    response_modality: String,
+    system_instruction: Option<String>,
+    voice: Option<String>,
+    tools: Option<JsonValue>,
+}
+
+impl GeminiLiveConfig {
+    /// Creates a setup for the given model that responds with text.
+    ///
+    /// # Arguments
+    /// * `model` - The name of a Live model, e.g. "gemini-2.0-flash-live-001"
+    ///
+    /// # Returns
+    /// * A new GeminiLiveConfig instance
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            response_modality: "TEXT".to_string(),
+            system_instruction: None,
+            voice: None,
+            tools: None,
+        }
+    }
+
+    /// Sets whether the model responds with "TEXT" or "AUDIO".
+    ///
+    /// # Arguments
+    /// * `modality` - The response modality
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_response_modality(&mut self, modality: &str) -> &mut Self {
+        self.response_modality = modality.to_string();
+        self
+    }
+
+    /// Sets the system instruction of the session.
+    ///
+    /// # Arguments
+    /// * `instruction` - The system instruction text
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_system_instruction(&mut self, instruction: &str) -> &mut Self {
+        self.system_instruction = Some(instruction.to_string());
+        self
+    }
+
+    /// Sets the prebuilt voice used for audio responses, e.g. "Puck".
+    ///
+    /// # Arguments
+    /// * `voice` - The voice name
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_voice(&mut self, voice: &str) -> &mut Self {
+        self.voice = Some(voice.to_string());
+        self
+    }
+
+    /// Sets the tools the model may call, in the Gemini `tools` format.
+    ///
+    /// # Arguments
+    /// * `tools` - The tool declarations, e.g. serialized from `ToolRegistry::to_gemini_tool`
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_tools(&mut self, tools: JsonValue) -> &mut Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Converts the setup to the `setup` message of the Live API.
+    ///
+    /// # Returns
+    /// * The setup message as JSON
+    pub fn to_json(&self) -> JsonValue {
+        let mut generation_config = json!({ "responseModalities": [self.response_modality] });
+        if let Some(voice) = &self.voice {
+            generation_config["speechConfig"] = json!({
+                "voiceConfig": { "prebuiltVoiceConfig": { "voiceName": voice } }
+            });
+        }
+
+        let mut setup = json!({
+            "model": format!("models/{}", self.model.trim_start_matches("models/")),
+            "generationConfig": generation_config,
+        });
+        if let Some(instruction) = &self.system_instruction {
+            setup["systemInstruction"] = json!({ "parts": [{ "text": instruction }] });
+        }
+        if let Some(tools) = &self.tools {
+            setup["tools"] = tools.clone();
+        }
+
+        json!({ "setup": setup })
+    }
+}
+
+// ===
+// ENUM: GeminiLiveEvent
+// ===
+
+/// An output event received from a Gemini Live session.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeminiLiveEvent {
+    /// A chunk of generated text.
+    Text(String),
+
+    /// A chunk of generated audio.
+    Audio {
+        /// The decoded audio bytes, e.g. 16-bit PCM.
+        data: Vec<u8>,
+        /// The audio format, e.g. "audio/pcm;rate=24000".
+        mime_type: String,
+    },
+
+    /// The model asks for one or more tools to be called.
+    ToolCall(Vec<GeminiLiveToolCall>),
+
+    /// The model finished its turn.
+    TurnComplete,
+
+    /// The model's turn was interrupted by new user input.
+    Interrupted,
+
+    /// Token counts of the session so far.
+    Usage(GeminiUsageMetadata),
+
+    /// The server will close the connection soon.
+    GoAway,
+
+    /// Any other server message, as received.
+    Other(JsonValue),
+}
+
+/// A tool call requested by the model during a Live session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeminiLiveToolCall {
+    /// The id to send back with the response, if the server assigned one.
+    pub id: Option<String>,
+    /// The name of the tool.
+    pub name: String,
+    /// The call arguments.
+    pub args: JsonValue,
+}
+
+// ===
+// STRUCT: GeminiLiveSession
+// ===
+
+/// A bidirectional streaming session with the Gemini Live API.
+///
+/// The session holds a WebSocket connection to the `BidiGenerateContent` endpoint.
+/// Text and audio input can be sent at any time, while output arrives as a stream
+/// of `GeminiLiveEvent`s read with `next_event`.
+pub struct GeminiLiveSession {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    events: VecDeque<GeminiLiveEvent>,
+    #[cfg(feature = "audit")]
+    model: String,
+    #[cfg(feature = "audit")]
+    audit: Option<LiveAudit>,
+}
+
+impl GeminiLiveSession {
+    /// Connects to the Gemini Live API and sends the session setup.
+    ///
+    /// # Arguments
+    /// * `api_key` - The API key to use
+    /// * `config` - The session setup
+    ///
+    /// # Returns
+    /// * `Ok(GeminiLiveSession)` once the server has accepted the setup
+    /// * `Err(Box<dyn Error>)` if connecting failed or the setup was rejected
+    pub async fn connect(api_key: &str, config: &GeminiLiveConfig) -> Result<Self, Box<dyn Error>> {
+        let url = format!("{GEMINI_LIVE_URL}?key={api_key}");
+        Self::connect_url(&url, config).await
+    }
+
+    /// Connects to a Live endpoint at a custom URL, e.g. a proxy, and sends the session setup.
+    ///
+    /// # Arguments
+    /// * `url` - The WebSocket URL, including any authentication parameters
+    /// * `config` - The session setup
+    ///
+    /// # Returns
+    /// * `Ok(GeminiLiveSession)` once the server has accepted the setup
+    /// * `Err(Box<dyn Error>)` if connecting failed or the setup was rejected
+    pub async fn connect_url(url: &str, config: &GeminiLiveConfig) -> Result<Self, Box<dyn Error>> {
+        // Don't leak the API key in the URL of connection errors.
+        let (socket, _) = connect_async(url)
+            .await
+            .map_err(|err| format!("failed to connect to the Live API: {err}"))?;
+        let mut session = Self {
+            socket,
+            events: VecDeque::new(),
+            #[cfg(feature = "audit")]
+            model: config.model.clone(),
+            #[cfg(feature = "audit")]
+            audit: None,
+        };
+
+        session.send_json(config.to_json()).await?;
+        match session.read_message().await? {
+            Some(message) if message.get("setupComplete").is_some() => Ok(session),
+            Some(message) => Err(format!("unexpected reply to the Live setup: {message}").into()),
+            None => Err("the Live API closed the connection during setup".into()),
+        }
+    }
+
+    /// Records each turn of the session in an audit log.
+    ///
+    /// A turn runs from the first message sent after the previous turn until the
+    /// model completes or is interrupted. Its record holds the messages sent and
+    /// received; a turn cut short by the session closing is recorded with an error.
+    ///
+    /// # Arguments
+    /// * `audit_log` - The log to write the turns to
+    #[cfg(feature = "audit")]
+    pub fn set_audit_log(&mut self, audit_log: AuditLog) {
+        self.audit = Some(LiveAudit {
+            audit_log,
+            sent: Vec::new(),
+            received: Vec::new(),
+            start: None,
+        });
+    }
+
+    /// Sends a complete user turn of text; the model responds once it arrives.
+    ///
+    /// # Arguments
+    /// * `text` - The user text
+    ///
+    /// # Returns
+    /// * `Ok(())` if the message was sent
+    pub async fn send_text(&mut self, text: &str) -> Result<(), Box<dyn Error>> {
+        self.send_json(json!({
+            "clientContent": {
+                "turns": [{ "role": "user", "parts": [{ "text": text }] }],
+                "turnComplete": true
+            }
+        }))
+        .await
+    }
+
+    /// Streams a chunk of audio input, e.g. from a microphone.
+    ///
+    /// The server detects the end of speech itself and responds when the user stops talking.
+    ///
+    /// # Arguments
+    /// * `data` - Raw little-endian 16-bit PCM audio
+    /// * `sample_rate` - The sample rate of the audio, e.g. 16000
+    ///
+    /// # Returns
+    /// * `Ok(())` if the chunk was sent
+    pub async fn send_audio(
+        &mut self,
+        data: &[u8],
+        sample_rate: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        self.send_json(json!({
+            "realtimeInput": {
+                "audio": {
+                    "data": BASE64.encode(data),
+                    "mimeType": format!("audio/pcm;rate={sample_rate}")
+                }
+            }
+        }))
+        .await
+    }
+
+    /// Signals that the audio input stream has ended, e.g. the microphone was turned off.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the message was sent
+    pub async fn end_audio(&mut self) -> Result<(), Box<dyn Error>> {
+        self.send_json(json!({ "realtimeInput": { "audioStreamEnd": true } }))
+            .await
+    }
+
+    /// Sends the result of a tool call requested with `GeminiLiveEvent::ToolCall`.
+    ///
+    /// # Arguments
+    /// * `call` - The tool call that was executed
+    /// * `response` - The tool result as JSON
+    ///
+    /// # Returns
+    /// * `Ok(())` if the message was sent
+    pub async fn send_tool_response(
+        &mut self,
+        call: &GeminiLiveToolCall,
+        response: JsonValue,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut function_response = json!({ "name": call.name, "response": response });
+        if let Some(id) = &call.id {
+            function_response["id"] = json!(id);
+        }
+
+        self.send_json(json!({ "toolResponse": { "functionResponses": [function_response] } }))
+            .await
+    }
+
+    /// Reads the next output event.
+    ///
+    /// # Returns
+    /// * `Ok(Some(GeminiLiveEvent))` with the next event
+    /// * `Ok(None)` if the server closed the session
+    /// * `Err(Box<dyn Error>)` if the connection failed
+    pub async fn next_event(&mut self) -> Result<Option<GeminiLiveEvent>, Box<dyn Error>> {
+        while self.events.is_empty() {
+            let Some(message) = self.read_message().await? else {
+                return Ok(None);
+            };
+            self.events.extend(parse_events(message));
+        }
+
+        Ok(self.events.pop_front())
+    }
+
+    /// Closes the session.
+    pub async fn close(mut self) -> Result<(), Box<dyn Error>> {
+        self.socket.close(None).await?;
+        Ok(())
+    }
+
+    /// Sends one JSON message.
+    async fn send_json(&mut self, message: JsonValue) -> Result<(), Box<dyn Error>> {
+        self.socket.send(Message::text(message.to_string())).await?;
+
+        #[cfg(feature = "audit")]
+        if let Some(audit) = &mut self.audit {
+            audit.start.get_or_insert_with(Instant::now);
+            audit.sent.push(message);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the next JSON message, writing the audit record of the turn once it ends.
+    async fn read_message(&mut self) -> Result<Option<JsonValue>, Box<dyn Error>> {
+        let result = self.read_socket().await;
+
+        #[cfg(feature = "audit")]
+        if let Some(audit) = &mut self.audit {
+            match &result {
+                Ok(Some(message)) => {
+                    audit.received.push(message.clone());
+                    let content = message.get("serverContent");
+                    let ended = ["turnComplete", "interrupted"]
+                        .iter()
+                        .any(|field| content.and_then(|c| c.get(field)) == Some(&json!(true)));
+                    if ended {
+                        audit.write(&self.model, None);
+                    }
+                }
+                Ok(None) => audit.write(&self.model, Some("the session closed during the turn")),
+                Err(error) => audit.write(&self.model, Some(&error.to_string())),
+            }
+        }
+
+        result
+    }
+
+    /// Reads the next JSON message, skipping control frames.
+    async fn read_socket(&mut self) -> Result<Option<JsonValue>, Box<dyn Error>> {
+        while let Some(message) = self.socket.next().await {
+            match message? {
+                // The server sends JSON in binary frames as well as text frames.
+                Message::Text(text) => return Ok(Some(serde_json::from_str(&text)?)),
+                Message::Binary(bytes) => return Ok(Some(serde_json::from_slice(&bytes)?)),
+                Message::Close(_) => return Ok(None),
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+// ===
+// TRAIT: GeminiLiveSession (Drop)
+// ===
+
+#[cfg(feature = "audit")]
+impl Drop for GeminiLiveSession {
+    /// Writes the audit record of a turn still running when the session is dropped.
+    fn drop(&mut self) {
+        if let Some(audit) = &mut self.audit {
+            audit.write(&self.model, Some("the session closed during the turn"));
+        }
+    }
+}
+
+/// The audit log of a Live session and the messages of the current turn.
+#[cfg(feature = "audit")]
+struct LiveAudit {
+    audit_log: AuditLog,
+    sent: Vec<JsonValue>,
+    received: Vec<JsonValue>,
+    start: Option<Instant>,
+}
+
+#[cfg(feature = "audit")]
+impl LiveAudit {
+    /// Writes the record of the current turn, if one has started, and starts the next.
+    fn write(&mut self, model: &str, error: Option<&str>) {
+        let Some(start) = self.start.take() else {
+            return;
+        };
+
+        let request = json!({ "model": model, "messages": std::mem::take(&mut self.sent) });
+        let received = std::mem::take(&mut self.received);
+        let mut record =
+            AuditRecord::new("gemini", "BidiGenerateContent", request, start.elapsed());
+        record.model = Some(model.to_string());
+        record.error = error.map(str::to_string);
+
+        // The Live API reports usage on the message that completes the turn.
+        let usage = received
+            .iter()
+            .rev()
+            .find_map(|message| message.get("usageMetadata"));
+        let count = |fields: &[&str]| {
+            fields
+                .iter()
+                .find_map(|field| usage?.get(field)?.as_u64())
+                .map(|count| count as u32)
+        };
+        record.prompt_tokens = count(&["promptTokenCount"]);
+        record.completion_tokens = count(&["responseTokenCount", "candidatesTokenCount"]);
+        record.response = Some(json!({ "messages": received }));
+
+        // An audit log that cannot be written does not fail the session.
+        let _ = self.audit_log.record(record);
+    }
+}
+
+/// Splits a server message into events, in order.
+fn parse_events(message: JsonValue) -> Vec<GeminiLiveEvent> {
+    let mut events = Vec::new();
+
+    if let Some(content) = message.get("serverContent") {
+        let parts = content
+            .pointer("/modelTurn/parts")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten();
+        for part in parts {
+            if let Some(text) = part.get("text").and_then(JsonValue::as_str) {
+                events.push(GeminiLiveEvent::Text(text.to_string()));
+            } else if let Some(inline) = part.get("inlineData")
+                && let Some(data) = inline.get("data").and_then(JsonValue::as_str)
+                && let Ok(data) = BASE64.decode(data)
+            {
+                let mime_type = inline.get("mimeType").and_then(JsonValue::as_str);
+                events.push(GeminiLiveEvent::Audio {
+                    data,
+                    mime_type: mime_type.unwrap_or_default().to_string(),
+                });
+            }
+        }
+
+        if content.get("interrupted") == Some(&json!(true)) {
+            events.push(GeminiLiveEvent::Interrupted);
+        }
+        if content.get("turnComplete") == Some(&json!(true)) {
+            events.push(GeminiLiveEvent::TurnComplete);
+        }
+    }
+
+    if let Some(calls) = message
+        .pointer("/toolCall/functionCalls")
+        .and_then(JsonValue::as_array)
+    {
+        let calls = calls
+            .iter()
+            .map(|call| GeminiLiveToolCall {
+                id: call
+                    .get("id")
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string),
+                name: call["name"].as_str().unwrap_or_default().to_string(),
+                args: call.get("args").cloned().unwrap_or(json!({})),
+            })
+            .collect();
+        events.push(GeminiLiveEvent::ToolCall(calls));
+    }
+
+    if let Some(usage) = message.get("usageMetadata")
+        && let Ok(usage) = serde_json::from_value(usage.clone())
+    {
+        events.push(GeminiLiveEvent::Usage(usage));
+    }
+
+    if message.get("goAway").is_some() {
+        events.push(GeminiLiveEvent::GoAway);
+    }
+
+    if events.is_empty() && message.get("serverContent").is_none() {
+        events.push(GeminiLiveEvent::Other(message));
+    }
+
+    events
+}
+
+// ===
+// TESTS: GeminiLiveSession
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Accepts one connection, answers the setup, then replies to the first client message.
+    async fn live_server(
+        replies: Vec<JsonValue>,
+    ) -> (String, tokio::task::JoinHandle<Vec<JsonValue>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut received = Vec::new();
+
+            for reply in std::iter::once(json!({ "setupComplete": {} })).chain(replies) {
+                let Some(Ok(Message::Text(text))) = socket.next().await else {
+                    break;
+                };
+                received.push(serde_json::from_str(&text).unwrap());
+                // Reply in a binary frame, as the Live API does.
+                socket
+                    .send(Message::binary(reply.to_string().into_bytes()))
+                    .await
+                    .unwrap();
+            }
+
+            socket.close(None).await.unwrap();
+            received
+        });
+
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_send_text_and_receive_events() {
+        let (url, server) = live_server(vec![json!({
+            "serverContent": {
+                "modelTurn": { "parts": [{ "text": "Hello" }, { "text": " there!" }] },
+                "turnComplete": true
+            },
+            "usageMetadata": { "promptTokenCount": 3, "totalTokenCount": 5 }
+        })])
+        .await;
+
+        let mut config = GeminiLiveConfig::new("gemini-2.0-flash-live-001");
+        config.set_system_instruction("Be brief.");
+        let mut session = GeminiLiveSession::connect_url(&url, &config).await.unwrap();
+        session.send_text("Hi").await.unwrap();
+
+        let mut events = Vec::new();
+        while let Some(event) = session.next_event().await.unwrap() {
+            events.push(event);
+        }
+
+        assert_eq!(events[0], GeminiLiveEvent::Text("Hello".to_string()));
+        assert_eq!(events[1], GeminiLiveEvent::Text(" there!".to_string()));
+        assert_eq!(events[2], GeminiLiveEvent::TurnComplete);
+        assert!(
+            matches!(&events[3], GeminiLiveEvent::Usage(usage) if usage.prompt_token_count == Some(3))
+        );
+
+        let received = server.await.unwrap();
+        assert_eq!(
+            received[0]["setup"]["model"],
+            "models/gemini-2.0-flash-live-001"
+        );
+        assert_eq!(
+            received[0]["setup"]["systemInstruction"]["parts"][0]["text"],
+            "Be brief."
+        );
+        assert_eq!(
+            received[1]["clientContent"]["turns"][0]["parts"][0]["text"],
+            "Hi"
+        );
+    }
+
+    #[cfg(feature = "audit")]
+    #[tokio::test]
+    async fn test_turns_are_audited() {
+        use std::sync::{Arc, Mutex};
+
+        let (url, server) = live_server(vec![json!({
+            "serverContent": {
+                "modelTurn": { "parts": [{ "text": "Hello!" }] },
+                "turnComplete": true
+            },
+            "usageMetadata": { "promptTokenCount": 3, "responseTokenCount": 2 }
+        })])
+        .await;
+
+        // Capture the records as the redactors see them.
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let captured = records.clone();
+        let mut audit_log = AuditLog::new(std::io::sink());
+        audit_log.add_redactor(move |record| captured.lock().unwrap().push(record.clone()));
+
+        let config = GeminiLiveConfig::new("gemini-2.0-flash-live-001");
+        let mut session = GeminiLiveSession::connect_url(&url, &config).await.unwrap();
+        session.set_audit_log(audit_log);
+        session.send_text("Hi").await.unwrap();
+        while session.next_event().await.unwrap().is_some() {}
+        drop(session);
+        server.await.unwrap();
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.endpoint, "BidiGenerateContent");
+        assert_eq!(record.model.as_deref(), Some("gemini-2.0-flash-live-001"));
+        assert_eq!(
+            record.request["messages"][0]["clientContent"]["turns"][0]["parts"][0]["text"],
+            "Hi"
+        );
+        let response = record.response.as_ref().unwrap();
+        assert_eq!(
+            response["messages"][0]["serverContent"]["modelTurn"]["parts"][0]["text"],
+            "Hello!"
+        );
+        assert_eq!(record.prompt_tokens, Some(3));
+        assert_eq!(record.completion_tokens, Some(2));
+        assert_eq!(record.error, None);
+    }
+
+    #[test]
+    fn test_parse_audio_and_tool_calls() {
+        let events = parse_events(json!({
+            "serverContent": {
+                "modelTurn": { "parts": [{
+                    "inlineData": { "mimeType": "audio/pcm;rate=24000", "data": BASE64.encode([1, 2, 3]) }
+                }] },
+                "interrupted": true
+            }
+        }));
+        assert_eq!(
+            events,
+            [
+                GeminiLiveEvent::Audio {
+                    data: vec![1, 2, 3],
+                    mime_type: "audio/pcm;rate=24000".to_string()
+                },
+                GeminiLiveEvent::Interrupted
+            ]
+        );
+
+        let events = parse_events(json!({
+            "toolCall": { "functionCalls": [{ "id": "c1", "name": "get_time", "args": {} }] }
+        }));
+        assert_eq!(
+            events,
+            [GeminiLiveEvent::ToolCall(vec![GeminiLiveToolCall {
+                id: Some("c1".to_string()),
+                name: "get_time".to_string(),
+                args: json!({})
+            }])]
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Translation between Gemini requests and responses and the OpenAI chat
+//! completions format, used when a `Gemini` client talks to the
+//! OpenAI-compatible endpoint; see `GeminiTransport::OpenAi`.
+
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use std::collections::VecDeque;
+
+/// Translates a `generateContent` request into a chat completions request.
+///
+/// # Arguments
+///
+/// * `model` - The model to name in the request.
+/// * `request` - The request in the Gemini format.
+/// * `stream` - Whether to ask for a streamed response.
+///
+/// # Returns
+///
+/// * `JsonValue` - The request in the OpenAI format.
+pub(crate) fn to_openai_request(model: &str, request: &JsonValue, stream: bool) -> JsonValue {
+    let mut messages = Vec::new();
+    let mut call_ids = VecDeque::new();
+    let mut next_call_id = 0;
+
+    if let Some(system) = request
+        .get("systemInstruction")
+        .or_else(|| request.get("system_instruction"))
+    {
+        messages.push(json!({ "role": "system", "content": part_texts(system) }));
+    }
+
+    for content in request["contents"].as_array().into_iter().flatten() {
+        let role = match content["role"].as_str() {
+            Some("model") => "assistant",
+            Some("system") => "system",
+            _ => "user",
+        };
+
+        let mut parts = Vec::new();
+        let mut tool_calls = Vec::new();
+        for part in content["parts"].as_array().into_iter().flatten() {
+            if let Some(text) = part["text"].as_str() {
+                parts.push(json!({ "type": "text", "text": text }));
+            } else if let Some(data) = part.get("inlineData").or_else(|| part.get("inline_data")) {
+                let mime_type = data
+                    .get("mimeType")
+                    .or_else(|| data.get("mime_type"))
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or("application/octet-stream");
+                let url = format!(
+                    "data:{mime_type};base64,{}",
+                    data["data"].as_str().unwrap_or("")
+                );
+                parts.push(json!({ "type": "image_url", "image_url": { "url": url } }));
+            } else if let Some(call) = part.get("functionCall") {
+                let id = format!("call_{next_call_id}");
+                next_call_id += 1;
+                call_ids.push_back(id.clone());
+                tool_calls.push(json!({
+                    "id": id,
+                    "type": "function",
+                    "function": {
+                        "name": call["name"],
+                        "arguments": call["args"].to_string(),
+                    }
+                }));
+            } else if let Some(response) = part
+                .get("functionResponse")
+                .or_else(|| part.get("function_response"))
+            {
+                // Answers are matched to the calls they follow, in order.
+                let id = call_ids
+                    .pop_front()
+                    .unwrap_or_else(|| format!("call_{next_call_id}"));
+                messages.push(json!({
+                    "role": "tool",
+                    "tool_call_id": id,
+                    "content": response["response"].to_string(),
+                }));
+            }
+        }
+
+        if parts.is_empty() && tool_calls.is_empty() {
+            continue;
+        }
+
+        // Plain text is sent as a string, which every model accepts.
+        let text_only = parts.iter().all(|part| part["type"] == "text");
+        let mut message = json!({ "role": role });
+        message["content"] = if text_only {
+            let texts: Vec<&str> = parts.iter().filter_map(|p| p["text"].as_str()).collect();
+            JsonValue::from(texts.concat())
+        } else {
+            JsonValue::from(parts)
+        };
+        if !tool_calls.is_empty() {
+            message["tool_calls"] = JsonValue::from(tool_calls);
+        }
+        messages.push(message);
+    }
+
+    let mut openai = json!({ "model": model, "messages": messages });
+    if stream {
+        openai["stream"] = JsonValue::from(true);
+    }
+
+    let tools: Vec<JsonValue> = request["tools"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|tool| {
+            tool["functionDeclarations"]
+                .as_array()
+                .into_iter()
+                .flatten()
+        })
+        .map(|function| json!({ "type": "function", "function": function }))
+        .collect();
+    if !tools.is_empty() {
+        openai["tools"] = JsonValue::from(tools);
+    }
+
+    let config = &request["generationConfig"];
+    let options = [
+        ("temperature", "temperature"),
+        ("topP", "top_p"),
+        ("maxOutputTokens", "max_tokens"),
+        ("seed", "seed"),
+        ("candidateCount", "n"),
+        ("stopSequences", "stop"),
+    ];
+    for (gemini, openai_name) in options {
+        if let Some(value) = config.get(gemini) {
+            openai[openai_name] = value.clone();
+        }
+    }
+
+    openai
+}
+
+/// Translates a chat completions response, or one streamed chunk of it, into the
+/// `generateContent` format.
+///
+/// Error bodies, which both endpoints send as `{"error": {...}}`, pass through
+/// unchanged, as does anything that is not a chat completion.
+///
+/// # Arguments
+///
+/// * `response` - The response in the OpenAI format.
+///
+/// # Returns
+///
+/// * `JsonValue` - The response in the Gemini format.
+pub(crate) fn from_openai_response(response: &JsonValue) -> JsonValue {
+    // The OpenAI-compatible endpoint wraps some errors in a list.
+    if let Some(error) = response.as_array().and_then(|list| list.first())
+        && error.get("error").is_some()
+    {
+        return error.clone();
+    }
+    let Some(choices) = response["choices"].as_array() else {
+        return response.clone();
+    };
+
+    let candidates: Vec<JsonValue> = choices
+        .iter()
+        .map(|choice| {
+            let message = choice.get("message").or_else(|| choice.get("delta"));
+            let mut parts = Vec::new();
+            if let Some(text) = message.and_then(|m| m["content"].as_str()) {
+                parts.push(json!({ "text": text }));
+            }
+            let tool_calls = message.and_then(|m| m["tool_calls"].as_array());
+            for call in tool_calls.into_iter().flatten() {
+                let arguments = &call["function"]["arguments"];
+                let args = arguments
+                    .as_str()
+                    .and_then(|text| serde_json::from_str(text).ok())
+                    .unwrap_or_else(|| arguments.clone());
+                parts.push(json!({
+                    "functionCall": { "name": call["function"]["name"], "args": args }
+                }));
+            }
+
+            let mut candidate = json!({
+                "index": choice["index"],
+                "content": { "role": "model", "parts": parts },
+            });
+            if let Some(reason) = choice["finish_reason"].as_str() {
+                candidate["finishReason"] = JsonValue::from(finish_reason(reason));
+            }
+            candidate
+        })
+        .collect();
+
+    let mut gemini = json!({ "candidates": candidates });
+    if let Some(model) = response.get("model") {
+        gemini["modelVersion"] = model.clone();
+    }
+    if let Some(usage) = response.get("usage").filter(|usage| usage.is_object()) {
+        gemini["usageMetadata"] = json!({
+            "promptTokenCount": usage["prompt_tokens"],
+            "candidatesTokenCount": usage["completion_tokens"],
+            "totalTokenCount": usage["total_tokens"],
+        });
+    }
+    gemini
+}
+
+/// Maps an OpenAI finish reason to its Gemini name.
+fn finish_reason(reason: &str) -> &str {
+    match reason {
+        "stop" | "tool_calls" | "function_call" => "STOP",
+        "length" => "MAX_TOKENS",
+        "content_filter" => "SAFETY",
+        other => other,
+    }
+}
+
+/// Joins the text parts of a Gemini content object.
+fn part_texts(content: &JsonValue) -> String {
+    content["parts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|part| part["text"].as_str())
+        .collect()
+}
+
+// ===
+// TESTS: gemini_openai
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GeminiFunctionResponse, GeminiPart, GeminiRequest, GeminiResponse};
+
+    #[test]
+    fn test_request_translation() {
+        let mut request = GeminiRequest::from_str("What's the weather in Paris?");
+        request.add_content(
+            serde_json::from_value(json!({
+                "role": "model",
+                "parts": [{ "functionCall": { "name": "weather", "args": { "city": "Paris" } } }]
+            }))
+            .unwrap(),
+        );
+        request.add_content(crate::GeminiContent::tool(GeminiPart::FunctionResponse(
+            GeminiFunctionResponse::new("weather", json!("sunny")),
+        )));
+        request
+            .generation_config
+            .get_or_insert_with(Default::default)
+            .set_stop_sequences(&["END"]);
+
+        let openai = to_openai_request("gemini-2.0-flash", &request.to_json(), false);
+        assert_eq!(openai["model"], "gemini-2.0-flash");
+        assert_eq!(openai["stop"], json!(["END"]));
+        assert!(openai.get("stream").is_none());
+
+        let messages = openai["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[0],
+            json!({ "role": "user", "content": "What's the weather in Paris?" })
+        );
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(messages[1]["tool_calls"][0]["id"], "call_0");
+        assert_eq!(
+            messages[1]["tool_calls"][0]["function"]["arguments"],
+            r#"{"city":"Paris"}"#
+        );
+        assert_eq!(messages[2]["role"], "tool");
+        assert_eq!(messages[2]["tool_call_id"], "call_0");
+        assert_eq!(messages[2]["content"], r#"{"result":"sunny"}"#);
+    }
+
+    #[test]
+    fn test_response_translation() {
+        let openai = json!({
+            "model": "gemini-2.0-flash",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": "Checking.",
+                    "tool_calls": [{
+                        "id": "call_0",
+                        "type": "function",
+                        "function": { "name": "weather", "arguments": "{\"city\":\"Paris\"}" }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": { "prompt_tokens": 7, "completion_tokens": 3, "total_tokens": 10 }
+        });
+
+        let response: GeminiResponse =
+            serde_json::from_value(from_openai_response(&openai)).unwrap();
+        assert_eq!(response.text(), Some("Checking."));
+        assert_eq!(response.functions()[0].args(), &json!({ "city": "Paris" }));
+        assert_eq!(
+            response.candidate(0).unwrap().finish_reason.as_deref(),
+            Some("STOP")
+        );
+
+        let error = json!([{ "error": { "code": 400, "message": "bad request" } }]);
+        assert_eq!(from_openai_response(&error), error[0]);
+    }
+}
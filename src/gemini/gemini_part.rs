@@ -1,12 +1,16 @@
+use crate::{AudioClip, ImageData};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use serde_json::json;
+use std::io;
 
 // ===
 // STRUCT: GeminiPartCodeExecutable
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiPartCodeExecutable {
     pub language: String,
     pub code: String,
@@ -16,7 +20,7 @@ pub struct GeminiPartCodeExecutable {
 // STRUCT: GeminiPartCode
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiPartCode {
     pub executable_code: GeminiPartCodeExecutable,
 }
@@ -36,7 +40,7 @@ impl GeminiPartCode {
 // STRUCT: GeminiPartText
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiPartText {
     pub text: String,
 }
@@ -45,7 +49,7 @@ pub struct GeminiPartText {
 // STRUCT: GeminiFunctionCall
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiFunctionCall {
     #[serde(rename = "functionCall")]
     function_call: GeminiFunctionCallDetails,
@@ -69,7 +73,7 @@ impl GeminiFunctionCall {
 // STRUCT: GeminiFunctionCallDetails
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiFunctionCallDetails {
     pub name: String,
     pub args: JsonValue,
@@ -79,7 +83,7 @@ pub struct GeminiFunctionCallDetails {
 // STRUCT: GeminiFunctionResponse
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiFunctionResponse {
     // #[serde(rename = "functionResponse")]
     pub function_response: GeminiFunctionResponseDetails,
@@ -104,17 +108,100 @@ impl GeminiFunctionResponse {
 // STRUCT: GeminiFunctionResponseDetails
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiFunctionResponseDetails {
     pub name: String,
     pub response: JsonValue,
 }
 
+// ===
+// STRUCT: GeminiPartInlineData
+// ===
+
+/// Media sent inline with a request or returned inline in a response, e.g. audio or images.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeminiPartInlineData {
+    #[serde(rename = "inlineData", alias = "inline_data")]
+    pub inline_data: GeminiInlineData,
+}
+
+impl GeminiPartInlineData {
+    /// Creates an inline data part from raw bytes.
+    ///
+    /// # Arguments
+    /// * `mime_type` - The media format, e.g. "audio/wav"
+    /// * `data` - The raw bytes, which are base64-encoded
+    ///
+    /// # Returns
+    /// * A new GeminiPartInlineData instance
+    pub fn new(mime_type: &str, data: &[u8]) -> Self {
+        GeminiPartInlineData {
+            inline_data: GeminiInlineData {
+                mime_type: mime_type.to_string(),
+                data: BASE64.encode(data),
+            },
+        }
+    }
+
+    /// Returns the media format of the data.
+    pub fn mime_type(&self) -> &str {
+        &self.inline_data.mime_type
+    }
+
+    /// Returns true if the data is audio.
+    pub fn is_audio(&self) -> bool {
+        self.mime_type().starts_with("audio/")
+    }
+
+    /// Returns true if the data is an image.
+    pub fn is_image(&self) -> bool {
+        self.mime_type().starts_with("image/")
+    }
+
+    /// Decodes the base64 payload into raw bytes.
+    pub fn bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        BASE64.decode(&self.inline_data.data)
+    }
+
+    /// Decodes the payload as an image, checking it against its mime type.
+    ///
+    /// # Returns
+    /// * `Ok(ImageData)` with the decoded image
+    /// * `Err(io::Error)` if the data is not valid base64 or not an image of the declared type
+    pub fn to_image(&self) -> io::Result<ImageData> {
+        ImageData::from_base64(self.mime_type(), &self.inline_data.data)
+    }
+}
+
+impl From<&AudioClip> for GeminiPartInlineData {
+    fn from(clip: &AudioClip) -> Self {
+        Self::new(clip.mime_type(), clip.data())
+    }
+}
+
+impl From<&ImageData> for GeminiPartInlineData {
+    fn from(image: &ImageData) -> Self {
+        Self::new(image.mime_type(), image.data())
+    }
+}
+
+// ===
+// STRUCT: GeminiInlineData
+// ===
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeminiInlineData {
+    #[serde(rename = "mimeType", alias = "mime_type")]
+    pub mime_type: String,
+    /// The base64-encoded bytes.
+    pub data: String,
+}
+
 // ===
 // STRUCT: GeminiPartUnknown
 // ===
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiPartUnknown {
     pub value: JsonValue,
 }
@@ -123,11 +210,47 @@ pub struct GeminiPartUnknown {
 // ENUM: GeminiPart
 // ===
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GeminiPart {
     Code(GeminiPartCode),
     FunctionCall(GeminiFunctionCall),
     FunctionResponse(GeminiFunctionResponse),
+    InlineData(GeminiPartInlineData),
     Text(GeminiPartText),
 }
+
+// ===
+// TESTS: GeminiPart
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_data_round_trip() {
+        let clip = AudioClip::new("audio/wav", vec![1, 2, 3]);
+        let part = GeminiPart::InlineData(GeminiPartInlineData::from(&clip));
+        assert_eq!(
+            serde_json::to_value(&part).unwrap(),
+            json!({ "inlineData": { "mimeType": "audio/wav", "data": "AQID" } })
+        );
+
+        let part: GeminiPart = serde_json::from_value(
+            json!({ "inline_data": { "mime_type": "audio/mp3", "data": "AQID" } }),
+        )
+        .unwrap();
+        let GeminiPart::InlineData(part) = part else {
+            panic!("expected inline data");
+        };
+        assert!(part.is_audio());
+        assert_eq!(part.bytes().unwrap(), vec![1, 2, 3]);
+        assert!(part.to_image().is_err());
+
+        let image = ImageData::new("image/jpeg", vec![0xFF, 0xD8, 0xFF]);
+        let part = GeminiPartInlineData::from(&image);
+        assert!(part.is_image());
+        assert_eq!(part.to_image().unwrap(), image);
+    }
+}
@@ -5,11 +5,13 @@
 /// Represents the role of a content part in a Gemini API request.
 ///
 /// The role defines who or what is responsible for a particular content part.
-/// Gemini supports system, user, and tool roles.
+/// Gemini supports system, user, model, and tool roles; the model role marks the
+/// model's own turns in a conversation history.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GeminiRole {
     System,
     User,
+    Model,
     Tool,
 }
 
@@ -26,6 +28,7 @@ impl GeminiRole {
         match self {
             GeminiRole::System => "system",
             GeminiRole::User => "user",
+            GeminiRole::Model => "model",
             GeminiRole::Tool => "tool",
         }
     }
@@ -37,10 +40,12 @@ impl GeminiRole {
     ///
     /// # Returns
     /// * The corresponding GeminiRole, or None if the string doesn't match
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(role: &str) -> Option<Self> {
         match role.to_lowercase().as_str() {
             "system" => Some(GeminiRole::System),
             "user" => Some(GeminiRole::User),
+            "model" | "assistant" => Some(GeminiRole::Model),
             "tool" => Some(GeminiRole::Tool),
             _ => None,
         }
@@ -55,7 +60,7 @@ impl GeminiRole {
 ///
 /// A prompt consists of text content and an optional role (user, system, or tool)
 /// that determines how the model interprets the content.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GeminiPrompt {
     pub role: Option<GeminiRole>,
     pub text: String,
@@ -91,6 +96,7 @@ impl GeminiPromptSystem {
     ///
     /// # Returns
     /// * A GeminiPrompt with the System role and provided text
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(text: &str) -> GeminiPrompt {
         GeminiPrompt {
             role: Some(GeminiRole::System),
@@ -113,6 +119,7 @@ impl GeminiPromptTool {
     ///
     /// # Returns
     /// * A GeminiPrompt with the Tool role and provided text
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(text: &str) -> GeminiPrompt {
         GeminiPrompt {
             role: Some(GeminiRole::Tool),
@@ -135,6 +142,7 @@ impl GeminiPromptUser {
     ///
     /// # Returns
     /// * A GeminiPrompt with the User role and provided text
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(text: &str) -> GeminiPrompt {
         GeminiPrompt {
             role: Some(GeminiRole::User),
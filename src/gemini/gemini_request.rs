@@ -1,12 +1,16 @@
+use crate::GeminiFunctionCallingMode;
 use crate::GeminiFunctionResponse;
+use crate::GeminiGenerationConfig;
 use crate::GeminiPart;
 use crate::GeminiPrompt;
 use crate::GeminiRole;
+use crate::GeminiToolConfig;
 use crate::GeminiToolDeclaration;
 use crate::{GeminiContent, GeminiResponse};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::fmt;
+use std::ops::Index;
 
 // ===
 // STRUCT: GeminiRequest
@@ -15,13 +19,27 @@ use std::fmt;
 /// Represents a request to the Gemini API.
 ///
 /// Contains a collection of content parts that make up the conversation or prompt.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiRequest {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub contents: Vec<GeminiContent>,
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<GeminiToolDeclaration>,
+
+    #[serde(
+        rename = "toolConfig",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub tool_config: Option<GeminiToolConfig>,
+
+    #[serde(
+        rename = "generationConfig",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub generation_config: Option<GeminiGenerationConfig>,
 }
 
 // ===
@@ -37,6 +55,8 @@ impl GeminiRequest {
         Self {
             contents: Vec::new(),
             tools: Vec::new(),
+            tool_config: None,
+            generation_config: None,
         }
     }
 
@@ -48,16 +68,8 @@ impl GeminiRequest {
     /// # Returns
     /// * A new GeminiRequest containing the prompt
     pub fn from_prompt(prompt: &GeminiPrompt) -> Self {
-        let mut content = GeminiContent::new();
-
-        if let Some(role) = &prompt.role {
-            content.set_role(*role);
-        }
-
-        content.add_text(prompt.text.as_str());
-
         let mut request = GeminiRequest::new();
-        request.add_content(content);
+        request.add_content(GeminiContent::from(prompt));
         request
     }
 
@@ -68,12 +80,10 @@ impl GeminiRequest {
     ///
     /// # Returns
     /// * A new GeminiRequest containing the text
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(text: &str) -> Self {
-        let mut content = GeminiContent::new();
-        content.add_text(text);
-
         let mut request = GeminiRequest::new();
-        request.add_content(content);
+        request.add_content(GeminiContent::from(text));
         request
     }
 
@@ -82,7 +92,7 @@ impl GeminiRequest {
     /// # Returns
     /// * JsonValue representation of the request
     pub fn to_json(&self) -> JsonValue {
-        serde_json::to_value(&self).unwrap_or_default()
+        serde_json::to_value(self).unwrap_or_default()
     }
 
     /// Converts the request to a pretty-printed JSON string.
@@ -117,12 +127,9 @@ impl GeminiRequest {
         &mut self,
         function_response: GeminiFunctionResponse,
     ) -> &mut Self {
-        let mut content = GeminiContent::new();
-        content.set_role(GeminiRole::Tool);
-        content.add_part(GeminiPart::FunctionResponse(function_response));
-
-        self.add_content(content);
-        self
+        self.add_content(GeminiContent::tool(GeminiPart::FunctionResponse(
+            function_response,
+        )))
     }
 
     /// Adds a prompt with a specified role to the request.
@@ -133,19 +140,13 @@ impl GeminiRequest {
     /// # Returns
     /// * &mut Self for method chaining
     pub fn add_prompt(&mut self, prompt: &GeminiPrompt) -> &mut Self {
-        let mut content = GeminiContent::new();
-
-        if let Some(role) = &prompt.role {
-            content.set_role(*role);
-        }
-
-        content.add_text(prompt.text.as_str());
-        self.add_content(content)
+        self.add_content(GeminiContent::from(prompt))
     }
 
     /// Adds a response content to the request.
     ///
-    /// This is useful for building conversation history.
+    /// This is useful for building conversation history. The content is added with the
+    /// model role, as the API expects for the model's own turns.
     ///
     /// # Arguments
     /// * `response` - The GeminiResponse to add to the request
@@ -154,11 +155,53 @@ impl GeminiRequest {
     /// * &mut Self for method chaining
     pub fn add_response(&mut self, response: &GeminiResponse) -> &mut Self {
         if let Some(content) = response.content() {
-            self.add_content(content.clone());
+            let mut content = content.clone();
+            content.set_role(GeminiRole::Model);
+            self.add_content(content);
         }
         self
     }
 
+    /// Sets the generation config of the request.
+    ///
+    /// # Arguments
+    /// * `config` - The GeminiGenerationConfig to use
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_generation_config(&mut self, config: GeminiGenerationConfig) -> &mut Self {
+        self.generation_config = Some(config);
+        self
+    }
+
+    /// Sets the stop sequences in the generation config of the request.
+    ///
+    /// # Arguments
+    /// * `stop_sequences` - The sequences that end generation
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_stop_sequences(&mut self, stop_sequences: &[&str]) -> &mut Self {
+        self.generation_config
+            .get_or_insert_with(GeminiGenerationConfig::new)
+            .set_stop_sequences(stop_sequences);
+        self
+    }
+
+    /// Sets the number of candidate responses in the generation config of the request.
+    ///
+    /// # Arguments
+    /// * `count` - The number of candidates to generate
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_candidate_count(&mut self, count: u32) -> &mut Self {
+        self.generation_config
+            .get_or_insert_with(GeminiGenerationConfig::new)
+            .set_candidate_count(count);
+        self
+    }
+
     /// Adds a tool declaration to the request.
     ///
     /// # Arguments
@@ -170,6 +213,34 @@ impl GeminiRequest {
         self.tools.push(tool);
         self
     }
+
+    /// Controls whether the model may, must or must not call the declared functions.
+    ///
+    /// # Arguments
+    /// * `mode` - The function calling mode
+    /// * `allowed_function_names` - The functions the model may call in `Any` mode;
+    ///   empty allows all declared functions
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_function_calling(
+        &mut self,
+        mode: GeminiFunctionCallingMode,
+        allowed_function_names: &[&str],
+    ) -> &mut Self {
+        self.tool_config = Some(GeminiToolConfig::new(mode, allowed_function_names));
+        self
+    }
+}
+
+// ===
+// TRAIT: Default for GeminiRequest
+// ===
+
+impl Default for GeminiRequest {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // ===
@@ -216,7 +287,7 @@ impl From<&str> for GeminiRequest {
 // TRAIT: GeminiRequest (From<String>)
 // ===
 
-/// Implementation of From<String> trait for GeminiRequest
+/// Implementation of `From<String>` trait for GeminiRequest
 ///
 /// Allows conversion from a String to a GeminiRequest
 impl From<String> for GeminiRequest {
@@ -236,7 +307,7 @@ impl From<String> for GeminiRequest {
 // TRAIT: GeminiRequest (From<GeminiPrompt>)
 // ===
 
-/// Implementation of From<GeminiPrompt> trait for GeminiRequest
+/// Implementation of `From<GeminiPrompt>` trait for GeminiRequest
 ///
 /// Allows conversion from a GeminiPrompt to a GeminiRequest
 impl From<GeminiPrompt> for GeminiRequest {
@@ -261,6 +332,42 @@ impl From<GeminiPrompt> for GeminiRequest {
     }
 }
 
+// ===
+// TRAIT: GeminiRequest (collection of contents)
+// ===
+
+/// Creates a request with the given contents, e.g. a conversation history.
+impl FromIterator<GeminiContent> for GeminiRequest {
+    fn from_iter<I: IntoIterator<Item = GeminiContent>>(iter: I) -> Self {
+        let mut request = GeminiRequest::new();
+        request.contents = iter.into_iter().collect();
+        request
+    }
+}
+
+impl Extend<GeminiContent> for GeminiRequest {
+    fn extend<I: IntoIterator<Item = GeminiContent>>(&mut self, iter: I) {
+        self.contents.extend(iter);
+    }
+}
+
+impl<'a> IntoIterator for &'a GeminiRequest {
+    type Item = &'a GeminiContent;
+    type IntoIter = std::slice::Iter<'a, GeminiContent>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.contents.iter()
+    }
+}
+
+impl Index<usize> for GeminiRequest {
+    type Output = GeminiContent;
+
+    fn index(&self, index: usize) -> &GeminiContent {
+        &self.contents[index]
+    }
+}
+
 // ===
 // TESTS: GeminiRequest
 // ===
@@ -412,6 +519,23 @@ mod tests {
             .as_array()
             .unwrap();
         assert_eq!(contents.len(), 1);
+        assert!(json.get("toolConfig").is_none());
+    }
+
+    #[test]
+    fn test_gemini_request_function_calling() {
+        let mut request = GeminiRequest::from_str("Extract the invoice fields");
+        request.set_function_calling(GeminiFunctionCallingMode::Any, &["save_invoice"]);
+
+        let json = request.to_json();
+        assert_eq!(json["toolConfig"]["functionCallingConfig"]["mode"], "ANY");
+        assert_eq!(
+            json["toolConfig"]["functionCallingConfig"]["allowedFunctionNames"][0],
+            "save_invoice"
+        );
+
+        let parsed: GeminiToolConfig = serde_json::from_value(json["toolConfig"].clone()).unwrap();
+        assert_eq!(Some(parsed), request.tool_config);
     }
 
     #[test]
@@ -439,6 +563,7 @@ mod tests {
     fn test_gemini_role_as_str() {
         assert_eq!(GeminiRole::System.as_str(), "system");
         assert_eq!(GeminiRole::User.as_str(), "user");
+        assert_eq!(GeminiRole::Model.as_str(), "model");
         assert_eq!(GeminiRole::Tool.as_str(), "tool");
     }
 
@@ -447,6 +572,8 @@ mod tests {
         assert_eq!(GeminiRole::from_str("system"), Some(GeminiRole::System));
         assert_eq!(GeminiRole::from_str("USER"), Some(GeminiRole::User));
         assert_eq!(GeminiRole::from_str("Tool"), Some(GeminiRole::Tool));
+        assert_eq!(GeminiRole::from_str("model"), Some(GeminiRole::Model));
+        assert_eq!(GeminiRole::from_str("assistant"), Some(GeminiRole::Model));
         assert_eq!(GeminiRole::from_str("unknown"), None);
     }
 
@@ -469,20 +596,26 @@ mod tests {
             content: response_content,
             finish_reason: None,
             index: Some(0),
+            safety_ratings: Vec::new(),
         };
 
         // Create the response with the candidate
         let response = GeminiResponse {
             candidates: Some(vec![candidate]),
             error: None,
+            prompt_feedback: None,
+            usage_metadata: None,
+            model_version: None,
+            metrics: None,
         };
 
         // Test adding the response to the request
         request.add_response(&response);
 
         // Verify it was added correctly
+        // The response is stored as a model turn, whatever role it came back with
         assert_eq!(request.contents.len(), 2);
-        assert_eq!(request.contents[1].role(), Some(GeminiRole::System));
+        assert_eq!(request.contents[1].role(), Some(GeminiRole::Model));
 
         if let GeminiPart::Text(text_part) = &request.contents[1].parts[0] {
             assert_eq!(text_part.text, "Response text");
@@ -490,4 +623,24 @@ mod tests {
             panic!("Expected text part");
         }
     }
+
+    #[test]
+    fn test_collection_traits() {
+        let mut request: GeminiRequest = ["Hi", "Hello!"]
+            .into_iter()
+            .map(GeminiContent::from)
+            .collect();
+        request.extend([GeminiContent::user("Bye")]);
+
+        assert_eq!(request[2].role(), Some(GeminiRole::User));
+        let texts: Vec<&str> = (&request)
+            .into_iter()
+            .flatten()
+            .filter_map(|part| match part {
+                GeminiPart::Text(text_part) => Some(text_part.text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, ["Hi", "Hello!", "Bye"]);
+    }
 }
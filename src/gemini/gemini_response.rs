@@ -1,19 +1,145 @@
-use crate::{GeminiContent, GeminiFunctionCall, GeminiPart};
+use crate::{
+    GeminiContent, GeminiFunctionCall, GeminiPart, GeminiResponseError, GenerationStats, ImageData,
+    StreamMetrics,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Finish reasons that mean the candidate was cut off by a filter rather than completed.
+const BLOCKING_FINISH_REASONS: &[&str] = &[
+    "SAFETY",
+    "RECITATION",
+    "BLOCKLIST",
+    "PROHIBITED_CONTENT",
+    "SPII",
+    "IMAGE_SAFETY",
+];
+
+// ===
+// STRUCT: GeminiSafetyRating
+// ===
+
+/// The rated probability of a piece of content being harmful in one category.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeminiSafetyRating {
+    /// The harm category, e.g. "HARM_CATEGORY_DANGEROUS_CONTENT".
+    pub category: String,
+
+    /// The probability of harm, e.g. "NEGLIGIBLE" or "HIGH".
+    pub probability: String,
+
+    /// Whether the content was blocked because of this rating.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub blocked: Option<bool>,
+}
+
+// ===
+// STRUCT: GeminiPromptFeedback
+// ===
+
+/// Feedback about the prompt, present when the prompt was blocked.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeminiPromptFeedback {
+    /// Why the prompt was blocked, if it was.
+    #[serde(
+        rename = "blockReason",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub block_reason: Option<String>,
+
+    /// The safety ratings of the prompt.
+    #[serde(
+        rename = "safetyRatings",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub safety_ratings: Vec<GeminiSafetyRating>,
+}
+
+// ===
+// STRUCT: GeminiUsageMetadata
+// ===
+
+/// Token counts reported with a response.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GeminiUsageMetadata {
+    /// The number of tokens in the prompt.
+    pub prompt_token_count: Option<u32>,
+    /// The number of tokens in the generated candidates.
+    pub candidates_token_count: Option<u32>,
+    /// The number of tokens spent on thinking, for thinking models.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thoughts_token_count: Option<u32>,
+    /// The total number of tokens of the request.
+    pub total_token_count: Option<u32>,
+}
 
 // ===
 // STRUCT: GeminiCandidate
 // ===
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiCandidate {
     pub index: Option<u32>,
+
+    /// The generated content; empty when the candidate was blocked.
+    #[serde(default)]
     pub content: GeminiContent,
 
     #[serde(rename = "finishReason")]
     pub finish_reason: Option<String>,
+
+    #[serde(
+        rename = "safetyRatings",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub safety_ratings: Vec<GeminiSafetyRating>,
+}
+
+// ===
+// PUBLIC: GeminiCandidate
+// ===
+
+impl GeminiCandidate {
+    /// Extracts the text from the first part of the candidate's content.
+    ///
+    /// # Returns
+    /// * `Some(&str)` containing the text if the first part is text
+    /// * `None` if the content is empty or the first part isn't text
+    pub fn text(&self) -> Option<&str> {
+        match self.content.parts.first() {
+            Some(GeminiPart::Text(text_part)) => Some(&text_part.text),
+            _ => None,
+        }
+    }
+
+    /// Joins the text of every text part of the candidate's content, in order.
+    ///
+    /// Parts that aren't text, such as function calls, are skipped.
+    ///
+    /// # Returns
+    /// * `Some(String)` containing the joined text if the content has at least one text part
+    /// * `None` if the content has no text parts
+    pub fn full_text(&self) -> Option<String> {
+        let mut texts = self
+            .content
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                GeminiPart::Text(text_part) => Some(text_part.text.as_str()),
+                _ => None,
+            })
+            .peekable();
+
+        texts.peek()?;
+        Some(texts.collect())
+    }
 }
 
 // ===
@@ -24,13 +150,42 @@ pub struct GeminiCandidate {
 ///
 /// This struct encapsulates the response data received from the Gemini API,
 /// providing structured access to the generated content candidates.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GeminiResponse {
     /// The generated candidates from the Gemini model.
     pub candidates: Option<Vec<GeminiCandidate>>,
 
     /// Information about the error that occurred, if any.
     pub error: Option<JsonValue>,
+
+    /// Feedback about the prompt, including why it was blocked.
+    #[serde(
+        rename = "promptFeedback",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub prompt_feedback: Option<GeminiPromptFeedback>,
+
+    /// The token counts of the request, sent with the last chunk of a stream.
+    #[serde(
+        rename = "usageMetadata",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub usage_metadata: Option<GeminiUsageMetadata>,
+
+    /// The version of the model that generated the response.
+    #[serde(
+        rename = "modelVersion",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub model_version: Option<String>,
+
+    /// The client-side latency of the request, set on the final response of a stream
+    /// and on responses returned by `Gemini::generate`.
+    #[serde(skip)]
+    pub metrics: Option<StreamMetrics>,
 }
 
 // ===
@@ -47,19 +202,43 @@ impl GeminiResponse {
         serde_json::to_string_pretty(&self).unwrap_or_default()
     }
 
+    /// Returns the token counts and client-side timing of the response.
+    ///
+    /// Gemini reports no server durations, so only the wall time is set, from the
+    /// latency metrics of the request.
+    ///
+    /// # Returns
+    /// * A `GenerationStats` with the fields the response provides
+    pub fn stats(&self) -> GenerationStats {
+        let usage = self.usage_metadata.clone().unwrap_or_default();
+        GenerationStats {
+            model: self.model_version.clone(),
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count,
+            wall_time: self.metrics.as_ref().map(StreamMetrics::total_time),
+            ..GenerationStats::default()
+        }
+    }
+
     /// Returns a reference to the content of the first candidate in the response.
     ///
     /// # Returns
     /// * `Some(&GeminiContent1)` if there is at least one candidate in the response
     /// * `None` if there are no candidates
     pub fn content(&self) -> Option<&GeminiContent> {
-        if let Some(candidates) = &self.candidates {
-            if let Some(candidate) = candidates.get(0) {
-                return Some(&candidate.content);
-            }
-        }
+        self.candidate(0).map(|candidate| &candidate.content)
+    }
 
-        None
+    /// Returns the candidate at the given position in the response.
+    ///
+    /// # Arguments
+    /// * `index` - The position of the candidate, starting at 0
+    ///
+    /// # Returns
+    /// * `Some(&GeminiCandidate)` if the response has a candidate at that position
+    /// * `None` otherwise
+    pub fn candidate(&self, index: usize) -> Option<&GeminiCandidate> {
+        self.candidates.as_ref()?.get(index)
     }
 
     /// Extracts the text from the first part of the first candidate in the response.
@@ -68,15 +247,100 @@ impl GeminiResponse {
     /// * `Some(&str)` containing the text if there is at least one candidate with a text part
     /// * `None` if there are no candidates or the first part isn't text
     pub fn text(&self) -> Option<&str> {
-        if let Some(candidates) = &self.candidates {
-            if let Some(candidate) = candidates.get(0) {
-                if let GeminiPart::Text(text_part) = &candidate.content.parts[0] {
-                    return Some(&text_part.text);
-                }
-            }
+        self.candidate(0)?.text()
+    }
+
+    /// Joins the text of every text part of the first candidate in the response.
+    ///
+    /// Unlike `text`, this keeps the whole answer when the model splits it across several
+    /// parts or interleaves it with function calls.
+    ///
+    /// # Returns
+    /// * `Some(String)` containing the joined text if the first candidate has a text part
+    /// * `None` if there are no candidates or the first candidate has no text parts
+    pub fn full_text(&self) -> Option<String> {
+        self.candidate(0)?.full_text()
+    }
+
+    /// Extracts the full text of every candidate in the response, in order.
+    ///
+    /// Use with `GeminiGenerationConfig::set_candidate_count` to compare alternative answers.
+    ///
+    /// # Returns
+    /// * A vector with the joined text parts of each candidate that has text
+    pub fn texts(&self) -> Vec<String> {
+        self.candidates
+            .iter()
+            .flatten()
+            .filter_map(GeminiCandidate::full_text)
+            .collect()
+    }
+
+    /// Returns why the prompt or the first candidate was blocked, if it was.
+    ///
+    /// # Returns
+    /// * `Some(&str)` with the prompt's block reason, or the first candidate's finish reason
+    ///   if generation was stopped by a filter (e.g. "SAFETY" or "RECITATION")
+    /// * `None` if nothing was blocked
+    pub fn block_reason(&self) -> Option<&str> {
+        if let Some(reason) = self
+            .prompt_feedback
+            .as_ref()
+            .and_then(|feedback| feedback.block_reason.as_deref())
+        {
+            return Some(reason);
         }
 
-        None
+        self.candidates
+            .as_ref()
+            .and_then(|candidates| candidates.first())
+            .and_then(|candidate| candidate.finish_reason.as_deref())
+            .filter(|reason| BLOCKING_FINISH_REASONS.contains(reason))
+    }
+
+    /// Explains why the response has no usable content.
+    ///
+    /// # Returns
+    /// * `Some(GeminiResponseError)` if the API returned an error, the prompt or first candidate
+    ///   was blocked, or there are no candidates
+    /// * `None` if the response has a candidate that completed normally
+    pub fn response_error(&self) -> Option<GeminiResponseError> {
+        if let Some(error) = &self.error {
+            return Some(GeminiResponseError::Api {
+                code: error
+                    .get("code")
+                    .and_then(JsonValue::as_u64)
+                    .and_then(|code| u16::try_from(code).ok()),
+                message: error
+                    .get("message")
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string()),
+            });
+        }
+
+        if let Some(feedback) = &self.prompt_feedback
+            && let Some(reason) = &feedback.block_reason
+        {
+            return Some(GeminiResponseError::PromptBlocked {
+                reason: reason.clone(),
+                safety_ratings: feedback.safety_ratings.clone(),
+            });
+        }
+
+        let Some(candidate) = self.candidates.as_ref().and_then(|c| c.first()) else {
+            return Some(GeminiResponseError::NoCandidates);
+        };
+
+        match candidate.finish_reason.as_deref() {
+            Some(reason) if BLOCKING_FINISH_REASONS.contains(&reason) => {
+                Some(GeminiResponseError::CandidateBlocked {
+                    finish_reason: reason.to_string(),
+                    safety_ratings: candidate.safety_ratings.clone(),
+                })
+            }
+            _ => None,
+        }
     }
 
     /// Returns a vector of references to all function call parts in the first candidate's content.
@@ -85,25 +349,73 @@ impl GeminiResponse {
     /// * `Vec<&GeminiPartFunctionCall>` containing all function call parts in the first candidate,
     ///   or an empty vector if there are no candidates or no function call parts.
     pub fn functions(&self) -> Vec<&GeminiFunctionCall> {
-        if let Some(candidates) = &self.candidates {
-            if let Some(candidate) = candidates.get(0) {
-                return candidate
-                    .content
-                    .parts
-                    .iter()
-                    .filter_map(|part| {
-                        if let GeminiPart::FunctionCall(function_call) = part {
-                            Some(function_call)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-            }
+        if let Some(candidates) = &self.candidates
+            && let Some(candidate) = candidates.first()
+        {
+            return candidate
+                .content
+                .parts
+                .iter()
+                .filter_map(|part| {
+                    if let GeminiPart::FunctionCall(function_call) = part {
+                        Some(function_call)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
         }
 
         Vec::new()
     }
+
+    /// Decodes the images returned inline in the first candidate's content.
+    ///
+    /// Image generation models return each image as an inline data part; see
+    /// `Gemini::generate_image`. Parts that fail to decode, or whose data does not
+    /// match their mime type, are skipped.
+    ///
+    /// # Returns
+    /// * A vector of images, in order, or an empty vector if there are no images
+    pub fn images(&self) -> Vec<ImageData> {
+        let Some(content) = self.content() else {
+            return Vec::new();
+        };
+
+        content
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                GeminiPart::InlineData(data) if data.is_image() => data.to_image().ok(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Writes the images of the first candidate to files in a directory.
+    ///
+    /// The files are named `<prefix>-<n>.<ext>`, with the extension taken from the mime type.
+    ///
+    /// # Arguments
+    /// * `dir` - The directory to write to; it must exist
+    /// * `prefix` - The start of each file name, e.g. "image"
+    ///
+    /// # Returns
+    /// * `Ok(Vec<PathBuf>)` with the paths of the written files
+    /// * `Err(io::Error)` if a file could not be written
+    pub fn save_images(&self, dir: impl AsRef<Path>, prefix: &str) -> io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+
+        for (index, image) in self.images().into_iter().enumerate() {
+            let path = dir
+                .as_ref()
+                .join(format!("{prefix}-{index}.{}", image.extension()));
+            image.save(&path)?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
 }
 
 // ===
@@ -162,3 +474,191 @@ impl TryFrom<JsonValue> for GeminiResponse {
         serde_json::from_value(json_value)
     }
 }
+
+// ===
+// TESTS: GeminiResponse
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stats_from_usage_metadata() {
+        let response = GeminiResponse::try_from(json!({
+            "candidates": [{ "content": { "role": "model", "parts": [{ "text": "Hi" }] } }],
+            "usageMetadata": {
+                "promptTokenCount": 4,
+                "candidatesTokenCount": 2,
+                "totalTokenCount": 6
+            },
+            "modelVersion": "gemini-2.0-flash"
+        }))
+        .unwrap();
+
+        let stats = response.stats();
+        assert_eq!(stats.model.as_deref(), Some("gemini-2.0-flash"));
+        assert_eq!(stats.prompt_tokens, Some(4));
+        assert_eq!(stats.completion_tokens, Some(2));
+        assert_eq!(stats.tokens_used(), 6);
+        assert_eq!(stats.eval_duration, None);
+    }
+
+    #[test]
+    fn test_prompt_blocked() {
+        let response = GeminiResponse::try_from(json!({
+            "promptFeedback": {
+                "blockReason": "SAFETY",
+                "safetyRatings": [
+                    { "category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE" },
+                    { "category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH", "blocked": true }
+                ]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(response.text(), None);
+        assert_eq!(response.block_reason(), Some("SAFETY"));
+
+        let error = response.response_error().unwrap();
+        assert_eq!(error.to_string(), "prompt blocked: SAFETY");
+        let blocking = error.blocking_ratings();
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].category, "HARM_CATEGORY_DANGEROUS_CONTENT");
+    }
+
+    #[test]
+    fn test_candidate_blocked_without_content() {
+        let response = GeminiResponse::try_from(json!({
+            "candidates": [{ "finishReason": "RECITATION", "index": 0 }]
+        }))
+        .unwrap();
+
+        assert_eq!(response.text(), None);
+        assert_eq!(response.block_reason(), Some("RECITATION"));
+        assert!(matches!(
+            response.response_error(),
+            Some(GeminiResponseError::CandidateBlocked { finish_reason, .. }) if finish_reason == "RECITATION"
+        ));
+    }
+
+    #[test]
+    fn test_api_error_and_success() {
+        let response = GeminiResponse::try_from(json!({
+            "error": { "code": 400, "message": "API key not valid", "status": "INVALID_ARGUMENT" }
+        }))
+        .unwrap();
+        assert_eq!(
+            response.response_error().unwrap().to_string(),
+            "Gemini API error 400: API key not valid"
+        );
+
+        let response = GeminiResponse::try_from(json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [{ "text": "Hi" }] },
+                "finishReason": "STOP"
+            }]
+        }))
+        .unwrap();
+        assert_eq!(response.block_reason(), None);
+        assert!(response.response_error().is_none());
+
+        let empty = GeminiResponse::try_from(json!({})).unwrap();
+        assert_eq!(
+            empty.response_error(),
+            Some(GeminiResponseError::NoCandidates)
+        );
+    }
+
+    #[test]
+    fn test_multiple_candidates() {
+        let response = GeminiResponse::try_from(json!({
+            "candidates": [
+                { "content": { "role": "model", "parts": [{ "text": "One" }] }, "index": 0 },
+                { "content": { "role": "model", "parts": [] }, "index": 1 },
+                { "content": { "role": "model", "parts": [{ "text": "Three" }] }, "index": 2 }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(response.texts(), ["One", "Three"]);
+        assert_eq!(response.candidate(2).unwrap().text(), Some("Three"));
+        assert!(response.candidate(3).is_none());
+        assert!(
+            GeminiResponse::try_from(json!({}))
+                .unwrap()
+                .texts()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_full_text_joins_mixed_parts() {
+        let response = GeminiResponse::try_from(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        { "text": "Let me check. " },
+                        { "functionCall": { "name": "get_weather", "args": { "city": "Oslo" } } },
+                        { "text": "It is sunny." }
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(response.text(), Some("Let me check. "));
+        assert_eq!(
+            response.full_text().as_deref(),
+            Some("Let me check. It is sunny.")
+        );
+        assert_eq!(response.texts(), ["Let me check. It is sunny."]);
+        assert_eq!(response.functions().len(), 1);
+
+        let calls_only = GeminiResponse::try_from(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{ "functionCall": { "name": "get_weather", "args": {} } }]
+                }
+            }]
+        }))
+        .unwrap();
+        assert_eq!(calls_only.full_text(), None);
+    }
+
+    #[test]
+    fn test_images() {
+        let response = GeminiResponse::try_from(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        { "text": "Here is your cat." },
+                        { "inlineData": { "mimeType": "image/png", "data": "iVBO" } },
+                        { "inlineData": { "mimeType": "image/jpeg", "data": "/9j/" } },
+                        { "inlineData": { "mimeType": "image/gif", "data": "/9j/" } }
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+
+        let images = response.images();
+        assert_eq!(images.len(), 2);
+        assert_eq!(
+            images[0],
+            ImageData::new("image/png", vec![0x89, 0x50, 0x4E])
+        );
+        assert_eq!(images[1].mime_type(), "image/jpeg");
+
+        let dir = std::env::temp_dir().join("ollie_images_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = response.save_images(&dir, "cat").unwrap();
+        assert_eq!(paths, [dir.join("cat-0.png"), dir.join("cat-1.jpg")]);
+        assert_eq!(std::fs::read(&paths[1]).unwrap(), vec![0xFF, 0xD8, 0xFF]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
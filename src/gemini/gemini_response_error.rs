@@ -0,0 +1,92 @@
+use crate::GeminiSafetyRating;
+use std::error::Error;
+use std::fmt;
+
+// ===
+// ENUM: GeminiResponseError
+// ===
+
+/// Explains why a Gemini response carries no usable content.
+///
+/// Returned by `GeminiResponse::response_error` so callers can react, for
+/// example by relaxing safety settings or rephrasing the prompt.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeminiResponseError {
+    /// The prompt itself was blocked, so no candidates were generated.
+    PromptBlocked {
+        /// The block reason reported by the API, e.g. "SAFETY" or "OTHER".
+        reason: String,
+        /// The safety ratings of the prompt.
+        safety_ratings: Vec<GeminiSafetyRating>,
+    },
+
+    /// Generation was stopped by a filter, e.g. for safety or recitation.
+    CandidateBlocked {
+        /// The finish reason of the candidate, e.g. "SAFETY" or "RECITATION".
+        finish_reason: String,
+        /// The safety ratings of the candidate.
+        safety_ratings: Vec<GeminiSafetyRating>,
+    },
+
+    /// The API returned an error object instead of candidates.
+    Api {
+        /// The HTTP status code, if reported.
+        code: Option<u16>,
+        /// The error message.
+        message: String,
+    },
+
+    /// The response has no candidates and no explanation.
+    NoCandidates,
+}
+
+// ===
+// PUBLIC: GeminiResponseError
+// ===
+
+impl GeminiResponseError {
+    /// Returns the safety ratings that led to the block, if any.
+    ///
+    /// # Returns
+    /// * The ratings marked as blocked, or all ratings if none is marked
+    pub fn blocking_ratings(&self) -> Vec<&GeminiSafetyRating> {
+        let ratings = match self {
+            Self::PromptBlocked { safety_ratings, .. }
+            | Self::CandidateBlocked { safety_ratings, .. } => safety_ratings,
+            _ => return Vec::new(),
+        };
+
+        let blocked: Vec<_> = ratings.iter().filter(|r| r.blocked == Some(true)).collect();
+        if blocked.is_empty() {
+            ratings.iter().collect()
+        } else {
+            blocked
+        }
+    }
+}
+
+// ===
+// TRAIT: GeminiResponseError (fmt::Display)
+// ===
+
+impl fmt::Display for GeminiResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PromptBlocked { reason, .. } => write!(f, "prompt blocked: {reason}"),
+            Self::CandidateBlocked { finish_reason, .. } => {
+                write!(f, "response blocked: {finish_reason}")
+            }
+            Self::Api {
+                code: Some(code),
+                message,
+            } => write!(f, "Gemini API error {code}: {message}"),
+            Self::Api {
+                code: None,
+                message,
+            } => write!(f, "Gemini API error: {message}"),
+            Self::NoCandidates => write!(f, "response contains no candidates"),
+        }
+    }
+}
+
+impl Error for GeminiResponseError {}
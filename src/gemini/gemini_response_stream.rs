@@ -1,13 +1,28 @@
-use crate::GeminiResponse;
+use crate::gemini::gemini_openai::from_openai_response;
+#[cfg(feature = "audit")]
+use crate::{AuditLog, AuditRecord};
+use crate::{
+    GeminiCandidate, GeminiPart, GeminiResponse, GeminiUsageMetadata, StopSequenceFilter,
+    StreamMetrics, StreamTimer, TextAccumulator,
+};
 use reqwest::Response as HttpResponse;
+use serde_json::Value as JsonValue;
 
 /// A stream for processing Gemini API responses.
 ///
 /// This struct wraps an HTTP response and provides methods to parse and extract
-/// Gemini response data from the server-sent event (SSE) format.
+/// Gemini response data from the server-sent event (SSE) format. The chunks are
+/// timed as they arrive; see `metrics`.
 pub struct GeminiResponseStream {
-    http_response: HttpResponse,
+    http_response: Option<HttpResponse>,
+    local_event: Option<String>,
     responses: Vec<GeminiResponse>,
+    stop_filter: StopSequenceFilter,
+    text: TextAccumulator,
+    timer: StreamTimer,
+    openai_chunks: bool,
+    #[cfg(feature = "audit")]
+    audit: Option<StreamAudit>,
 }
 
 impl GeminiResponseStream {
@@ -19,12 +34,74 @@ impl GeminiResponseStream {
     /// # Returns
     /// * A new GeminiResponseStream instance
     pub fn new(http_response: HttpResponse) -> Self {
+        Self::with_timer(http_response, StreamTimer::start())
+    }
+
+    /// Creates a new GeminiResponseStream whose latency is measured by an already started timer.
+    ///
+    /// # Arguments
+    /// * `http_response` - The HTTP response to wrap
+    /// * `timer` - The timer started just before the request was sent
+    ///
+    /// # Returns
+    /// * A new GeminiResponseStream instance
+    pub fn with_timer(http_response: HttpResponse, timer: StreamTimer) -> Self {
+        Self::from_source(Some(http_response), None, timer)
+    }
+
+    /// Creates a stream that yields one response without any HTTP exchange, e.g. the
+    /// reply to a dry run.
+    ///
+    /// # Arguments
+    /// * `response_json` - The response, in the native Gemini format
+    /// * `timer` - The timer started when the request was made
+    ///
+    /// # Returns
+    /// * A new GeminiResponseStream that ends after the response
+    pub(crate) fn from_response(response_json: &JsonValue, timer: StreamTimer) -> Self {
+        Self::from_source(None, Some(format!("data: {response_json}\n\n")), timer)
+    }
+
+    /// Creates a stream reading from an HTTP response, or from a single buffered event.
+    fn from_source(
+        http_response: Option<HttpResponse>,
+        local_event: Option<String>,
+        timer: StreamTimer,
+    ) -> Self {
         GeminiResponseStream {
             http_response,
+            local_event,
             responses: Vec::new(),
+            stop_filter: StopSequenceFilter::default(),
+            text: TextAccumulator::new(),
+            timer,
+            openai_chunks: false,
+            #[cfg(feature = "audit")]
+            audit: None,
         }
     }
 
+    /// Sets whether the chunks come from the OpenAI-compatible endpoint and need translating.
+    pub(crate) fn set_openai_chunks(&mut self, openai_chunks: bool) -> &mut Self {
+        self.openai_chunks = openai_chunks;
+        self
+    }
+
+    /// Sets stop sequences to enforce on the streamed text.
+    ///
+    /// Text from the first stop sequence onwards is removed and the stream ends
+    /// once a stop sequence has been observed, even if the server keeps sending.
+    ///
+    /// # Arguments
+    /// * `stop_sequences` - The sequences that end generation
+    ///
+    /// # Returns
+    /// * &mut Self for method chaining
+    pub fn set_stop_sequences(&mut self, stop_sequences: &[String]) -> &mut Self {
+        self.stop_filter = StopSequenceFilter::new(stop_sequences);
+        self
+    }
+
     /// Fetches and parses the next chunk of data from the stream.
     ///
     /// This method retrieves the next chunk from the HTTP response, parses it as an SSE message,
@@ -33,23 +110,117 @@ impl GeminiResponseStream {
     /// # Returns
     /// * `Some(GeminiResponse)` if a valid response chunk was received and parsed
     /// * `None` if the stream has ended or an error occurred during parsing
+    ///
+    /// The response that finishes the stream carries the latency metrics.
     pub async fn read(&mut self) -> Option<&GeminiResponse> {
-        let bytes = self.http_response.chunk().await.ok()?;
+        if self.stop_filter.is_stopped() {
+            self.timer.finish();
+            #[cfg(feature = "audit")]
+            self.write_audit(None);
+            return None;
+        }
 
-        if bytes.is_none() {
+        let Some(mut response) = self.read_response().await else {
+            self.timer.finish();
+            #[cfg(feature = "audit")]
+            self.write_audit(None);
             return None;
+        };
+
+        if !self.stop_filter.is_empty() {
+            enforce_stop_sequences(&mut self.stop_filter, &mut response);
         }
 
-        let bytes = bytes.unwrap();
-        let string = String::from_utf8(bytes.to_vec()).ok()?;
-        let slice = string.split_once("data:")?.1;
-        let response: GeminiResponse = serde_json::from_str(slice).ok()?;
+        let text = response.full_text().unwrap_or_default();
+        self.timer.record_chunk(!text.is_empty());
+        self.text.push(&text);
+
+        let finished = response
+            .candidate(0)
+            .is_some_and(|candidate| candidate.finish_reason.is_some());
+        if finished || self.stop_filter.is_stopped() {
+            self.timer.finish();
+            response.metrics = Some(self.timer.metrics());
+        }
 
         // Save the response
         self.responses.push(response);
         self.responses.last()
     }
 
+    /// Writes the exchange to an audit log once the stream ends or is dropped.
+    ///
+    /// # Arguments
+    /// * `audit_log` - The log to write the record to
+    /// * `model` - The model the request was sent to
+    /// * `request_json` - The request body that was sent
+    #[cfg(feature = "audit")]
+    pub(crate) fn set_audit_log(
+        &mut self,
+        audit_log: AuditLog,
+        model: &str,
+        request_json: &JsonValue,
+    ) {
+        self.audit = Some(StreamAudit {
+            audit_log,
+            model: model.to_string(),
+            request_json: request_json.clone(),
+        });
+    }
+
+    /// Writes the audit record of the exchange, if not written yet.
+    #[cfg(feature = "audit")]
+    fn write_audit(&mut self, error: Option<&str>) {
+        let Some(audit) = self.audit.take() else {
+            return;
+        };
+
+        let response = self.final_response().map(serde_json::to_value);
+        let result = match response {
+            Some(Ok(response)) => Ok(response),
+            Some(Err(err)) => Err(err.into()),
+            None => Err(error
+                .unwrap_or("no response received from the Gemini API")
+                .into()),
+        };
+        let duration = self.metrics().total_time();
+        let mut record = AuditRecord::gemini(&audit.model, &audit.request_json, &result, duration);
+        record.endpoint = "streamGenerateContent".to_string();
+        if let Some(error) = error {
+            record.error = Some(error.to_string());
+        }
+
+        // An audit log that cannot be written does not fail the stream.
+        let _ = audit.audit_log.record(record);
+    }
+
+    /// Fetches the next chunk and parses it as a response.
+    async fn read_response(&mut self) -> Option<GeminiResponse> {
+        let string = match self.local_event.take() {
+            Some(event) => event,
+            None => {
+                let bytes = self.http_response.as_mut()?.chunk().await.ok()??;
+                String::from_utf8(bytes.to_vec()).ok()?
+            }
+        };
+        let slice = string.split_once("data:")?.1;
+        let json: JsonValue = serde_json::from_str(slice).ok()?;
+        if self.openai_chunks {
+            serde_json::from_value(from_openai_response(&json)).ok()
+        } else {
+            serde_json::from_value(json).ok()
+        }
+    }
+
+    /// Returns the latency metrics recorded so far.
+    ///
+    /// # Returns
+    /// * The time to first token, the inter-chunk latencies and the total wall time,
+    ///   which runs until now if the stream has not ended
+    pub fn metrics(&self) -> StreamMetrics {
+        self.timer.metrics()
+    }
+
     /// Returns a reference to the stored responses that have been collected from the stream.
     ///
     /// This method allows accessing all the response objects that have been
@@ -63,16 +234,256 @@ impl GeminiResponseStream {
 
     /// Concatenates the text content from all responses into a single String.
     ///
-    /// This method iterates through all collected responses and combines
-    /// their text content sequentially.
-    ///
     /// # Returns
     /// * A String containing the combined text from all responses
     pub fn text(&self) -> String {
-        self.responses
-            .iter()
-            .filter_map(|response| response.text())
-            .collect::<Vec<&str>>()
-            .join("")
+        self.final_text()
+    }
+
+    /// Returns the text received so far, accumulated as the responses were read.
+    ///
+    /// # Returns
+    /// * A String containing the combined text from all responses
+    pub fn final_text(&self) -> String {
+        self.text.text()
+    }
+
+    /// Merges the responses read so far into one, e.g. to log once the stream ends.
+    ///
+    /// The parts of each candidate are joined in order, with consecutive text parts
+    /// concatenated, and the last finish reason and safety ratings are kept. The
+    /// usage counts cover the whole stream, and the latency metrics are attached.
+    ///
+    /// # Returns
+    /// * `Some(GeminiResponse)` with the merged response
+    /// * `None` if no response has been read
+    pub fn final_response(&self) -> Option<GeminiResponse> {
+        let mut response = merge_responses(&self.responses)?;
+        response.metrics = Some(self.metrics());
+        Some(response)
+    }
+}
+
+// ===
+// TRAIT: GeminiResponseStream (Drop)
+// ===
+
+#[cfg(feature = "audit")]
+impl Drop for GeminiResponseStream {
+    /// Writes the audit record of a stream dropped before `read` returned `None`.
+    ///
+    /// A stream dropped after a response with a finish reason counts as complete.
+    fn drop(&mut self) {
+        let finished = self.responses.last().is_some_and(|response| {
+            response
+                .candidate(0)
+                .is_some_and(|candidate| candidate.finish_reason.is_some())
+        });
+        let error = (!finished).then_some("the stream was dropped before it completed");
+        self.write_audit(error);
+    }
+}
+
+/// The audit log a stream writes its exchange to; see `set_audit_log`.
+#[cfg(feature = "audit")]
+struct StreamAudit {
+    audit_log: AuditLog,
+    model: String,
+    request_json: JsonValue,
+}
+
+/// Merges streamed responses into one; see `GeminiResponseStream::final_response`.
+fn merge_responses(responses: &[GeminiResponse]) -> Option<GeminiResponse> {
+    let (first, rest) = responses.split_first()?;
+    let mut merged = first.clone();
+
+    for response in rest {
+        for (position, candidate) in response.candidates.iter().flatten().enumerate() {
+            let candidates = merged.candidates.get_or_insert_with(Vec::new);
+            let index = candidate.index.unwrap_or(position as u32);
+            let existing = candidates
+                .iter_mut()
+                .enumerate()
+                .find(|(position, merged)| merged.index.unwrap_or(*position as u32) == index);
+            match existing {
+                Some((_, existing)) => merge_candidate(existing, candidate),
+                None => candidates.push(candidate.clone()),
+            }
+        }
+
+        if let Some(usage) = &response.usage_metadata {
+            merge_usage(
+                merged.usage_metadata.get_or_insert_with(Default::default),
+                usage,
+            );
+        }
+        if response.error.is_some() {
+            merged.error = response.error.clone();
+        }
+        if merged.prompt_feedback.is_none() {
+            merged.prompt_feedback = response.prompt_feedback.clone();
+        }
+        if response.model_version.is_some() {
+            merged.model_version = response.model_version.clone();
+        }
+    }
+
+    Some(merged)
+}
+
+/// Appends the parts of a streamed candidate to the merged one.
+fn merge_candidate(merged: &mut GeminiCandidate, next: &GeminiCandidate) {
+    for part in &next.content.parts {
+        match (merged.content.parts.last_mut(), part) {
+            (Some(GeminiPart::Text(last)), GeminiPart::Text(text)) => {
+                last.text.push_str(&text.text)
+            }
+            _ => merged.content.parts.push(part.clone()),
+        }
+    }
+    if next.finish_reason.is_some() {
+        merged.finish_reason = next.finish_reason.clone();
+    }
+    if !next.safety_ratings.is_empty() {
+        merged.safety_ratings = next.safety_ratings.clone();
+    }
+}
+
+/// Combines the token counts of a streamed chunk with the merged counts.
+///
+/// Each chunk reports running totals, so the largest count of each kind is kept;
+/// adding them up would count the same tokens several times.
+fn merge_usage(merged: &mut GeminiUsageMetadata, next: &GeminiUsageMetadata) {
+    merged.prompt_token_count = merged.prompt_token_count.max(next.prompt_token_count);
+    merged.candidates_token_count = merged
+        .candidates_token_count
+        .max(next.candidates_token_count);
+    merged.thoughts_token_count = merged.thoughts_token_count.max(next.thoughts_token_count);
+    merged.total_token_count = merged.total_token_count.max(next.total_token_count);
+}
+
+/// Passes the text parts of the first candidate through the stop sequence filter.
+///
+/// Held back text is flushed into the last text part once the candidate finishes,
+/// and the finish reason is set to "STOP" when a stop sequence cuts the text.
+fn enforce_stop_sequences(filter: &mut StopSequenceFilter, response: &mut GeminiResponse) {
+    let Some(candidate) = response
+        .candidates
+        .as_mut()
+        .and_then(|candidates| candidates.first_mut())
+    else {
+        return;
+    };
+
+    for part in candidate.content.parts.iter_mut() {
+        if let GeminiPart::Text(text_part) = part {
+            text_part.text = filter.push(&text_part.text);
+        }
+    }
+
+    if filter.is_stopped() {
+        candidate.finish_reason = Some("STOP".to_string());
+    } else if candidate.finish_reason.is_some() {
+        let remaining = filter.finish();
+        let last_text = candidate
+            .content
+            .parts
+            .iter_mut()
+            .rev()
+            .find_map(|part| match part {
+                GeminiPart::Text(text_part) => Some(text_part),
+                _ => None,
+            });
+        if let Some(text_part) = last_text {
+            text_part.text.push_str(&remaining);
+        }
+    }
+}
+
+// ===
+// TESTS: GeminiResponseStream
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chunk(text: &str, finish_reason: Option<&str>) -> GeminiResponse {
+        serde_json::from_value(json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [{ "text": text }] },
+                "finishReason": finish_reason
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_enforce_stop_sequences() {
+        let mut filter = StopSequenceFilter::new(&["END"]);
+
+        let mut first = chunk("one E", None);
+        enforce_stop_sequences(&mut filter, &mut first);
+        assert_eq!(first.text(), Some("one "));
+
+        let mut second = chunk("ND two", None);
+        enforce_stop_sequences(&mut filter, &mut second);
+        assert_eq!(second.text(), Some(""));
+        assert_eq!(
+            second.candidates.unwrap()[0].finish_reason.as_deref(),
+            Some("STOP")
+        );
+        assert!(filter.is_stopped());
+    }
+
+    #[test]
+    fn test_held_back_text_flushed_on_finish() {
+        let mut filter = StopSequenceFilter::new(&["END"]);
+
+        let mut first = chunk("the E", None);
+        enforce_stop_sequences(&mut filter, &mut first);
+        assert_eq!(first.text(), Some("the "));
+
+        let mut last = chunk("", Some("STOP"));
+        enforce_stop_sequences(&mut filter, &mut last);
+        assert_eq!(last.text(), Some("E"));
+    }
+
+    #[test]
+    fn test_merge_responses() {
+        let mut first = chunk("Checking ", None);
+        first.usage_metadata = Some(GeminiUsageMetadata {
+            prompt_token_count: Some(7),
+            total_token_count: Some(7),
+            ..Default::default()
+        });
+        first.model_version = Some("gemini-2.0-flash".to_string());
+        let second: GeminiResponse = serde_json::from_value(json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [
+                    { "text": "the weather." },
+                    { "functionCall": { "name": "weather", "args": { "city": "Paris" } } }
+                ] },
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": { "promptTokenCount": 7, "candidatesTokenCount": 5, "totalTokenCount": 12 }
+        }))
+        .unwrap();
+
+        let merged = merge_responses(&[first, second]).unwrap();
+        let candidate = merged.candidate(0).unwrap();
+        assert_eq!(candidate.content.parts.len(), 2);
+        assert_eq!(candidate.text(), Some("Checking the weather."));
+        assert_eq!(candidate.finish_reason.as_deref(), Some("STOP"));
+        assert_eq!(merged.functions()[0].name(), "weather");
+        assert_eq!(merged.model_version.as_deref(), Some("gemini-2.0-flash"));
+
+        let usage = merged.usage_metadata.unwrap();
+        assert_eq!(usage.prompt_token_count, Some(7));
+        assert_eq!(usage.candidates_token_count, Some(5));
+        assert_eq!(usage.total_token_count, Some(12));
+
+        assert!(merge_responses(&[]).is_none());
     }
 }
@@ -1,24 +1,70 @@
 // Re-export Gemini module contents
+#[doc(hidden)]
+#[allow(clippy::module_inception)]
+#[cfg(feature = "transport")]
 pub mod gemini;
+#[doc(inline)]
+#[cfg(feature = "transport")]
 pub use gemini::*;
 
+#[doc(hidden)]
+pub mod gemini_batch;
+#[doc(inline)]
+pub use gemini_batch::*;
+
+#[doc(hidden)]
 pub mod gemini_content;
+#[doc(inline)]
 pub use gemini_content::*;
 
+#[doc(hidden)]
+pub mod gemini_generation_config;
+#[doc(inline)]
+pub use gemini_generation_config::*;
+
+#[doc(hidden)]
 pub mod gemini_function;
+#[doc(inline)]
 pub use gemini_function::*;
 
+#[doc(hidden)]
 pub mod gemini_part;
+#[doc(inline)]
 pub use gemini_part::*;
 
+#[doc(hidden)]
 pub mod gemini_prompt;
+#[doc(inline)]
 pub use gemini_prompt::*;
 
+#[doc(hidden)]
 pub mod gemini_response;
+#[doc(inline)]
 pub use gemini_response::*;
 
+#[doc(hidden)]
+pub mod gemini_response_error;
+#[doc(inline)]
+pub use gemini_response_error::*;
+
+#[cfg(feature = "transport")]
+mod gemini_openai;
+
+#[doc(hidden)]
+#[cfg(feature = "transport")]
 pub mod gemini_response_stream;
+#[doc(inline)]
+#[cfg(feature = "transport")]
 pub use gemini_response_stream::*;
 
+#[doc(hidden)]
 pub mod gemini_request;
+#[doc(inline)]
 pub use gemini_request::*;
+
+#[doc(hidden)]
+#[cfg(feature = "live")]
+pub mod gemini_live_session;
+#[doc(inline)]
+#[cfg(feature = "live")]
+pub use gemini_live_session::*;
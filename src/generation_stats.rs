@@ -0,0 +1,117 @@
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+// ===
+// STRUCT: GenerationStats
+// ===
+
+/// Token counts and timings of one generated response.
+///
+/// Returned by `OllamaResponse::stats` and `GeminiResponse::stats`. The struct
+/// implements `Display` for a short human-readable summary and `Serialize`, with
+/// durations in seconds, for logging. Fields the provider did not report are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GenerationStats {
+    /// The model that generated the response.
+    pub model: Option<String>,
+    /// The number of prompt tokens.
+    pub prompt_tokens: Option<u32>,
+    /// The number of generated tokens.
+    pub completion_tokens: Option<u32>,
+    /// The time the server spent generating.
+    #[serde(serialize_with = "seconds")]
+    pub eval_duration: Option<Duration>,
+    /// The total time the server spent on the request.
+    #[serde(serialize_with = "seconds")]
+    pub total_duration: Option<Duration>,
+    /// The wall time measured by the client, including network latency.
+    #[serde(serialize_with = "seconds")]
+    pub wall_time: Option<Duration>,
+}
+
+impl GenerationStats {
+    /// Returns the number of prompt and generated tokens together.
+    pub fn tokens_used(&self) -> u32 {
+        self.prompt_tokens.unwrap_or(0) + self.completion_tokens.unwrap_or(0)
+    }
+
+    /// Returns the generation rate in tokens per second.
+    ///
+    /// Uses the server's eval duration if reported, otherwise the client's wall time.
+    pub fn eval_rate(&self) -> Option<f64> {
+        let tokens = self.completion_tokens?;
+        let seconds = self.eval_duration.or(self.wall_time)?.as_secs_f64();
+        (seconds > 0.0).then(|| f64::from(tokens) / seconds)
+    }
+}
+
+/// Serializes an optional duration as fractional seconds.
+fn seconds<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    duration.map(|d| d.as_secs_f64()).serialize(serializer)
+}
+
+// ===
+// TRAIT: GenerationStats (fmt::Display)
+// ===
+
+impl fmt::Display for GenerationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "model: {}",
+            self.model.as_deref().unwrap_or("unknown model")
+        )?;
+        write!(f, "\ncontext used: {} tokens", self.tokens_used())?;
+        if let Some(tokens) = self.completion_tokens {
+            write!(f, "\ngenerated: {tokens} tokens")?;
+        }
+        if let Some(duration) = self.eval_duration {
+            write!(f, "\neval time: {:.2}s", duration.as_secs_f64())?;
+        }
+        if let Some(rate) = self.eval_rate() {
+            write!(f, "\neval rate: {rate:.1} tokens/s")?;
+        }
+        if let Some(duration) = self.wall_time {
+            write!(f, "\nwall time: {:.2}s", duration.as_secs_f64())?;
+        }
+        Ok(())
+    }
+}
+
+// ===
+// TESTS: GenerationStats
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_display_and_serialize() {
+        let stats = GenerationStats {
+            model: Some("gemma3".to_string()),
+            prompt_tokens: Some(20),
+            completion_tokens: Some(10),
+            eval_duration: Some(Duration::from_millis(500)),
+            ..GenerationStats::default()
+        };
+
+        assert_eq!(
+            stats.to_string(),
+            "model: gemma3\ncontext used: 30 tokens\ngenerated: 10 tokens\neval time: 0.50s\neval rate: 20.0 tokens/s"
+        );
+        assert_eq!(
+            serde_json::to_value(&stats).unwrap(),
+            json!({
+                "model": "gemma3",
+                "prompt_tokens": 20,
+                "completion_tokens": 10,
+                "eval_duration": 0.5,
+                "total_duration": null,
+                "wall_time": null
+            })
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::io;
+use std::path::Path;
+
+// ===
+// STRUCT: ImageData
+// ===
+
+/// An image together with its mime type, e.g. one returned inline by a Gemini
+/// image model.
+///
+/// Get the images of a response with `GeminiResponse::images`. With the `image`
+/// feature, `to_dynamic_image` decodes the pixels for further processing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageData {
+    mime_type: String,
+    data: Vec<u8>,
+}
+
+impl ImageData {
+    /// Creates an image from raw bytes in the given format.
+    ///
+    /// # Arguments
+    /// * `mime_type` - The image format, e.g. "image/png"
+    /// * `data` - The encoded image bytes
+    ///
+    /// # Returns
+    /// * A new ImageData instance
+    pub fn new(mime_type: &str, data: Vec<u8>) -> Self {
+        Self {
+            mime_type: mime_type.to_string(),
+            data,
+        }
+    }
+
+    /// Decodes a base64 image, as the provider APIs send them, and validates it.
+    ///
+    /// # Arguments
+    /// * `mime_type` - The declared image format, e.g. "image/png"
+    /// * `base64` - The base64-encoded image bytes
+    ///
+    /// # Returns
+    /// * `Ok(ImageData)` with the decoded bytes
+    /// * `Err(io::Error)` if the data is not valid base64 or fails `validate`
+    pub fn from_base64(mime_type: &str, base64: &str) -> io::Result<Self> {
+        let data = BASE64
+            .decode(base64)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let image = Self::new(mime_type, data);
+        image.validate()?;
+        Ok(image)
+    }
+
+    /// Returns the image format, e.g. "image/png".
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// Returns the encoded image bytes.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the image bytes encoded as base64.
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(&self.data)
+    }
+
+    /// Returns the usual file extension for the image format, e.g. "jpg".
+    pub fn extension(&self) -> &str {
+        match self.mime_type.as_str() {
+            "image/jpeg" | "image/jpg" => "jpg",
+            "image/svg+xml" => "svg",
+            other => other.strip_prefix("image/").unwrap_or("bin"),
+        }
+    }
+
+    /// Checks that the mime type names an image and agrees with the data.
+    ///
+    /// PNG, JPEG, GIF and WebP data is recognized from its leading bytes; data
+    /// in other formats is accepted as declared.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the image is consistent
+    /// * `Err(io::Error)` if the mime type is not an image type or the data is in another format
+    pub fn validate(&self) -> io::Result<()> {
+        let invalid = |message: String| Err(io::Error::new(io::ErrorKind::InvalidData, message));
+
+        if !self.mime_type.starts_with("image/") {
+            return invalid(format!("not an image type: {}", self.mime_type));
+        }
+        let declared = match self.mime_type.as_str() {
+            "image/jpg" => "image/jpeg",
+            other => other,
+        };
+        match Self::sniff_mime_type(&self.data) {
+            Some(actual) if actual != declared => {
+                invalid(format!("image declared as {} is {actual}", self.mime_type))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the image bytes to a file.
+    ///
+    /// # Arguments
+    /// * `path` - The file to write; its extension is used as given
+    ///
+    /// # Returns
+    /// * `Ok(())` once the file is written
+    /// * `Err(io::Error)` if the file could not be written
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, &self.data)
+    }
+
+    /// Decodes the pixels of the image.
+    ///
+    /// # Returns
+    /// * `Ok(DynamicImage)` with the decoded image
+    /// * `Err(ImageError)` if the format is not supported or the data is corrupt
+    #[cfg(feature = "image")]
+    pub fn to_dynamic_image(&self) -> Result<image::DynamicImage, image::ImageError> {
+        match image::ImageFormat::from_mime_type(&self.mime_type) {
+            Some(format) => image::load_from_memory_with_format(&self.data, format),
+            None => image::load_from_memory(&self.data),
+        }
+    }
+
+    /// Detects the image format from the leading bytes of the data.
+    fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+        let magic =
+            |offset: usize, bytes: &[u8]| data.get(offset..offset + bytes.len()) == Some(bytes);
+
+        if magic(0, b"\x89PNG\r\n\x1a\n") {
+            Some("image/png")
+        } else if magic(0, &[0xFF, 0xD8, 0xFF]) {
+            Some("image/jpeg")
+        } else if magic(0, b"GIF87a") || magic(0, b"GIF89a") {
+            Some("image/gif")
+        } else if magic(0, b"RIFF") && magic(8, b"WEBP") {
+            Some("image/webp")
+        } else {
+            None
+        }
+    }
+}
+
+// ===
+// TESTS: ImageData
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_base64_validates_mime_type() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let image = ImageData::from_base64("image/png", &BASE64.encode(png)).unwrap();
+        assert_eq!(image.data(), png);
+        assert_eq!(image.extension(), "png");
+
+        let mismatch = ImageData::from_base64("image/jpeg", &BASE64.encode(png)).unwrap_err();
+        assert!(mismatch.to_string().contains("image/png"), "{mismatch}");
+        assert!(ImageData::from_base64("audio/wav", &BASE64.encode(png)).is_err());
+        assert!(ImageData::from_base64("image/png", "not base64!").is_err());
+
+        // Formats that are not recognized are taken as declared.
+        let heic = ImageData::new("image/heic", vec![0, 0, 0, 24]);
+        assert!(heic.validate().is_ok());
+        assert_eq!(
+            ImageData::new("image/jpg", vec![0xFF, 0xD8, 0xFF]).extension(),
+            "jpg"
+        );
+        assert!(
+            ImageData::new("image/jpg", vec![0xFF, 0xD8, 0xFF])
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_to_dynamic_image() {
+        let mut png = Vec::new();
+        image::RgbImage::new(2, 3)
+            .write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let decoded = ImageData::new("image/png", png).to_dynamic_image().unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (2, 3));
+        assert!(
+            ImageData::new("image/png", vec![1, 2, 3])
+                .to_dynamic_image()
+                .is_err()
+        );
+    }
+}
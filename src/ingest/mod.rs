@@ -0,0 +1,4 @@
+#[doc(hidden)]
+pub mod web_page;
+#[doc(inline)]
+pub use web_page::*;
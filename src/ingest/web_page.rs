@@ -0,0 +1,834 @@
+use crate::TextChunker;
+#[cfg(feature = "transport")]
+use std::error::Error;
+#[cfg(feature = "transport")]
+use std::time::Duration;
+
+/// The maximum time `fetch_url` waits for a page, including its body.
+#[cfg(feature = "transport")]
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The largest page body, in bytes, that `fetch_url` downloads.
+#[cfg(feature = "transport")]
+pub const MAX_PAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Elements whose content is never page text, e.g. scripts.
+const RAW_TEXT_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "textarea", "title",
+];
+
+/// Elements that hold navigation, forms and other page furniture.
+const BOILERPLATE_TAGS: &[&str] = &[
+    "nav", "aside", "footer", "form", "button", "iframe", "select", "dialog", "menu",
+];
+
+/// Roles that mark page furniture.
+const BOILERPLATE_ROLES: &[&str] = &["navigation", "banner", "contentinfo", "complementary"];
+
+/// Words that mark page furniture when they make up a class or id, or one of its
+/// parts separated by "-" or "_", e.g. "cookie-banner" but not "commentary".
+const BOILERPLATE_NAMES: &[&str] = &[
+    "advert",
+    "banner",
+    "breadcrumb",
+    "comment",
+    "cookie",
+    "footer",
+    "menu",
+    "modal",
+    "navbar",
+    "navigation",
+    "newsletter",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "subscribe",
+];
+
+/// Elements that start a new paragraph.
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Elements that never have a closing tag.
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+// ===
+// STRUCT: WebPage
+// ===
+
+/// The readable text of a web page, with its title and metadata.
+///
+/// Navigation, headers, footers, sidebars, forms, scripts and paragraphs that are
+/// mostly links are left out. When the page marks its content with an `<article>`
+/// or `<main>` element, only the text inside it is kept, even if the element sits
+/// inside page furniture such as a page-wide `<form>`. Paragraphs are separated
+/// by blank lines, so the text splits cleanly with a `TextChunker`; the text of
+/// `<pre>` elements keeps its whitespace.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::TextChunker;
+/// use ollie_rs::ingest::fetch_url;
+///
+/// let page = fetch_url("https://example.com/article").await?;
+/// println!("{:?}", page.title);
+/// for chunk in page.chunks(&TextChunker::new(512)) {
+///     println!("{chunk}\n---");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WebPage {
+    /// The address the page was read from, after any redirects.
+    pub url: String,
+    /// The page title, preferring the Open Graph title over the `<title>` element.
+    pub title: Option<String>,
+    /// The page description from its metadata.
+    pub description: Option<String>,
+    /// The author from the page metadata.
+    pub author: Option<String>,
+    /// The language of the page, e.g. "en", from the `lang` attribute.
+    pub language: Option<String>,
+    /// The readable text, with paragraphs separated by blank lines.
+    pub text: String,
+}
+
+impl WebPage {
+    /// Extracts the readable text and metadata from an HTML document.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The address the document was read from
+    /// * `html` - The HTML source
+    ///
+    /// # Returns
+    ///
+    /// The page; its text is empty if the document has no readable content.
+    pub fn from_html(url: &str, html: &str) -> Self {
+        let extractor = extract(html);
+
+        // Prefer the page's own marking of its content over everything else.
+        let best = extractor
+            .blocks
+            .iter()
+            .map(|block| block.region)
+            .max()
+            .unwrap_or(Region::Page);
+        let paragraphs: Vec<&str> = extractor
+            .blocks
+            .iter()
+            .filter(|block| block.region == best && !block.is_mostly_links())
+            .map(|block| block.text.as_str())
+            .collect();
+
+        Self {
+            url: url.to_string(),
+            title: extractor
+                .meta(&["og:title", "twitter:title"])
+                .or_else(|| extractor.title.clone()),
+            description: extractor.meta(&["description", "og:description"]),
+            author: extractor.meta(&["author", "article:author"]),
+            language: extractor.language.clone(),
+            text: paragraphs.join("\n\n"),
+        }
+    }
+
+    /// Splits the text into chunks, e.g. to embed or rerank with the `rag` helpers.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunker` - The chunker that sets the chunk size
+    ///
+    /// # Returns
+    ///
+    /// The chunks of the text, in order.
+    pub fn chunks(&self, chunker: &TextChunker) -> Vec<String> {
+        chunker.chunks(&self.text)
+    }
+
+    /// Formats the page as a Markdown document with its title and address, ready
+    /// to add to a conversation, e.g. to chat about the page.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        if let Some(title) = &self.title {
+            markdown.push_str(&format!("# {title}\n\n"));
+        }
+        markdown.push_str(&format!("Source: {}\n\n{}", self.url, self.text));
+        markdown
+    }
+}
+
+/// Downloads a web page and extracts its readable text; see `WebPage`.
+///
+/// Plain text responses are kept as they are. The download fails after
+/// [`FETCH_TIMEOUT`] or once the body exceeds [`MAX_PAGE_BYTES`].
+///
+/// # Arguments
+///
+/// * `url` - The http or https address of the page
+///
+/// # Returns
+///
+/// * `Ok(WebPage)` - The page.
+/// * `Err(Box<dyn Error>)` - If the request fails or times out, the server answers
+///   with an error status, or the page is too large.
+#[cfg(feature = "transport")]
+pub async fn fetch_url(url: &str) -> Result<WebPage, Box<dyn Error>> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("ollie-rs/", env!("CARGO_PKG_VERSION")))
+        .timeout(FETCH_TIMEOUT)
+        .build()?;
+    let mut response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(format!("failed to fetch {url}: {}", response.status()).into());
+    }
+
+    let final_url = response.url().to_string();
+    let plain_text = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/plain"));
+    let too_large = || format!("the page at {url} is larger than {MAX_PAGE_BYTES} bytes");
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_PAGE_BYTES as u64)
+    {
+        return Err(too_large().into());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if bytes.len() + chunk.len() > MAX_PAGE_BYTES {
+            return Err(too_large().into());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    let body = String::from_utf8_lossy(&bytes);
+
+    if plain_text {
+        return Ok(WebPage {
+            url: final_url,
+            text: body.trim().to_string(),
+            ..Default::default()
+        });
+    }
+    Ok(WebPage::from_html(&final_url, &body))
+}
+
+// ===
+// STRUCT: Extractor
+// ===
+
+/// How strongly the page marks an element as its main content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Region {
+    Page,
+    Main,
+    Article,
+}
+
+/// A paragraph of page text.
+struct Block {
+    text: String,
+    link_chars: usize,
+    region: Region,
+}
+
+impl Block {
+    /// Returns true if most of the paragraph is link text, as in menus and link lists.
+    fn is_mostly_links(&self) -> bool {
+        let chars = self.text.chars().filter(|c| !c.is_whitespace()).count();
+        self.link_chars * 2 > chars
+    }
+}
+
+/// An open element.
+struct Frame {
+    name: String,
+    skip: bool,
+    hidden: bool,
+    link: bool,
+    pre: bool,
+    region: Region,
+}
+
+/// Collects the paragraphs and metadata of a document as it is parsed.
+#[derive(Default)]
+struct Extractor {
+    stack: Vec<Frame>,
+    blocks: Vec<Block>,
+    current: String,
+    link_chars: usize,
+    region: Option<Region>,
+    pre: bool,
+    bullet: bool,
+    title: Option<String>,
+    language: Option<String>,
+    meta: Vec<(String, String)>,
+}
+
+impl Extractor {
+    /// Handles an opening tag.
+    fn open(&mut self, name: String, attributes: &[(String, String)], self_closing: bool) {
+        let attribute = |key: &str| {
+            attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        };
+
+        match name.as_str() {
+            "html" => self.language = attribute("lang").and_then(clean),
+            "meta" => {
+                let key = attribute("name").or_else(|| attribute("property"));
+                if let (Some(key), Some(content)) = (key, attribute("content")) {
+                    self.meta.push((key.to_lowercase(), content.to_string()));
+                }
+            }
+            _ => {}
+        }
+
+        if BLOCK_TAGS.contains(&name.as_str()) {
+            self.flush();
+        }
+        if self_closing || VOID_TAGS.contains(&name.as_str()) {
+            return;
+        }
+
+        let parent = self.stack.last();
+        let own_region = match name.as_str() {
+            "article" => Region::Article,
+            "main" => Region::Main,
+            _ if attribute("role") == Some("main") => Region::Main,
+            _ => Region::Page,
+        };
+        let region = parent
+            .map_or(Region::Page, |frame| frame.region)
+            .max(own_region);
+        // Content marked as such is kept inside furniture, unless it is hidden.
+        let hidden = parent.is_some_and(|frame| frame.hidden)
+            || attribute("hidden").is_some()
+            || attribute("aria-hidden") == Some("true");
+        let skip = hidden
+            || (parent.is_some_and(|frame| frame.skip) && own_region == Region::Page)
+            || is_boilerplate(&name, region, &attribute);
+        self.bullet |= name == "li" && !skip;
+        let frame = Frame {
+            skip,
+            hidden,
+            link: parent.is_some_and(|frame| frame.link) || name == "a",
+            pre: parent.is_some_and(|frame| frame.pre) || name == "pre",
+            region,
+            name,
+        };
+        self.stack.push(frame);
+    }
+
+    /// Handles a closing tag, also closing any elements left open inside it.
+    fn close(&mut self, name: &str) {
+        if let Some(index) = self.stack.iter().rposition(|frame| frame.name == name) {
+            self.stack.truncate(index);
+        }
+        if BLOCK_TAGS.contains(&name) {
+            self.flush();
+        }
+        if name == "li" {
+            self.bullet = false;
+        }
+    }
+
+    /// Adds text found between tags.
+    fn text(&mut self, raw: &str) {
+        let (skip, link, pre, region) = self
+            .stack
+            .last()
+            .map_or((false, false, false, Region::Page), |frame| {
+                (frame.skip, frame.link, frame.pre, frame.region)
+            });
+        if skip || raw.is_empty() {
+            return;
+        }
+
+        let text = decode_entities(raw);
+        if pre {
+            self.current.push_str(&text);
+            self.pre = true;
+        } else if text.trim().is_empty() {
+            if !self.current.is_empty() && !self.current.ends_with(char::is_whitespace) {
+                self.current.push(' ');
+            }
+            return;
+        } else {
+            if text.starts_with(char::is_whitespace)
+                && !self.current.is_empty()
+                && !self.current.ends_with(char::is_whitespace)
+            {
+                self.current.push(' ');
+            }
+            self.current
+                .push_str(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+            if text.ends_with(char::is_whitespace) {
+                self.current.push(' ');
+            }
+        }
+
+        if link {
+            self.link_chars += text.chars().filter(|c| !c.is_whitespace()).count();
+        }
+        self.region = Some(self.region.map_or(region, |current| current.max(region)));
+    }
+
+    /// Ends the current paragraph.
+    fn flush(&mut self) {
+        // Preformatted text keeps its indentation, only losing surrounding blank lines.
+        let text = if self.pre {
+            let text = self.current.trim_end();
+            let start = text
+                .char_indices()
+                .take_while(|(_, c)| c.is_whitespace())
+                .filter(|(_, c)| *c == '\n')
+                .last()
+                .map_or(0, |(index, _)| index + 1);
+            &text[start..]
+        } else {
+            self.current.trim()
+        };
+        if !text.is_empty() {
+            let text = if self.bullet {
+                format!("- {text}")
+            } else {
+                text.to_string()
+            };
+            self.blocks.push(Block {
+                text,
+                link_chars: self.link_chars,
+                region: self.region.unwrap_or(Region::Page),
+            });
+            self.bullet = false;
+        }
+        self.current.clear();
+        self.link_chars = 0;
+        self.region = None;
+        self.pre = false;
+    }
+
+    /// Returns the first non-empty `<meta>` content among the given names.
+    fn meta(&self, keys: &[&str]) -> Option<String> {
+        keys.iter().find_map(|key| {
+            self.meta
+                .iter()
+                .filter(|(name, _)| name == key)
+                .find_map(|(_, content)| clean(content))
+        })
+    }
+}
+
+/// Parses a document into paragraphs and metadata.
+fn extract(html: &str) -> Extractor {
+    let mut extractor = Extractor::default();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            extractor.text(rest);
+            break;
+        };
+        extractor.text(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            let end = tag.find('>').unwrap_or(tag.len());
+            extractor.close(&tag_name(&tag[..end]));
+            rest = tag.get(end + 1..).unwrap_or("");
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let end = tag_end(rest);
+            let tag = &rest[1..end];
+            rest = rest.get(end + 1..).unwrap_or("");
+
+            let name = tag_name(tag);
+            if RAW_TEXT_TAGS.contains(&name.as_str()) {
+                let (content, after) = raw_text(rest, &name);
+                if name == "title" && extractor.title.is_none() {
+                    extractor.title = clean(&decode_entities(content));
+                }
+                rest = after;
+            } else {
+                let attributes = attributes(&tag[name.len()..]);
+                extractor.open(name, &attributes, tag.ends_with('/'));
+            }
+        } else {
+            extractor.text("<");
+            rest = &rest[1..];
+        }
+    }
+
+    extractor.flush();
+    extractor
+}
+
+/// Returns true if an element holds page furniture rather than content.
+fn is_boilerplate<'a>(
+    name: &str,
+    region: Region,
+    attribute: &impl Fn(&str) -> Option<&'a str>,
+) -> bool {
+    if BOILERPLATE_TAGS.contains(&name) || (name == "header" && region == Region::Page) {
+        return true;
+    }
+    if attribute("role").is_some_and(|role| BOILERPLATE_ROLES.contains(&role)) {
+        return true;
+    }
+    if matches!(name, "html" | "body" | "article" | "main") {
+        return false;
+    }
+
+    let names = format!(
+        "{} {}",
+        attribute("class").unwrap_or(""),
+        attribute("id").unwrap_or("")
+    )
+    .to_lowercase();
+    names
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .any(|word| BOILERPLATE_NAMES.contains(&word))
+}
+
+/// Returns the lowercased element name at the start of a tag.
+fn tag_name(tag: &str) -> String {
+    let tag = tag.trim_start();
+    let end = tag
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+        .unwrap_or(tag.len());
+    tag[..end].to_ascii_lowercase()
+}
+
+/// Returns the position of the `>` that ends the tag at the start of `source`,
+/// skipping any inside quoted attribute values.
+fn tag_end(source: &str) -> usize {
+    let mut quote = None;
+    for (index, c) in source.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return index,
+            _ => {}
+        }
+    }
+    source.len()
+}
+
+/// Splits the content of a raw text element, e.g. a script, from what follows
+/// its closing tag.
+fn raw_text<'a>(source: &'a str, name: &str) -> (&'a str, &'a str) {
+    let mut from = 0;
+    while let Some(offset) = source[from..].find("</") {
+        let start = from + offset;
+        let candidate = &source.as_bytes()[start + 2..];
+        if candidate.len() >= name.len()
+            && candidate[..name.len()].eq_ignore_ascii_case(name.as_bytes())
+        {
+            let end = source[start..]
+                .find('>')
+                .map_or(source.len(), |end| start + end + 1);
+            return (&source[..start], &source[end..]);
+        }
+        from = start + 2;
+    }
+    (source, "")
+}
+
+/// Parses the attributes of a tag, with lowercased names and decoded values.
+fn attributes(source: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = source;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..end].to_ascii_lowercase();
+        rest = rest[end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let body = &after[1..];
+                    let end = body.find(quote).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            value = decode_entities(raw);
+            rest = remaining;
+        }
+
+        if !name.is_empty() {
+            attributes.push((name, value));
+        }
+    }
+
+    attributes
+}
+
+/// Replaces character references, e.g. "&amp;" or "&#8217;", by the characters they name.
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let reference = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((entity(&rest[1..end + 1])?, end + 2)));
+        match reference {
+            Some((c, length)) => {
+                decoded.push(c);
+                rest = &rest[length..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Returns the character a reference names, without its `&` and `;`.
+fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// Collapses whitespace, returning `None` if nothing is left.
+fn clean(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+// ===
+// TESTS: WebPage
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <title>Tides explained | Example News</title>
+  <meta property="og:title" content="Tides explained">
+  <meta name="description" content="Why the sea rises &amp; falls.">
+  <meta name="author" content="Ada Lovelace">
+  <style>body { color: black; }</style>
+  <script>if (a < b) { document.write("</p>"); }</script>
+</head>
+<body>
+  <header><a href="/">Example News</a></header>
+  <nav><ul><li><a href="/world">World</a></li><li><a href="/science">Science</a></li></ul></nav>
+  <main>
+    <article>
+      <h1>Tides explained</h1>
+      <p>The Moon&#8217;s gravity pulls the oceans <em>toward</em> it,
+         twice a day.</p>
+      <!-- an ad slot -->
+      <div class="share-buttons"><a href="#">Share</a></div>
+      <p>The Sun adds a smaller pull. See <a href="/spring">spring tides</a> for more.</p>
+      <ul><li>High tide</li><li>Low tide</li></ul>
+      <p><a href="/next">Next article</a></p>
+      <pre>tide = moon + sun
+  (roughly)</pre>
+    </article>
+    <aside>Most read: nothing</aside>
+  </main>
+  <div class="cookie-banner" hidden>We use cookies.</div>
+  <footer>&copy; 2024 Example News</footer>
+</body>
+</html>"##;
+
+    #[test]
+    fn test_from_html_keeps_the_article() {
+        let page = WebPage::from_html("https://example.com/tides", ARTICLE);
+
+        assert_eq!(page.url, "https://example.com/tides");
+        assert_eq!(page.title.as_deref(), Some("Tides explained"));
+        assert_eq!(
+            page.description.as_deref(),
+            Some("Why the sea rises & falls.")
+        );
+        assert_eq!(page.author.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(page.language.as_deref(), Some("en"));
+        assert_eq!(
+            page.text,
+            "Tides explained\n\n\
+             The Moon’s gravity pulls the oceans toward it, twice a day.\n\n\
+             The Sun adds a smaller pull. See spring tides for more.\n\n\
+             - High tide\n\n\
+             - Low tide\n\n\
+             tide = moon + sun\n  (roughly)"
+        );
+
+        let chunks = page.chunks(&TextChunker::new(20));
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.join("\n\n"), page.text);
+        assert!(
+            page.to_markdown()
+                .starts_with("# Tides explained\n\nSource: https://example.com/tides\n\n")
+        );
+    }
+
+    #[test]
+    fn test_from_html_without_article() {
+        let html = "<title>Notes</title><body><div id=sidebar><p>Archive</p></div>\
+                    <p>First &lt;note&gt;.<br>Second line</p><div>Loose text</div>";
+        let page = WebPage::from_html("https://example.com/", html);
+
+        assert_eq!(page.title.as_deref(), Some("Notes"));
+        assert_eq!(page.description, None);
+        assert_eq!(page.text, "First <note>.\n\nSecond line\n\nLoose text");
+        assert_eq!(
+            decode_entities("fish &chips; &#x41;&unknown;"),
+            "fish &chips; A&unknown;"
+        );
+    }
+
+    #[test]
+    fn test_from_html_keeps_content_inside_furniture() {
+        let html = "<body><form id=aspnetForm><nav><a href=/>Home</a></nav>\
+                    <div class=\"commentary shared-notes\"><main>\
+                    <p>Kept.</p><div class=related-links>Gone.</div></main>\
+                    <p>Form text.</p><div hidden><article>Hidden.</article></div></form></body>";
+        let page = WebPage::from_html("https://example.com/", html);
+
+        assert_eq!(page.text, "Kept.");
+        assert_eq!(
+            WebPage::from_html("", "<div class=commentary>Kept.</div>").text,
+            "Kept."
+        );
+    }
+
+    #[test]
+    fn test_from_html_keeps_whitespace_in_pre() {
+        let html = "<p>Code:</p><pre>\n    fn main() {\n\n        <b>run</b>();\n    }\n</pre>\
+                    <pre><code>a</code>\n\n<code>b</code></pre>";
+        let page = WebPage::from_html("https://example.com/", html);
+
+        assert_eq!(
+            page.text,
+            "Code:\n\n    fn main() {\n\n        run();\n    }\n\na\n\nb"
+        );
+    }
+
+    #[cfg(feature = "transport")]
+    #[tokio::test]
+    async fn test_fetch_url_rejects_large_pages() {
+        let body = "a".repeat(MAX_PAGE_BYTES + 1);
+        let server = crate::mock_server::MockServer::start(vec![body]).await;
+
+        let error = fetch_url(&format!("http://{}/", server.addr()))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("is larger than"));
+    }
+
+    #[cfg(feature = "transport")]
+    #[tokio::test]
+    async fn test_fetch_url() {
+        let server = crate::mock_server::MockServer::start(vec![ARTICLE.to_string()]).await;
+        let url = format!("http://{}/tides", server.addr());
+
+        let page = fetch_url(&url).await.unwrap();
+        assert_eq!(page.url, url);
+        assert_eq!(page.title.as_deref(), Some("Tides explained"));
+        assert!(page.text.starts_with("Tides explained\n\nThe Moon’s"));
+        assert!(server.headers()[0].starts_with("get /tides"));
+    }
+}
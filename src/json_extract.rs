@@ -0,0 +1,230 @@
+use crate::XmlUtil;
+use serde_json::Value as JsonValue;
+use std::error::Error;
+use std::fmt;
+
+/// Parses JSON out of model output, tolerating the wrapping models tend to add.
+///
+/// Thinking blocks (`<think>...</think>`) are removed, a Markdown code fence such
+/// as ```` ```json ```` is unwrapped, and any commentary before the first `{` or
+/// `[` and after the end of the JSON value is ignored.
+///
+/// # Arguments
+/// * `text` - The model output
+///
+/// # Returns
+/// * The first JSON value in the text, or an error with the text that failed to parse
+pub fn extract_json(text: &str) -> Result<JsonValue, JsonExtractError> {
+    let without_thinking = strip_thinking(text);
+    let candidate = unfence(&without_thinking).trim();
+
+    if let Ok(value) = serde_json::from_str(candidate) {
+        return Ok(value);
+    }
+
+    let Some(start) = candidate.find(['{', '[']) else {
+        return Err(JsonExtractError::new(
+            "no JSON object or array found",
+            candidate,
+            None,
+        ));
+    };
+
+    let mut values = serde_json::Deserializer::from_str(&candidate[start..]).into_iter();
+    match values.next() {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(error)) => Err(JsonExtractError::new(
+            &error.to_string(),
+            &candidate[start..],
+            Some((error.line(), error.column())),
+        )),
+        None => Err(JsonExtractError::new(
+            "no JSON value found",
+            candidate,
+            None,
+        )),
+    }
+}
+
+/// Removes thinking blocks, including a leading one whose opening tag is missing.
+fn strip_thinking(text: &str) -> String {
+    let text = XmlUtil::remove_tag(text, "think").unwrap_or_else(|| text.to_string());
+    match text.rfind("</think>") {
+        Some(end) => text[end + "</think>".len()..].to_string(),
+        None => text,
+    }
+}
+
+/// Returns the contents of the first Markdown code fence, or the text itself.
+fn unfence(text: &str) -> &str {
+    let Some(open) = text.find("```") else {
+        return text;
+    };
+
+    // Skip the language tag, e.g. "json", on the opening line.
+    let after_open = &text[open + 3..];
+    let body_start = after_open
+        .find('\n')
+        .map_or(after_open.len(), |pos| pos + 1);
+    let body = &after_open[body_start..];
+
+    match body.find("```") {
+        Some(close) => &body[..close],
+        None => body,
+    }
+}
+
+// ===
+// STRUCT: JsonAttempt
+// ===
+
+/// One answer given to a question that asked for JSON.
+///
+/// Returned by `OllamaSession::ask_json_with_retries` for every attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonAttempt {
+    /// The text of the answer.
+    pub answer: String,
+    /// Why the answer was rejected, or `None` for the answer that was accepted.
+    pub problem: Option<String>,
+}
+
+// ===
+// STRUCT: JsonAnswer
+// ===
+
+/// A JSON answer that matched its schema, with the attempts it took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonAnswer {
+    /// The parsed answer.
+    pub value: JsonValue,
+    /// Every answer given, in order; the last one is the accepted answer.
+    pub attempts: Vec<JsonAttempt>,
+}
+
+// ===
+// STRUCT: JsonRetryError
+// ===
+
+/// The error returned when no JSON answer matched its schema, with the attempts made.
+///
+/// Returned by `OllamaSession::ask_json_with_retries`; downcast the error to
+/// inspect the rejected answers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonRetryError {
+    /// Every answer given, in order, with the reason it was rejected.
+    pub attempts: Vec<JsonAttempt>,
+}
+
+// ===
+// TRAIT: JsonRetryError (fmt::Display)
+// ===
+
+impl fmt::Display for JsonRetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no valid JSON answer after {} attempts",
+            self.attempts.len()
+        )?;
+        if let Some(problem) = self.attempts.last().and_then(|a| a.problem.as_ref()) {
+            write!(f, ": {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for JsonRetryError {}
+
+// ===
+// STRUCT: JsonExtractError
+// ===
+
+/// The error returned when no JSON value can be parsed out of model output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonExtractError {
+    message: String,
+    text: String,
+    position: Option<(usize, usize)>,
+}
+
+impl JsonExtractError {
+    fn new(message: &str, text: &str, position: Option<(usize, usize)>) -> Self {
+        Self {
+            message: message.to_string(),
+            text: text.to_string(),
+            position,
+        }
+    }
+
+    /// Returns a description of what went wrong.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the text that was parsed, after thinking blocks and fences were removed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the line and column of the parse error within `text`, if known.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+}
+
+// ===
+// TRAIT: JsonExtractError (fmt::Display)
+// ===
+
+impl fmt::Display for JsonExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON in model output: {}", self.message)?;
+        if let Some((line, column)) = self.position
+            && let Some(offending) = self.text.lines().nth(line.saturating_sub(1))
+        {
+            write!(f, "\n{offending}\n{:>column$}", "^")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for JsonExtractError {}
+
+// ===
+// TESTS: extract_json
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_json() {
+        assert_eq!(extract_json(r#"{"a": 1}"#), Ok(json!({ "a": 1 })));
+        assert_eq!(
+            extract_json("<think>The user wants JSON.</think>\n```json\n[1, 2]\n```\nDone!"),
+            Ok(json!([1, 2]))
+        );
+        assert_eq!(
+            extract_json("Sure! Here it is: {\"city\": \"Paris\"} Let me know if you need more."),
+            Ok(json!({ "city": "Paris" }))
+        );
+        assert_eq!(
+            extract_json("reasoning without an opening tag</think>{\"ok\": true}"),
+            Ok(json!({ "ok": true }))
+        );
+    }
+
+    #[test]
+    fn test_extract_json_errors() {
+        let error = extract_json("No JSON here.").unwrap_err();
+        assert_eq!(error.text(), "No JSON here.");
+        assert_eq!(error.position(), None);
+
+        let error = extract_json("```json\n{\n  \"a\": 1,\n  \"b\": oops\n}\n```").unwrap_err();
+        assert_eq!(error.position().map(|(line, _)| line), Some(3));
+        assert!(error.to_string().contains("\"b\": oops"));
+    }
+}
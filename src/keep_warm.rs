@@ -0,0 +1,233 @@
+use crate::{Ollama, OllamaRequest};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// ===
+// STRUCT: KeepWarm
+// ===
+
+/// Keeps a model loaded on an Ollama server by pinging it on a schedule.
+///
+/// Ollama unloads a model after it has been idle for its keep-alive duration, and
+/// the next request then waits for it to load again. Each ping is a generate
+/// request without a prompt, which loads the model if needed and resets its
+/// keep-alive timer without generating anything.
+///
+/// ```no_run
+/// # async fn example() {
+/// use ollie_rs::KeepWarm;
+/// use std::time::Duration;
+///
+/// let handle = KeepWarm::new("127.0.0.1:11434", "gemma3:1b", Duration::from_secs(240)).start();
+/// // ... serve interactive requests ...
+/// handle.stop().await;
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeepWarm {
+    ollama: Ollama,
+    model: String,
+    interval: Duration,
+    jitter: Duration,
+    keep_alive: Option<String>,
+}
+
+impl KeepWarm {
+    /// Creates a schedule that pings a model on the given server.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_addr` - The server address, e.g. "127.0.0.1:11434".
+    /// * `model` - The model to keep loaded.
+    /// * `interval` - The time between pings.
+    ///
+    /// # Returns
+    ///
+    /// A new `KeepWarm`, with no jitter.
+    pub fn new(server_addr: &str, model: &str, interval: Duration) -> Self {
+        Self {
+            ollama: Ollama::new(server_addr),
+            model: model.to_string(),
+            interval,
+            jitter: Duration::ZERO,
+            keep_alive: None,
+        }
+    }
+
+    /// Sets the largest random delay added to each interval.
+    ///
+    /// Jitter spreads out the pings of many processes warming the same server.
+    pub fn set_jitter(&mut self, jitter: Duration) -> &mut Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the keep-alive duration sent with each ping, e.g. "10m".
+    ///
+    /// By default it is twice the longest time between pings, so one late or
+    /// failed ping does not let the model unload.
+    pub fn set_keep_alive(&mut self, keep_alive: &str) -> &mut Self {
+        self.keep_alive = Some(keep_alive.to_string());
+        self
+    }
+
+    /// Returns the keep-alive duration sent with each ping.
+    pub fn keep_alive(&self) -> String {
+        self.keep_alive.clone().unwrap_or_else(|| {
+            let longest = self.interval + self.jitter;
+            format!("{}s", (longest * 2).as_secs().max(1))
+        })
+    }
+
+    /// Starts pinging on a tokio task, with the first ping sent right away.
+    ///
+    /// # Returns
+    ///
+    /// A handle that stops the task when `stop` is called or the handle is dropped.
+    pub fn start(&self) -> KeepWarmHandle {
+        let (stop_sender, mut stop_receiver) = watch::channel(false);
+        let pings = Arc::new(AtomicU64::new(0));
+        let failures = Arc::new(AtomicU64::new(0));
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model(&self.model)
+            .set_keep_alive(&self.keep_alive());
+
+        let ollama = self.ollama.clone();
+        let interval = self.interval;
+        let jitter = self.jitter;
+        let task_pings = pings.clone();
+        let task_failures = failures.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let ok = match ollama.generate(&request, |_| {}).await {
+                    Ok(response) => response.error().is_none(),
+                    Err(_) => false,
+                };
+                let counter = if ok { &task_pings } else { &task_failures };
+                counter.fetch_add(1, Ordering::Relaxed);
+
+                let delay = interval + random_jitter(jitter);
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = stop_receiver.changed() => break,
+                }
+            }
+        });
+
+        KeepWarmHandle {
+            stop_sender,
+            task: Some(task),
+            pings,
+            failures,
+        }
+    }
+}
+
+/// Picks a random delay between zero and `jitter`.
+fn random_jitter(jitter: Duration) -> Duration {
+    if jitter.is_zero() {
+        return Duration::ZERO;
+    }
+    jitter.mul_f64(rand::random::<f64>())
+}
+
+// ===
+// STRUCT: KeepWarmHandle
+// ===
+
+/// A running `KeepWarm` task.
+///
+/// Dropping the handle stops the task without waiting for it.
+#[derive(Debug)]
+pub struct KeepWarmHandle {
+    stop_sender: watch::Sender<bool>,
+    task: Option<JoinHandle<()>>,
+    pings: Arc<AtomicU64>,
+    failures: Arc<AtomicU64>,
+}
+
+impl KeepWarmHandle {
+    /// Returns the number of pings the server answered successfully.
+    pub fn pings(&self) -> u64 {
+        self.pings.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of pings that failed, e.g. because the server was down.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` until the task is stopped.
+    pub fn is_running(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+
+    /// Stops the task, waiting for a ping in flight to finish.
+    pub async fn stop(mut self) {
+        let _ = self.stop_sender.send(true);
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+    }
+}
+
+impl Drop for KeepWarmHandle {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+// ===
+// TESTS: KeepWarm
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, ndjson};
+    use serde_json::json;
+
+    #[test]
+    fn test_default_keep_alive() {
+        let mut keep_warm = KeepWarm::new("127.0.0.1:11434", "mock", Duration::from_secs(240));
+        assert_eq!(keep_warm.keep_alive(), "480s");
+
+        keep_warm.set_jitter(Duration::from_secs(60));
+        assert_eq!(keep_warm.keep_alive(), "600s");
+
+        keep_warm.set_keep_alive("1h");
+        assert_eq!(keep_warm.keep_alive(), "1h");
+    }
+
+    #[tokio::test]
+    async fn test_pings_until_stopped() {
+        let loaded = ndjson(&[json!({
+            "model": "mock",
+            "response": "",
+            "done": true,
+            "done_reason": "load"
+        })]);
+        let server = MockServer::start(vec![loaded; 3]).await;
+
+        let mut keep_warm = KeepWarm::new(&server.addr(), "mock", Duration::from_millis(20));
+        keep_warm.set_jitter(Duration::from_millis(5));
+        let handle = keep_warm.start();
+
+        while handle.pings() < 3 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(handle.is_running());
+        handle.stop().await;
+
+        let requests = server.requests();
+        assert!(requests.len() >= 3);
+        assert_eq!(requests[0], json!({ "model": "mock", "keep_alive": "1s" }));
+    }
+}
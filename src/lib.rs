@@ -1,8 +1,131 @@
+#[cfg(feature = "transport")]
+pub mod agents;
+#[cfg(feature = "transport")]
+pub use agents::*;
+
+pub mod audio;
+pub use audio::*;
+
+#[cfg(feature = "audit")]
+pub mod audit;
+#[cfg(feature = "audit")]
+pub use audit::*;
+
+#[cfg(feature = "transport")]
+pub mod bench;
+#[cfg(feature = "transport")]
+pub use bench::*;
+
+pub mod classification;
+pub use classification::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod content_filter;
+pub use content_filter::*;
+
+pub mod core;
+
+pub mod env;
+
+pub mod eval;
+
 pub mod gemini;
 pub use gemini::*;
 
+pub mod image_data;
+pub use image_data::*;
+
+pub mod ingest;
+
 pub mod ollama;
 pub use ollama::*;
 
+pub mod tools;
+pub use tools::*;
+
+pub mod generation_stats;
+pub use generation_stats::*;
+
+pub mod json_extract;
+pub use json_extract::*;
+
+#[cfg(feature = "transport")]
+pub mod keep_warm;
+#[cfg(feature = "transport")]
+pub use keep_warm::*;
+
+#[cfg(feature = "transport")]
+pub mod memory;
+#[cfg(feature = "transport")]
+pub use memory::*;
+
+#[cfg(feature = "transport")]
+pub mod pipeline;
+
+pub mod prelude;
+
+pub mod presets;
+pub use presets::*;
+
+#[cfg(feature = "transport")]
+pub mod proxy_config;
+#[cfg(feature = "transport")]
+pub use proxy_config::*;
+
+#[cfg(feature = "transport")]
+pub mod rag;
+
+pub mod security;
+
+pub mod stop_sequence;
+pub use stop_sequence::*;
+
+pub mod stream_metrics;
+pub use stream_metrics::*;
+
+#[cfg(feature = "transport")]
+pub mod stream_tee;
+#[cfg(feature = "transport")]
+pub use stream_tee::*;
+
+pub mod text;
+pub use text::*;
+
+pub mod text_accumulator;
+pub use text_accumulator::*;
+
+#[cfg(feature = "transport")]
+pub mod tls_config;
+#[cfg(feature = "transport")]
+pub use tls_config::*;
+
+pub mod token_breakdown;
+pub use token_breakdown::*;
+
+pub mod transcript;
+pub use transcript::*;
+
+pub mod translation;
+pub use translation::*;
+
+#[cfg(feature = "transport")]
+pub mod typewriter;
+#[cfg(feature = "transport")]
+pub use typewriter::*;
+
 pub mod xml_util;
 pub use xml_util::*;
+
+#[cfg(all(test, feature = "transport"))]
+mod mock_server;
+
+#[cfg(feature = "macros")]
+pub use ollie_macros::ollie_tool;
+
+/// Re-exports used by code generated from the `ollie-macros` crate.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+}
@@ -0,0 +1,418 @@
+use crate::{Ollama, OllamaHistory, OllamaMessage, OllamaRole, OllamaSession, Transcript};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value as JsonValue, json};
+use std::error::Error;
+use std::path::Path;
+
+/// The instruction sent to pull facts out of a conversation.
+const EXTRACT_PROMPT: &str = "Read the conversation below and list the facts about the user \
+that are worth remembering in future conversations: their name, preferences, plans, \
+circumstances and the like. Write each fact as a short standalone sentence about \"The user\". \
+Skip small talk and anything only relevant to this conversation. Answer with JSON like \
+{\"facts\": [\"The user lives in Lyon.\"]}, with an empty list if there is nothing to remember.";
+
+/// The first line of the system message `inject` adds, by which it finds the
+/// message again to update it.
+const MEMORY_HEADER: &str = "What you remember about the user from earlier conversations:";
+
+/// The similarity above which a new fact is considered a repeat of a stored one.
+const DEFAULT_DUPLICATE_SIMILARITY: f32 = 0.95;
+
+// ===
+// STRUCT: MemoryFact
+// ===
+
+/// A fact stored in a `Memory`, with the embedding it is recalled by.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryFact {
+    /// The fact, as a standalone sentence.
+    pub text: String,
+    /// The embedding of the text.
+    pub embedding: Vec<f32>,
+}
+
+// ===
+// STRUCT: Memory
+// ===
+
+/// A long-term store of facts learned from conversations.
+///
+/// `extract` asks a model for the facts worth keeping from a session, and
+/// `inject` adds the ones relevant to a new query to another session as a system
+/// message. Facts are embedded with an Ollama embedding model and recalled by
+/// cosine similarity; `save` and `load` keep them across runs.
+#[derive(Debug, Clone)]
+pub struct Memory {
+    ollama: Ollama,
+    embedding_model: String,
+    facts: Vec<MemoryFact>,
+    duplicate_similarity: f32,
+}
+
+impl Memory {
+    /// Creates an empty memory.
+    ///
+    /// # Arguments
+    /// * `ollama` - The client used to compute embeddings
+    /// * `embedding_model` - The embedding model, e.g. "nomic-embed-text"
+    pub fn new(ollama: Ollama, embedding_model: &str) -> Self {
+        Self {
+            ollama,
+            embedding_model: embedding_model.to_string(),
+            facts: Vec::new(),
+            duplicate_similarity: DEFAULT_DUPLICATE_SIMILARITY,
+        }
+    }
+
+    /// Returns the stored facts, oldest first.
+    pub fn facts(&self) -> &[MemoryFact] {
+        &self.facts
+    }
+
+    /// Returns the number of stored facts.
+    pub fn len(&self) -> usize {
+        self.facts.len()
+    }
+
+    /// Returns `true` if no facts are stored.
+    pub fn is_empty(&self) -> bool {
+        self.facts.is_empty()
+    }
+
+    /// Sets the similarity above which a new fact is dropped as a repeat.
+    ///
+    /// # Arguments
+    /// * `similarity` - The cosine similarity threshold, 0.95 by default
+    pub fn set_duplicate_similarity(&mut self, similarity: f32) -> &mut Self {
+        self.duplicate_similarity = similarity;
+        self
+    }
+
+    /// Stores facts, skipping those too similar to one already stored.
+    ///
+    /// # Arguments
+    /// * `facts` - The facts to store
+    ///
+    /// # Returns
+    /// * The facts that were stored
+    pub async fn remember(&mut self, facts: &[&str]) -> Result<Vec<String>, Box<dyn Error>> {
+        let facts: Vec<&str> = facts
+            .iter()
+            .map(|fact| fact.trim())
+            .filter(|fact| !fact.is_empty())
+            .collect();
+        if facts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let embeddings = self.ollama.embed(&self.embedding_model, &facts).await?;
+        let mut stored = Vec::new();
+        for (text, embedding) in facts.into_iter().zip(embeddings) {
+            let repeat = self.facts.iter().any(|fact| {
+                cosine_similarity(&fact.embedding, &embedding) >= self.duplicate_similarity
+            });
+            if !repeat {
+                self.facts.push(MemoryFact {
+                    text: text.to_string(),
+                    embedding,
+                });
+                stored.push(text.to_string());
+            }
+        }
+
+        Ok(stored)
+    }
+
+    /// Asks a model for the facts worth keeping from a conversation and stores them.
+    ///
+    /// The request is sent on a fork of `session` with the conversation as text, so
+    /// the session's model and options are used and its history is left unchanged.
+    /// Extraction takes a full model turn, so apps typically run it once a
+    /// conversation ends, or on a spawned task.
+    ///
+    /// # Arguments
+    /// * `session` - The conversation to learn from
+    ///
+    /// # Returns
+    /// * The new facts that were stored
+    pub async fn extract(
+        &mut self,
+        session: &mut OllamaSession,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        let conversation = Transcript::from_messages(
+            session
+                .messages()
+                .iter()
+                .filter(|message| matches!(message["role"].as_str(), Some("user" | "assistant"))),
+        )
+        .to_text();
+        if conversation.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // The conversation is sent as text, so the fork starts without the history.
+        let mut fork = session.fork();
+        fork.restore(OllamaHistory::default());
+        let answer = fork
+            .ask_json(
+                &format!("{EXTRACT_PROMPT}\n\nConversation:\n{conversation}"),
+                extract_schema(),
+            )
+            .await?;
+        let facts: Vec<&str> = answer["facts"]
+            .as_array()
+            .map(|facts| facts.iter().filter_map(JsonValue::as_str).collect())
+            .unwrap_or_default();
+        self.remember(&facts).await
+    }
+
+    /// Finds the stored facts most similar to a query.
+    ///
+    /// # Arguments
+    /// * `query` - The text to match, e.g. the user's next message
+    /// * `limit` - The maximum number of facts to return
+    ///
+    /// # Returns
+    /// * The facts with their cosine similarity to the query, most similar first
+    pub async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<(&MemoryFact, f32)>, Box<dyn Error>> {
+        if self.facts.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let embedding = self
+            .ollama
+            .embed(&self.embedding_model, &[query])
+            .await?
+            .remove(0);
+        let mut matches: Vec<(&MemoryFact, f32)> = self
+            .facts
+            .iter()
+            .map(|fact| (fact, cosine_similarity(&fact.embedding, &embedding)))
+            .collect();
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    /// Adds the facts relevant to a query to a session as a system message.
+    ///
+    /// The session keeps a single memory message: later calls replace its facts
+    /// in place, so injecting before every turn does not grow the history.
+    ///
+    /// # Arguments
+    /// * `session` - The session to add the message to
+    /// * `query` - The text to match, e.g. the user's next message
+    /// * `limit` - The maximum number of facts to add
+    ///
+    /// # Returns
+    /// * The number of facts added; the session is left unchanged when there are none
+    pub async fn inject(
+        &self,
+        session: &mut OllamaSession,
+        query: &str,
+        limit: usize,
+    ) -> Result<usize, Box<dyn Error>> {
+        let matches = self.recall(query, limit).await?;
+        if matches.is_empty() {
+            return Ok(0);
+        }
+
+        let lines: Vec<String> = matches
+            .iter()
+            .map(|(fact, _)| format!("- {}", fact.text))
+            .collect();
+        let content = format!("{MEMORY_HEADER}\n{}", lines.join("\n"));
+
+        let existing = session.messages().iter().position(|message| {
+            message["role"] == "system"
+                && message["content"]
+                    .as_str()
+                    .is_some_and(|content| content.starts_with(MEMORY_HEADER))
+        });
+        match existing {
+            Some(index) => {
+                let message = OllamaMessage::new()
+                    .set_role(OllamaRole::System)
+                    .set_content(&content)
+                    .to_json();
+                session.replace_message(index, message);
+            }
+            None => session.system(&content),
+        }
+        Ok(matches.len())
+    }
+
+    /// Writes the stored facts to a JSON file.
+    ///
+    /// # Arguments
+    /// * `path` - The file to write
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let json = json!({ "model": self.embedding_model, "facts": self.facts });
+        std::fs::write(path, serde_json::to_string_pretty(&json)?)?;
+        Ok(())
+    }
+
+    /// Adds the facts from a file written by `save`.
+    ///
+    /// # Arguments
+    /// * `path` - The file to read
+    ///
+    /// # Returns
+    /// * An error if the file cannot be read, or was saved with another embedding
+    ///   model, whose vectors could not be compared with this one's
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let mut json: JsonValue = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let model = json["model"].as_str().unwrap_or_default();
+        if model != self.embedding_model {
+            return Err(format!(
+                "the memory was saved with embedding model '{model}', not '{}'",
+                self.embedding_model
+            )
+            .into());
+        }
+
+        let facts: Vec<MemoryFact> = serde_json::from_value(json["facts"].take())?;
+        self.facts.extend(facts);
+        Ok(())
+    }
+}
+
+/// Builds the JSON schema for the list of extracted facts.
+fn extract_schema() -> JsonValue {
+    json!({
+        "type": "object",
+        "properties": {
+            "facts": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["facts"]
+    })
+}
+
+/// Returns the cosine similarity of two vectors, or 0.0 if either is zero or
+/// their lengths differ.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+// ===
+// TESTS: Memory
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body, ndjson};
+
+    fn embed_body(embeddings: JsonValue) -> String {
+        ndjson(&[json!({ "embeddings": embeddings })])
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_extract_and_inject() {
+        let server = MockServer::start(vec![
+            chat_body(&[
+                r#"{"facts": ["The user lives in Lyon.", "The user is vegetarian.", "The user lives in Lyon, France."]}"#,
+            ]),
+            embed_body(json!([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.99, 0.05, 0.0]])),
+            embed_body(json!([[0.1, 1.0, 0.0]])),
+            embed_body(json!([[1.0, 0.1, 0.0]])),
+        ])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.system("You are a helpful assistant.");
+        session.user("I'm in Lyon and I don't eat meat. Any dinner ideas?");
+        session.assistant("Try a ratatouille.");
+        let before = session.messages().len();
+
+        let mut memory = Memory::new(Ollama::new(&server.addr()), "mock-embed");
+        let stored = memory.extract(&mut session).await.unwrap();
+        assert_eq!(
+            stored,
+            vec!["The user lives in Lyon.", "The user is vegetarian."]
+        );
+        assert_eq!(session.messages().len(), before);
+
+        let prompt = server.requests()[0]["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(prompt.contains("I'm in Lyon"));
+        assert!(!prompt.contains("You are a helpful assistant."));
+        assert_eq!(server.requests()[1]["model"], "mock-embed");
+
+        let mut next = OllamaSession::remote("mock", &server.addr());
+        let added = memory
+            .inject(&mut next, "What should I cook tonight?", 1)
+            .await
+            .unwrap();
+        assert_eq!(added, 1);
+
+        let message = next.messages().iter().next().unwrap();
+        assert_eq!(message["role"], "system");
+        assert!(
+            message["content"]
+                .as_str()
+                .unwrap()
+                .ends_with("- The user is vegetarian.")
+        );
+
+        // A later injection updates the memory message instead of adding another.
+        next.user("What should I cook tonight?");
+        next.assistant("A mushroom risotto.");
+        memory
+            .inject(&mut next, "Where could I eat out?", 1)
+            .await
+            .unwrap();
+        assert_eq!(next.messages().len(), 3);
+        let message = next.messages().iter().next().unwrap();
+        assert!(
+            message["content"]
+                .as_str()
+                .unwrap()
+                .ends_with("- The user lives in Lyon.")
+        );
+        assert!(next.is_pinned(0));
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let server = MockServer::start(vec![embed_body(json!([[0.6, 0.8]]))]).await;
+        let mut memory = Memory::new(Ollama::new(&server.addr()), "mock-embed");
+        memory.remember(&["The user has a cat."]).await.unwrap();
+
+        let path = std::env::temp_dir().join(format!("ollie-memory-{}.json", std::process::id()));
+        memory.save(&path).unwrap();
+
+        let mut restored = Memory::new(Ollama::new(&server.addr()), "mock-embed");
+        restored.load(&path).unwrap();
+        assert_eq!(restored.facts(), memory.facts());
+
+        let mut other = Memory::new(Ollama::new(&server.addr()), "other-embed");
+        assert!(other.load(&path).is_err());
+        assert!(other.is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
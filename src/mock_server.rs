@@ -0,0 +1,200 @@
+//! A minimal in-process HTTP server used by unit tests to stand in for an
+//! Ollama server. Each accepted connection is answered with the next queued body.
+
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Marks a body whose connection is cut off after it is sent; see `interrupted_chat_body`.
+const INTERRUPTED: &str = "\0interrupted\0";
+
+/// Marks a body after which the connection stays open; see `stalled_chat_body`.
+const STALLED: &str = "\0stalled\0";
+
+/// Marks a body sent with an error status; see `error_body`.
+const STATUS: &str = "\0status\0";
+
+pub(crate) struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<JsonValue>>>,
+    headers: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    /// Starts a server answering successive requests with the given NDJSON bodies.
+    pub(crate) async fn start(bodies: Vec<String>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let headers = Arc::new(Mutex::new(Vec::new()));
+        let bodies = Arc::new(Mutex::new(VecDeque::from(bodies)));
+
+        let recorded = requests.clone();
+        let recorded_headers = headers.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let (head, request) = read_request(&mut socket).await;
+                recorded.lock().unwrap().push(request);
+                recorded_headers.lock().unwrap().push(head);
+
+                let body = bodies.lock().unwrap().pop_front().unwrap_or_default();
+                tokio::spawn(async move {
+                    if let Some((status, body)) = body
+                        .strip_prefix(STATUS)
+                        .and_then(|rest| rest.split_once(' '))
+                    {
+                        let head = format!(
+                            "HTTP/1.1 {status} Error\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\n"
+                        );
+                        let _ = socket.write_all(head.as_bytes()).await;
+                        let _ = socket.write_all(body.as_bytes()).await;
+                        let _ = socket.shutdown().await;
+                        return;
+                    }
+
+                    let (body, cut_off, stall) =
+                        match (body.strip_prefix(INTERRUPTED), body.strip_prefix(STALLED)) {
+                            (Some(rest), _) => (rest, true, false),
+                            (_, Some(rest)) => (rest, true, true),
+                            _ => (body.as_str(), false, false),
+                        };
+
+                    // Promise more bytes than are sent, so the client sees an incomplete stream.
+                    let head = if cut_off {
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nContent-Length: {}\r\n\r\n",
+                            body.len() + 1024
+                        )
+                    } else {
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n".to_string()
+                    };
+                    let _ = socket.write_all(head.as_bytes()).await;
+                    let _ = socket.write_all(body.as_bytes()).await;
+                    if stall {
+                        // Hold the connection until the client drops it.
+                        let mut buf = [0u8; 1];
+                        let _ = socket.read(&mut buf).await;
+                    }
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+
+        Self {
+            addr,
+            requests,
+            headers,
+        }
+    }
+
+    /// Returns the server address as a "host:port" string.
+    pub(crate) fn addr(&self) -> String {
+        self.addr.to_string()
+    }
+
+    /// Returns the JSON bodies of all requests received so far.
+    pub(crate) fn requests(&self) -> Vec<JsonValue> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the lowercased request lines and headers of all requests received so far.
+    pub(crate) fn headers(&self) -> Vec<String> {
+        self.headers.lock().unwrap().clone()
+    }
+}
+
+/// Builds a streamed chat body emitting each text as a separate chunk.
+pub(crate) fn chat_body(texts: &[&str]) -> String {
+    let mut lines: Vec<JsonValue> = texts
+        .iter()
+        .map(|text| {
+            json!({
+                "model": "mock",
+                "message": { "role": "assistant", "content": text },
+                "done": false
+            })
+        })
+        .collect();
+
+    lines.push(json!({
+        "model": "mock",
+        "message": { "role": "assistant", "content": "" },
+        "done": true,
+        "done_reason": "stop",
+        "eval_count": 10,
+        "prompt_eval_count": 20
+    }));
+
+    ndjson(&lines)
+}
+
+/// Builds a streamed chat body emitting each text as a chunk, then dropping the
+/// connection before the stream is complete.
+pub(crate) fn interrupted_chat_body(texts: &[&str]) -> String {
+    let lines: Vec<JsonValue> = texts
+        .iter()
+        .map(|text| {
+            json!({
+                "model": "mock",
+                "message": { "role": "assistant", "content": text },
+                "done": false
+            })
+        })
+        .collect();
+
+    format!("{INTERRUPTED}{}", ndjson(&lines))
+}
+
+/// Builds a streamed chat body emitting each text as a chunk, then sending
+/// nothing more until the client closes the connection.
+pub(crate) fn stalled_chat_body(texts: &[&str]) -> String {
+    let body = interrupted_chat_body(texts);
+    format!("{STALLED}{}", &body[INTERRUPTED.len()..])
+}
+
+/// Builds a body sent with an HTTP error status instead of 200, e.g. a proxy's
+/// "502 Bad Gateway" page.
+pub(crate) fn error_body(status: u16, body: &str) -> String {
+    format!("{STATUS}{status} {body}")
+}
+
+/// Joins JSON values into a newline-delimited body.
+pub(crate) fn ndjson(lines: &[JsonValue]) -> String {
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> (String, JsonValue) {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 4096];
+
+    loop {
+        let n = socket.read(&mut buf).await.unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+
+        let Some(header_end) = data.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+
+        let headers = String::from_utf8_lossy(&data[..header_end]).to_lowercase();
+        let length = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+
+        if data.len() >= header_end + 4 + length {
+            let body = &data[header_end + 4..header_end + 4 + length];
+            let body = serde_json::from_slice(body).unwrap_or(JsonValue::Null);
+            return (headers, body);
+        }
+    }
+
+    (String::new(), JsonValue::Null)
+}
@@ -0,0 +1,314 @@
+use crate::{Ollama, OllamaOptions, OllamaRequest, OllamaResponse, ProxyConfig, TlsConfig};
+use serde_json::json;
+use std::error::Error;
+
+// ===
+// STRUCT: CompletionSession
+// ===
+
+/// A session for generate-style workflows, without chat messages.
+///
+/// Where `OllamaSession` keeps a message history for `/api/chat`, this session
+/// sends each prompt to `/api/generate` with the `context` tokens the server
+/// returned for the previous one. Use it for base models, raw prompts formatted
+/// with `OllamaChatTemplate`, or templates that work better as plain completion.
+///
+/// Responses stream through a callback and carry the same statistics as chat
+/// responses; see `OllamaResponse::stats`.
+#[derive(Clone, Debug)]
+pub struct CompletionSession {
+    ollama: Ollama,
+    request: OllamaRequest,
+    options: OllamaOptions,
+    context: Vec<u32>,
+    last_response: Option<OllamaResponse>,
+}
+
+impl CompletionSession {
+    /// Creates a new completion session with the specified model.
+    ///
+    /// Connects to the server in the OLLAMA_SERVER environment variable if set,
+    /// otherwise to the local Ollama server (127.0.0.1:11434).
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The name of the Ollama model to use for this session.
+    ///
+    /// # Returns
+    ///
+    /// A new `CompletionSession` with an empty context.
+    pub fn new(model: &str) -> Self {
+        match std::env::var("OLLAMA_SERVER") {
+            Ok(host) => Self::remote(model, &host),
+            Err(_) => Self::local(model),
+        }
+    }
+
+    /// Creates a new completion session using the local Ollama server.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The name of the Ollama model to use for this session.
+    ///
+    /// # Returns
+    ///
+    /// A new `CompletionSession` with an empty context.
+    pub fn local(model: &str) -> Self {
+        Self::with_client(model, Ollama::default())
+    }
+
+    /// Creates a new completion session using the specified server.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The name of the Ollama model to use for this session.
+    /// * `server_address` - The server address (e.g., "127.0.0.1:11434").
+    ///
+    /// # Returns
+    ///
+    /// A new `CompletionSession` with an empty context.
+    pub fn remote(model: &str, server_address: &str) -> Self {
+        Self::with_client(model, Ollama::new(server_address))
+    }
+
+    fn with_client(model: &str, ollama: Ollama) -> Self {
+        let mut request = OllamaRequest::new();
+        request.set_model(model);
+
+        Self {
+            ollama,
+            request,
+            options: OllamaOptions::new(),
+            context: Vec::new(),
+            last_response: None,
+        }
+    }
+
+    /// Returns the name of the model used by this session.
+    pub fn model(&self) -> Option<&String> {
+        self.request.model()
+    }
+
+    /// Returns the context window size of the model, in tokens.
+    pub fn context_window_size(&self) -> u32 {
+        self.options.num_ctx().unwrap_or(2048)
+    }
+
+    /// Sets the context window size of the model, sent as the `num_ctx` option.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_ctx` - The context window size, in tokens.
+    pub fn set_context_window_size(&mut self, num_ctx: u32) {
+        self.options.set_num_ctx(num_ctx);
+    }
+
+    /// Gets a mutable reference to the options for configuring model behavior.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the `OllamaOptions` instance.
+    pub fn options(&mut self) -> &mut OllamaOptions {
+        &mut self.options
+    }
+
+    /// Sets the system prompt the model's template places before each prompt.
+    ///
+    /// Ignored in raw mode, where the prompt must include any instructions.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The system prompt.
+    pub fn system(&mut self, content: &str) {
+        self.request.set_extra("system", json!(content));
+    }
+
+    /// Sets whether prompts are sent as-is, bypassing the model's template.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - Whether to enable raw mode.
+    pub fn set_raw(&mut self, raw: bool) {
+        self.request.set_raw(raw);
+    }
+
+    /// Returns the context tokens sent with the next prompt.
+    ///
+    /// They encode the prompts and responses so far, as returned by the server.
+    pub fn context(&self) -> &[u32] {
+        &self.context
+    }
+
+    /// Replaces the context tokens sent with the next prompt, e.g. to resume a
+    /// session saved earlier.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The tokens of `OllamaResponse::context`.
+    pub fn set_context(&mut self, context: &[u32]) {
+        self.context = context.to_vec();
+    }
+
+    /// Forgets the context and the last response, starting a fresh completion.
+    pub fn reset(&mut self) {
+        self.context.clear();
+        self.last_response = None;
+    }
+
+    /// Returns the final response of the last prompt, with its statistics.
+    pub fn last_response(&self) -> Option<&OllamaResponse> {
+        self.last_response.as_ref()
+    }
+
+    /// Sends a prompt, continuing from the context of the previous one.
+    ///
+    /// The context returned with the response replaces the session's context. A
+    /// response without one, e.g. in a dry run, leaves it unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The text to complete.
+    /// * `callback` - A function that will be called with each chunk of the response
+    ///   as it is received.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OllamaResponse, Box<dyn Error>>` - The complete response, or an
+    ///   error if the request failed.
+    pub async fn prompt<F>(
+        &mut self,
+        prompt: &str,
+        mut callback: F,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        self.request
+            .set_prompt(prompt)
+            .set_options(self.options.to_json()?)
+            .set_stream(true)
+            .set_context(&self.context);
+
+        let response = self
+            .ollama
+            .generate(&self.request, |chunk| {
+                if let Some(text) = chunk.text() {
+                    callback(text);
+                }
+            })
+            .await?;
+
+        if let Some(context) = response.context() {
+            self.context = context.to_vec();
+        }
+        self.last_response = Some(response.clone());
+        Ok(response)
+    }
+
+    /// Routes requests through a proxy; see `Ollama::set_proxy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy settings.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the settings were applied.
+    /// * `Err(Box<dyn Error>)` - If the proxy URL is invalid.
+    pub fn set_proxy(&mut self, proxy: &ProxyConfig) -> Result<(), Box<dyn Error>> {
+        self.ollama.set_proxy(proxy)?;
+        Ok(())
+    }
+
+    /// Connects to the server over HTTPS; see `Ollama::set_tls`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls` - The certificate settings.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the settings were applied.
+    /// * `Err(Box<dyn Error>)` - If the HTTP client cannot be created.
+    pub fn set_tls(&mut self, tls: &TlsConfig) -> Result<(), Box<dyn Error>> {
+        self.ollama.set_tls(tls)?;
+        Ok(())
+    }
+
+    /// Answers prompts locally with their JSON payload instead of sending them;
+    /// see `Ollama::set_dry_run`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dry_run` - Whether to answer requests locally.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.ollama.set_dry_run(dry_run);
+    }
+}
+
+// ===
+// TESTS: CompletionSession
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, ndjson};
+    use serde_json::Value as JsonValue;
+
+    fn generate_body(texts: &[&str], context: &[u32]) -> String {
+        let mut lines: Vec<JsonValue> = texts
+            .iter()
+            .map(|text| json!({ "model": "mock", "response": text, "done": false }))
+            .collect();
+        lines.push(json!({
+            "model": "mock",
+            "response": "",
+            "done": true,
+            "done_reason": "stop",
+            "context": context,
+            "eval_count": 2,
+            "prompt_eval_count": 5
+        }));
+        ndjson(&lines)
+    }
+
+    #[tokio::test]
+    async fn test_prompt_rolls_context() {
+        let server = MockServer::start(vec![
+            generate_body(&["Once upon", " a time"], &[1, 2, 3]),
+            generate_body(&["The end."], &[1, 2, 3, 4, 5]),
+        ])
+        .await;
+        let mut session = CompletionSession::remote("mock", &server.addr());
+        session.set_raw(true);
+        session.options().set_temperature(0.5);
+
+        let mut streamed = String::new();
+        let response = session
+            .prompt("Tell a story.", |chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+        assert_eq!(streamed, "Once upon a time");
+        assert_eq!(response.text(), Some("Once upon a time"));
+        assert_eq!(response.stats().completion_tokens, Some(2));
+        assert_eq!(session.context(), [1, 2, 3]);
+
+        session.prompt("Finish it.", |_| {}).await.unwrap();
+        assert_eq!(session.context(), [1, 2, 3, 4, 5]);
+        assert_eq!(session.last_response().unwrap().text(), Some("The end."));
+
+        let requests = server.requests();
+        assert!(server.headers()[0].contains("/api/generate"));
+        assert_eq!(requests[0]["context"], json!([]));
+        assert_eq!(requests[0]["raw"], json!(true));
+        assert_eq!(requests[0]["options"]["temperature"], json!(0.5));
+        assert_eq!(requests[1]["prompt"], json!("Finish it."));
+        assert_eq!(requests[1]["context"], json!([1, 2, 3]));
+        assert!(requests[1].get("messages").is_none());
+
+        session.reset();
+        assert!(session.context().is_empty());
+        assert!(session.last_response().is_none());
+    }
+}
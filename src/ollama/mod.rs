@@ -1,20 +1,138 @@
+#[doc(hidden)]
+#[allow(clippy::module_inception)]
+#[cfg(feature = "transport")]
 pub mod ollama;
+#[doc(inline)]
+#[cfg(feature = "transport")]
 pub use ollama::*;
 
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod completion_session;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use completion_session::*;
+
+#[doc(hidden)]
 pub mod tool;
+#[doc(inline)]
 pub use tool::*;
 
+#[doc(hidden)]
+#[cfg(feature = "transport")]
 pub mod ollama_session;
+#[doc(inline)]
+#[cfg(feature = "transport")]
 pub use ollama_session::*;
 
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod ollama_session_hooks;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use ollama_session_hooks::*;
+
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod ollama_abort_handle;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use ollama_abort_handle::*;
+
+#[doc(hidden)]
+pub mod ollama_chat_template;
+#[doc(inline)]
+pub use ollama_chat_template::*;
+
+#[doc(hidden)]
+pub mod ollama_checkpoint;
+#[doc(inline)]
+pub use ollama_checkpoint::*;
+
+#[doc(hidden)]
+pub mod ollama_create_request;
+#[doc(inline)]
+pub use ollama_create_request::*;
+
+#[doc(hidden)]
+pub mod ollama_grammar;
+#[doc(inline)]
+pub use ollama_grammar::*;
+
+#[doc(hidden)]
+pub mod ollama_history;
+#[doc(inline)]
+pub use ollama_history::*;
+
+#[doc(hidden)]
 pub mod ollama_message;
+#[doc(inline)]
 pub use ollama_message::*;
 
+#[doc(hidden)]
 pub mod ollama_options;
+#[doc(inline)]
 pub use ollama_options::*;
 
+#[doc(hidden)]
+pub mod ollama_progress;
+#[doc(inline)]
+pub use ollama_progress::*;
+
+#[doc(hidden)]
+pub mod ollama_push_request;
+#[doc(inline)]
+pub use ollama_push_request::*;
+
+#[doc(hidden)]
 pub mod ollama_response;
+#[doc(inline)]
 pub use ollama_response::*;
 
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod ollama_response_stream;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use ollama_response_stream::*;
+
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod ollama_bounded_stream;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use ollama_bounded_stream::*;
+
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod ollama_queue;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use ollama_queue::*;
+
+#[doc(hidden)]
+pub mod ollama_role;
+#[doc(inline)]
+pub use ollama_role::*;
+
+#[doc(hidden)]
 pub mod ollama_request;
+#[doc(inline)]
 pub use ollama_request::*;
+
+#[doc(hidden)]
+pub mod ollama_request_report;
+#[doc(inline)]
+pub use ollama_request_report::*;
+
+#[doc(hidden)]
+pub mod ollama_stream_error;
+#[doc(inline)]
+pub use ollama_stream_error::*;
+
+#[doc(hidden)]
+#[cfg(feature = "transport")]
+pub mod shared_session;
+#[doc(inline)]
+#[cfg(feature = "transport")]
+pub use shared_session::*;
@@ -1,5 +1,15 @@
-use crate::{OllamaRequest, OllamaResponse};
+#[cfg(feature = "audit")]
+use crate::{AuditLog, AuditRecord};
+use crate::{
+    OllamaCreateRequest, OllamaMessage, OllamaProgress, OllamaPushRequest, OllamaRegistryAuth,
+    OllamaRequest, OllamaResponse, OllamaResponseStream, OllamaRole, OllamaStreamError,
+    ProxyConfig, StreamTimer, TlsConfig,
+};
+use serde_json::Value as JsonValue;
+use serde_json::json;
 use std::error::Error;
+use std::fmt;
+use std::io::Write;
 use std::net::SocketAddr;
 use std::str::FromStr;
 
@@ -12,11 +22,21 @@ use std::str::FromStr;
 /// This struct provides methods for sending requests to an Ollama server
 /// and processing the responses. It supports both the 'generate' and 'chat'
 /// endpoints, as well as handling streaming responses.
+#[derive(Clone)]
 pub struct Ollama {
     /// The network address (IP and port) where the Ollama server is running
     server_addr: SocketAddr,
     /// HTTP client used for making requests to the Ollama server
     http_client: reqwest::Client,
+    /// How requests reach the server
+    proxy: ProxyConfig,
+    /// The certificate settings, if the server is reached over HTTPS
+    tls: Option<TlsConfig>,
+    /// Log that records every exchange, streamed or not
+    #[cfg(feature = "audit")]
+    audit_log: Option<AuditLog>,
+    /// Whether `generate` and `chat` requests are answered locally instead of sent
+    dry_run: bool,
 }
 
 impl Ollama {
@@ -37,9 +57,96 @@ impl Ollama {
         Self {
             server_addr: SocketAddr::from_str(server_addr_str).unwrap(),
             http_client: reqwest::Client::new(),
+            proxy: ProxyConfig::Environment,
+            tls: None,
+            #[cfg(feature = "audit")]
+            audit_log: None,
+            dry_run: false,
         }
     }
 
+    /// Records every exchange in an audit log, including those read through `chat_stream`,
+    /// `generate_stream` and `stream`
+    ///
+    /// The record of a streamed exchange is written once the stream ends, fails or is
+    /// dropped; a stream dropped part way is recorded with the text read so far.
+    ///
+    /// ## Arguments
+    ///
+    /// * `audit_log` - The log to write the exchanges to
+    ///
+    /// ## Returns
+    ///
+    /// A mutable reference to this client for method chaining
+    #[cfg(feature = "audit")]
+    pub fn set_audit_log(&mut self, audit_log: AuditLog) -> &mut Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Sets how requests reach the server, e.g. directly even when `HTTP_PROXY` is set
+    ///
+    /// By default the proxy environment variables apply, as for any `reqwest` client.
+    ///
+    /// ## Arguments
+    ///
+    /// * `proxy` - The proxy setting
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(&mut Self)` - This client, for method chaining
+    /// * `Err(Box<dyn Error>)` - If the proxy URL is invalid
+    pub fn set_proxy(&mut self, proxy: &ProxyConfig) -> Result<&mut Self, Box<dyn Error>> {
+        self.http_client = Self::http_client(proxy, self.tls.as_ref(), self.server_addr)?;
+        self.proxy = proxy.clone();
+        Ok(self)
+    }
+
+    /// Connects to the server over HTTPS, verifying its certificate as configured
+    ///
+    /// Use it for a server behind an HTTPS reverse proxy, e.g. one with a
+    /// certificate from a private CA added to `tls`. Unless `tls` sets a server
+    /// name, the certificate must be issued for the server's IP address.
+    ///
+    /// ## Arguments
+    ///
+    /// * `tls` - The certificate settings
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(&mut Self)` - This client, for method chaining
+    /// * `Err(Box<dyn Error>)` - If the HTTP client cannot be created
+    pub fn set_tls(&mut self, tls: &TlsConfig) -> Result<&mut Self, Box<dyn Error>> {
+        self.http_client = Self::http_client(&self.proxy, Some(tls), self.server_addr)?;
+        self.tls = Some(tls.clone());
+        Ok(self)
+    }
+
+    /// Answers `generate` and `chat` requests locally instead of sending them
+    ///
+    /// In a dry run, every generation request, streamed or not, gets a single final
+    /// response whose text is the JSON payload that would have been sent, with the
+    /// done reason "dry_run". Use it to inspect prompts and tool schemas without a
+    /// server. Model management and `embed` requests are still sent. Dry runs are
+    /// not written to the audit log.
+    ///
+    /// ## Arguments
+    ///
+    /// * `dry_run` - Whether to answer requests locally
+    ///
+    /// ## Returns
+    ///
+    /// A mutable reference to this client for method chaining
+    pub fn set_dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Returns whether generation requests are answered locally; see `set_dry_run`
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
     /// Returns the server address this client is configured to connect to
     ///
     /// ## Returns
@@ -68,7 +175,7 @@ impl Ollama {
     where
         F: FnMut(&OllamaResponse),
     {
-        let url = format!("http://{}/api/generate", self.server_addr);
+        let url = self.url("/api/generate");
         self.request(&url, request, callback).await
     }
 
@@ -95,10 +202,153 @@ impl Ollama {
     where
         F: FnMut(&OllamaResponse),
     {
-        let url = format!("http://{}/api/chat", self.server_addr);
+        let url = self.url("/api/chat");
         self.request(&url, request, callback).await
     }
 
+    /// Asks a model a single question and returns its answer.
+    ///
+    /// A shortcut for scripts: it sends the prompt as a one-message chat without
+    /// streaming and returns the trimmed text. Use `chat` or an `OllamaSession`
+    /// for conversations, options or streaming.
+    ///
+    /// ## Arguments
+    ///
+    /// * `model` - The name of the model to ask, e.g. "gemma3:1b".
+    /// * `prompt` - The question or instruction.
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(String)` - The model's answer.
+    /// * `Err(Box<dyn Error>)` - If the request failed or the server reported an error.
+    pub async fn ask(&self, model: &str, prompt: &str) -> Result<String, Box<dyn Error>> {
+        self.send_question(model, prompt, false, |_| {}).await
+    }
+
+    /// Asks a model a single question, passing the answer to a callback as it streams in.
+    ///
+    /// Pass `print_chunk` to show the answer on stdout as it is generated:
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use ollie_rs::{Ollama, print_chunk};
+    ///
+    /// let answer = Ollama::default()
+    ///     .ask_stream("gemma3:1b", "Why is the sky blue?", print_chunk)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Arguments
+    ///
+    /// * `model` - The name of the model to ask, e.g. "gemma3:1b".
+    /// * `prompt` - The question or instruction.
+    /// * `callback` - A function called with each chunk of text as it arrives.
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(String)` - The whole answer, trimmed.
+    /// * `Err(Box<dyn Error>)` - If the request failed or the server reported an error.
+    pub async fn ask_stream<F>(
+        &self,
+        model: &str,
+        prompt: &str,
+        callback: F,
+    ) -> Result<String, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        self.send_question(model, prompt, true, callback).await
+    }
+
+    /// Sends a generate request and returns a stream of the response chunks.
+    ///
+    /// ## Arguments
+    ///
+    /// * `request` - An `OllamaRequest` object containing the model, prompt, and other generation parameters.
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(OllamaResponseStream)` - The stream of response chunks.
+    /// * `Err(Box<dyn Error>)` - If the request could not be sent.
+    pub async fn generate_stream(
+        &self,
+        request: &OllamaRequest,
+    ) -> Result<OllamaResponseStream, Box<dyn Error>> {
+        let url = self.url("/api/generate");
+        self.stream(&url, request).await
+    }
+
+    /// Sends a chat request and returns a stream of the response chunks.
+    ///
+    /// ## Arguments
+    ///
+    /// * `request` - An `OllamaRequest` object containing the model, messages, and other chat parameters.
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(OllamaResponseStream)` - The stream of response chunks.
+    /// * `Err(Box<dyn Error>)` - If the request could not be sent.
+    pub async fn chat_stream(
+        &self,
+        request: &OllamaRequest,
+    ) -> Result<OllamaResponseStream, Box<dyn Error>> {
+        let url = self.url("/api/chat");
+        self.stream(&url, request).await
+    }
+
+    /// Sends an HTTP POST request with a JSON payload and returns a stream of the response chunks.
+    ///
+    /// ## Arguments
+    ///
+    /// * `url` - The target URL for the POST request.
+    /// * `request` - An `OllamaRequest` object containing the request parameters.
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(OllamaResponseStream)` - The stream of response chunks.
+    /// * `Err(Box<dyn Error>)` - If the request could not be sent.
+    pub async fn stream(
+        &self,
+        url: &str,
+        request: &OllamaRequest,
+    ) -> Result<OllamaResponseStream, Box<dyn Error>> {
+        let timer = StreamTimer::start();
+        if self.dry_run {
+            // The payload must not be cut short at the request's stop sequences.
+            let mut echo = OllamaRequest::new();
+            echo.set_stream(request.stream().unwrap_or(true));
+            for (key, value) in request.metadata() {
+                echo.set_metadata(key, value);
+            }
+            let chunk = dry_run_response(url, request)?;
+            return Ok(OllamaResponseStream::from_line(&chunk, &echo, timer));
+        }
+
+        let http_response = match self.http_client.post(url).json(request).send().await {
+            Ok(http_response) => http_response,
+            Err(error) => {
+                // An audit log that cannot be written does not fail the request.
+                #[cfg(feature = "audit")]
+                if let Some(audit_log) = &self.audit_log {
+                    let result = Err(error.to_string().into());
+                    let duration = timer.metrics().total_time();
+                    let _ = audit_log.record(AuditRecord::ollama(url, request, &result, duration));
+                }
+                return Err(error.into());
+            }
+        };
+
+        #[cfg_attr(not(feature = "audit"), allow(unused_mut))]
+        let mut stream = OllamaResponseStream::with_timer(http_response, request, timer);
+        #[cfg(feature = "audit")]
+        if let Some(audit_log) = &self.audit_log {
+            stream.set_audit_log(audit_log.clone(), url, request);
+        }
+        Ok(stream)
+    }
+
     /// Sends an HTTP POST request with a JSON payload and processes the response with a callback.
     ///
     /// This is a helper function used by `generate` and `chat`.
@@ -113,6 +363,8 @@ impl Ollama {
     ///
     /// * `Ok(OllamaResponse)` - The final response if successful.
     /// * `Err(Box<dyn Error>)` - Any error that occurred during the request or processing.
+    ///   If the stream fails part way, the error is an `OllamaStreamError` holding the
+    ///   partial response.
     pub async fn request<F>(
         &self,
         url: &str,
@@ -122,45 +374,315 @@ impl Ollama {
     where
         F: FnMut(&OllamaResponse),
     {
-        // Send a POST request to the Ollama server with the JSON payload.
-        let mut http_response = self.http_client.post(url).json(request).send().await?;
-        let mut response = None;
-        let mut accumulated_text = String::new();
-
-        while let Some(chunk_bytes) = http_response.chunk().await? {
-            // Deserialize the chunk into a OllamaRequest object.
-            let chunk_string = String::from_utf8_lossy(&chunk_bytes);
-            let chunk_json = serde_json::from_str(&chunk_string)?;
-            let chunk_response = OllamaResponse::from_json(chunk_json)?;
-
-            // Accumulate the content text (if streaming).
-            if let Some(text) = chunk_response.text() {
-                accumulated_text.push_str(text);
+        let mut stream = self.stream(url, request).await?;
+        loop {
+            match stream.read().await {
+                Ok(Some(chunk)) => callback(chunk),
+                Ok(None) => break,
+                // Keep the text generated before the failure, e.g. a server restart.
+                Err(error) => return Err(OllamaStreamError::new(stream.response(), error).into()),
+            }
+        }
+
+        let response = stream.response();
+
+        // Builds without grammar support fail the request; name the option in the error.
+        if let Some(r) = &response
+            && let Some(error) = r.error()
+            && request
+                .options()
+                .is_some_and(|options| options.get("grammar").is_some())
+        {
+            return Err(format!("the server rejected the grammar option: {error}").into());
+        }
+
+        response.ok_or_else(|| "no response received from the Ollama server".into())
+    }
+
+    /// Creates a model on the server, e.g. one with a system prompt or parameters baked in
+    ///
+    /// ## Arguments
+    ///
+    /// * `request` - The model to create, built field by field or parsed from a Modelfile
+    /// * `callback` - A function that will be called with each progress update as it arrives
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(())` - If the server reported that the model was created
+    /// * `Err(Box<dyn Error>)` - If the request failed or the server reported an error
+    pub async fn create_model<F>(
+        &self,
+        request: &OllamaCreateRequest,
+        callback: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&OllamaProgress),
+    {
+        let url = self.url("/api/create");
+        let http_request = self.http_client.post(&url).json(&request.to_json());
+        self.progress_request(http_request, callback).await
+    }
+
+    /// Pushes a model to a registry, e.g. to publish a customized model from CI
+    ///
+    /// ## Arguments
+    ///
+    /// * `request` - The model to push, with any registry credentials
+    /// * `callback` - A function that will be called with each progress update as it arrives
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(())` - If the server reported that the model was pushed
+    /// * `Err(Box<dyn Error>)` - If the request failed or the server reported an error,
+    ///   e.g. because the registry rejected the credentials
+    pub async fn push_model<F>(
+        &self,
+        request: &OllamaPushRequest,
+        callback: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&OllamaProgress),
+    {
+        let url = self.url("/api/push");
+        let mut http_request = self.http_client.post(&url).json(&request.to_json());
+        http_request = match request.auth() {
+            Some(OllamaRegistryAuth::Basic { username, password }) => {
+                http_request.basic_auth(username, Some(password))
+            }
+            Some(OllamaRegistryAuth::Bearer(token)) => http_request.bearer_auth(token),
+            None => http_request,
+        };
+
+        self.progress_request(http_request, callback).await
+    }
+
+    /// Copies a model on the server under a new name
+    ///
+    /// ## Arguments
+    ///
+    /// * `source` - The name of the existing model
+    /// * `destination` - The name of the copy
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(())` - If the model was copied
+    /// * `Err(Box<dyn Error>)` - If the request failed, e.g. because the source model does not exist
+    pub async fn copy_model(&self, source: &str, destination: &str) -> Result<(), Box<dyn Error>> {
+        let url = self.url("/api/copy");
+        let body = serde_json::json!({ "source": source, "destination": destination });
+        let http_response = self.http_client.post(&url).json(&body).send().await?;
+
+        let status = http_response.status();
+        if !status.is_success() {
+            let text = http_response.text().await.unwrap_or_default();
+            return Err(server_error(status, &text).into());
+        }
+
+        Ok(())
+    }
+
+    /// Computes embedding vectors for a batch of texts
+    ///
+    /// ## Arguments
+    ///
+    /// * `model` - The embedding model to use, e.g. "nomic-embed-text"
+    /// * `inputs` - The texts to embed
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(Vec<Vec<f32>>)` - One vector per input, in the same order
+    /// * `Err(Box<dyn Error>)` - If the request failed or the server returned the wrong number of vectors
+    pub async fn embed(
+        &self,
+        model: &str,
+        inputs: &[&str],
+    ) -> Result<Vec<Vec<f32>>, Box<dyn Error>> {
+        let url = self.url("/api/embed");
+        let body = serde_json::json!({ "model": model, "input": inputs });
+        let http_response = self.http_client.post(&url).json(&body).send().await?;
+
+        let status = http_response.status();
+        let text = http_response.text().await?;
+        if !status.is_success() {
+            return Err(server_error(status, &text).into());
+        }
+
+        let mut json: JsonValue = serde_json::from_str(&text)?;
+        let embeddings: Vec<Vec<f32>> = serde_json::from_value(json["embeddings"].take())?;
+        if embeddings.len() != inputs.len() {
+            return Err(format!(
+                "expected {} embeddings, the server returned {}",
+                inputs.len(),
+                embeddings.len()
+            )
+            .into());
+        }
+
+        Ok(embeddings)
+    }
+
+    /// Creates the HTTP client for the given proxy and certificate settings.
+    fn http_client(
+        proxy: &ProxyConfig,
+        tls: Option<&TlsConfig>,
+        server_addr: SocketAddr,
+    ) -> Result<reqwest::Client, Box<dyn Error>> {
+        let mut builder = proxy.apply(reqwest::Client::builder())?;
+        if let Some(tls) = tls {
+            builder = tls.apply(builder);
+            // The certificate is verified against the name, but the server is
+            // still reached at its address.
+            if let Some(name) = tls.server_name() {
+                builder = builder.resolve(name, server_addr);
             }
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Returns the URL of an API path on the server, e.g. "/api/chat".
+    fn url(&self, path: &str) -> String {
+        match self.tls.as_ref().map(TlsConfig::server_name) {
+            Some(Some(name)) => format!("https://{name}:{}{path}", self.server_addr.port()),
+            Some(None) => format!("https://{}{path}", self.server_addr),
+            None => format!("http://{}{path}", self.server_addr),
+        }
+    }
+
+    /// Sends a one-message chat and collects the text of its chunks.
+    async fn send_question<F>(
+        &self,
+        model: &str,
+        prompt: &str,
+        stream: bool,
+        mut callback: F,
+    ) -> Result<String, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        let mut request = OllamaRequest::new();
+        request.set_model(model).set_stream(stream).add_message(
+            OllamaMessage::new()
+                .set_role(OllamaRole::User)
+                .set_content(prompt)
+                .to_json(),
+        );
 
-            // Forward the response to the callback.
-            callback(&chunk_response);
-            response = Some(chunk_response);
+        // Collect the chunks, as the server may stream even when asked not to.
+        let mut answer = String::new();
+        let response = self
+            .chat(&request, |chunk| {
+                if let Some(text) = chunk.text().filter(|text| !text.is_empty()) {
+                    callback(text);
+                    answer.push_str(text);
+                }
+            })
+            .await?;
+        if let Some(error) = response.error() {
+            return Err(error.into());
         }
+        Ok(answer.trim().to_string())
+    }
 
-        let streaming = request.stream().unwrap_or(true);
-
-        // If streaming, set the accumulated text in the final response.
-        if streaming {
-            if let Some(r) = &mut response {
-                // If the request contains messages, set the accumulated text as the final response.
-                if let Some(message) = r.message() {
-                    let mut message = message.clone();
-                    message.set_content(&accumulated_text);
-                    r.set_message(message);
-                } else {
-                    // Otherwise, set the accumulated text as the final response.
-                    r.set_response(&accumulated_text);
+    /// Sends a model management request and reads its progress updates, calling `callback` with each.
+    async fn progress_request<F>(
+        &self,
+        http_request: reqwest::RequestBuilder,
+        mut callback: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&OllamaProgress),
+    {
+        let mut http_response = http_request.send().await?;
+        // Only a successful response streams progress; an error body may not even be
+        // JSON, e.g. a proxy's error page.
+        let status = http_response.status();
+        if !status.is_success() {
+            let body = http_response.text().await.unwrap_or_default();
+            return Err(server_error(status, &body).into());
+        }
+
+        let mut buffer = Vec::new();
+        let mut succeeded = false;
+
+        loop {
+            let chunk = http_response.chunk().await?;
+            let ended = chunk.is_none();
+            buffer.extend_from_slice(chunk.as_deref().unwrap_or_default());
+
+            // Parse every complete line, and whatever is left once the body ends.
+            while let Some(end) = buffer
+                .iter()
+                .position(|&b| b == b'\n')
+                .or((ended && !buffer.is_empty()).then_some(buffer.len()))
+            {
+                let line: Vec<u8> = buffer.drain(..end).collect();
+                if !buffer.is_empty() {
+                    buffer.remove(0);
                 }
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+
+                let json: JsonValue = serde_json::from_slice(&line)?;
+                if let Some(error) = json.get("error") {
+                    let message = error
+                        .as_str()
+                        .map_or_else(|| error.to_string(), str::to_string);
+                    return Err(message.into());
+                }
+
+                let progress: OllamaProgress = serde_json::from_value(json)?;
+                succeeded |= progress.is_success();
+                callback(&progress);
+            }
+
+            if ended {
+                break;
             }
         }
 
-        Ok(response.unwrap())
+        if !succeeded {
+            return Err("the server did not report success".into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints a chunk of streamed text to stdout at once, for use as a streaming callback.
+///
+/// ## Arguments
+///
+/// * `text` - The chunk to print.
+pub fn print_chunk(text: &str) {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(text.as_bytes());
+    let _ = stdout.flush();
+}
+
+/// Builds the response a dry run gets instead of the server's: one final chunk
+/// whose text is the payload of the request.
+fn dry_run_response(url: &str, request: &OllamaRequest) -> Result<JsonValue, Box<dyn Error>> {
+    let payload = serde_json::to_string_pretty(request)?;
+    let mut chunk = json!({ "model": request.model(), "done": true, "done_reason": "dry_run" });
+    if url.ends_with("/api/generate") {
+        chunk["response"] = payload.into();
+    } else {
+        chunk["message"] = json!({ "role": "assistant", "content": payload });
+    }
+
+    Ok(chunk)
+}
+
+/// Describes a failed response, using the server's `error` message if the body has one.
+fn server_error(status: reqwest::StatusCode, body: &str) -> String {
+    let message = serde_json::from_str::<JsonValue>(body)
+        .ok()
+        .and_then(|json| json.get("error")?.as_str().map(str::to_string));
+
+    match message {
+        Some(message) => format!("{status}: {message}"),
+        None => format!("the server returned {status}"),
     }
 }
 
@@ -175,10 +697,20 @@ impl Default for Ollama {
     ///
     /// A new `Ollama` instance connected to 127.0.0.1:11434
     fn default() -> Self {
-        Self {
-            server_addr: SocketAddr::from_str("127.0.0.1:11434").unwrap(),
-            http_client: reqwest::Client::new(),
-        }
+        Self::new("127.0.0.1:11434")
+    }
+}
+
+// ===
+// TRAIT: Ollama (fmt::Debug)
+// ===
+
+impl fmt::Debug for Ollama {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ollama")
+            .field("server_addr", &self.server_addr)
+            .field("dry_run", &self.dry_run)
+            .finish_non_exhaustive()
     }
 }
 
@@ -188,7 +720,11 @@ impl Default for Ollama {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{OllamaFunction, OllamaFunctionParameters, OllamaMessage, OllamaTools};
+    use crate::mock_server::{MockServer, chat_body, error_body, ndjson};
+    use crate::{
+        OllamaFunction, OllamaFunctionParameters, OllamaMessage, OllamaOptions, OllamaTools,
+    };
+    use serde_json::json;
 
     /// Tests basic text generation functionality with the Ollama API
     ///
@@ -236,7 +772,7 @@ mod tests {
     async fn test_chat_request1() {
         let ollama = Ollama::default();
         let message = OllamaMessage::new()
-            .set_role("user")
+            .set_role(OllamaRole::User)
             .set_content("can you explain briefly, why is the sky blue?")
             .to_json();
 
@@ -295,7 +831,7 @@ mod tests {
         temperature_function.set_parameters(params);
         tools.push_function(temperature_function);
         let message = OllamaMessage::new()
-            .set_role("user")
+            .set_role(OllamaRole::User)
             .set_content("What is the current weather in Paris?")
             .to_json();
 
@@ -334,7 +870,7 @@ mod tests {
 
         // For now, we'll create a simple follow-up message instead
         let follow_up_message = OllamaMessage::new()
-            .set_role("user")
+            .set_role(OllamaRole::User)
             .set_content("Thank you for the weather information.")
             .to_json();
 
@@ -361,4 +897,311 @@ mod tests {
         // 2. The response contained the expected tool call information
         // 3. The model handled the tool response correctly
     }
+
+    /// Tests that stop sequences are enforced on streamed output when the server ignores them
+    #[tokio::test]
+    async fn test_chat_enforces_stop_sequences() {
+        let server = MockServer::start(vec![chat_body(&["Sure.\nUs", "er: hi", " again"])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut options = OllamaOptions::new();
+        options.set_stop(&["\nUser:"]);
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .set_options(options.to_json().unwrap())
+            .add_message(
+                OllamaMessage::new()
+                    .set_role(OllamaRole::User)
+                    .set_content("hi")
+                    .to_json(),
+            );
+
+        let mut streamed = String::new();
+        let response = ollama
+            .chat(&request, |response| {
+                streamed.push_str(response.text().unwrap_or_default());
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(streamed, "Sure.");
+        assert_eq!(response.text(), Some("Sure."));
+        assert_eq!(response.done(), Some(&true));
+        assert_eq!(response.done_reason(), Some("stop"));
+    }
+
+    /// Tests that per-token log probabilities from all chunks are collected on the final response
+    #[tokio::test]
+    async fn test_chat_collects_logprobs() {
+        let body = ndjson(&[
+            json!({
+                "model": "mock",
+                "message": { "role": "assistant", "content": "Yes" },
+                "logprobs": [{ "token": "Yes", "logprob": -0.1, "top_logprobs": [
+                    { "token": "Yes", "logprob": -0.1 },
+                    { "token": "No", "logprob": -2.4 }
+                ]}],
+                "done": false
+            }),
+            json!({
+                "model": "mock",
+                "message": { "role": "assistant", "content": "." },
+                "logprobs": [{ "token": ".", "logprob": -0.3 }],
+                "done": true
+            }),
+        ]);
+        let server = MockServer::start(vec![body]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut options = OllamaOptions::new();
+        options.set_logprobs(true).set_top_logprobs(2);
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .set_options(options.to_json().unwrap());
+
+        let response = ollama.chat(&request, |_| {}).await.unwrap();
+        let logprobs = response.logprobs().unwrap();
+        assert_eq!(logprobs.len(), 2);
+        assert_eq!(logprobs[0].top_logprobs[1].token, "No");
+        assert!((response.mean_logprob().unwrap() + 0.2).abs() < 1e-9);
+
+        let sent = &server.requests()[0];
+        assert_eq!(sent["logprobs"], true);
+        assert_eq!(sent["top_logprobs"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_ask_stream_passes_chunks_on() {
+        let server = MockServer::start(vec![chat_body(&["Blue", " light", " scatters."])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut chunks = Vec::new();
+        let answer = ollama
+            .ask_stream("mock", "Why is the sky blue?", |chunk| {
+                chunks.push(chunk.to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(chunks, ["Blue", " light", " scatters."]);
+        assert_eq!(answer, "Blue light scatters.");
+        assert_eq!(server.requests()[0]["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn test_ask_returns_the_answer() {
+        let server = MockServer::start(vec![chat_body(&[" Paris", ".\n"])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let answer = ollama.ask("mock", "Capital of France?").await.unwrap();
+        assert_eq!(answer, "Paris.");
+
+        let sent = &server.requests()[0];
+        assert_eq!(sent["model"], "mock");
+        assert_eq!(sent["stream"], false);
+        assert_eq!(
+            sent["messages"],
+            json!([{ "role": "user", "content": "Capital of France?" }])
+        );
+    }
+
+    /// Tests that requests go through the configured proxy
+    #[tokio::test]
+    async fn test_requests_go_through_the_proxy() {
+        let proxy = MockServer::start(vec![chat_body(&["Hello"])]).await;
+        let mut ollama = Ollama::new("10.255.255.1:11434");
+        ollama
+            .set_proxy(&ProxyConfig::Url(format!("http://{}", proxy.addr())))
+            .unwrap();
+
+        let answer = ollama.ask("mock", "Hi").await.unwrap();
+        assert_eq!(answer, "Hello");
+        assert!(
+            proxy.headers()[0].starts_with("post http://10.255.255.1:11434/api/chat "),
+            "{}",
+            proxy.headers()[0]
+        );
+
+        let invalid = ProxyConfig::Url("not a url".to_string());
+        assert!(ollama.set_proxy(&invalid).is_err());
+    }
+
+    /// Tests that a TLS configuration switches the client to HTTPS
+    #[test]
+    fn test_tls_uses_https() {
+        let mut ollama = Ollama::default();
+        assert_eq!(ollama.url("/api/chat"), "http://127.0.0.1:11434/api/chat");
+
+        ollama.set_tls(&TlsConfig::new()).unwrap();
+        assert_eq!(ollama.url("/api/chat"), "https://127.0.0.1:11434/api/chat");
+        ollama.set_proxy(&ProxyConfig::Direct).unwrap();
+        assert_eq!(
+            ollama.url("/api/embed"),
+            "https://127.0.0.1:11434/api/embed"
+        );
+
+        let mut tls = TlsConfig::new();
+        tls.set_server_name("ollama.internal");
+        ollama.set_tls(&tls).unwrap();
+        assert_eq!(
+            ollama.url("/api/chat"),
+            "https://ollama.internal:11434/api/chat"
+        );
+    }
+
+    /// Tests that a dry run returns the request payload without sending it
+    #[tokio::test]
+    async fn test_dry_run_returns_the_payload() {
+        let server = MockServer::start(vec![]).await;
+        let mut ollama = Ollama::new(&server.addr());
+        ollama.set_dry_run(true);
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .set_options(json!({ "stop": ["\n"] }))
+            .add_message(json!({ "role": "user", "content": "Hi" }));
+        let response = ollama.chat(&request, |_| {}).await.unwrap();
+        assert_eq!(response.done_reason(), Some("dry_run"));
+        let payload: JsonValue = serde_json::from_str(response.text().unwrap()).unwrap();
+        assert_eq!(payload, request.to_json());
+
+        request.set_prompt("Hello");
+        let mut stream = ollama.generate_stream(&request).await.unwrap();
+        while stream.read().await.unwrap().is_some() {}
+        let payload = stream.response().unwrap().text().unwrap().to_string();
+        assert_eq!(
+            serde_json::from_str::<JsonValue>(&payload).unwrap()["prompt"],
+            "Hello"
+        );
+
+        assert!(server.requests().is_empty());
+    }
+
+    /// Tests that a server error for a request with a grammar is reported as a rejected grammar
+    #[tokio::test]
+    async fn test_rejected_grammar_is_an_error() {
+        let body = ndjson(&[json!({ "error": "failed to parse grammar" })]);
+        let server = MockServer::start(vec![body]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut options = OllamaOptions::new();
+        options.set_grammar("root ::= \"yes\"");
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .set_options(options.to_json().unwrap());
+
+        let error = ollama.chat(&request, |_| {}).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "the server rejected the grammar option: failed to parse grammar"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_and_copy_model() {
+        let body = ndjson(&[
+            json!({ "status": "using existing layer sha256:abc" }),
+            json!({ "status": "writing manifest" }),
+            json!({ "status": "success" }),
+        ]);
+        let server = MockServer::start(vec![body, String::new()]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut request = OllamaCreateRequest::new("mario");
+        request.set_from("llama3.2").set_system("You are Mario.");
+        let mut statuses = Vec::new();
+        ollama
+            .create_model(&request, |progress| statuses.push(progress.status.clone()))
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 3);
+        assert_eq!(statuses[2], "success");
+
+        ollama.copy_model("mario", "mario-backup").await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0], request.to_json());
+        assert_eq!(
+            requests[1],
+            json!({ "source": "mario", "destination": "mario-backup" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_model_reports_server_error() {
+        let body = ndjson(&[json!({ "error": "model 'nope' not found" })]);
+        let server = MockServer::start(vec![body]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut request = OllamaCreateRequest::new("mario");
+        request.set_from("nope");
+        let error = ollama.create_model(&request, |_| {}).await.err().unwrap();
+        assert_eq!(error.to_string(), "model 'nope' not found");
+    }
+
+    #[tokio::test]
+    async fn test_create_model_reports_error_status() {
+        let server = MockServer::start(vec![
+            error_body(502, "<html><body>Bad Gateway</body></html>"),
+            error_body(404, r#"{"error":"model 'nope' not found"}"#),
+        ])
+        .await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut request = OllamaCreateRequest::new("mario");
+        request.set_from("nope");
+        let error = ollama.create_model(&request, |_| {}).await.err().unwrap();
+        assert_eq!(error.to_string(), "the server returned 502 Bad Gateway");
+
+        let request = OllamaPushRequest::new("team/mario");
+        let error = ollama.push_model(&request, |_| {}).await.err().unwrap();
+        assert_eq!(error.to_string(), "404 Not Found: model 'nope' not found");
+    }
+
+    #[tokio::test]
+    async fn test_push_model_sends_credentials() {
+        let body = ndjson(&[
+            json!({ "status": "pushing sha256:abc", "digest": "sha256:abc", "total": 100, "completed": 100 }),
+            json!({ "status": "success" }),
+        ]);
+        let server = MockServer::start(vec![body]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut request = OllamaPushRequest::new("team/mario");
+        request.set_auth(OllamaRegistryAuth::Bearer("ci-token".to_string()));
+        let mut fractions = Vec::new();
+        ollama
+            .push_model(&request, |progress| fractions.extend(progress.fraction()))
+            .await
+            .unwrap();
+
+        assert_eq!(fractions, [1.0]);
+        assert!(server.headers()[0].contains("authorization: bearer ci-token"));
+    }
+
+    #[tokio::test]
+    async fn test_embed() {
+        let body = ndjson(&[json!({ "embeddings": [[0.1, 0.2], [0.3, 0.4]] })]);
+        let server = MockServer::start(vec![body]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let embeddings = ollama
+            .embed("nomic-embed-text", &["first", "second"])
+            .await
+            .unwrap();
+        assert_eq!(embeddings, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+        assert_eq!(
+            server.requests()[0],
+            json!({ "model": "nomic-embed-text", "input": ["first", "second"] })
+        );
+
+        let error = ollama.embed("nomic-embed-text", &["third"]).await;
+        assert!(error.is_err());
+    }
 }
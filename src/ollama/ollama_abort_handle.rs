@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+
+// ===
+// STRUCT: OllamaAbortHandle
+// ===
+
+/// Aborts the requests of an `OllamaSession` from another task, e.g. a stop button.
+///
+/// Obtained from `OllamaSession::abort_handle`. Clones of the handle control the
+/// same session.
+#[derive(Clone, Debug, Default)]
+pub struct OllamaAbortHandle {
+    state: Arc<AbortState>,
+}
+
+#[derive(Debug, Default)]
+struct AbortState {
+    aborted: AtomicBool,
+    notify: Notify,
+}
+
+impl OllamaAbortHandle {
+    /// Creates a handle that has not been aborted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels any update in flight and marks the session as aborted.
+    pub fn abort(&self) {
+        self.state.aborted.store(true, Ordering::SeqCst);
+        self.state.notify.notify_waiters();
+    }
+
+    /// Returns `true` once `abort` has been called, until the session clears it.
+    pub fn is_aborted(&self) -> bool {
+        self.state.aborted.load(Ordering::SeqCst)
+    }
+
+    /// Clears the aborted mark.
+    pub(crate) fn clear(&self) {
+        self.state.aborted.store(false, Ordering::SeqCst);
+    }
+
+    /// Completes once `abort` has been called.
+    pub(crate) async fn aborted(&self) {
+        loop {
+            // Created before the check, so an abort in between is not missed.
+            let notified = self.state.notify.notified();
+            if self.is_aborted() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{OllamaResponse, OllamaResponseStream, OllamaStreamError};
+use std::error::Error;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// The outcome of the reading task: the complete response, or the partial
+/// response and the error that ended the stream.
+type ReadResult = Result<Option<OllamaResponse>, (Option<OllamaResponse>, String)>;
+
+// ===
+// STRUCT: OllamaBoundedStream
+// ===
+
+/// The text of a response stream read on a background task into a bounded buffer.
+///
+/// When the consumer falls behind, e.g. a text-to-speech engine reading the
+/// chunks out loud, the buffer fills and the task stops reading the HTTP
+/// response until there is room again. The server then waits on the connection
+/// instead of the chunks piling up in memory.
+///
+/// Created with `OllamaResponseStream::bounded`:
+///
+/// ```no_run
+/// # async fn example(request: ollie_rs::OllamaRequest) -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::Ollama;
+///
+/// let ollama = Ollama::default();
+/// let mut stream = ollama.chat_stream(&request).await?.bounded(8);
+/// while let Some(chunk) = stream.recv().await {
+///     // speak(&chunk).await;
+/// }
+/// let response = stream.finish().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OllamaBoundedStream {
+    receiver: mpsc::Receiver<String>,
+    task: JoinHandle<ReadResult>,
+}
+
+impl OllamaBoundedStream {
+    /// Starts reading a response stream into a buffer of `capacity` chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The response stream to read.
+    /// * `capacity` - The number of text chunks buffered. Values below 1 are treated
+    ///   as 1.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaBoundedStream`.
+    pub fn new(mut stream: OllamaResponseStream, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let task = tokio::spawn(async move {
+            loop {
+                let text = match stream.read().await {
+                    Ok(Some(chunk)) => chunk.text().unwrap_or_default().to_string(),
+                    Ok(None) => return Ok(stream.response()),
+                    Err(err) => return Err((stream.response(), err.to_string())),
+                };
+                // Waiting for room here is what stops the HTTP reads. A closed
+                // channel means the consumer is gone, so reading stops.
+                if !text.is_empty() && sender.send(text).await.is_err() {
+                    return Ok(stream.response());
+                }
+            }
+        });
+
+        Self { receiver, task }
+    }
+
+    /// Waits for the next chunk of text.
+    ///
+    /// # Returns
+    ///
+    /// The chunk, or `None` once the stream has ended.
+    pub async fn recv(&mut self) -> Option<String> {
+        self.receiver.recv().await
+    }
+
+    /// Returns the number of chunks buffered and not yet received.
+    pub fn buffered(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Stops reading and returns the complete response.
+    ///
+    /// Call it once `recv` returns `None`. Called earlier, the chunks not yet
+    /// received are dropped and the response holds the text read so far.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OllamaResponse)` - The response.
+    /// * `Err(Box<dyn Error>)` - If no chunk was read, or an `OllamaStreamError`
+    ///   holding the partial response if the stream failed.
+    pub async fn finish(mut self) -> Result<OllamaResponse, Box<dyn Error>> {
+        self.receiver.close();
+        match self.task.await? {
+            Ok(Some(response)) => Ok(response),
+            Ok(None) => Err("the stream ended without a response".into()),
+            Err((partial, error)) => Err(Box::new(OllamaStreamError::new(partial, error.into()))),
+        }
+    }
+}
+
+// ===
+// TESTS: OllamaBoundedStream
+// ===
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_server::{MockServer, chat_body};
+    use crate::{Ollama, OllamaRequest};
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn chat_request() -> OllamaRequest {
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .add_message(json!({ "role": "user", "content": "Hi" }));
+        request
+    }
+
+    #[tokio::test]
+    async fn test_buffer_applies_backpressure() {
+        let server = MockServer::start(vec![chat_body(&["a", "b", "c", "d"])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut stream = ollama
+            .chat_stream(&chat_request())
+            .await
+            .unwrap()
+            .bounded(2);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(stream.buffered(), 2);
+
+        let mut text = String::new();
+        while let Some(chunk) = stream.recv().await {
+            text.push_str(&chunk);
+        }
+        assert_eq!(text, "abcd");
+
+        let response = stream.finish().await.unwrap();
+        assert_eq!(response.text(), Some("abcd"));
+    }
+
+    #[tokio::test]
+    async fn test_finish_early_keeps_text_read() {
+        let server = MockServer::start(vec![chat_body(&["a", "b", "c", "d"])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut stream = ollama
+            .chat_stream(&chat_request())
+            .await
+            .unwrap()
+            .bounded(1);
+        assert_eq!(stream.recv().await.as_deref(), Some("a"));
+
+        let response = stream.finish().await.unwrap();
+        let text = response.text().unwrap();
+        assert!(text.starts_with("ab") && text.len() < 4, "{text}");
+    }
+
+    #[tokio::test]
+    async fn test_zero_capacity_is_treated_as_one() {
+        let server = MockServer::start(vec![chat_body(&["a", "b"])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut stream = ollama
+            .chat_stream(&chat_request())
+            .await
+            .unwrap()
+            .bounded(0);
+        assert_eq!(stream.recv().await.as_deref(), Some("a"));
+        assert_eq!(stream.recv().await.as_deref(), Some("b"));
+        assert_eq!(stream.finish().await.unwrap().text(), Some("ab"));
+    }
+}
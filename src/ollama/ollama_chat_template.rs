@@ -0,0 +1,203 @@
+use crate::{OllamaHistory, OllamaRequest};
+use serde_json::{Value as JsonValue, json};
+
+// ===
+// ENUM: OllamaChatTemplate
+// ===
+
+/// A prompt format for rendering chat messages into a single raw prompt.
+///
+/// Use it with models whose server-side template is missing or broken, or to
+/// experiment with a template by hand: render the messages, then send the prompt
+/// to the generate endpoint with `raw` set, so the server applies no template
+/// of its own. `apply` does both to a chat request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OllamaChatTemplate {
+    /// The ChatML format used by Qwen and many fine-tunes: `<|im_start|>role ... <|im_end|>`.
+    ChatMl,
+    /// The Llama 3 format: `<|start_header_id|>role<|end_header_id|> ... <|eot_id|>`.
+    Llama3,
+    /// The Gemma format: `<start_of_turn>role ... <end_of_turn>`, with no system role.
+    Gemma,
+}
+
+impl OllamaChatTemplate {
+    /// Looks up a template by name: "chatml", "llama3" or "gemma".
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The template name, in any case.
+    ///
+    /// # Returns
+    ///
+    /// The template, or `None` if the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "chatml" => Some(Self::ChatMl),
+            "llama3" => Some(Self::Llama3),
+            "gemma" => Some(Self::Gemma),
+            _ => None,
+        }
+    }
+
+    /// Returns the tokens that end a turn, which should stop generation in raw mode.
+    pub fn stop_sequences(&self) -> &'static [&'static str] {
+        match self {
+            Self::ChatMl => &["<|im_end|>"],
+            Self::Llama3 => &["<|eot_id|>"],
+            Self::Gemma => &["<end_of_turn>"],
+        }
+    }
+
+    /// Renders messages into a prompt that ends with the start of an assistant turn.
+    ///
+    /// The beginning-of-sequence token is left out, since the server adds it when
+    /// tokenizing. Gemma has no system role, so system messages are prepended to the
+    /// next user message.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The conversation, as sent to the chat endpoint.
+    ///
+    /// # Returns
+    ///
+    /// The rendered prompt.
+    pub fn render(&self, messages: &OllamaHistory) -> String {
+        let mut prompt = String::new();
+        let mut pending_system = String::new();
+
+        for message in messages.iter() {
+            let role = message["role"].as_str().unwrap_or("user");
+            let content = message["content"].as_str().unwrap_or_default();
+
+            match self {
+                Self::ChatMl => {
+                    prompt.push_str(&format!("<|im_start|>{role}\n{content}<|im_end|>\n"));
+                }
+                Self::Llama3 => {
+                    let role = if role == "tool" { "ipython" } else { role };
+                    prompt.push_str(&format!(
+                        "<|start_header_id|>{role}<|end_header_id|>\n\n{content}<|eot_id|>"
+                    ));
+                }
+                Self::Gemma if role == "system" => {
+                    pending_system.push_str(content);
+                    pending_system.push_str("\n\n");
+                }
+                Self::Gemma => {
+                    let role = if role == "assistant" { "model" } else { "user" };
+                    let system = std::mem::take(&mut pending_system);
+                    prompt.push_str(&format!(
+                        "<start_of_turn>{role}\n{system}{content}<end_of_turn>\n"
+                    ));
+                }
+            }
+        }
+
+        prompt.push_str(match self {
+            Self::ChatMl => "<|im_start|>assistant\n",
+            Self::Llama3 => "<|start_header_id|>assistant<|end_header_id|>\n\n",
+            Self::Gemma => "<start_of_turn>model\n",
+        });
+        prompt
+    }
+
+    /// Converts a chat request into a raw generate request using this template.
+    ///
+    /// The request's messages are rendered into its prompt and removed, `raw` is set,
+    /// and the template's stop sequences are added to the options unless the request
+    /// already has stop sequences. Send the result with `Ollama::generate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - A chat request with messages.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaRequest` for the generate endpoint.
+    pub fn apply(&self, request: &OllamaRequest) -> OllamaRequest {
+        let no_messages = OllamaHistory::default();
+        let prompt = self.render(request.messages().unwrap_or(&no_messages));
+
+        let mut json = request.to_json();
+        if let Some(object) = json.as_object_mut() {
+            object.remove("messages");
+        }
+        let mut raw_request = OllamaRequest::from_json(json).unwrap_or_default();
+        raw_request.set_prompt(&prompt).set_raw(true);
+
+        let mut options = request.options().cloned().unwrap_or_else(|| json!({}));
+        if options.get("stop").is_none()
+            && let Some(object) = options.as_object_mut()
+        {
+            let stop: Vec<JsonValue> = self.stop_sequences().iter().map(|s| json!(s)).collect();
+            object.insert("stop".to_string(), JsonValue::Array(stop));
+        }
+        raw_request.set_options(options);
+
+        raw_request
+    }
+}
+
+// ===
+// TESTS: OllamaChatTemplate
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> OllamaHistory {
+        OllamaHistory::from(vec![
+            json!({ "role": "system", "content": "Be brief." }),
+            json!({ "role": "user", "content": "Hi" }),
+            json!({ "role": "assistant", "content": "Hello!" }),
+            json!({ "role": "user", "content": "Bye" }),
+        ])
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            OllamaChatTemplate::ChatMl.render(&conversation()),
+            "<|im_start|>system\nBe brief.<|im_end|>\n<|im_start|>user\nHi<|im_end|>\n\
+             <|im_start|>assistant\nHello!<|im_end|>\n<|im_start|>user\nBye<|im_end|>\n\
+             <|im_start|>assistant\n"
+        );
+        assert_eq!(
+            OllamaChatTemplate::Llama3.render(&conversation()),
+            "<|start_header_id|>system<|end_header_id|>\n\nBe brief.<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\nHi<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\nHello!<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\nBye<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+        assert_eq!(
+            OllamaChatTemplate::Gemma.render(&conversation()),
+            "<start_of_turn>user\nBe brief.\n\nHi<end_of_turn>\n\
+             <start_of_turn>model\nHello!<end_of_turn>\n\
+             <start_of_turn>user\nBye<end_of_turn>\n\
+             <start_of_turn>model\n"
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("qwen3")
+            .set_messages(conversation())
+            .set_options(json!({ "temperature": 0.2 }));
+
+        let raw = OllamaChatTemplate::from_name("ChatML")
+            .unwrap()
+            .apply(&request);
+        assert!(raw.messages().is_none());
+        assert_eq!(raw.raw(), Some(true));
+        assert!(raw.prompt().unwrap().ends_with("<|im_start|>assistant\n"));
+        assert_eq!(
+            raw.options(),
+            Some(&json!({ "temperature": 0.2, "stop": ["<|im_end|>"] }))
+        );
+    }
+}
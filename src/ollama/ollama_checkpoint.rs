@@ -0,0 +1,67 @@
+use crate::OllamaHistory;
+use std::collections::BTreeSet;
+
+// ===
+// STRUCT: OllamaCheckpoint
+// ===
+
+/// A saved point of a conversation: its history and the messages pinned in it.
+///
+/// Captured by `OllamaSession::checkpoint` and passed back to `restore`. The
+/// history shares its messages with the session, so checkpoints are cheap.
+#[derive(Clone, Debug, Default)]
+pub struct OllamaCheckpoint {
+    history: OllamaHistory,
+    pinned: BTreeSet<usize>,
+}
+
+impl OllamaCheckpoint {
+    /// Creates a checkpoint from a history and the indexes of its pinned messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - The saved history.
+    /// * `pinned` - The indexes of the messages history trimming must keep; any
+    ///   past the end of the history are ignored.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaCheckpoint`.
+    pub fn new(history: OllamaHistory, pinned: BTreeSet<usize>) -> Self {
+        let len = history.len();
+        let pinned = pinned.into_iter().filter(|index| *index < len).collect();
+        Self { history, pinned }
+    }
+
+    /// Returns the saved history.
+    pub fn history(&self) -> &OllamaHistory {
+        &self.history
+    }
+
+    /// Returns the indexes of the pinned messages.
+    pub fn pinned(&self) -> &BTreeSet<usize> {
+        &self.pinned
+    }
+
+    /// Splits the checkpoint into its history and pinned indexes.
+    pub fn into_parts(self) -> (OllamaHistory, BTreeSet<usize>) {
+        (self.history, self.pinned)
+    }
+}
+
+// ===
+// TRAIT: Conversions for OllamaCheckpoint
+// ===
+
+/// Pins the system messages of the history, as `OllamaSession::system` does.
+impl From<OllamaHistory> for OllamaCheckpoint {
+    fn from(history: OllamaHistory) -> Self {
+        let pinned = history
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| message["role"] == "system")
+            .map(|(index, _)| index)
+            .collect();
+        Self { history, pinned }
+    }
+}
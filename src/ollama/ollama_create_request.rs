@@ -0,0 +1,376 @@
+use crate::OllamaMessage;
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+// ===
+// STRUCT: OllamaCreateRequest
+// ===
+
+/// A request to the Ollama `create` endpoint, deriving a new model from an existing one.
+///
+/// Build it field by field, or parse a Modelfile with `from_modelfile`, then send it
+/// with `Ollama::create_model`.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct OllamaCreateRequest {
+    model: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+
+    #[serde(skip_serializing_if = "JsonMap::is_empty")]
+    parameters: JsonMap<String, JsonValue>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    messages: Vec<OllamaMessage>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    adapters: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    files: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quantize: Option<String>,
+}
+
+impl OllamaCreateRequest {
+    /// Creates a request for a model with the given name and nothing else set.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The name of the model to create, e.g. "mario:latest".
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaCreateRequest`.
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Parses a Modelfile into a request.
+    ///
+    /// The `FROM`, `SYSTEM`, `TEMPLATE`, `PARAMETER`, `MESSAGE` and `LICENSE`
+    /// instructions are supported, including `"""` quoted multi-line values.
+    /// `ADAPTER` instructions are rejected, because the server needs the adapter
+    /// uploaded as a blob first; add uploaded adapters with `add_adapter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The name of the model to create.
+    /// * `modelfile` - The contents of the Modelfile.
+    ///
+    /// # Returns
+    ///
+    /// The parsed request, or an error naming the first instruction that could not be parsed.
+    pub fn from_modelfile(model: &str, modelfile: &str) -> Result<Self, Box<dyn Error>> {
+        let mut request = Self::new(model);
+        let mut lines = modelfile.lines();
+
+        while let Some(line) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (instruction, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let args = args.trim();
+
+            match instruction.to_ascii_uppercase().as_str() {
+                "FROM" => {
+                    request.set_from(args);
+                }
+                "SYSTEM" => {
+                    request.set_system(&read_value(args, &mut lines)?);
+                }
+                "TEMPLATE" => {
+                    request.set_template(&read_value(args, &mut lines)?);
+                }
+                "LICENSE" => {
+                    request.set_license(&read_value(args, &mut lines)?);
+                }
+                "PARAMETER" => {
+                    let (key, value) = args
+                        .split_once(char::is_whitespace)
+                        .ok_or_else(|| format!("PARAMETER without a value: {line}"))?;
+                    request.add_parameter(
+                        key,
+                        parse_parameter(&read_value(value.trim(), &mut lines)?),
+                    );
+                }
+                "MESSAGE" => {
+                    let (role, content) = args
+                        .split_once(char::is_whitespace)
+                        .ok_or_else(|| format!("MESSAGE without content: {line}"))?;
+                    let mut message = OllamaMessage::new();
+                    message
+                        .set_role(role)
+                        .set_content(&read_value(content.trim(), &mut lines)?);
+                    request.add_message(message);
+                }
+                "ADAPTER" => {
+                    return Err(format!(
+                        "ADAPTER is not supported in a parsed Modelfile; upload the adapter \
+                         and use add_adapter: {line}"
+                    )
+                    .into());
+                }
+                _ => return Err(format!("unknown Modelfile instruction: {line}").into()),
+            }
+        }
+
+        Ok(request)
+    }
+
+    /// Returns the name of the model to create.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Serializes the request into a `serde_json::Value`.
+    ///
+    /// Serialization cannot fail: every field is a string, a message or a JSON value,
+    /// and every map has string keys.
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).expect("an OllamaCreateRequest always serializes to JSON")
+    }
+
+    /// Sets the model or blob the new model is derived from.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The name of an existing model, e.g. "llama3.2".
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn set_from(&mut self, from: &str) -> &mut Self {
+        self.from = Some(from.to_string());
+        self
+    }
+
+    /// Sets the system prompt baked into the new model.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The system prompt.
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn set_system(&mut self, system: &str) -> &mut Self {
+        self.system = Some(system.to_string());
+        self
+    }
+
+    /// Sets the prompt template of the new model.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The template, in Ollama's Go template syntax.
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn set_template(&mut self, template: &str) -> &mut Self {
+        self.template = Some(template.to_string());
+        self
+    }
+
+    /// Sets the license of the new model.
+    ///
+    /// # Arguments
+    ///
+    /// * `license` - The license text.
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn set_license(&mut self, license: &str) -> &mut Self {
+        self.license = Some(license.to_string());
+        self
+    }
+
+    /// Sets a default parameter of the new model, e.g. "temperature" or "num_ctx".
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The parameter name.
+    /// * `value` - The parameter value.
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn set_parameter(&mut self, key: &str, value: JsonValue) -> &mut Self {
+        self.parameters.insert(key.to_string(), value);
+        self
+    }
+
+    /// Adds a message to the conversation history baked into the new model.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to add.
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn add_message(&mut self, message: OllamaMessage) -> &mut Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Adds a LoRA adapter that was uploaded to the server as a blob.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The file name of the adapter.
+    /// * `digest` - The SHA256 digest of the uploaded blob, e.g. "sha256:...".
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn add_adapter(&mut self, file_name: &str, digest: &str) -> &mut Self {
+        self.adapters
+            .insert(file_name.to_string(), digest.to_string());
+        self
+    }
+
+    /// Adds a model weights file that was uploaded to the server as a blob.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The file name of the weights, e.g. "model.gguf".
+    /// * `digest` - The SHA256 digest of the uploaded blob, e.g. "sha256:...".
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn add_file(&mut self, file_name: &str, digest: &str) -> &mut Self {
+        self.files.insert(file_name.to_string(), digest.to_string());
+        self
+    }
+
+    /// Sets the quantization applied to the new model, e.g. "q4_K_M".
+    ///
+    /// # Arguments
+    ///
+    /// * `quantize` - The quantization type.
+    ///
+    /// Returns the modified `OllamaCreateRequest` instance.
+    pub fn set_quantize(&mut self, quantize: &str) -> &mut Self {
+        self.quantize = Some(quantize.to_string());
+        self
+    }
+
+    /// Adds a parameter parsed from a Modelfile, collecting repeated `stop` values.
+    fn add_parameter(&mut self, key: &str, value: JsonValue) {
+        if key != "stop" {
+            self.set_parameter(key, value);
+            return;
+        }
+
+        let stop = self
+            .parameters
+            .entry("stop")
+            .or_insert_with(|| JsonValue::Array(Vec::new()));
+        if let JsonValue::Array(values) = stop {
+            values.push(JsonValue::String(
+                value.as_str().unwrap_or_default().to_string(),
+            ));
+        }
+    }
+}
+
+/// Reads an instruction argument, unquoting it and joining `"""` quoted lines.
+fn read_value<'a>(
+    args: &str,
+    lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<String, Box<dyn Error>> {
+    let Some(rest) = args.strip_prefix("\"\"\"") else {
+        let unquoted = args
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'));
+        return Ok(unquoted.unwrap_or(args).to_string());
+    };
+
+    if let Some(end) = rest.find("\"\"\"") {
+        return Ok(rest[..end].to_string());
+    }
+
+    let mut value = rest.to_string();
+    for line in lines {
+        if let Some(end) = line.find("\"\"\"") {
+            value.push('\n');
+            value.push_str(&line[..end]);
+            return Ok(value.trim_start_matches('\n').to_string());
+        }
+        value.push('\n');
+        value.push_str(line);
+    }
+
+    Err("unterminated \"\"\" in Modelfile".into())
+}
+
+/// Parses a parameter value as a number or boolean where possible.
+fn parse_parameter(value: &str) -> JsonValue {
+    if let Ok(number) = value.parse::<i64>() {
+        JsonValue::from(number)
+    } else if let Ok(number) = value.parse::<f64>() {
+        JsonValue::from(number)
+    } else if let Ok(flag) = value.parse::<bool>() {
+        JsonValue::from(flag)
+    } else {
+        JsonValue::from(value)
+    }
+}
+
+// ===
+// TESTS: OllamaCreateRequest
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_modelfile() {
+        let modelfile = r#"
+# A plumber.
+FROM llama3.2
+PARAMETER temperature 0.7
+PARAMETER num_ctx 4096
+PARAMETER stop "<|end|>"
+PARAMETER stop "User:"
+SYSTEM """
+You are Mario.
+Answer as Mario."""
+MESSAGE user Who are you?
+MESSAGE assistant "It's-a me, Mario!"
+"#;
+
+        let request = OllamaCreateRequest::from_modelfile("mario", modelfile).unwrap();
+        assert_eq!(
+            request.to_json(),
+            json!({
+                "model": "mario",
+                "from": "llama3.2",
+                "system": "You are Mario.\nAnswer as Mario.",
+                "parameters": {
+                    "temperature": 0.7,
+                    "num_ctx": 4096,
+                    "stop": ["<|end|>", "User:"]
+                },
+                "messages": [
+                    { "role": "user", "content": "Who are you?" },
+                    { "role": "assistant", "content": "It's-a me, Mario!" }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_modelfile_errors() {
+        assert!(OllamaCreateRequest::from_modelfile("m", "ADAPTER ./lora.gguf").is_err());
+        assert!(OllamaCreateRequest::from_modelfile("m", "BOGUS x").is_err());
+        assert!(OllamaCreateRequest::from_modelfile("m", "SYSTEM \"\"\"open").is_err());
+    }
+}
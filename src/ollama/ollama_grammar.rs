@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+
+// ===
+// STRUCT: OllamaGrammar
+// ===
+
+/// Helpers for building and checking GBNF grammars for constrained generation.
+///
+/// Grammars are passed to the server with `OllamaOptions::set_grammar`. Only some
+/// Ollama builds honor the option; when the server rejects a grammar, the request
+/// fails with an error naming the grammar option.
+pub struct OllamaGrammar;
+
+impl OllamaGrammar {
+    /// Builds a grammar that only accepts one of the given strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `choices` - The allowed outputs.
+    ///
+    /// # Returns
+    ///
+    /// A GBNF grammar whose root matches exactly one of the choices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaGrammar;
+    ///
+    /// let grammar = OllamaGrammar::choice(&["yes", "no"]);
+    /// assert_eq!(grammar, "root ::= \"yes\" | \"no\"\n");
+    /// ```
+    pub fn choice(choices: &[&str]) -> String {
+        let alternatives: Vec<String> = choices.iter().map(|choice| literal(choice)).collect();
+        format!("root ::= {}\n", alternatives.join(" | "))
+    }
+
+    /// Builds a grammar that accepts a JSON object with exactly the given keys, in order.
+    ///
+    /// Each value may be any JSON string, number, boolean or `null`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys of the object.
+    ///
+    /// # Returns
+    ///
+    /// A GBNF grammar whose root matches the JSON object.
+    pub fn json_object(keys: &[&str]) -> String {
+        let members: Vec<String> = keys
+            .iter()
+            .map(|key| format!("{} ws \":\" ws value", literal(&format!("\"{key}\""))))
+            .collect();
+
+        let body = if members.is_empty() {
+            "ws".to_string()
+        } else {
+            format!("ws {} ws", members.join(" ws \",\" ws "))
+        };
+
+        format!(
+            "root ::= \"{{\" {body} \"}}\"\n\
+             value ::= string | number | \"true\" | \"false\" | \"null\"\n\
+             string ::= \"\\\"\" ( [^\"\\\\] | \"\\\\\" [\"\\\\/bfnrt] )* \"\\\"\"\n\
+             number ::= \"-\"? [0-9]+ (\".\" [0-9]+)? ([eE] [-+]? [0-9]+)?\n\
+             ws ::= [ \\t\\n]*\n"
+        )
+    }
+
+    /// Performs basic checks on a grammar before it is sent to the server.
+    ///
+    /// The check verifies that the grammar defines a `root` rule, that every rule
+    /// referenced is defined, and that literals and character classes are closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `grammar` - The GBNF grammar to check.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If no problems were found.
+    /// * `Err(String)` - A description of the first problem found.
+    pub fn validate(grammar: &str) -> Result<(), String> {
+        let mut defined = HashSet::new();
+        let mut referenced = Vec::new();
+        let mut current: Option<String> = None;
+
+        for (number, line) in grammar.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let rhs = match line.split_once("::=") {
+                Some((name, rhs)) => {
+                    let name = name.trim();
+                    if name.is_empty() || !name.chars().all(is_rule_char) {
+                        return Err(format!("line {}: invalid rule name '{name}'", number + 1));
+                    }
+                    defined.insert(name.to_string());
+                    current = Some(name.to_string());
+                    rhs
+                }
+                None if current.is_some() => line,
+                None => return Err(format!("line {}: expected a rule definition", number + 1)),
+            };
+
+            for name in references(rhs).map_err(|err| format!("line {}: {err}", number + 1))? {
+                referenced.push(name);
+            }
+        }
+
+        if !defined.contains("root") {
+            return Err("grammar has no 'root' rule".to_string());
+        }
+
+        if let Some(missing) = referenced.iter().find(|name| !defined.contains(*name)) {
+            return Err(format!("rule '{missing}' is referenced but not defined"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` for characters allowed in rule names.
+fn is_rule_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Quotes a string as a GBNF literal.
+fn literal(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Collects the rule names referenced in the right-hand side of a rule.
+fn references(rhs: &str) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    let mut chars = rhs.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '[' => {
+                let close = if c == '"' { '"' } else { ']' };
+                let mut closed = false;
+                while let Some(inner) = chars.next() {
+                    if inner == '\\' {
+                        chars.next();
+                    } else if inner == close {
+                        closed = true;
+                        break;
+                    }
+                }
+                if !closed {
+                    let kind = if c == '"' {
+                        "literal"
+                    } else {
+                        "character class"
+                    };
+                    return Err(format!("unterminated {kind}"));
+                }
+            }
+            '#' => break,
+            c if is_rule_char(c) => {
+                let mut name = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !is_rule_char(next) {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                names.push(name);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(names)
+}
+
+// ===
+// TESTS: OllamaGrammar
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choice_escapes_literals() {
+        let grammar = OllamaGrammar::choice(&["say \"hi\"", "a\\b"]);
+        assert_eq!(grammar, "root ::= \"say \\\"hi\\\"\" | \"a\\\\b\"\n");
+        assert!(OllamaGrammar::validate(&grammar).is_ok());
+    }
+
+    #[test]
+    fn test_json_object_is_valid() {
+        let grammar = OllamaGrammar::json_object(&["name", "age"]);
+        assert!(grammar.starts_with(
+            "root ::= \"{\" ws \"\\\"name\\\"\" ws \":\" ws value ws \",\" ws \"\\\"age\\\"\""
+        ));
+        assert_eq!(OllamaGrammar::validate(&grammar), Ok(()));
+        assert_eq!(
+            OllamaGrammar::validate(&OllamaGrammar::json_object(&[])),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        assert_eq!(
+            OllamaGrammar::validate("answer ::= \"yes\""),
+            Err("grammar has no 'root' rule".to_string())
+        );
+        assert_eq!(
+            OllamaGrammar::validate("root ::= item"),
+            Err("rule 'item' is referenced but not defined".to_string())
+        );
+        assert_eq!(
+            OllamaGrammar::validate("root ::= \"open"),
+            Err("line 1: unterminated literal".to_string())
+        );
+    }
+}
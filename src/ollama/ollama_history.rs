@@ -0,0 +1,343 @@
+use crate::OllamaMessage;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value as JsonValue;
+use std::ops::Index;
+use std::sync::Arc;
+
+// ===
+// STRUCT: OllamaHistory
+// ===
+
+/// The message history of a chat request, stored as shared immutable segments.
+///
+/// Messages are appended to a private tail; `freeze` turns the tail into a segment
+/// held behind an `Arc`. Cloning a history only copies the segment pointers and the
+/// tail, so forks and checkpoints of a long conversation share its prior context
+/// instead of deep-cloning every message. The history serializes as a flat array.
+#[derive(Clone, Debug, Default)]
+pub struct OllamaHistory {
+    segments: Vec<Arc<[JsonValue]>>,
+    tail: Vec<JsonValue>,
+}
+
+impl OllamaHistory {
+    /// Creates a new, empty history.
+    pub const fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            tail: Vec::new(),
+        }
+    }
+
+    /// Returns the number of messages in the history.
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|s| s.len()).sum::<usize>() + self.tail.len()
+    }
+
+    /// Returns `true` if the history has no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends a message to the history.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to append.
+    pub fn push(&mut self, message: JsonValue) {
+        self.tail.push(message);
+    }
+
+    /// Removes the last message from the history and returns it.
+    ///
+    /// Popping from a frozen segment copies the rest of that segment into the tail.
+    ///
+    /// # Returns
+    ///
+    /// The removed message, or `None` if the history is empty.
+    pub fn pop(&mut self) -> Option<JsonValue> {
+        if let Some(message) = self.tail.pop() {
+            return Some(message);
+        }
+
+        let segment = self.segments.pop()?;
+        let mut messages = segment.to_vec();
+        let message = messages.pop();
+        self.tail = messages;
+        message
+    }
+
+    /// Replaces the message at the given position.
+    ///
+    /// Replacing a message in a frozen segment copies that segment, so forks and
+    /// checkpoints sharing it keep the original.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the message.
+    /// * `message` - The new message.
+    ///
+    /// # Returns
+    ///
+    /// The replaced message, or `None` if `index` is out of range.
+    pub fn replace(&mut self, index: usize, message: JsonValue) -> Option<JsonValue> {
+        let mut index = index;
+        for segment in &mut self.segments {
+            if index < segment.len() {
+                let mut messages = segment.to_vec();
+                let old = std::mem::replace(&mut messages[index], message);
+                *segment = Arc::from(messages);
+                return Some(old);
+            }
+            index -= segment.len();
+        }
+
+        let slot = self.tail.get_mut(index)?;
+        Some(std::mem::replace(slot, message))
+    }
+
+    /// Returns the message at the given position.
+    pub fn get(&self, index: usize) -> Option<&JsonValue> {
+        let mut index = index;
+        for segment in &self.segments {
+            if index < segment.len() {
+                return Some(&segment[index]);
+            }
+            index -= segment.len();
+        }
+        self.tail.get(index)
+    }
+
+    /// Returns the last message, if any.
+    pub fn last(&self) -> Option<&JsonValue> {
+        self.tail
+            .last()
+            .or_else(|| self.segments.last().and_then(|s| s.last()))
+    }
+
+    /// Returns an iterator over the messages, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &JsonValue> {
+        self.segments
+            .iter()
+            .flat_map(|segment| segment.iter())
+            .chain(self.tail.iter())
+    }
+
+    /// Copies the messages into a vector.
+    pub fn to_vec(&self) -> Vec<JsonValue> {
+        self.iter().cloned().collect()
+    }
+
+    /// Moves the unfrozen messages into a new shared segment.
+    ///
+    /// Sessions freeze their history after every turn, so later clones share it.
+    pub fn freeze(&mut self) {
+        if !self.tail.is_empty() {
+            let tail = std::mem::take(&mut self.tail);
+            self.segments.push(Arc::from(tail));
+        }
+    }
+
+    /// Freezes the history and returns a copy that shares all of its messages.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaHistory` that can be extended independently of this one.
+    pub fn fork(&mut self) -> Self {
+        self.freeze();
+        self.clone()
+    }
+}
+
+// ===
+// TRAIT: Serialize / Deserialize for OllamaHistory
+// ===
+
+impl Serialize for OllamaHistory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for OllamaHistory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<JsonValue>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+// ===
+// TRAIT: Conversions for OllamaHistory
+// ===
+
+impl From<Vec<JsonValue>> for OllamaHistory {
+    fn from(messages: Vec<JsonValue>) -> Self {
+        Self {
+            segments: Vec::new(),
+            tail: messages,
+        }
+    }
+}
+
+impl From<&[JsonValue]> for OllamaHistory {
+    fn from(messages: &[JsonValue]) -> Self {
+        Self::from(messages.to_vec())
+    }
+}
+
+impl FromIterator<JsonValue> for OllamaHistory {
+    fn from_iter<I: IntoIterator<Item = JsonValue>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl From<Vec<OllamaMessage>> for OllamaHistory {
+    fn from(messages: Vec<OllamaMessage>) -> Self {
+        messages.iter().map(OllamaMessage::to_json).collect()
+    }
+}
+
+impl FromIterator<OllamaMessage> for OllamaHistory {
+    fn from_iter<I: IntoIterator<Item = OllamaMessage>>(iter: I) -> Self {
+        iter.into_iter().map(|message| message.to_json()).collect()
+    }
+}
+
+impl Extend<JsonValue> for OllamaHistory {
+    fn extend<I: IntoIterator<Item = JsonValue>>(&mut self, iter: I) {
+        self.tail.extend(iter);
+    }
+}
+
+impl<'a> IntoIterator for &'a OllamaHistory {
+    type Item = &'a JsonValue;
+    type IntoIter = Box<dyn DoubleEndedIterator<Item = &'a JsonValue> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl IntoIterator for OllamaHistory {
+    type Item = JsonValue;
+    type IntoIter = std::vec::IntoIter<JsonValue>;
+
+    /// Moves the messages out; frozen segments are copied, since they may be shared.
+    fn into_iter(self) -> Self::IntoIter {
+        if self.segments.is_empty() {
+            self.tail.into_iter()
+        } else {
+            self.to_vec().into_iter()
+        }
+    }
+}
+
+impl Index<usize> for OllamaHistory {
+    type Output = JsonValue;
+
+    fn index(&self, index: usize) -> &JsonValue {
+        self.get(index).expect("history index out of bounds")
+    }
+}
+
+// ===
+// TRAIT: PartialEq for OllamaHistory
+// ===
+
+impl PartialEq for OllamaHistory {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl PartialEq<[JsonValue]> for OllamaHistory {
+    fn eq(&self, other: &[JsonValue]) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl PartialEq<Vec<JsonValue>> for OllamaHistory {
+    fn eq(&self, other: &Vec<JsonValue>) -> bool {
+        self == other.as_slice()
+    }
+}
+
+// ===
+// TESTS: OllamaHistory
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(content: &str) -> JsonValue {
+        json!({ "role": "user", "content": content })
+    }
+
+    #[test]
+    fn test_fork_shares_segments() {
+        let mut history = OllamaHistory::from(vec![message("one"), message("two")]);
+        let mut fork = history.fork();
+        assert!(Arc::ptr_eq(&history.segments[0], &fork.segments[0]));
+
+        history.push(message("three"));
+        fork.push(message("other"));
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2], message("three"));
+        assert_eq!(fork[2], message("other"));
+        assert_eq!(fork.last(), Some(&message("other")));
+    }
+
+    #[test]
+    fn test_pop_across_segments() {
+        let mut history = OllamaHistory::from(vec![message("one"), message("two")]);
+        history.freeze();
+        history.push(message("three"));
+
+        assert_eq!(history.pop(), Some(message("three")));
+        assert_eq!(history.pop(), Some(message("two")));
+        assert_eq!(history, vec![message("one")]);
+        assert_eq!(history.pop(), Some(message("one")));
+        assert_eq!(history.pop(), None);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_replace_copies_shared_segments() {
+        let mut history = OllamaHistory::from(vec![message("one"), message("two")]);
+        let fork = history.fork();
+        history.push(message("three"));
+
+        assert_eq!(history.replace(1, message("2")), Some(message("two")));
+        assert_eq!(history.replace(2, message("3")), Some(message("three")));
+        assert_eq!(history.replace(3, message("4")), None);
+        assert_eq!(history, vec![message("one"), message("2"), message("3")]);
+        assert_eq!(fork, vec![message("one"), message("two")]);
+    }
+
+    #[test]
+    fn test_serializes_flat() {
+        let mut history = OllamaHistory::from(vec![message("one")]);
+        history.freeze();
+        history.push(message("two"));
+
+        let json = serde_json::to_value(&history).unwrap();
+        assert_eq!(json, json!([message("one"), message("two")]));
+        let parsed: OllamaHistory = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, history);
+    }
+
+    #[test]
+    fn test_iterates_like_a_collection() {
+        let mut history: OllamaHistory = vec![message("one")].into_iter().collect();
+        history.freeze();
+        history.extend([message("two"), message("three")]);
+
+        let mut contents = Vec::new();
+        for message in &history {
+            contents.push(message["content"].as_str().unwrap());
+        }
+        assert_eq!(contents, ["one", "two", "three"]);
+        assert_eq!(history.clone().into_iter().count(), 3);
+    }
+}
@@ -1,28 +1,54 @@
 use crate::xml_util::XmlUtil;
+use crate::{AudioClip, OllamaRole, OllamaToolCalls};
 use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
+use serde_json::{Map as JsonMap, Value as JsonValue};
 
 // ===
 // STRUCT: OllamaMessage
 // ===
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct OllamaMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     role: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     content: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<JsonValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    images: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio: Option<Vec<String>>,
+
+    /// Fields not modeled by this type, kept so they survive a round trip.
+    #[serde(flatten)]
+    extra: JsonMap<String, JsonValue>,
 }
 
 impl OllamaMessage {
     /// Creates a new, empty `OllamaMessage`.
     ///
-    /// Both `role` and `content` fields are initialized to `None`.
+    /// All fields are initialized to `None`.
     pub fn new() -> Self {
         OllamaMessage {
             role: None,
             content: None,
+            thinking: None,
+            tool_calls: None,
+            tool_name: None,
+            images: None,
+            audio: None,
+            extra: JsonMap::new(),
         }
     }
 
@@ -44,29 +70,29 @@ impl OllamaMessage {
     ///
     /// Fields that are `None` will be skipped during serialization.
     ///
-    /// # Panics
-    ///
-    /// Panics if serialization fails, which should generally not happen for this struct.
+    /// Serialization cannot fail: every field is a string, a list of strings or a
+    /// JSON value, and every map has string keys.
     pub fn to_json(&self) -> JsonValue {
-        serde_json::to_value(&self).unwrap()
+        serde_json::to_value(self).expect("an OllamaMessage always serializes to JSON")
     }
 
     /// Returns the role of the message.
     ///
     /// Returns `None` if the role is not set.
-    pub fn role(&self) -> Option<&str> {
-        self.role.as_deref()
+    pub fn role(&self) -> Option<OllamaRole> {
+        self.role.as_deref().map(OllamaRole::from)
     }
 
     /// Sets the role of the message.
     ///
     /// # Arguments
     ///
-    /// * `role` - The role to set (e.g., "user", "assistant", "system").
+    /// * `role` - The role to set, e.g. `OllamaRole::User` or a name read at runtime
+    ///   such as "user".
     ///
     /// Returns the modified `OllamaMessage` instance.
-    pub fn set_role(&mut self, role: &str) -> &mut Self {
-        self.role = Some(role.to_string());
+    pub fn set_role(&mut self, role: impl Into<OllamaRole>) -> &mut Self {
+        self.role = Some(role.into().as_str().to_string());
         self
     }
 
@@ -88,6 +114,140 @@ impl OllamaMessage {
         self
     }
 
+    /// Returns the reasoning of a thinking model, sent apart from the content.
+    pub fn thinking(&self) -> Option<&str> {
+        self.thinking.as_deref()
+    }
+
+    /// Sets the reasoning of a thinking model.
+    ///
+    /// # Arguments
+    ///
+    /// * `thinking` - The reasoning text.
+    ///
+    /// Returns the modified `OllamaMessage` instance.
+    pub fn set_thinking(&mut self, thinking: &str) -> &mut Self {
+        self.thinking = Some(thinking.to_string());
+        self
+    }
+
+    /// Returns the tool calls requested by the model in this message.
+    ///
+    /// Returns `None` if the message contains no tool calls.
+    pub fn tool_calls(&self) -> Option<OllamaToolCalls> {
+        self.tool_calls.as_ref().map(OllamaToolCalls::from)
+    }
+
+    /// Sets the tool calls of the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_calls` - The tool calls made by the assistant.
+    ///
+    /// Returns the modified `OllamaMessage` instance.
+    pub fn set_tool_calls(&mut self, tool_calls: &OllamaToolCalls) -> &mut Self {
+        self.tool_calls = Some(tool_calls.as_json().clone());
+        self
+    }
+
+    /// Returns the name of the tool whose result this message carries.
+    ///
+    /// Returns `None` if the tool name is not set.
+    pub fn tool_name(&self) -> Option<&str> {
+        self.tool_name.as_deref()
+    }
+
+    /// Sets the name of the tool whose result this message carries.
+    ///
+    /// Used on messages with the "tool" role so the model can match each result
+    /// to the call that produced it.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The name of the called tool.
+    ///
+    /// Returns the modified `OllamaMessage` instance.
+    pub fn set_tool_name(&mut self, tool_name: &str) -> &mut Self {
+        self.tool_name = Some(tool_name.to_string());
+        self
+    }
+
+    /// Returns the images attached to the message.
+    ///
+    /// Returns `None` if the message has no images.
+    pub fn images(&self) -> Option<&[String]> {
+        self.images.as_deref()
+    }
+
+    /// Attaches an image to the message, for use with multimodal models.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The base64-encoded image data.
+    ///
+    /// Returns the modified `OllamaMessage` instance.
+    pub fn add_image(&mut self, image: &str) -> &mut Self {
+        self.images
+            .get_or_insert_with(Vec::new)
+            .push(image.to_string());
+        self
+    }
+
+    /// Replaces the images attached to the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `images` - The base64-encoded images.
+    ///
+    /// Returns the modified `OllamaMessage` instance.
+    pub fn set_images(&mut self, images: &[String]) -> &mut Self {
+        self.images = Some(images.to_vec());
+        self
+    }
+
+    /// Returns the base64-encoded audio clips attached to the message.
+    ///
+    /// Returns `None` if the message has no audio.
+    pub fn audio(&self) -> Option<&[String]> {
+        self.audio.as_deref()
+    }
+
+    /// Attaches an audio clip to the message, for use with models that accept audio input.
+    ///
+    /// The clip is sent base64-encoded in the message's `audio` field and passed
+    /// through as-is; servers or models without audio support ignore or reject it.
+    ///
+    /// # Arguments
+    ///
+    /// * `clip` - The audio to attach, e.g. loaded with `AudioClip::from_file`.
+    ///
+    /// Returns the modified `OllamaMessage` instance.
+    pub fn add_audio(&mut self, clip: &AudioClip) -> &mut Self {
+        self.audio
+            .get_or_insert_with(Vec::new)
+            .push(clip.to_base64());
+        self
+    }
+
+    /// Returns the fields of the message that are not modeled by this type.
+    ///
+    /// Unknown fields sent by newer servers are kept here when parsing and
+    /// written back when serializing, so no data is lost in a round trip.
+    pub fn extra(&self) -> &JsonMap<String, JsonValue> {
+        &self.extra
+    }
+
+    /// Sets a field that is not modeled by this type, e.g. one added by a newer server.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The field name.
+    /// * `value` - The field value.
+    pub fn set_extra(&mut self, key: &str, value: JsonValue) -> &mut Self {
+        self.extra.insert(key.to_string(), value);
+        self
+    }
+
     /// Creates a clone of the OllamaMessage with <think></think> tags and their content removed.
     ///
     /// Uses XmlUtil::remove_tag() to remove the <think></think> tags from the content field.
@@ -104,13 +264,66 @@ impl OllamaMessage {
         let cleaned_content = XmlUtil::remove_tag(content_str, "think")?;
 
         // Create a clone with the cleaned content
-        Some(OllamaMessage {
-            role: self.role.clone(),
-            content: Some(cleaned_content),
-        })
+        let mut cleaned = self.clone();
+        cleaned.content = Some(cleaned_content);
+        Some(cleaned)
     }
 }
 
+// ===
+// TRAIT: Default for OllamaMessage
+// ===
+
+impl Default for OllamaMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ===
+// MACRO: messages!
+// ===
+
+/// Builds a `Vec<OllamaMessage>` from role and content pairs.
+///
+/// Each role is one of `system`, `user`, `assistant`, `tool` or `control`, so a
+/// misspelled role fails to compile. The content is anything that derefs to `str`.
+/// The vector converts into an `OllamaHistory`, e.g. for `OllamaRequest::set_messages`.
+///
+/// ```
+/// use ollie_rs::{OllamaRequest, OllamaRole, messages};
+///
+/// let examples = messages![
+///     system "Answer with one word.",
+///     user "Capital of France?",
+///     assistant "Paris",
+/// ];
+/// assert_eq!(examples[2].role(), Some(OllamaRole::Assistant));
+///
+/// let mut request = OllamaRequest::new();
+/// request.set_messages(examples);
+/// ```
+#[macro_export]
+macro_rules! messages {
+    (@role system) => { $crate::OllamaRole::System };
+    (@role user) => { $crate::OllamaRole::User };
+    (@role assistant) => { $crate::OllamaRole::Assistant };
+    (@role tool) => { $crate::OllamaRole::Tool };
+    (@role control) => { $crate::OllamaRole::Control };
+    ($($role:ident $content:expr),* $(,)?) => {
+        {
+            let messages: ::std::vec::Vec<$crate::OllamaMessage> = ::std::vec![$({
+                let mut message = $crate::OllamaMessage::new();
+                message
+                    .set_role($crate::messages!(@role $role))
+                    .set_content(::core::convert::AsRef::<str>::as_ref(&$content));
+                message
+            }),*];
+            messages
+        }
+    };
+}
+
 // ===
 // TESTS: OllamaMessage
 // ===
@@ -124,7 +337,7 @@ mod tests {
     fn test_new() {
         let msg = OllamaMessage::new();
         // Check that getters return None for None fields
-        assert_eq!(msg.role(), None); // Fixed: Compare with None
+        assert_eq!(msg.role(), None);
         // Check that content() returns None when not set
         assert_eq!(msg.content(), None);
         // Check internal state is None
@@ -135,8 +348,10 @@ mod tests {
     #[test]
     fn test_set_role() {
         let mut msg = OllamaMessage::new();
-        msg.set_role("user");
-        assert_eq!(msg.role(), Some("user")); // Fixed: Compare with Some("user")
+        msg.set_role(OllamaRole::User);
+        assert_eq!(msg.role(), Some(OllamaRole::User));
+        msg.set_role("assistant");
+        assert_eq!(msg.role(), Some(OllamaRole::Assistant));
     }
 
     #[test]
@@ -155,7 +370,7 @@ mod tests {
         let msg_result = OllamaMessage::from_json(json_data);
         assert!(msg_result.is_ok());
         let msg = msg_result.unwrap();
-        assert_eq!(msg.role(), Some("assistant")); // Fixed: Compare with Some("assistant")
+        assert_eq!(msg.role(), Some(OllamaRole::Assistant));
         assert_eq!(msg.content(), Some("Hi there!"));
     }
 
@@ -179,7 +394,7 @@ mod tests {
         let msg_result = OllamaMessage::from_json(json_data);
         assert!(msg_result.is_ok());
         let msg = msg_result.unwrap();
-        assert_eq!(msg.role(), Some("user")); // Fixed: Compare with Some("user")
+        assert_eq!(msg.role(), Some(OllamaRole::User));
         // Check getter returns None for missing field
         assert_eq!(msg.content(), None);
         // Check internal state is None
@@ -189,7 +404,7 @@ mod tests {
     #[test]
     fn test_to_json() {
         let mut msg = OllamaMessage::new();
-        msg.set_role("system")
+        msg.set_role(OllamaRole::System)
             .set_content("You are a helpful assistant.");
         let json_val = msg.to_json();
         let expected_json = json!({
@@ -210,7 +425,7 @@ mod tests {
     #[test]
     fn test_to_json_partial() {
         let mut msg = OllamaMessage::new();
-        msg.set_role("user");
+        msg.set_role(OllamaRole::User);
         let json_val = msg.to_json();
         let expected_json = json!({ "role": "user" });
         assert_eq!(json_val, expected_json);
@@ -222,17 +437,107 @@ mod tests {
         assert_eq!(json_val, expected_json);
     }
 
+    #[test]
+    fn test_tool_calls() {
+        let json_data = json!({
+            "role": "assistant",
+            "content": "",
+            "tool_calls": [
+                { "function": { "name": "get_weather", "arguments": { "city": "Paris" } } }
+            ]
+        });
+        let msg = OllamaMessage::from_json(json_data.clone()).unwrap();
+        let tool_calls = msg.tool_calls().unwrap();
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls.tool_call(0).unwrap().name(), Some("get_weather"));
+        assert_eq!(msg.to_json(), json_data);
+
+        let mut copy = OllamaMessage::new();
+        copy.set_tool_calls(&tool_calls);
+        assert_eq!(copy.tool_calls().unwrap().len(), 1);
+        assert!(OllamaMessage::new().tool_calls().is_none());
+    }
+
+    #[test]
+    fn test_tool_name_and_images() {
+        let mut result = OllamaMessage::new();
+        result
+            .set_role(OllamaRole::Tool)
+            .set_tool_name("get_weather")
+            .set_content("{\"temperature\": 21}");
+        let json_data = json!({
+            "role": "tool",
+            "content": "{\"temperature\": 21}",
+            "tool_name": "get_weather"
+        });
+        assert_eq!(result.to_json(), json_data);
+        assert_eq!(
+            OllamaMessage::from_json(json_data).unwrap().tool_name(),
+            Some("get_weather")
+        );
+
+        let mut msg = OllamaMessage::new();
+        msg.set_role(OllamaRole::User)
+            .set_content("What is in this picture?")
+            .add_image("aGVsbG8=");
+        let json_data = json!({
+            "role": "user",
+            "content": "What is in this picture?",
+            "images": ["aGVsbG8="]
+        });
+        assert_eq!(msg.to_json(), json_data);
+        let msg = OllamaMessage::from_json(json_data).unwrap();
+        assert_eq!(msg.images(), Some(&["aGVsbG8=".to_string()][..]));
+        assert!(OllamaMessage::new().images().is_none());
+    }
+
+    #[test]
+    fn test_audio() {
+        let mut msg = OllamaMessage::new();
+        msg.set_role(OllamaRole::User)
+            .set_content("Summarize this voice note.")
+            .add_audio(&AudioClip::new("audio/wav", b"hello".to_vec()));
+        let json_data = json!({
+            "role": "user",
+            "content": "Summarize this voice note.",
+            "audio": ["aGVsbG8="]
+        });
+        assert_eq!(msg.to_json(), json_data);
+        let msg = OllamaMessage::from_json(json_data).unwrap();
+        assert_eq!(msg.audio(), Some(&["aGVsbG8=".to_string()][..]));
+    }
+
+    #[test]
+    fn test_messages_macro() {
+        let answer = String::from("Paris");
+        let history: crate::OllamaHistory = crate::messages![
+            system "Answer with one word.",
+            user "Capital of France?",
+            assistant answer,
+        ]
+        .into();
+        assert_eq!(
+            history.to_vec(),
+            vec![
+                json!({ "role": "system", "content": "Answer with one word." }),
+                json!({ "role": "user", "content": "Capital of France?" }),
+                json!({ "role": "assistant", "content": "Paris" }),
+            ]
+        );
+        assert!(crate::messages![].is_empty());
+    }
+
     #[test]
     fn test_remove_thinking_with_think_tags() {
         let mut msg = OllamaMessage::new();
-        msg.set_role("assistant").set_content(
+        msg.set_role(OllamaRole::Assistant).set_content(
             "Here's my response. <think>Let me think about this...</think> The answer is 42.",
         );
 
         let result = msg.remove_thinking();
         assert!(result.is_some());
         let cleaned_msg = result.unwrap();
-        assert_eq!(cleaned_msg.role(), Some("assistant"));
+        assert_eq!(cleaned_msg.role(), Some(OllamaRole::Assistant));
         assert_eq!(
             cleaned_msg.content(),
             Some("Here's my response.  The answer is 42.")
@@ -242,7 +547,7 @@ mod tests {
     #[test]
     fn test_remove_thinking_no_think_tags() {
         let mut msg = OllamaMessage::new();
-        msg.set_role("user")
+        msg.set_role(OllamaRole::User)
             .set_content("Just a regular message without thinking tags.");
 
         let result = msg.remove_thinking();
@@ -259,7 +564,7 @@ mod tests {
     #[test]
     fn test_remove_thinking_multiple_tags() {
         let mut msg = OllamaMessage::new();
-        msg.set_role("assistant").set_content(
+        msg.set_role(OllamaRole::Assistant).set_content(
             "Start <think>first thought</think> middle <think>second thought</think> end.",
         );
 
@@ -272,7 +577,7 @@ mod tests {
     #[test]
     fn test_remove_thinking_with_attributes() {
         let mut msg = OllamaMessage::new();
-        msg.set_role("assistant")
+        msg.set_role(OllamaRole::Assistant)
             .set_content("Response <think type=\"analysis\">detailed thinking</think> continues.");
 
         let result = msg.remove_thinking();
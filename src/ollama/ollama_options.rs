@@ -1,8 +1,18 @@
-use serde::{Deserialize, Serialize};
+use serde::ser::Error as _;
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 
-#[derive(Serialize, Deserialize)]
+/// The seed used by `OllamaOptions::deterministic`.
+pub const DETERMINISTIC_SEED: i32 = 42;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grammar: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logprobs: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     num_ctx: Option<u32>,
 
@@ -13,7 +23,28 @@ pub struct OllamaOptions {
     num_predict: Option<i32>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_finite"
+    )]
     temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_logprobs: Option<u32>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_finite"
+    )]
+    top_p: Option<f32>,
 }
 
 impl OllamaOptions {
@@ -32,9 +63,41 @@ impl OllamaOptions {
             num_gpu: None,
             num_predict: None,
             num_ctx: None,
+            seed: None,
+            stop: None,
+            logprobs: None,
+            top_logprobs: None,
+            grammar: None,
+            top_k: None,
+            top_p: None,
         }
     }
 
+    /// Creates options for reproducible output: zero temperature, greedy sampling
+    /// and the fixed seed `DETERMINISTIC_SEED`.
+    ///
+    /// The same model, prompt and options then produce the same output on the same
+    /// server. Override the seed with `set_seed` to get a different, equally
+    /// reproducible run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::{DETERMINISTIC_SEED, OllamaOptions};
+    ///
+    /// let options = OllamaOptions::deterministic();
+    /// assert_eq!(options.temperature(), Some(0.0));
+    /// assert_eq!(options.seed(), Some(DETERMINISTIC_SEED));
+    /// ```
+    pub fn deterministic() -> Self {
+        let mut options = Self::new();
+        options
+            .set_temperature(0.0)
+            .set_top_k(1)
+            .set_seed(DETERMINISTIC_SEED);
+        options
+    }
+
     /// Deserializes an `OllamaOptions` from a `serde_json::Value`.
     ///
     /// # Arguments
@@ -74,7 +137,10 @@ impl OllamaOptions {
     ///
     /// # Returns
     ///
-    /// A `serde_json::Value` representing the options.
+    /// A `Result` containing either:
+    /// - `Ok(JsonValue)` - The options as a JSON object
+    /// - `Err(serde_json::Error)` - If the temperature or top-p is NaN or infinite,
+    ///   which JSON cannot represent
     ///
     /// # Examples
     ///
@@ -85,15 +151,123 @@ impl OllamaOptions {
     /// let mut options = OllamaOptions::new();
     /// options.set_num_ctx(2048).set_temperature(0.5);
     ///
-    /// let json_val = options.to_json();
+    /// let json_val = options.to_json().unwrap();
     ///
     /// assert_eq!(json_val, json!({
     ///     "num_ctx": 2048,
     ///     "temperature": 0.5
     /// }));
     /// ```
-    pub fn to_json(&self) -> JsonValue {
-        serde_json::to_value(&self).unwrap()
+    pub fn to_json(&self) -> Result<JsonValue, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    /// Returns the grammar constraining generation, or `None` if not set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&str>` containing the GBNF grammar if set, otherwise `None`.
+    pub fn grammar(&self) -> Option<&str> {
+        self.grammar.as_deref()
+    }
+
+    /// Sets a GBNF grammar that the generated output must match.
+    ///
+    /// Only some Ollama builds support grammars. Use `OllamaGrammar` to build grammars
+    /// for common shapes and to check a grammar before sending it.
+    ///
+    /// # Arguments
+    ///
+    /// * `grammar` - The GBNF grammar to constrain generation with.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated value for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::{OllamaGrammar, OllamaOptions};
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_grammar(&OllamaGrammar::choice(&["yes", "no"]));
+    /// assert!(options.grammar().is_some());
+    /// ```
+    pub fn set_grammar(&mut self, grammar: &str) -> &mut Self {
+        self.grammar = Some(grammar.to_string());
+        self
+    }
+
+    /// Returns whether log probabilities are requested, or `None` if not set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<bool>` containing the logprobs setting if set, otherwise `None`.
+    pub fn logprobs(&self) -> Option<bool> {
+        self.logprobs
+    }
+
+    /// Sets whether the server should return the log probability of each generated token.
+    ///
+    /// The server expects this setting at the top level of the request rather than in
+    /// the options, so `OllamaRequest::set_options` moves it there.
+    ///
+    /// # Arguments
+    ///
+    /// * `logprobs` - Whether to return per-token log probabilities.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated value for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaOptions;
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_logprobs(true);
+    /// assert_eq!(options.logprobs(), Some(true));
+    /// ```
+    pub fn set_logprobs(&mut self, logprobs: bool) -> &mut Self {
+        self.logprobs = Some(logprobs);
+        self
+    }
+
+    /// Returns the number of alternative tokens to return per position, or `None` if not set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<u32>` containing the number of top log probabilities if set, otherwise `None`.
+    pub fn top_logprobs(&self) -> Option<u32> {
+        self.top_logprobs
+    }
+
+    /// Sets the number of most likely alternative tokens returned for each generated token.
+    ///
+    /// Only takes effect when `logprobs` is enabled. Like `logprobs`, this setting is
+    /// moved to the top level of the request by `OllamaRequest::set_options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `top_logprobs` - The number of alternatives to return per token.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated value for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaOptions;
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_logprobs(true).set_top_logprobs(5);
+    /// assert_eq!(options.top_logprobs(), Some(5));
+    /// ```
+    pub fn set_top_logprobs(&mut self, top_logprobs: u32) -> &mut Self {
+        self.top_logprobs = Some(top_logprobs);
+        self
     }
 
     /// Returns the number of context tokens, or `None` if not set.
@@ -195,6 +369,43 @@ impl OllamaOptions {
         self
     }
 
+    /// Returns the stop sequences, or `None` if not set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&[String]>` containing the stop sequences if set, otherwise `None`.
+    pub fn stop(&self) -> Option<&[String]> {
+        self.stop.as_deref()
+    }
+
+    /// Sets the stop sequences.
+    ///
+    /// Generation ends when the model produces any of these sequences. The stop
+    /// sequences are also enforced on streamed output by the client, for models
+    /// that ignore them on the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `stop` - The sequences that end generation.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated value for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaOptions;
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_stop(&["\nUser:"]);
+    /// assert_eq!(options.stop(), Some(&["\nUser:".to_string()][..]));
+    /// ```
+    pub fn set_stop(&mut self, stop: &[&str]) -> &mut Self {
+        self.stop = Some(stop.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
     /// Returns the temperature value, or `None` if not set.
     ///
     /// # Returns
@@ -228,6 +439,197 @@ impl OllamaOptions {
         self.temperature = Some(temperature);
         self
     }
+
+    /// Returns the random number seed, or `None` if not set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<i32>` containing the seed if set, otherwise `None`.
+    pub fn seed(&self) -> Option<i32> {
+        self.seed
+    }
+
+    /// Sets the random number seed used for sampling.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed; the same seed and prompt produce the same output.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated value for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaOptions;
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_seed(7);
+    /// assert_eq!(options.seed(), Some(7));
+    /// ```
+    pub fn set_seed(&mut self, seed: i32) -> &mut Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the top-k sampling value, or `None` if not set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<u32>` containing the top-k value if set, otherwise `None`.
+    pub fn top_k(&self) -> Option<u32> {
+        self.top_k
+    }
+
+    /// Sets the top-k sampling value.
+    ///
+    /// # Arguments
+    ///
+    /// * `top_k` - The number of most likely tokens sampled from at each step.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated value for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaOptions;
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_top_k(40);
+    /// assert_eq!(options.top_k(), Some(40));
+    /// ```
+    pub fn set_top_k(&mut self, top_k: u32) -> &mut Self {
+        self.top_k = Some(top_k);
+        self
+    }
+
+    /// Returns the top-p (nucleus) sampling value, or `None` if not set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<f32>` containing the top-p value if set, otherwise `None`.
+    pub fn top_p(&self) -> Option<f32> {
+        self.top_p
+    }
+
+    /// Sets the top-p (nucleus) sampling value.
+    ///
+    /// # Arguments
+    ///
+    /// * `top_p` - The cumulative probability of the tokens sampled from at each step.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated value for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaOptions;
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_top_p(0.9);
+    /// assert_eq!(options.top_p(), Some(0.9));
+    /// ```
+    pub fn set_top_p(&mut self, top_p: f32) -> &mut Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Overrides these options with every option that is set in `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The options to apply on top of these.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated values for method chaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::OllamaOptions;
+    ///
+    /// let mut options = OllamaOptions::new();
+    /// options.set_num_ctx(8192).set_temperature(0.7);
+    ///
+    /// let mut preset = OllamaOptions::new();
+    /// preset.set_temperature(0.1);
+    ///
+    /// options.merge(&preset);
+    /// assert_eq!(options.num_ctx(), Some(8192));
+    /// assert_eq!(options.temperature(), Some(0.1));
+    /// ```
+    pub fn merge(&mut self, other: &OllamaOptions) -> &mut Self {
+        let other = other.clone();
+        self.grammar = other.grammar.or(self.grammar.take());
+        self.logprobs = other.logprobs.or(self.logprobs);
+        self.num_ctx = other.num_ctx.or(self.num_ctx);
+        self.num_gpu = other.num_gpu.or(self.num_gpu);
+        self.num_predict = other.num_predict.or(self.num_predict);
+        self.seed = other.seed.or(self.seed);
+        self.stop = other.stop.or(self.stop.take());
+        self.temperature = other.temperature.or(self.temperature);
+        self.top_k = other.top_k.or(self.top_k);
+        self.top_logprobs = other.top_logprobs.or(self.top_logprobs);
+        self.top_p = other.top_p.or(self.top_p);
+        self
+    }
+
+    /// Clears every option that is set in `other`, e.g. to take back options
+    /// applied with `merge`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The options whose keys to clear.
+    ///
+    /// # Returns
+    ///
+    /// Self with the updated values for method chaining.
+    pub fn unset(&mut self, other: &OllamaOptions) -> &mut Self {
+        fn clear<T, U>(value: &mut Option<T>, key: &Option<U>) {
+            if key.is_some() {
+                *value = None;
+            }
+        }
+        clear(&mut self.grammar, &other.grammar);
+        clear(&mut self.logprobs, &other.logprobs);
+        clear(&mut self.num_ctx, &other.num_ctx);
+        clear(&mut self.num_gpu, &other.num_gpu);
+        clear(&mut self.num_predict, &other.num_predict);
+        clear(&mut self.seed, &other.seed);
+        clear(&mut self.stop, &other.stop);
+        clear(&mut self.temperature, &other.temperature);
+        clear(&mut self.top_k, &other.top_k);
+        clear(&mut self.top_logprobs, &other.top_logprobs);
+        clear(&mut self.top_p, &other.top_p);
+        self
+    }
+}
+
+/// Serializes a sampling parameter, failing for NaN and infinities instead of
+/// writing them as `null`.
+fn serialize_finite<S: Serializer>(value: &Option<f32>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) if !value.is_finite() => Err(S::Error::custom(format!(
+            "option values must be finite numbers, not {value}"
+        ))),
+        _ => value.serialize(serializer),
+    }
+}
+
+// ===
+// TRAIT: Default for OllamaOptions
+// ===
+
+impl Default for OllamaOptions {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // ===
@@ -246,7 +648,11 @@ mod tests {
         assert!(options.num_ctx.is_none());
         assert!(options.num_gpu.is_none());
         assert!(options.num_predict.is_none());
+        assert!(options.stop.is_none());
         assert!(options.temperature.is_none());
+        assert!(options.logprobs.is_none());
+        assert!(options.top_logprobs.is_none());
+        assert!(options.grammar.is_none());
     }
 
     #[test]
@@ -256,14 +662,30 @@ mod tests {
             .set_num_ctx(2048)
             .set_num_gpu(1)
             .set_num_predict(100)
+            .set_stop(&["###"])
             .set_temperature(0.7);
 
         assert_eq!(options.num_ctx(), Some(2048));
         assert_eq!(options.num_gpu(), Some(1));
         assert_eq!(options.num_predict(), Some(100));
+        assert_eq!(options.stop(), Some(&["###".to_string()][..]));
         assert_eq!(options.temperature(), Some(0.7));
     }
 
+    #[test]
+    fn test_to_json_non_finite() {
+        let mut options = OllamaOptions::new();
+        options.set_temperature(f32::NAN);
+        let error = options.to_json().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "option values must be finite numbers, not NaN"
+        );
+
+        options.set_temperature(0.5).set_top_p(f32::INFINITY);
+        assert!(options.to_json().is_err());
+    }
+
     #[test]
     fn test_from_json_valid() {
         let json_data = json!({
@@ -314,7 +736,7 @@ mod tests {
         let mut options = OllamaOptions::new();
         options.set_num_ctx(2048).set_temperature(0.7);
 
-        let json_val = options.to_json();
+        let json_val = options.to_json().unwrap();
 
         // Extract values to compare individually
         let json_num_ctx = json_val["num_ctx"].as_u64().unwrap() as u32;
@@ -329,10 +751,32 @@ mod tests {
         assert!(json_val.get("num_predict").is_none());
     }
 
+    #[test]
+    fn test_deterministic() {
+        let mut options = OllamaOptions::new();
+        options.set_num_ctx(4096);
+        options.merge(&OllamaOptions::deterministic());
+
+        assert_eq!(
+            options.to_json().unwrap(),
+            json!({ "num_ctx": 4096, "seed": 42, "temperature": 0.0, "top_k": 1 })
+        );
+    }
+
+    #[test]
+    fn test_unset() {
+        let mut options = OllamaOptions::new();
+        options.set_num_ctx(4096);
+        options.merge(&OllamaOptions::deterministic());
+        options.unset(&OllamaOptions::deterministic());
+
+        assert_eq!(options.to_json().unwrap(), json!({ "num_ctx": 4096 }));
+    }
+
     #[test]
     fn test_to_json_empty() {
         let options = OllamaOptions::new();
-        let json_val = options.to_json();
+        let json_val = options.to_json().unwrap();
         // Because of skip_serializing_if, empty fields should not be present
         let expected_json = json!({});
         assert_eq!(json_val, expected_json);
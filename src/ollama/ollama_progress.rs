@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+// ===
+// STRUCT: OllamaProgress
+// ===
+
+/// A progress update streamed by the model management endpoints, e.g. while a
+/// model is created or pushed.
+///
+/// Layer uploads report a `digest` with `total` and `completed` byte counts;
+/// other steps only report a `status`. The last update of a successful
+/// operation has the status "success".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OllamaProgress {
+    /// What the server is doing, e.g. "pushing manifest".
+    pub status: String,
+    /// The layer being transferred, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// The size of the layer in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    /// The number of bytes of the layer transferred so far.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<u64>,
+}
+
+impl OllamaProgress {
+    /// Returns the fraction of the current layer transferred, from 0.0 to 1.0.
+    ///
+    /// Returns `None` if the update carries no byte counts.
+    pub fn fraction(&self) -> Option<f64> {
+        let total = self.total.filter(|total| *total > 0)?;
+        Some(self.completed.unwrap_or(0) as f64 / total as f64)
+    }
+
+    /// Returns true if this update reports that the operation succeeded.
+    pub fn is_success(&self) -> bool {
+        self.status == "success"
+    }
+}
+
+// ===
+// TESTS: OllamaProgress
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fraction() {
+        let progress: OllamaProgress = serde_json::from_value(json!({
+            "status": "pushing sha256:abc",
+            "digest": "sha256:abc",
+            "total": 200,
+            "completed": 50
+        }))
+        .unwrap();
+        assert_eq!(progress.fraction(), Some(0.25));
+        assert!(!progress.is_success());
+
+        let progress: OllamaProgress =
+            serde_json::from_value(json!({ "status": "success" })).unwrap();
+        assert_eq!(progress.fraction(), None);
+        assert!(progress.is_success());
+    }
+}
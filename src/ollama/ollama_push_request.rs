@@ -0,0 +1,143 @@
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+// ===
+// ENUM: OllamaRegistryAuth
+// ===
+
+/// Credentials sent with a push request.
+///
+/// The Ollama server signs pushes to ollama.com with its own key, so no credentials
+/// are needed there. They are for servers behind an authenticating proxy, or set up
+/// to forward the `Authorization` header to a private registry.
+#[derive(Clone, PartialEq)]
+pub enum OllamaRegistryAuth {
+    /// HTTP basic authentication with a user name and password.
+    Basic { username: String, password: String },
+    /// A bearer token, e.g. a CI access token.
+    Bearer(String),
+}
+
+// ===
+// TRAIT: OllamaRegistryAuth (fmt::Debug)
+// ===
+
+/// Keeps the secrets out of logs.
+impl fmt::Debug for OllamaRegistryAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            Self::Bearer(_) => f.debug_tuple("Bearer").field(&"<redacted>").finish(),
+        }
+    }
+}
+
+// ===
+// STRUCT: OllamaPushRequest
+// ===
+
+/// A request to the Ollama `push` endpoint, uploading a model to a registry.
+///
+/// Send it with `Ollama::push_model`. The model name must include the registry
+/// namespace, e.g. "myuser/mario:latest" or "registry.example.com/team/mario".
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct OllamaPushRequest {
+    model: String,
+
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    insecure: bool,
+
+    #[serde(skip)]
+    auth: Option<OllamaRegistryAuth>,
+}
+
+impl OllamaPushRequest {
+    /// Creates a request to push the given model.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - The name of the model, including its registry namespace.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaPushRequest`.
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            insecure: false,
+            auth: None,
+        }
+    }
+
+    /// Returns the name of the model to push.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Returns the credentials sent with the request, if any.
+    pub fn auth(&self) -> Option<&OllamaRegistryAuth> {
+        self.auth.as_ref()
+    }
+
+    /// Serializes the request body into a `serde_json::Value`.
+    ///
+    /// The credentials are not part of the body; they are sent as a header.
+    /// Serialization cannot fail, since the body holds only strings and flags.
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).expect("an OllamaPushRequest always serializes to JSON")
+    }
+
+    /// Allows pushing to a registry over plain HTTP or with an unverified certificate.
+    ///
+    /// # Arguments
+    ///
+    /// * `insecure` - Whether insecure connections are allowed.
+    ///
+    /// Returns the modified `OllamaPushRequest` instance.
+    pub fn set_insecure(&mut self, insecure: bool) -> &mut Self {
+        self.insecure = insecure;
+        self
+    }
+
+    /// Sets the credentials sent with the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - The credentials.
+    ///
+    /// Returns the modified `OllamaPushRequest` instance.
+    pub fn set_auth(&mut self, auth: OllamaRegistryAuth) -> &mut Self {
+        self.auth = Some(auth);
+        self
+    }
+}
+
+// ===
+// TESTS: OllamaPushRequest
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_body_and_redacted_auth() {
+        let mut request = OllamaPushRequest::new("team/mario");
+        assert_eq!(request.to_json(), json!({ "model": "team/mario" }));
+
+        request
+            .set_insecure(true)
+            .set_auth(OllamaRegistryAuth::Bearer("secret".to_string()));
+        assert_eq!(
+            request.to_json(),
+            json!({ "model": "team/mario", "insecure": true })
+        );
+        assert!(!format!("{request:?}").contains("secret"));
+    }
+}
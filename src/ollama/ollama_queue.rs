@@ -0,0 +1,601 @@
+use crate::{Ollama, OllamaRequest, OllamaResponse, OllamaStreamError};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::{Notify, oneshot};
+
+// ===
+// ENUM: OllamaPriority
+// ===
+
+/// The lane a request waits in on an `OllamaQueue`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OllamaPriority {
+    /// User-facing work, e.g. a chat turn; served before any batch request.
+    #[default]
+    Interactive,
+    /// Background work, e.g. summarizing documents; yields to interactive requests.
+    Batch,
+}
+
+impl OllamaPriority {
+    /// Returns the index of the lane in `QueueState::lanes`.
+    fn lane(self) -> usize {
+        match self {
+            Self::Interactive => 0,
+            Self::Batch => 1,
+        }
+    }
+}
+
+// ===
+// STRUCT: OllamaQueueStats
+// ===
+
+/// A snapshot of the load on an `OllamaQueue`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OllamaQueueStats {
+    /// The number of requests waiting for a slot, in both lanes.
+    pub waiting: usize,
+    /// The number of batch requests waiting for a slot.
+    pub waiting_batch: usize,
+    /// The number of requests running.
+    pub running: usize,
+    /// The largest number of requests that have waited at once.
+    pub peak_waiting: usize,
+    /// The number of requests that have finished, successfully or not.
+    pub completed: u64,
+    /// The number of times a batch stream gave up its slot to an interactive request.
+    pub preempted: u64,
+}
+
+// ===
+// STRUCT: OllamaQueue
+// ===
+
+/// Limits the number of requests running at once against an Ollama server.
+///
+/// A local server shares one GPU between all requests; running many at once makes
+/// them compete for VRAM and can force models to unload. Requests beyond the limit
+/// wait in two lanes: interactive requests are served before batch requests, and
+/// each lane is first-come, first-served. The queue is cheap to clone, and clones
+/// share the same slots, so it can be handed to every task that uses the server;
+/// `with_priority` gives a clone that submits to another lane.
+///
+/// A batch chat stream is preempted when an interactive request has to wait: it
+/// keeps the chunks received so far, closes its connection, which stops generation,
+/// and gives up its slot. Once it gets a slot again, ahead of other batch requests,
+/// the request is re-sent with the partial text as a final assistant message, which
+/// the model continues, so the callback and the returned response see one
+/// uninterrupted answer. Generate streams, and futures passed to `run`, keep their
+/// slot until they finish.
+///
+/// `run` queues any future, such as an `OllamaSession::update`, so sessions can
+/// share the limit too.
+#[derive(Clone, Debug)]
+pub struct OllamaQueue {
+    ollama: Ollama,
+    priority: OllamaPriority,
+    max_concurrent: usize,
+    state: Arc<Mutex<QueueState>>,
+    interactive_waiting: Arc<Notify>,
+}
+
+impl OllamaQueue {
+    /// Creates a queue in front of a client.
+    ///
+    /// # Arguments
+    ///
+    /// * `ollama` - The client requests are sent with.
+    /// * `max_concurrent` - The number of requests allowed to run at once; 1
+    ///   serializes them. Values below 1 are treated as 1.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaQueue` submitting interactive requests.
+    pub fn new(ollama: Ollama, max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            ollama,
+            priority: OllamaPriority::Interactive,
+            max_concurrent,
+            state: Arc::new(Mutex::new(QueueState {
+                available: max_concurrent,
+                ..Default::default()
+            })),
+            interactive_waiting: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Returns a clone of the queue that submits requests with another priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The lane the clone's requests wait in.
+    ///
+    /// # Returns
+    ///
+    /// An `OllamaQueue` sharing this queue's slots.
+    pub fn with_priority(&self, priority: OllamaPriority) -> Self {
+        Self {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the lane this queue's requests wait in.
+    pub fn priority(&self) -> OllamaPriority {
+        self.priority
+    }
+
+    /// Returns the client requests are sent with.
+    pub fn ollama(&self) -> &Ollama {
+        &self.ollama
+    }
+
+    /// Returns the number of requests allowed to run at once.
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Returns the current load on the queue.
+    pub fn stats(&self) -> OllamaQueueStats {
+        let mut state = self.lock();
+        let interactive = state.waiting(OllamaPriority::Interactive);
+        let batch = state.waiting(OllamaPriority::Batch);
+        OllamaQueueStats {
+            waiting: interactive + batch,
+            waiting_batch: batch,
+            running: state.running,
+            peak_waiting: state.peak_waiting,
+            completed: state.completed,
+            preempted: state.preempted,
+        }
+    }
+
+    /// Sends a generate request once a slot is free.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The generate request.
+    /// * `callback` - A function that will be called with each response chunk as it arrives.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OllamaResponse)` - The final response if successful.
+    /// * `Err(Box<dyn Error>)` - Any error that occurred during the request or processing.
+    pub async fn generate<F>(
+        &self,
+        request: &OllamaRequest,
+        callback: F,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&OllamaResponse),
+    {
+        self.run(self.ollama.generate(request, callback)).await
+    }
+
+    /// Sends a chat request once a slot is free.
+    ///
+    /// Batch requests are preempted by interactive ones; see the type documentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The chat request.
+    /// * `callback` - A function that will be called with each response chunk as it arrives.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OllamaResponse)` - The final response if successful.
+    /// * `Err(Box<dyn Error>)` - Any error that occurred during the request or processing.
+    ///   If the stream fails part way, the error is an `OllamaStreamError` holding the
+    ///   partial response.
+    pub async fn chat<F>(
+        &self,
+        request: &OllamaRequest,
+        callback: F,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&OllamaResponse),
+    {
+        match self.priority {
+            OllamaPriority::Interactive => self.run(self.ollama.chat(request, callback)).await,
+            OllamaPriority::Batch => {
+                let result = self.preemptible_chat(request, callback).await;
+                self.lock().completed += 1;
+                result
+            }
+        }
+    }
+
+    /// Runs a future once a slot is free, holding the slot until it completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The work to run, e.g. `session.update(|_| {})`.
+    ///
+    /// # Returns
+    ///
+    /// The output of the future.
+    pub async fn run<T>(&self, task: impl Future<Output = T>) -> T {
+        let slot = self.acquire(false).await;
+        let output = task.await;
+        drop(slot);
+
+        self.lock().completed += 1;
+        output
+    }
+
+    /// Streams a chat request, giving up the slot whenever an interactive request waits.
+    async fn preemptible_chat<F>(
+        &self,
+        request: &OllamaRequest,
+        mut callback: F,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&OllamaResponse),
+    {
+        let mut request = request.clone();
+        let mut prefix = String::new();
+        let mut slot = self.acquire(false).await;
+
+        loop {
+            if !prefix.is_empty() {
+                request.add_message(json!({ "role": "assistant", "content": prefix }));
+            }
+            // A failure to reconnect after a preemption keeps the text so far.
+            let mut stream = {
+                let stream = self.ollama.chat_stream(&request).await;
+                if !prefix.is_empty() {
+                    request.pop_message();
+                }
+                match stream {
+                    Ok(stream) => stream,
+                    Err(error) => {
+                        if prefix.is_empty() {
+                            return Err(error);
+                        }
+                        let mut error = OllamaStreamError::new(None, error);
+                        error.prepend_text(&prefix);
+                        return Err(error.into());
+                    }
+                }
+            };
+
+            let mut preempted = false;
+            loop {
+                // Created before the check, so an arrival in between is not missed.
+                let arrival = self.interactive_waiting.notified();
+                if self.lock().claim_preemption() {
+                    preempted = true;
+                    break;
+                }
+
+                // Only unparsed bytes are lost if the read is abandoned.
+                tokio::select! {
+                    read = stream.read() => match read {
+                        Ok(Some(chunk)) => {
+                            callback(chunk);
+                            if chunk.done() == Some(&true) {
+                                break;
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(error) => {
+                            let mut error = OllamaStreamError::new(stream.response(), error);
+                            error.prepend_text(&prefix);
+                            return Err(error.into());
+                        }
+                    },
+                    _ = arrival => {}
+                }
+            }
+
+            let response = stream.response();
+            if let Some(response) = &response {
+                prefix.push_str(response.text().unwrap_or_default());
+            }
+            if !preempted {
+                let mut response = response.ok_or("no response received from the Ollama server")?;
+                response.set_text(&prefix);
+                return Ok(response);
+            }
+
+            // Dropping the stream closes the connection, cancelling generation.
+            drop(stream);
+            drop(slot);
+            {
+                let mut state = self.lock();
+                state.yielding -= 1;
+                state.preempted += 1;
+            }
+            slot = self.acquire(true).await;
+        }
+    }
+
+    /// Waits for a slot in this queue's lane, at its front if `resume` is set.
+    async fn acquire(&self, resume: bool) -> Slot {
+        let receiver = {
+            let mut state = self.lock();
+            if state.available > 0 {
+                state.available -= 1;
+                state.running += 1;
+                return Slot::new(&self.state);
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            let lane = &mut state.lanes[self.priority.lane()];
+            if resume {
+                lane.push_front(sender);
+            } else {
+                lane.push_back(sender);
+            }
+            let waiting =
+                state.waiting(OllamaPriority::Interactive) + state.waiting(OllamaPriority::Batch);
+            state.peak_waiting = state.peak_waiting.max(waiting);
+            receiver
+        };
+
+        if self.priority == OllamaPriority::Interactive {
+            self.interactive_waiting.notify_waiters();
+        }
+        receiver
+            .await
+            .expect("a waiter is only dropped once its receiver is gone")
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        lock(&self.state)
+    }
+}
+
+/// Locks the queue state, ignoring poisoning since every update leaves it valid.
+fn lock(state: &Mutex<QueueState>) -> MutexGuard<'_, QueueState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// ===
+// STRUCT: QueueState
+// ===
+
+/// The slots and waiting requests shared by the clones of an `OllamaQueue`.
+#[derive(Debug, Default)]
+struct QueueState {
+    available: usize,
+    lanes: [VecDeque<oneshot::Sender<Slot>>; 2],
+    running: usize,
+    peak_waiting: usize,
+    completed: u64,
+    preempted: u64,
+    yielding: usize,
+}
+
+impl QueueState {
+    /// Decides whether a batch stream should give up its slot, counting it as
+    /// yielding until it does, so one interactive request preempts one stream.
+    fn claim_preemption(&mut self) -> bool {
+        if self.waiting(OllamaPriority::Interactive) > self.yielding {
+            self.yielding += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the number of requests waiting in a lane, dropping those that gave up.
+    fn waiting(&mut self, priority: OllamaPriority) -> usize {
+        let lane = &mut self.lanes[priority.lane()];
+        lane.retain(|sender| !sender.is_closed());
+        lane.len()
+    }
+
+    /// Hands free slots to waiting requests, interactive ones first.
+    fn grant(&mut self, state: &Arc<Mutex<QueueState>>) {
+        while self.available > 0 {
+            let Some(sender) = self.lanes.iter_mut().find_map(VecDeque::pop_front) else {
+                break;
+            };
+
+            self.available -= 1;
+            self.running += 1;
+            if let Err(mut slot) = sender.send(Slot::new(state)) {
+                // The request gave up waiting; keep the slot for the next one.
+                slot.state = None;
+                self.available += 1;
+                self.running -= 1;
+            }
+        }
+    }
+}
+
+// ===
+// STRUCT: Slot
+// ===
+
+/// A running request's hold on one of the queue's slots, released when dropped.
+#[derive(Debug)]
+struct Slot {
+    state: Option<Arc<Mutex<QueueState>>>,
+}
+
+impl Slot {
+    fn new(state: &Arc<Mutex<QueueState>>) -> Self {
+        Self {
+            state: Some(state.clone()),
+        }
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let Some(state) = self.state.take() else {
+            return;
+        };
+        let mut guard = lock(&state);
+        guard.available += 1;
+        guard.running -= 1;
+        guard.grant(&state);
+    }
+}
+
+// ===
+// TESTS: OllamaQueue
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaSession;
+    use crate::mock_server::{MockServer, chat_body, stalled_chat_body};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_limits_concurrency_in_order() {
+        let queue = OllamaQueue::new(Ollama::default(), 2);
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = Vec::new();
+        for id in 0..6 {
+            let queue = queue.clone();
+            let order = order.clone();
+            let active = active.clone();
+            let peak = peak.clone();
+            tasks.push(tokio::spawn(async move {
+                queue
+                    .run(async {
+                        order.lock().unwrap().push(id);
+                        let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        active.fetch_sub(1, Ordering::SeqCst);
+                    })
+                    .await
+            }));
+            // Let each task join the queue before the next one is spawned.
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+
+        let stats = queue.stats();
+        assert_eq!(stats.running, 2);
+        assert_eq!(stats.waiting, 4);
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2, 3, 4, 5]);
+        let stats = queue.stats();
+        assert_eq!(stats.waiting, 0);
+        assert_eq!(stats.running, 0);
+        assert_eq!(stats.peak_waiting, 4);
+        assert_eq!(stats.completed, 6);
+    }
+
+    #[tokio::test]
+    async fn test_interactive_lane_goes_first() {
+        let interactive = OllamaQueue::new(Ollama::default(), 1);
+        let batch = interactive.with_priority(OllamaPriority::Batch);
+        assert_eq!(batch.priority(), OllamaPriority::Batch);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let mut tasks = Vec::new();
+        for (queue, name) in [
+            (&batch, "batch 1"),
+            (&batch, "batch 2"),
+            (&interactive, "chat"),
+        ] {
+            let queue = queue.clone();
+            let order = order.clone();
+            tasks.push(tokio::spawn(async move {
+                queue
+                    .run(async {
+                        order.lock().unwrap().push(name);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    })
+                    .await
+            }));
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+
+        assert_eq!(interactive.stats().waiting_batch, 1);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec!["batch 1", "chat", "batch 2"]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_chat_is_preempted_and_resumed() {
+        let server = MockServer::start(vec![
+            stalled_chat_body(&["The sea is "]),
+            chat_body(&["Hi!"]),
+            chat_body(&["wide and deep."]),
+        ])
+        .await;
+        let interactive = OllamaQueue::new(Ollama::new(&server.addr()), 1);
+        let batch = interactive.with_priority(OllamaPriority::Batch);
+
+        let mut request = OllamaRequest::new();
+        request.set_model("mock");
+        request.add_message(json!({ "role": "user", "content": "Describe the sea." }));
+
+        let batch_task = tokio::spawn(async move {
+            let mut chunks = Vec::new();
+            let response = batch
+                .chat(&request, |chunk| {
+                    chunks.push(chunk.text().unwrap_or_default().to_string())
+                })
+                .await
+                .unwrap();
+            (response, chunks)
+        });
+
+        // Wait for the batch stream to stall, then take its slot with a chat turn.
+        while server.requests().is_empty() {
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Hello?");
+        let answer = interactive.run(session.update(|_| {})).await.unwrap();
+        assert_eq!(answer.text(), Some("Hi!"));
+
+        let (response, chunks) = batch_task.await.unwrap();
+        assert_eq!(response.text(), Some("The sea is wide and deep."));
+        assert_eq!(chunks.concat(), "The sea is wide and deep.");
+
+        let requests = server.requests();
+        assert_eq!(requests[2]["messages"][1]["role"], "assistant");
+        assert_eq!(requests[2]["messages"][1]["content"], "The sea is ");
+        let stats = interactive.stats();
+        assert_eq!(stats.preempted, 1);
+        assert_eq!(stats.completed, 2);
+        assert_eq!(stats.running, 0);
+    }
+
+    #[tokio::test]
+    async fn test_chat_and_session_updates() {
+        let server = MockServer::start(vec![chat_body(&["Hi"]), chat_body(&["Hello"])]).await;
+        let queue = OllamaQueue::new(Ollama::new(&server.addr()), 0);
+        assert_eq!(queue.max_concurrent(), 1);
+
+        let mut request = OllamaRequest::new();
+        request.set_model("mock");
+        let response = queue.chat(&request, |_| {}).await.unwrap();
+        assert_eq!(response.text(), Some("Hi"));
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Hello?");
+        let response = queue.run(session.update(|_| {})).await.unwrap();
+        assert_eq!(response.text(), Some("Hello"));
+        assert_eq!(queue.stats().completed, 2);
+    }
+}
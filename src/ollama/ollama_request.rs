@@ -1,19 +1,99 @@
-use crate::OllamaResponse;
+use crate::{OllamaHistory, OllamaResponse, OllamaTools};
 use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::BTreeMap;
 use std::fmt;
 
+/// The message roles understood by the chat endpoint.
+const KNOWN_ROLES: &[&str] = &["system", "user", "assistant", "tool"];
+
+// ===
+// ENUM: OllamaDiagnosticLevel
+// ===
+
+/// How serious a problem found by `OllamaRequest::validate` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OllamaDiagnosticLevel {
+    /// The request may not behave as intended, e.g. an option that some models ignore.
+    Warning,
+    /// The server will reject the request or ignore part of it.
+    Error,
+}
+
+// ===
+// STRUCT: OllamaDiagnostic
+// ===
+
+/// A problem found by `OllamaRequest::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OllamaDiagnostic {
+    /// How serious the problem is.
+    pub level: OllamaDiagnosticLevel,
+    /// The path of the offending field, e.g. `messages[2].role`.
+    pub field: String,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl OllamaDiagnostic {
+    fn error(field: impl Into<String>, message: &str) -> Self {
+        Self {
+            level: OllamaDiagnosticLevel::Error,
+            field: field.into(),
+            message: message.to_string(),
+        }
+    }
+
+    fn warning(field: impl Into<String>, message: &str) -> Self {
+        Self {
+            level: OllamaDiagnosticLevel::Warning,
+            field: field.into(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Returns `true` if the diagnostic is an error rather than a warning.
+    pub fn is_error(&self) -> bool {
+        self.level == OllamaDiagnosticLevel::Error
+    }
+}
+
+impl fmt::Display for OllamaDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.level {
+            OllamaDiagnosticLevel::Warning => "warning",
+            OllamaDiagnosticLevel::Error => "error",
+        };
+        write!(f, "{level}: {}: {}", self.field, self.message)
+    }
+}
+
 // ===
 // STRUCT: OllamaRequest
 // ===
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct OllamaRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<Vec<u32>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<JsonValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    images: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logprobs: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     model: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    messages: Option<Vec<JsonValue>>,
+    messages: Option<OllamaHistory>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     options: Option<JsonValue>,
@@ -21,8 +101,25 @@ pub struct OllamaRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     prompt: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<JsonValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_logprobs: Option<u32>,
+
+    /// Application data carried to the response and the audit log, never sent.
+    #[serde(skip)]
+    metadata: BTreeMap<String, String>,
+
+    /// Fields not modeled by this type, kept so they survive a round trip.
+    #[serde(flatten)]
+    extra: JsonMap<String, JsonValue>,
 }
 
 impl OllamaRequest {
@@ -35,11 +132,21 @@ impl OllamaRequest {
     /// A new instance of `OllamaRequest`.
     pub fn new() -> Self {
         Self {
+            context: None,
+            format: None,
+            images: None,
+            keep_alive: None,
             model: None,
             messages: None,
             options: None,
             prompt: None,
+            raw: None,
             stream: None,
+            tools: None,
+            logprobs: None,
+            top_logprobs: None,
+            metadata: BTreeMap::new(),
+            extra: JsonMap::new(),
         }
     }
 
@@ -69,10 +176,12 @@ impl OllamaRequest {
     ///
     /// # Returns
     ///
-    /// A `serde_json::Value` representing the serialized `OllamaRequest`.
-    /// Panics if serialization fails (which should generally not happen for this struct).
+    /// A `serde_json::Value` representing the serialized `OllamaRequest`. Serialization
+    /// cannot fail, since every field is a string, an integer, a flag or a JSON value,
+    /// and every map has string keys. Options go through `OllamaOptions::to_json`,
+    /// which rejects the values JSON cannot hold.
     pub fn to_json(&self) -> JsonValue {
-        serde_json::to_value(&self).unwrap()
+        serde_json::to_value(self).expect("an OllamaRequest always serializes to JSON")
     }
 
     /// Returns a reference to the model name, if set.
@@ -98,26 +207,38 @@ impl OllamaRequest {
         self
     }
 
-    /// Returns a reference to the vector of messages, if set.
+    /// Returns a reference to the message history, if set.
     ///
     /// # Returns
     ///
-    /// An `Option<&Vec<JsonValue>>` containing the messages.
-    pub fn messages(&self) -> Option<&Vec<JsonValue>> {
+    /// An `Option<&OllamaHistory>` containing the messages.
+    pub fn messages(&self) -> Option<&OllamaHistory> {
         self.messages.as_ref()
     }
 
+    /// Returns a mutable reference to the message history, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&mut OllamaHistory>` containing the messages.
+    pub fn messages_mut(&mut self) -> Option<&mut OllamaHistory> {
+        self.messages.as_mut()
+    }
+
     /// Sets the messages for the request.
     ///
+    /// A `Vec` or `OllamaHistory` is moved into the request without copying the
+    /// messages; a slice is cloned.
+    ///
     /// # Arguments
     ///
-    /// * `messages` - A vector of `serde_json::Value` representing the messages.
+    /// * `messages` - The messages, as a `Vec`, slice or `OllamaHistory`.
     ///
     /// # Returns
     ///
     /// The modified `OllamaRequest` instance.
-    pub fn set_messages(&mut self, messages: &Vec<JsonValue>) -> &mut Self {
-        self.messages = Some(messages.clone());
+    pub fn set_messages(&mut self, messages: impl Into<OllamaHistory>) -> &mut Self {
+        self.messages = Some(messages.into());
         self
     }
 
@@ -135,11 +256,20 @@ impl OllamaRequest {
     pub fn add_message(&mut self, message: JsonValue) -> &mut Self {
         match &mut self.messages {
             Some(messages) => messages.push(message),
-            None => self.messages = Some(vec![message]),
+            None => self.messages = Some(OllamaHistory::from(vec![message])),
         }
         self
     }
 
+    /// Removes the last message from the request and returns it.
+    ///
+    /// # Returns
+    ///
+    /// The removed message, or `None` if there are no messages.
+    pub fn pop_message(&mut self) -> Option<JsonValue> {
+        self.messages.as_mut()?.pop()
+    }
+
     /// Returns a reference to the options JSON value, if set.
     ///
     /// # Returns
@@ -151,15 +281,73 @@ impl OllamaRequest {
 
     /// Sets the options for the request.
     ///
+    /// The `logprobs` and `top_logprobs` options are moved to the top level of
+    /// the request, where the server expects them.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - A `serde_json::Value` representing the options, moved into the request.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn set_options(&mut self, mut options: JsonValue) -> &mut Self {
+        if let Some(object) = options.as_object_mut() {
+            if let Some(logprobs) = object.remove("logprobs").and_then(|v| v.as_bool()) {
+                self.logprobs = Some(logprobs);
+            }
+            if let Some(top_logprobs) = object.remove("top_logprobs").and_then(|v| v.as_u64()) {
+                self.top_logprobs = Some(top_logprobs as u32);
+            }
+        }
+
+        self.options = Some(options);
+        self
+    }
+
+    /// Returns whether per-token log probabilities are requested, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<bool>` indicating whether log probabilities are requested.
+    pub fn logprobs(&self) -> Option<bool> {
+        self.logprobs
+    }
+
+    /// Sets whether the server should return per-token log probabilities.
+    ///
     /// # Arguments
     ///
-    /// * `options` - A `serde_json::Value` representing the options.
+    /// * `logprobs` - Whether to return log probabilities.
     ///
     /// # Returns
     ///
     /// The modified `OllamaRequest` instance.
-    pub fn set_options(&mut self, options: &JsonValue) -> &mut Self {
-        self.options = Some(options.clone());
+    pub fn set_logprobs(&mut self, logprobs: bool) -> &mut Self {
+        self.logprobs = Some(logprobs);
+        self
+    }
+
+    /// Returns the number of alternative tokens returned per position, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<u32>` containing the number of top log probabilities.
+    pub fn top_logprobs(&self) -> Option<u32> {
+        self.top_logprobs
+    }
+
+    /// Sets the number of most likely alternative tokens returned per position.
+    ///
+    /// # Arguments
+    ///
+    /// * `top_logprobs` - The number of alternatives to return per token.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn set_top_logprobs(&mut self, top_logprobs: u32) -> &mut Self {
+        self.top_logprobs = Some(top_logprobs);
         self
     }
 
@@ -186,6 +374,58 @@ impl OllamaRequest {
         self
     }
 
+    /// Returns the context tokens carried over from a previous generation, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&[u32]>` containing the context tokens.
+    pub fn context(&self) -> Option<&[u32]> {
+        self.context.as_deref()
+    }
+
+    /// Sets the context tokens returned by a previous generation.
+    ///
+    /// The generate endpoint continues from this context, which keeps a short
+    /// conversational memory without chat messages. See `CompletionSession`.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The tokens of `OllamaResponse::context`.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn set_context(&mut self, context: &[u32]) -> &mut Self {
+        self.context = Some(context.to_vec());
+        self
+    }
+
+    /// Returns whether the prompt is sent without the model's template, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<bool>` indicating whether raw mode is enabled.
+    pub fn raw(&self) -> Option<bool> {
+        self.raw
+    }
+
+    /// Sets whether the prompt is sent to the model as-is, bypassing its template.
+    ///
+    /// Raw prompts must already be formatted for the model, e.g. with `OllamaChatTemplate`.
+    /// Only the generate endpoint supports raw mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - Whether to enable raw mode.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn set_raw(&mut self, raw: bool) -> &mut Self {
+        self.raw = Some(raw);
+        self
+    }
+
     /// Adds the message content from an Ollama response JSON to the request's messages.
     ///
     /// This method looks for a "message" field within the provided `response` JSON.
@@ -204,19 +444,14 @@ impl OllamaRequest {
     /// The potentially modified `OllamaRequest` instance.
     pub fn add_response(&mut self, response: &OllamaResponse) -> &mut Self {
         if let Some(message) = response.message() {
-            // Try to remove thinking tags from the message
-            let cleaned_message = message.remove_thinking();
-            match cleaned_message {
-                Some(cleaned) => {
-                    // If thinking tags were removed, use the cleaned message
-                    let message_json = cleaned.to_json();
-                    return self.add_message(message_json);
-                }
-                None => {
-                    // If no thinking tags were found, use the original message
-                    let message_json = message.to_json();
-                    return self.add_message(message_json);
-                }
+            // Try to remove thinking tags from the message; if none were found,
+            // serialize the original message without cloning it first.
+            let message_json = match message.remove_thinking() {
+                Some(cleaned) => serde_json::to_value(cleaned),
+                None => serde_json::to_value(message),
+            };
+            if let Ok(message_json) = message_json {
+                return self.add_message(message_json);
             }
         }
 
@@ -245,6 +480,262 @@ impl OllamaRequest {
         self.stream = Some(stream);
         self
     }
+
+    /// Returns a reference to the tools JSON value, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&JsonValue>` containing the tool declarations.
+    pub fn tools(&self) -> Option<&JsonValue> {
+        self.tools.as_ref()
+    }
+
+    /// Sets the tools the model may call.
+    ///
+    /// # Arguments
+    ///
+    /// * `tools` - The `OllamaTools` collection of function declarations.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn set_tools(&mut self, tools: &OllamaTools) -> &mut Self {
+        self.tools = Some(tools.as_json().clone());
+        self
+    }
+
+    /// Returns a reference to the response format, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&JsonValue>` containing either `"json"` or a JSON schema.
+    pub fn format(&self) -> Option<&JsonValue> {
+        self.format.as_ref()
+    }
+
+    /// Sets the format the response must follow.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Either the string `"json"` for JSON mode, or a JSON schema
+    ///   object the response must conform to.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn set_format(&mut self, format: JsonValue) -> &mut Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Returns the keep-alive duration, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&str>` containing the duration, e.g. "5m".
+    pub fn keep_alive(&self) -> Option<&str> {
+        self.keep_alive.as_deref()
+    }
+
+    /// Sets how long the model stays loaded after the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep_alive` - A duration such as "10m" or "1h"; "0" unloads the model
+    ///   immediately and "-1" keeps it loaded indefinitely.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn set_keep_alive(&mut self, keep_alive: &str) -> &mut Self {
+        self.keep_alive = Some(keep_alive.to_string());
+        self
+    }
+
+    /// Returns a reference to the images of a generate request, if set.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<&Vec<String>>` containing the base64-encoded images.
+    pub fn images(&self) -> Option<&Vec<String>> {
+        self.images.as_ref()
+    }
+
+    /// Returns the fields of the request that are not modeled by this type.
+    ///
+    /// Unknown fields sent by newer servers are kept here when parsing and
+    /// written back when serializing, so no data is lost in a round trip.
+    pub fn extra(&self) -> &JsonMap<String, JsonValue> {
+        &self.extra
+    }
+
+    /// Sets a field that is not modeled by this type, e.g. one added by a newer server.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The field name.
+    /// * `value` - The field value.
+    pub fn set_extra(&mut self, key: &str, value: JsonValue) -> &mut Self {
+        self.extra.insert(key.to_string(), value);
+        self
+    }
+
+    /// Returns the application metadata attached to the request.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Attaches application metadata, e.g. a request, user or trace id.
+    ///
+    /// Metadata is not sent to the server. It is copied to the response and to
+    /// the audit log record, so model calls can be correlated with the
+    /// application requests that made them.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The metadata name, e.g. "request_id".
+    /// * `value` - The metadata value.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> &mut Self {
+        self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Removes an entry of application metadata.
+    pub fn remove_metadata(&mut self, key: &str) -> &mut Self {
+        self.metadata.remove(key);
+        self
+    }
+
+    /// Adds an image to a generate request, for use with multimodal models.
+    ///
+    /// Chat requests carry images on their messages instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The base64-encoded image data.
+    ///
+    /// # Returns
+    ///
+    /// The modified `OllamaRequest` instance.
+    pub fn add_image(&mut self, image: &str) -> &mut Self {
+        self.images
+            .get_or_insert_with(Vec::new)
+            .push(image.to_string());
+        self
+    }
+
+    /// Checks the request for field combinations the server rejects or ignores.
+    ///
+    /// The checks cover a missing model, setting both a prompt and messages,
+    /// tools on a generate request, streaming tool calls (which older servers
+    /// and many models don't support), `top_logprobs` without `logprobs`, and
+    /// messages without a role or content.
+    ///
+    /// # Returns
+    ///
+    /// A vector of diagnostics, empty if no problems were found.
+    pub fn validate(&self) -> Vec<OllamaDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if self.model.as_deref().is_none_or(str::is_empty) {
+            diagnostics.push(OllamaDiagnostic::error("model", "no model is set"));
+        }
+
+        if self.prompt.is_some() && self.messages.is_some() {
+            diagnostics.push(OllamaDiagnostic::error(
+                "prompt",
+                "prompt and messages are both set; use prompt for /api/generate \
+                 or messages for /api/chat",
+            ));
+        }
+
+        if self.tools.is_some() {
+            if self.prompt.is_some() {
+                diagnostics.push(OllamaDiagnostic::error(
+                    "tools",
+                    "tools are only supported with messages (/api/chat)",
+                ));
+            }
+            if self.stream == Some(true) {
+                diagnostics.push(OllamaDiagnostic::warning(
+                    "stream",
+                    "streaming tool calls is not supported by all servers and models",
+                ));
+            }
+        }
+
+        if self.top_logprobs.is_some() && self.logprobs != Some(true) {
+            diagnostics.push(OllamaDiagnostic::warning(
+                "top_logprobs",
+                "top_logprobs has no effect unless logprobs is enabled",
+            ));
+        }
+
+        for (index, message) in self
+            .messages
+            .iter()
+            .flat_map(OllamaHistory::iter)
+            .enumerate()
+        {
+            validate_message(index, message, &mut diagnostics);
+        }
+
+        diagnostics
+    }
+}
+
+/// Checks one chat message for a valid role and content.
+fn validate_message(index: usize, message: &JsonValue, diagnostics: &mut Vec<OllamaDiagnostic>) {
+    if !message.is_object() {
+        diagnostics.push(OllamaDiagnostic::error(
+            format!("messages[{index}]"),
+            "message is not an object",
+        ));
+        return;
+    }
+
+    match message.get("role").map(JsonValue::as_str) {
+        None => diagnostics.push(OllamaDiagnostic::error(
+            format!("messages[{index}].role"),
+            "message has no role",
+        )),
+        Some(None) => diagnostics.push(OllamaDiagnostic::error(
+            format!("messages[{index}].role"),
+            "role is not a string",
+        )),
+        Some(Some(role)) if !KNOWN_ROLES.contains(&role) => {
+            diagnostics.push(OllamaDiagnostic::warning(
+                format!("messages[{index}].role"),
+                "role is not one of system, user, assistant or tool",
+            ))
+        }
+        _ => {}
+    }
+
+    let has_tool_calls = message
+        .get("tool_calls")
+        .is_some_and(|calls| !calls.is_null());
+    match message.get("content") {
+        None if !has_tool_calls => diagnostics.push(OllamaDiagnostic::error(
+            format!("messages[{index}].content"),
+            "message has no content",
+        )),
+        Some(content) if !content.is_string() => diagnostics.push(OllamaDiagnostic::error(
+            format!("messages[{index}].content"),
+            "content is not a string",
+        )),
+        _ => {}
+    }
+}
+
+// ===
+// TRAIT: Default for OllamaRequest
+// ===
+
+impl Default for OllamaRequest {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // ===
@@ -253,8 +744,7 @@ impl OllamaRequest {
 
 impl fmt::Display for OllamaRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let request_json = serde_json::to_value(&self).unwrap();
-        let pretty_string = serde_json::to_string_pretty(&request_json).unwrap();
+        let pretty_string = serde_json::to_string_pretty(self).map_err(|_| fmt::Error)?;
         write!(f, "{pretty_string}")
     }
 }
@@ -284,16 +774,57 @@ mod tests {
 
         let mut req = OllamaRequest::new();
         req.set_model("llama2")
-            .set_messages(&messages)
-            .set_options(&options)
+            .set_messages(messages.as_slice())
+            .set_options(options.clone())
             .set_stream(true);
 
         assert_eq!(req.model(), Some(&"llama2".to_string()));
-        assert_eq!(req.messages(), Some(&messages));
+        assert_eq!(req.messages().unwrap(), &messages);
         assert_eq!(req.options(), Some(&options));
         assert_eq!(req.stream(), Some(true));
     }
 
+    #[test]
+    fn test_set_options_lifts_logprobs() {
+        let mut req = OllamaRequest::new();
+        req.set_options(json!({"temperature": 0.2, "logprobs": true, "top_logprobs": 3}));
+
+        assert_eq!(req.logprobs(), Some(true));
+        assert_eq!(req.top_logprobs(), Some(3));
+        assert_eq!(
+            req.to_json(),
+            json!({
+                "logprobs": true,
+                "options": {"temperature": 0.2},
+                "top_logprobs": 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_pop_message() {
+        let mut req = OllamaRequest::new();
+        assert!(req.pop_message().is_none());
+
+        req.add_message(json!({"role": "user", "content": "Hi"}));
+        assert_eq!(
+            req.pop_message(),
+            Some(json!({"role": "user", "content": "Hi"}))
+        );
+        assert!(req.messages().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_messages_moves_vec() {
+        let messages = vec![json!({"role": "user", "content": "Hello"})];
+        let ptr = messages.as_ptr();
+
+        let mut req = OllamaRequest::new();
+        req.set_messages(messages);
+        let first = req.messages().unwrap().iter().next().unwrap();
+        assert!(std::ptr::eq(first, ptr));
+    }
+
     #[test]
     fn test_add_message() {
         let msg1 = json!({"role": "user", "content": "First message"});
@@ -301,10 +832,10 @@ mod tests {
 
         let mut req = OllamaRequest::new();
         req.add_message(msg1.clone());
-        assert_eq!(req.messages(), Some(&vec![msg1.clone()]));
+        assert_eq!(req.messages().unwrap(), &vec![msg1.clone()]);
 
         let req = req.add_message(msg2.clone());
-        assert_eq!(req.messages(), Some(&vec![msg1, msg2]));
+        assert_eq!(req.messages().unwrap(), &vec![msg1, msg2]);
     }
 
     #[test]
@@ -313,8 +844,8 @@ mod tests {
         let options = json!({"seed": 123});
         let mut req = OllamaRequest::new();
         req.set_model("test-model")
-            .set_messages(&messages)
-            .set_options(&options)
+            .set_messages(messages.as_slice())
+            .set_options(options.clone())
             .set_stream(false);
 
         let expected_json = json!({
@@ -462,6 +993,93 @@ mod tests {
         assert_eq!(req4.messages().unwrap()[0], initial_message);
     }
 
+    #[test]
+    fn test_format_keep_alive_images_round_trip() {
+        let schema = json!({"type": "object", "properties": {"age": {"type": "integer"}}});
+        let mut req = OllamaRequest::new();
+        req.set_model("llava")
+            .set_prompt("Describe the image.")
+            .set_format(schema.clone())
+            .set_keep_alive("10m")
+            .add_image("aGVsbG8=")
+            .add_image("d29ybGQ=");
+
+        let expected_json = json!({
+            "format": schema,
+            "images": ["aGVsbG8=", "d29ybGQ="],
+            "keep_alive": "10m",
+            "model": "llava",
+            "prompt": "Describe the image."
+        });
+        assert_eq!(req.to_json(), expected_json);
+
+        let req = OllamaRequest::from_json(expected_json.clone()).unwrap();
+        assert_eq!(req.format(), Some(&schema));
+        assert_eq!(req.keep_alive(), Some("10m"));
+        assert_eq!(req.images().map(Vec::len), Some(2));
+        assert_eq!(req.to_json(), expected_json);
+
+        let mut req = OllamaRequest::new();
+        req.set_format(json!("json"));
+        assert_eq!(req.to_json(), json!({"format": "json"}));
+    }
+
+    #[test]
+    fn test_tools_round_trip() {
+        let tools = json!([{
+            "type": "function",
+            "function": {"name": "get_time", "description": "Gets the time", "parameters": {}}
+        }]);
+        let json_data = json!({"model": "llama3.1", "tools": tools});
+
+        let req = OllamaRequest::from_json(json_data.clone()).unwrap();
+        assert_eq!(req.tools(), Some(&tools));
+        assert_eq!(req.to_json(), json_data);
+    }
+
+    #[test]
+    fn test_validate_valid_request() {
+        let mut req = OllamaRequest::new();
+        req.set_model("llama2")
+            .add_message(json!({"role": "user", "content": "Hi"}))
+            .add_message(json!({"role": "assistant", "tool_calls": []}));
+        assert!(req.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        let mut req = OllamaRequest::new();
+        req.set_prompt("Hi")
+            .set_messages(vec![
+                json!({"content": "no role"}),
+                json!({"role": "control", "content": "thinking"}),
+                json!({"role": "user"}),
+            ])
+            .set_tools(&OllamaTools::new())
+            .set_stream(true)
+            .set_top_logprobs(2);
+
+        let diagnostics = req.validate();
+        let summary: Vec<(bool, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.is_error(), d.field.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (true, "model"),
+                (true, "prompt"),
+                (true, "tools"),
+                (false, "stream"),
+                (false, "top_logprobs"),
+                (true, "messages[0].role"),
+                (false, "messages[1].role"),
+                (true, "messages[2].content"),
+            ]
+        );
+        assert_eq!(diagnostics[0].to_string(), "error: model: no model is set");
+    }
+
     #[test]
     fn test_to_json_with_prompt() {
         let mut req = OllamaRequest::new();
@@ -490,6 +1108,43 @@ mod tests {
         assert!(req.stream().is_none());
     }
 
+    #[test]
+    fn test_unknown_fields_round_trip() {
+        let json = json!({
+            "model": "llama2",
+            "messages": [{ "role": "user", "content": "Hi" }],
+            "think": true,
+            "future_option": { "nested": [1, 2] }
+        });
+
+        let mut request: OllamaRequest = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(request.extra()["think"], json!(true));
+        assert_eq!(serde_json::to_value(&request).unwrap(), json);
+
+        request.set_extra("think", json!(false));
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["think"],
+            json!(false)
+        );
+    }
+
+    #[test]
+    fn test_add_response_keeps_unknown_message_fields() {
+        let response = OllamaResponse::from_json(json!({
+            "model": "llama2",
+            "message": { "role": "assistant", "content": "Hi", "thinking": "Greet back." },
+            "done": true
+        }))
+        .unwrap();
+
+        let mut request = OllamaRequest::new();
+        request.add_response(&response);
+        assert_eq!(
+            request.messages().unwrap()[0],
+            json!({ "role": "assistant", "content": "Hi", "thinking": "Greet back." })
+        );
+    }
+
     #[test]
     fn test_add_response_with_thinking_tags() {
         // Test that thinking tags are removed when present
@@ -0,0 +1,170 @@
+use crate::{OllamaRequest, TokenBreakdown, estimate_tokens};
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+// ===
+// STRUCT: OllamaRequestReport
+// ===
+
+/// A readable snapshot of the request a session is about to send.
+///
+/// Returned by `OllamaSession::explain_next_request` to debug a context that is
+/// too large or instructions the model seems to ignore. The `Display` output
+/// lists the model, options, messages, tools and estimated tokens per section,
+/// followed by the payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OllamaRequestReport {
+    /// The model the request is sent to.
+    pub model: Option<String>,
+    /// The options sent with the request.
+    pub options: JsonValue,
+    /// The number of messages sent.
+    pub messages: usize,
+    /// The number of messages evicted to fit the history token limit.
+    pub evicted: usize,
+    /// The context window size of the model, in tokens.
+    pub context_window: u32,
+    /// The tokens per section: each message role, then the tool declarations and
+    /// the response format, if any. Counts are estimates where the server did not
+    /// report them.
+    pub sections: Vec<(String, u32)>,
+    /// The names of the tools the model may call.
+    pub tools: Vec<String>,
+    /// The request as it is sent to the server.
+    pub payload: JsonValue,
+}
+
+impl OllamaRequestReport {
+    /// Creates the report of a request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request as it is sent to the server.
+    /// * `breakdown` - The token counts of the request's messages.
+    /// * `context_window` - The context window size of the model, in tokens.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaRequestReport` with no evicted messages.
+    pub fn new(request: &OllamaRequest, breakdown: &TokenBreakdown, context_window: u32) -> Self {
+        let mut sections: Vec<(String, u32)> = breakdown.by_role().into_iter().collect();
+        if let Some(tools) = request.tools() {
+            sections.push((
+                "tool declarations".to_string(),
+                estimate_tokens(&tools.to_string()),
+            ));
+        }
+        if let Some(format) = request.format() {
+            sections.push(("format".to_string(), estimate_tokens(&format.to_string())));
+        }
+
+        let tools = request
+            .tools()
+            .and_then(JsonValue::as_array)
+            .map(|tools| {
+                tools
+                    .iter()
+                    .filter_map(|tool| tool["function"]["name"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            model: request.model().cloned(),
+            options: request.options().cloned().unwrap_or(JsonValue::Null),
+            messages: breakdown.messages().len(),
+            evicted: 0,
+            context_window,
+            sections,
+            tools,
+            payload: request.to_json(),
+        }
+    }
+
+    /// Returns the estimated number of tokens of the whole request.
+    pub fn total_tokens(&self) -> u32 {
+        self.sections.iter().map(|(_, tokens)| tokens).sum()
+    }
+
+    /// Returns `true` if the request is estimated to fit the context window.
+    pub fn fits_context(&self) -> bool {
+        self.total_tokens() <= self.context_window
+    }
+}
+
+// ===
+// TRAIT: OllamaRequestReport (fmt::Display)
+// ===
+
+impl fmt::Display for OllamaRequestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "model: {}", self.model.as_deref().unwrap_or("(none)"))?;
+        writeln!(f, "options: {}", self.options)?;
+        write!(f, "messages: {}", self.messages)?;
+        if self.evicted > 0 {
+            write!(f, " ({} evicted to fit the history limit)", self.evicted)?;
+        }
+        writeln!(f)?;
+        if !self.tools.is_empty() {
+            writeln!(f, "tools: {}", self.tools.join(", "))?;
+        }
+
+        let total = self.total_tokens();
+        write!(
+            f,
+            "tokens: ~{total} of {} in the context window",
+            self.context_window
+        )?;
+        if !self.fits_context() {
+            write!(f, " (too large, the server will truncate the prompt)")?;
+        }
+        writeln!(f)?;
+        for (section, tokens) in &self.sections {
+            let share = f64::from(*tokens) * 100.0 / f64::from(total.max(1));
+            writeln!(f, "  {section}: {tokens} ({share:.0}%)")?;
+        }
+
+        let payload = serde_json::to_string_pretty(&self.payload).map_err(|_| fmt::Error)?;
+        write!(f, "payload:\n{payload}")
+    }
+}
+
+// ===
+// TESTS: OllamaRequestReport
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OllamaFunction, OllamaTools};
+    use serde_json::json;
+
+    #[test]
+    fn test_report() {
+        let mut tools = OllamaTools::new();
+        tools.push_function(OllamaFunction::new("get_weather", "Gets the weather."));
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .set_options(json!({ "num_ctx": 16 }))
+            .set_tools(&tools)
+            .add_message(json!({ "role": "system", "content": "Answer in French." }))
+            .add_message(json!({ "role": "user", "content": "What is the weather in Paris?" }));
+        let breakdown = TokenBreakdown::from_messages(request.messages().unwrap().iter());
+
+        let report = OllamaRequestReport::new(&request, &breakdown, 16);
+        assert_eq!(report.messages, 2);
+        assert_eq!(report.tools, ["get_weather"]);
+        assert_eq!(report.sections[0], ("system".to_string(), 9));
+        assert_eq!(report.sections[1], ("user".to_string(), 12));
+        assert_eq!(report.sections[2].0, "tool declarations");
+        assert!(!report.fits_context());
+
+        let text = report.to_string();
+        assert!(text.starts_with("model: mock\noptions: {\"num_ctx\":16}\nmessages: 2\n"));
+        assert!(text.contains("tools: get_weather\n"), "{text}");
+        assert!(text.contains("  user: 12 ("), "{text}");
+        assert!(text.contains("too large"), "{text}");
+        assert!(text.ends_with(&serde_json::to_string_pretty(&request.to_json()).unwrap()));
+    }
+}
@@ -1,10 +1,66 @@
-use crate::OllamaMessage;
+use crate::{GenerationStats, JsonExtractError, OllamaMessage, StreamMetrics, extract_json};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::BTreeMap;
 use std::fmt;
+use std::time::Duration;
 
-#[derive(Serialize, Deserialize, Clone)]
+// ===
+// STRUCT: OllamaTopLogprob
+// ===
+
+/// A candidate token and its log probability at one position of the output.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OllamaTopLogprob {
+    /// The text of the token.
+    pub token: String,
+
+    /// The natural log of the probability of the token.
+    pub logprob: f64,
+
+    /// The UTF-8 bytes of the token, which may not be valid UTF-8 on their own.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub bytes: Option<Vec<u8>>,
+}
+
+// ===
+// STRUCT: OllamaLogprob
+// ===
+
+/// The log probability of a generated token, with the most likely alternatives if requested.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OllamaLogprob {
+    /// The text of the generated token.
+    pub token: String,
+
+    /// The natural log of the probability of the generated token.
+    pub logprob: f64,
+
+    /// The UTF-8 bytes of the token, which may not be valid UTF-8 on their own.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub bytes: Option<Vec<u8>>,
+
+    /// The most likely tokens at this position, when `top_logprobs` was requested.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub top_logprobs: Vec<OllamaTopLogprob>,
+}
+
+impl OllamaLogprob {
+    /// Returns the probability of the generated token, between 0 and 1.
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+// ===
+// STRUCT: OllamaResponse
+// ===
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct OllamaResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<Vec<u32>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     created_at: Option<String>,
 
@@ -26,6 +82,9 @@ pub struct OllamaResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     load_duration: Option<u64>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logprobs: Option<Vec<OllamaLogprob>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     model: Option<String>,
 
@@ -41,8 +100,31 @@ pub struct OllamaResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     response: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     total_duration: Option<u64>,
+
+    /// The number of automatic continuation turns stitched into this response.
+    #[serde(skip)]
+    continuations: u32,
+
+    /// The client-side latency of the streamed response.
+    #[serde(skip)]
+    metrics: Option<StreamMetrics>,
+
+    /// Whether the stream was abandoned before the server finished it.
+    #[serde(skip)]
+    truncated: bool,
+
+    /// The application metadata of the request this responds to.
+    #[serde(skip)]
+    metadata: BTreeMap<String, String>,
+
+    /// Fields not modeled by this type, kept so they survive a round trip.
+    #[serde(flatten)]
+    extra: JsonMap<String, JsonValue>,
 }
 
 impl OllamaResponse {
@@ -51,35 +133,26 @@ impl OllamaResponse {
         Ok(response)
     }
 
+    /// Serializes the response into a `serde_json::Value`.
+    ///
+    /// Serialization cannot fail: every field is a string, a number, a flag or a
+    /// JSON value, and every map has string keys.
     pub fn to_json(self) -> serde_json::Value {
-        serde_json::to_value(self).unwrap()
+        serde_json::to_value(self).expect("an OllamaResponse always serializes to JSON")
     }
 
-    pub fn print_stats(&self) {
-        let model = self.model().unwrap_or("unknown model");
-        let eval_tokens = self.eval_count.unwrap_or(0);
-
-        let eval_in_milliseconds = self.eval_duration.unwrap_or(0) / 1_000_000;
-        let eval_in_seconds = eval_in_milliseconds as f64 / 1_000.0;
-
-        let token_rate = if eval_in_seconds > 0.0 {
-            eval_tokens as f64 / eval_in_seconds as f64
-        } else {
-            0.0
-        };
-
-        let stats = json!({
-            "model": model,
-            "context_used": self.tokens_used(),
-            "eval_time": eval_in_seconds,
-            "eval_tokens": eval_tokens,
-            "eval_rate": token_rate,
-        });
-
-        println!(
-            "\n\n-> stats: {}",
-            serde_json::to_string_pretty(&stats).unwrap()
-        );
+    /// Returns the token counts and timings of the response.
+    ///
+    /// Meaningful on the final response of a stream, which carries the statistics.
+    pub fn stats(&self) -> GenerationStats {
+        GenerationStats {
+            model: self.model.clone(),
+            prompt_tokens: self.prompt_eval_count,
+            completion_tokens: self.eval_count,
+            eval_duration: self.eval_duration(),
+            total_duration: self.total_duration(),
+            wall_time: self.metrics.as_ref().map(StreamMetrics::total_time),
+        }
     }
 
     /// Returns the generated text from the model response.
@@ -88,10 +161,10 @@ impl OllamaResponse {
     /// falls back to the response field. Returns `None` if neither is available.
     pub fn text(&self) -> Option<&str> {
         // Look for the text in the message content first.
-        if let Some(message) = self.message() {
-            if message.content().is_some() {
-                return message.content();
-            }
+        if let Some(message) = self.message()
+            && message.content().is_some()
+        {
+            return message.content();
         }
 
         // If not found, look for the text in the response field.
@@ -103,9 +176,31 @@ impl OllamaResponse {
         None
     }
 
+    /// Parses the generated text as JSON.
+    ///
+    /// Thinking blocks, Markdown code fences and commentary around the JSON are
+    /// ignored; see `extract_json`.
+    ///
+    /// # Returns
+    ///
+    /// The JSON value, or an error with the text that failed to parse.
+    pub fn json(&self) -> Result<JsonValue, JsonExtractError> {
+        extract_json(self.text().unwrap_or_default())
+    }
+
     pub fn tokens_used(&self) -> u32 {
         self.eval_count.unwrap_or(0) + self.prompt_eval_count.unwrap_or(0)
     }
+
+    /// Returns the mean log probability of the generated tokens.
+    ///
+    /// This is a simple confidence score for the whole response; `exp(-mean)` is its perplexity.
+    /// Returns `None` if the response carries no log probabilities.
+    pub fn mean_logprob(&self) -> Option<f64> {
+        let logprobs = self.logprobs.as_ref().filter(|l| !l.is_empty())?;
+        let sum: f64 = logprobs.iter().map(|l| l.logprob).sum();
+        Some(sum / logprobs.len() as f64)
+    }
 }
 
 // ===
@@ -113,6 +208,14 @@ impl OllamaResponse {
 // ===
 
 impl OllamaResponse {
+    /// Returns the context tokens of a generate response.
+    ///
+    /// Only the final chunk carries them. Send them back with
+    /// `OllamaRequest::set_context` to continue from this response.
+    pub fn context(&self) -> Option<&[u32]> {
+        self.context.as_deref()
+    }
+
     /// Returns the creation time of the response.
     pub fn created_at(&self) -> Option<&str> {
         self.created_at.as_deref()
@@ -122,10 +225,18 @@ impl OllamaResponse {
         self.done.as_ref()
     }
 
+    pub fn set_done(&mut self, done: bool) {
+        self.done = Some(done);
+    }
+
     pub fn done_reason(&self) -> Option<&str> {
         self.done_reason.as_deref()
     }
 
+    pub fn set_done_reason(&mut self, done_reason: &str) {
+        self.done_reason = Some(done_reason.to_string());
+    }
+
     pub fn error(&self) -> Option<&str> {
         self.error.as_deref()
     }
@@ -134,18 +245,38 @@ impl OllamaResponse {
         self.eval_count.as_ref()
     }
 
-    pub fn eval_duration(&self) -> Option<&u64> {
-        self.eval_duration.as_ref()
+    /// Returns the time the server spent generating the response.
+    pub fn eval_duration(&self) -> Option<Duration> {
+        self.eval_duration.map(Duration::from_nanos)
+    }
+
+    /// Returns the generation rate in tokens per second, as measured by the server.
+    pub fn eval_rate(&self) -> Option<f64> {
+        rate(self.eval_count?, self.eval_duration()?)
+    }
+
+    /// Returns the time the server spent loading the model.
+    pub fn load_duration(&self) -> Option<Duration> {
+        self.load_duration.map(Duration::from_nanos)
+    }
+
+    /// Returns the per-token log probabilities, if they were requested.
+    pub fn logprobs(&self) -> Option<&[OllamaLogprob]> {
+        self.logprobs.as_deref()
     }
 
-    pub fn load_duration(&self) -> Option<&u64> {
-        self.load_duration.as_ref()
+    pub fn set_logprobs(&mut self, logprobs: Vec<OllamaLogprob>) {
+        self.logprobs = Some(logprobs);
     }
 
     pub fn message(&self) -> Option<&OllamaMessage> {
         self.message.as_ref()
     }
 
+    pub fn message_mut(&mut self) -> Option<&mut OllamaMessage> {
+        self.message.as_mut()
+    }
+
     pub fn set_message(&mut self, message: OllamaMessage) {
         self.message = Some(message);
     }
@@ -159,8 +290,14 @@ impl OllamaResponse {
         self.prompt_eval_count.as_ref()
     }
 
-    pub fn prompt_eval_duration(&self) -> Option<&u64> {
-        self.prompt_eval_duration.as_ref()
+    /// Returns the time the server spent evaluating the prompt.
+    pub fn prompt_eval_duration(&self) -> Option<Duration> {
+        self.prompt_eval_duration.map(Duration::from_nanos)
+    }
+
+    /// Returns the prompt evaluation rate in tokens per second, as measured by the server.
+    pub fn prompt_eval_rate(&self) -> Option<f64> {
+        rate(self.prompt_eval_count?, self.prompt_eval_duration()?)
     }
 
     pub fn response(&self) -> Option<&str> {
@@ -171,9 +308,107 @@ impl OllamaResponse {
         self.response = Some(response.to_string());
     }
 
-    pub fn total_duration(&self) -> Option<&u64> {
-        self.total_duration.as_ref()
+    /// Replaces the generated text, in the message content if present or in the response field otherwise.
+    pub fn set_text(&mut self, text: &str) {
+        match &mut self.message {
+            Some(message) => {
+                message.set_content(text);
+            }
+            None => self.set_response(text),
+        }
+    }
+
+    /// Returns the reasoning of a thinking model, from the message if present or
+    /// from the `thinking` field of a `generate` response otherwise.
+    pub fn thinking(&self) -> Option<&str> {
+        match &self.message {
+            Some(message) => message.thinking(),
+            None => self.thinking.as_deref(),
+        }
+    }
+
+    /// Replaces the reasoning, in the message if present or in the `thinking` field otherwise.
+    pub fn set_thinking(&mut self, thinking: &str) {
+        match &mut self.message {
+            Some(message) => {
+                message.set_thinking(thinking);
+            }
+            None => self.thinking = Some(thinking.to_string()),
+        }
+    }
+
+    /// Returns the total time the server spent on the request, including loading the model.
+    pub fn total_duration(&self) -> Option<Duration> {
+        self.total_duration.map(Duration::from_nanos)
+    }
+
+    /// Returns `true` if the text was stitched together from automatic continuation turns.
+    pub fn is_continued(&self) -> bool {
+        self.continuations > 0
+    }
+
+    /// Returns the number of automatic continuation turns stitched into this response.
+    pub fn continuations(&self) -> u32 {
+        self.continuations
+    }
+
+    pub fn set_continuations(&mut self, continuations: u32) {
+        self.continuations = continuations;
+    }
+
+    /// Returns the client-side latency metrics, set on the final response of a stream.
+    pub fn metrics(&self) -> Option<&StreamMetrics> {
+        self.metrics.as_ref()
+    }
+
+    pub fn set_metrics(&mut self, metrics: StreamMetrics) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Returns `true` if the client stopped reading before the response was complete,
+    /// e.g. because a deadline elapsed.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn set_truncated(&mut self, truncated: bool) {
+        self.truncated = truncated;
+    }
+
+    /// Returns the application metadata of the request, e.g. a correlation id.
+    ///
+    /// Set on the final response of a stream; see `OllamaRequest::set_metadata`.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
     }
+
+    pub fn set_metadata(&mut self, metadata: BTreeMap<String, String>) {
+        self.metadata = metadata;
+    }
+
+    /// Returns the fields of the response that are not modeled by this type.
+    ///
+    /// Unknown fields sent by newer servers are kept here when parsing and
+    /// written back when serializing, so no data is lost in a round trip.
+    pub fn extra(&self) -> &JsonMap<String, JsonValue> {
+        &self.extra
+    }
+
+    /// Sets a field that is not modeled by this type, e.g. one added by a newer server.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The field name.
+    /// * `value` - The field value.
+    pub fn set_extra(&mut self, key: &str, value: JsonValue) {
+        self.extra.insert(key.to_string(), value);
+    }
+}
+
+/// Returns `count` tokens per second of `duration`, or `None` for an empty duration.
+fn rate(count: u32, duration: Duration) -> Option<f64> {
+    let seconds = duration.as_secs_f64();
+    (seconds > 0.0).then(|| f64::from(count) / seconds)
 }
 
 // ===
@@ -189,8 +424,105 @@ impl fmt::Display for OllamaResponse {
     /// # Returns
     /// * Result indicating whether the formatting operation succeeded
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let json = serde_json::to_value(self).unwrap();
-        let pretty = serde_json::to_string_pretty(&json).unwrap_or_default();
+        let pretty = serde_json::to_string_pretty(self).map_err(|_| fmt::Error)?;
         write!(f, "{}", pretty)
     }
 }
+
+// ===
+// TESTS: OllamaResponse
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_durations_and_rates() {
+        let response = OllamaResponse::from_json(json!({
+            "done": true,
+            "eval_count": 50,
+            "eval_duration": 2_000_000_000u64,
+            "prompt_eval_count": 30,
+            "prompt_eval_duration": 100_000_000u64,
+            "load_duration": 5_000_000u64,
+            "total_duration": 2_200_000_000u64
+        }))
+        .unwrap();
+
+        assert_eq!(response.eval_duration(), Some(Duration::from_secs(2)));
+        assert_eq!(response.load_duration(), Some(Duration::from_millis(5)));
+        assert_eq!(response.total_duration(), Some(Duration::from_millis(2200)));
+        assert_eq!(response.eval_rate(), Some(25.0));
+        assert_eq!(response.prompt_eval_rate(), Some(300.0));
+
+        let empty = OllamaResponse::from_json(json!({ "eval_count": 5, "eval_duration": 0 }));
+        assert_eq!(empty.unwrap().eval_rate(), None);
+    }
+
+    #[test]
+    fn test_unknown_fields_round_trip() {
+        let json = json!({
+            "model": "gemma3",
+            "message": { "role": "assistant", "content": "Hi", "future_field": [1, 2] },
+            "done": true,
+            "server_metadata": { "region": "eu" }
+        });
+
+        let response = OllamaResponse::from_json(json.clone()).unwrap();
+        assert_eq!(
+            response.extra()["server_metadata"],
+            json!({ "region": "eu" })
+        );
+        assert_eq!(
+            response.message().unwrap().extra()["future_field"],
+            json!([1, 2])
+        );
+        assert_eq!(response.to_json(), json);
+    }
+
+    #[test]
+    fn test_json() {
+        let response = OllamaResponse::from_json(json!({
+            "message": { "role": "assistant", "content": "```json\n{\"answer\": 42}\n```" }
+        }))
+        .unwrap();
+        assert_eq!(response.json().unwrap(), json!({ "answer": 42 }));
+
+        let empty = OllamaResponse::from_json(json!({ "done": true })).unwrap();
+        assert!(empty.json().is_err());
+    }
+
+    #[test]
+    fn test_clone_eq() {
+        let response = OllamaResponse::from_json(json!({
+            "model": "gemma3",
+            "message": { "role": "assistant", "content": "Hi" },
+            "done": true
+        }))
+        .unwrap();
+
+        let mut copy = response.clone();
+        assert_eq!(copy, response);
+        copy.set_done_reason("length");
+        assert_ne!(copy, response);
+    }
+
+    #[test]
+    fn test_stats() {
+        let response = OllamaResponse::from_json(json!({
+            "model": "gemma3",
+            "eval_count": 10,
+            "eval_duration": 500_000_000u64,
+            "prompt_eval_count": 20
+        }))
+        .unwrap();
+
+        let stats = response.stats();
+        assert_eq!(stats.model.as_deref(), Some("gemma3"));
+        assert_eq!(stats.tokens_used(), 30);
+        assert_eq!(stats.eval_rate(), Some(20.0));
+        assert_eq!(stats.wall_time, None);
+    }
+}
@@ -0,0 +1,521 @@
+#[cfg(feature = "audit")]
+use crate::{AuditLog, AuditRecord};
+use crate::{
+    OllamaBoundedStream, OllamaLogprob, OllamaRequest, OllamaResponse, OllamaToolCalls,
+    StopSequenceFilter, StreamMetrics, StreamTimer, TextAccumulator,
+};
+use reqwest::Response as HttpResponse;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, VecDeque};
+use std::error::Error;
+
+// ===
+// STRUCT: OllamaResponseStream
+// ===
+
+/// A stream of response chunks from the Ollama `generate` or `chat` endpoints.
+///
+/// The stream parses the server's NDJSON output chunk by chunk, enforces the
+/// request's stop sequences, and accumulates the generated text, tool calls and
+/// log probabilities so the complete response is available once it ends. It
+/// also times the chunks as they arrive; see `metrics`.
+pub struct OllamaResponseStream {
+    http_response: Option<HttpResponse>,
+    buffer: Vec<u8>,
+    lines: VecDeque<Vec<u8>>,
+    finished: bool,
+    streaming: bool,
+    stop_filter: StopSequenceFilter,
+    text: TextAccumulator,
+    thinking: TextAccumulator,
+    tool_calls: OllamaToolCalls,
+    images: Vec<String>,
+    logprobs: Vec<OllamaLogprob>,
+    last: Option<OllamaResponse>,
+    timer: StreamTimer,
+    metadata: BTreeMap<String, String>,
+    #[cfg(feature = "audit")]
+    audit: Option<StreamAudit>,
+}
+
+impl OllamaResponseStream {
+    /// Creates a stream that reads the response to the given request.
+    ///
+    /// # Arguments
+    ///
+    /// * `http_response` - The HTTP response returned by the server.
+    /// * `request` - The request that was sent, for its stop sequences and stream setting.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaResponseStream`.
+    pub fn new(http_response: HttpResponse, request: &OllamaRequest) -> Self {
+        Self::with_timer(http_response, request, StreamTimer::start())
+    }
+
+    /// Creates a stream whose latency metrics are measured by an already started timer.
+    ///
+    /// # Arguments
+    ///
+    /// * `http_response` - The HTTP response returned by the server.
+    /// * `request` - The request that was sent, for its stop sequences and stream setting.
+    /// * `timer` - The timer started just before the request was sent.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaResponseStream`.
+    pub fn with_timer(
+        http_response: HttpResponse,
+        request: &OllamaRequest,
+        timer: StreamTimer,
+    ) -> Self {
+        Self::from_source(Some(http_response), request, timer)
+    }
+
+    /// Creates a stream that yields the given NDJSON line without any HTTP exchange,
+    /// e.g. the reply to a dry run.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The one chunk of the response, as JSON.
+    /// * `request` - The request being answered, for its stop sequences and stream setting.
+    /// * `timer` - The timer started when the request was made.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaResponseStream` that ends after the line.
+    pub(crate) fn from_line(line: &JsonValue, request: &OllamaRequest, timer: StreamTimer) -> Self {
+        let mut stream = Self::from_source(None, request, timer);
+        stream.lines.push_back(line.to_string().into_bytes());
+        stream.finished = true;
+        stream
+    }
+
+    /// Creates a stream reading from an HTTP response, or from its buffered lines only.
+    fn from_source(
+        http_response: Option<HttpResponse>,
+        request: &OllamaRequest,
+        timer: StreamTimer,
+    ) -> Self {
+        Self {
+            http_response,
+            buffer: Vec::new(),
+            lines: VecDeque::new(),
+            finished: false,
+            streaming: request.stream().unwrap_or(true),
+            stop_filter: StopSequenceFilter::new(&stop_sequences(request)),
+            text: TextAccumulator::new(),
+            thinking: TextAccumulator::new(),
+            tool_calls: OllamaToolCalls::new(),
+            images: Vec::new(),
+            logprobs: Vec::new(),
+            last: None,
+            timer,
+            metadata: request.metadata().clone(),
+            #[cfg(feature = "audit")]
+            audit: None,
+        }
+    }
+
+    /// Reads the next response chunk from the stream.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(&OllamaResponse))` - The next chunk.
+    /// * `Ok(None)` - If the stream has ended or a stop sequence was observed.
+    /// * `Err(Box<dyn Error>)` - If reading or parsing the stream failed.
+    pub async fn read(&mut self) -> Result<Option<&OllamaResponse>, Box<dyn Error>> {
+        let Some(chunk_json) = self.next_json().await? else {
+            return Ok(None);
+        };
+        self.accept(OllamaResponse::from_json(chunk_json)?);
+        Ok(self.last.as_ref())
+    }
+
+    /// Reads the next response chunk as the JSON the server sent, for fields
+    /// `OllamaResponse` does not cover.
+    ///
+    /// The chunk is accumulated as by `read`, so `final_text` and `response` still
+    /// work; the JSON returned is not trimmed by stop sequences.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(JsonValue))` - The next chunk.
+    /// * `Ok(None)` - If the stream has ended or a stop sequence was observed.
+    /// * `Err(Box<dyn Error>)` - If reading or parsing the stream failed.
+    pub async fn read_raw(&mut self) -> Result<Option<JsonValue>, Box<dyn Error>> {
+        let Some(chunk_json) = self.next_json().await? else {
+            return Ok(None);
+        };
+        self.accept(OllamaResponse::from_json(chunk_json.clone())?);
+        Ok(Some(chunk_json))
+    }
+
+    /// Returns the text generated so far.
+    ///
+    /// # Returns
+    ///
+    /// The concatenated text of all chunks read, after stop sequence enforcement.
+    pub fn final_text(&self) -> String {
+        self.text.text()
+    }
+
+    /// Returns the latency metrics recorded so far.
+    ///
+    /// # Returns
+    ///
+    /// The time to first token, the inter-chunk latencies and the total wall time,
+    /// which runs until now if the stream has not ended.
+    pub fn metrics(&self) -> StreamMetrics {
+        self.timer.metrics()
+    }
+
+    /// Builds the complete response from the chunks read so far.
+    ///
+    /// When streaming, the last chunk carries the statistics but no text, so the
+    /// accumulated text, thinking, tool calls, images and log probabilities are
+    /// merged into it. The latency metrics are attached in either case.
+    ///
+    /// # Returns
+    ///
+    /// The complete response, or `None` if no chunk has been read.
+    pub fn response(&self) -> Option<OllamaResponse> {
+        let mut response = self.last.clone()?;
+        response.set_metrics(self.metrics());
+        response.set_metadata(self.metadata.clone());
+        if !self.streaming {
+            return Some(response);
+        }
+
+        let text = self.final_text();
+        if let Some(message) = response.message_mut() {
+            message.set_content(&text);
+            if !self.tool_calls.is_empty() {
+                message.set_tool_calls(&self.tool_calls);
+            }
+            if !self.images.is_empty() {
+                message.set_images(&self.images);
+            }
+        } else {
+            response.set_response(&text);
+        }
+
+        if !self.thinking.is_empty() {
+            response.set_thinking(&self.thinking.text());
+        }
+
+        if !self.logprobs.is_empty() {
+            response.set_logprobs(self.logprobs.clone());
+        }
+
+        Some(response)
+    }
+
+    /// Reads the rest of the stream on a background task into a bounded buffer.
+    ///
+    /// The task stops reading the HTTP response while the buffer is full, so a
+    /// slow consumer holds back the server instead of the chunks piling up.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of text chunks buffered. Values below 1 are treated
+    ///   as 1.
+    ///
+    /// # Returns
+    ///
+    /// An `OllamaBoundedStream` yielding the text chunks.
+    pub fn bounded(self, capacity: usize) -> OllamaBoundedStream {
+        OllamaBoundedStream::new(self, capacity)
+    }
+
+    /// Writes the exchange to an audit log once the stream ends, fails or is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_log` - The log to write the record to.
+    /// * `url` - The URL the request was sent to.
+    /// * `request` - The request that was sent.
+    #[cfg(feature = "audit")]
+    pub(crate) fn set_audit_log(
+        &mut self,
+        audit_log: AuditLog,
+        url: &str,
+        request: &OllamaRequest,
+    ) {
+        self.audit = Some(StreamAudit {
+            audit_log,
+            url: url.to_string(),
+            request: request.clone(),
+        });
+    }
+
+    /// Writes the audit record of the exchange, if not written yet.
+    #[cfg(feature = "audit")]
+    fn write_audit(&mut self, error: Option<String>) {
+        let Some(audit) = self.audit.take() else {
+            return;
+        };
+
+        let result = self.response().ok_or_else(|| {
+            let error = error.as_deref();
+            error
+                .unwrap_or("no response received from the Ollama server")
+                .into()
+        });
+        let duration = self.metrics().total_time();
+        let mut record = AuditRecord::ollama(&audit.url, &audit.request, &result, duration);
+        if error.is_some() {
+            record.error = error;
+        }
+
+        // An audit log that cannot be written does not fail the stream.
+        let _ = audit.audit_log.record(record);
+    }
+
+    /// Reads the next NDJSON line from the HTTP response and parses it, writing
+    /// the audit record once the stream ends or fails.
+    async fn next_json(&mut self) -> Result<Option<JsonValue>, Box<dyn Error>> {
+        let result = self.read_json().await;
+
+        #[cfg(feature = "audit")]
+        match &result {
+            Ok(Some(_)) => {}
+            Ok(None) => self.write_audit(None),
+            Err(error) => self.write_audit(Some(error.to_string())),
+        }
+
+        result
+    }
+
+    /// Reads the next NDJSON line from the HTTP response and parses it.
+    async fn read_json(&mut self) -> Result<Option<JsonValue>, Box<dyn Error>> {
+        loop {
+            if self.stop_filter.is_stopped() {
+                self.timer.finish();
+                return Ok(None);
+            }
+
+            if let Some(line) = self.lines.pop_front() {
+                let line = String::from_utf8_lossy(&line);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Ok(Some(serde_json::from_str(&line)?));
+            }
+
+            if self.finished {
+                self.timer.finish();
+                return Ok(None);
+            }
+
+            // A chunk may hold several NDJSON lines or only part of one.
+            let chunk = match &mut self.http_response {
+                Some(http_response) => http_response.chunk().await?,
+                None => None,
+            };
+            match chunk {
+                Some(bytes) => {
+                    self.buffer.extend_from_slice(&bytes);
+                    self.lines.extend(drain_lines(&mut self.buffer));
+                }
+                None => {
+                    self.finished = true;
+                    self.lines.push_back(std::mem::take(&mut self.buffer));
+                }
+            }
+        }
+    }
+
+    /// Applies stop sequences to a chunk and accumulates its content.
+    fn accept(&mut self, mut chunk: OllamaResponse) {
+        self.timer
+            .record_chunk(chunk.text().is_some_and(|text| !text.is_empty()));
+
+        // Enforce stop sequences on the client, for models that ignore them.
+        if !self.stop_filter.is_empty() {
+            let mut text = self.stop_filter.push(chunk.text().unwrap_or_default());
+            if self.stop_filter.is_stopped() {
+                chunk.set_done(true);
+                chunk.set_done_reason("stop");
+            } else if chunk.done() == Some(&true) {
+                text.push_str(&self.stop_filter.finish());
+            }
+            chunk.set_text(&text);
+        }
+
+        if let Some(text) = chunk.text() {
+            self.text.push(text);
+        }
+
+        // Thinking models stream their reasoning ahead of the answer.
+        if let Some(thinking) = chunk.thinking() {
+            self.thinking.push(thinking);
+        }
+
+        // Tool calls and images arrive on intermediate chunks when streaming.
+        if let Some(message) = chunk.message() {
+            if let Some(calls) = message.tool_calls() {
+                for index in 0..calls.len() {
+                    if let Some(call) = calls.tool_call(index) {
+                        self.tool_calls.push_tool_call(call);
+                    }
+                }
+            }
+            if let Some(images) = message.images() {
+                self.images.extend_from_slice(images);
+            }
+        }
+
+        // Per-token log probabilities arrive with each chunk.
+        if let Some(logprobs) = chunk.logprobs() {
+            self.logprobs.extend_from_slice(logprobs);
+        }
+
+        self.last = Some(chunk);
+    }
+}
+
+// ===
+// TRAIT: OllamaResponseStream (Drop)
+// ===
+
+#[cfg(feature = "audit")]
+impl Drop for OllamaResponseStream {
+    /// Writes the audit record of a stream dropped before `read` returned `None`.
+    ///
+    /// A stream dropped after its final chunk was read counts as complete.
+    fn drop(&mut self) {
+        let done = self
+            .last
+            .as_ref()
+            .is_some_and(|last| last.done() == Some(&true));
+        let error = (!done).then(|| "the stream was dropped before it completed".to_string());
+        self.write_audit(error);
+    }
+}
+
+/// The audit log a stream writes its exchange to; see `set_audit_log`.
+#[cfg(feature = "audit")]
+struct StreamAudit {
+    audit_log: AuditLog,
+    url: String,
+    request: OllamaRequest,
+}
+
+/// Returns the stop sequences set in the request options, if any.
+fn stop_sequences(request: &OllamaRequest) -> Vec<String> {
+    request
+        .options()
+        .and_then(|options| options.get("stop"))
+        .and_then(|stop| stop.as_array())
+        .map(|stop| {
+            stop.iter()
+                .filter_map(|s| s.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Splits the complete, newline-terminated lines off the front of `buffer`.
+///
+/// Any trailing partial line is left in the buffer for the next chunk.
+fn drain_lines(buffer: &mut Vec<u8>) -> Vec<Vec<u8>> {
+    let mut lines = Vec::new();
+    while let Some(pos) = buffer.iter().position(|byte| *byte == b'\n') {
+        lines.push(buffer.drain(..=pos).collect());
+    }
+    lines
+}
+
+// ===
+// TESTS: OllamaResponseStream
+// ===
+
+#[cfg(test)]
+mod tests {
+    use crate::Ollama;
+    use crate::OllamaRequest;
+    use crate::mock_server::{MockServer, chat_body, ndjson};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_read_chunks_and_final_text() {
+        let server = MockServer::start(vec![chat_body(&["Hello", ", ", "world!"])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .add_message(json!({"role": "user", "content": "Hi"}));
+
+        let mut stream = ollama.chat_stream(&request).await.unwrap();
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.read().await.unwrap() {
+            chunks.push(chunk.text().unwrap_or_default().to_string());
+        }
+
+        assert_eq!(chunks, ["Hello", ", ", "world!", ""]);
+        assert_eq!(stream.final_text(), "Hello, world!");
+
+        let response = stream.response().unwrap();
+        assert_eq!(response.text(), Some("Hello, world!"));
+        assert_eq!(response.tokens_used(), 30);
+        assert!(stream.read().await.unwrap().is_none());
+
+        let metrics = response.metrics().unwrap();
+        assert_eq!(metrics.chunk_intervals().len(), 3);
+        assert!(metrics.time_to_first_token().unwrap() <= metrics.total_time());
+    }
+
+    #[tokio::test]
+    async fn test_read_raw_returns_the_chunk_json() {
+        let server = MockServer::start(vec![chat_body(&["Hello", "!"])]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .add_message(json!({"role": "user", "content": "Hi"}));
+
+        let mut stream = ollama.chat_stream(&request).await.unwrap();
+        let first = stream.read_raw().await.unwrap().unwrap();
+        assert_eq!(first["message"]["content"], "Hello");
+        assert_eq!(first["done"], false);
+
+        let next = stream.read().await.unwrap().unwrap();
+        assert_eq!(next.text(), Some("!"));
+        let last = stream.read_raw().await.unwrap().unwrap();
+        assert_eq!(last["eval_count"], 10);
+        assert!(stream.read_raw().await.unwrap().is_none());
+        assert_eq!(stream.response().unwrap().text(), Some("Hello!"));
+    }
+
+    #[tokio::test]
+    async fn test_response_merges_thinking_tool_calls_and_images() {
+        let call = json!({ "function": { "name": "weather", "arguments": { "city": "Paris" } } });
+        let body = ndjson(&[
+            json!({ "model": "mock", "message": { "role": "assistant", "content": "", "thinking": "The user " }, "done": false }),
+            json!({ "model": "mock", "message": { "role": "assistant", "content": "", "thinking": "wants weather." }, "done": false }),
+            json!({ "model": "mock", "message": { "role": "assistant", "content": "", "tool_calls": [call] }, "done": false }),
+            json!({ "model": "mock", "message": { "role": "assistant", "content": "Here.", "images": ["aGk="] }, "done": false }),
+            json!({ "model": "mock", "message": { "role": "assistant", "content": "" }, "done": true }),
+        ]);
+        let server = MockServer::start(vec![body]).await;
+        let ollama = Ollama::new(&server.addr());
+
+        let mut request = OllamaRequest::new();
+        request
+            .set_model("mock")
+            .add_message(json!({"role": "user", "content": "Weather?"}));
+
+        let mut stream = ollama.chat_stream(&request).await.unwrap();
+        while stream.read().await.unwrap().is_some() {}
+
+        let response = stream.response().unwrap();
+        let message = response.message().unwrap();
+        assert_eq!(message.content(), Some("Here."));
+        assert_eq!(response.thinking(), Some("The user wants weather."));
+        assert_eq!(message.tool_calls().unwrap().len(), 1);
+        assert_eq!(message.images(), Some(&["aGk=".to_string()][..]));
+    }
+}
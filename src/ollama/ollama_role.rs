@@ -0,0 +1,90 @@
+use std::fmt;
+
+// ===
+// ENUM: OllamaRole
+// ===
+
+/// The role of a message in an Ollama chat conversation.
+///
+/// Using the enum rather than a string catches typos like "assitant" at compile
+/// time. Roles a model's template defines beyond these, if any, go in `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OllamaRole {
+    /// Instructions that set up the conversation.
+    System,
+    /// A message from the user.
+    User,
+    /// A reply from the model.
+    Assistant,
+    /// The result of a tool call.
+    Tool,
+    /// A control message some models accept, e.g. to switch on thinking.
+    Control,
+    /// Any other role, sent as given.
+    Other(String),
+}
+
+impl OllamaRole {
+    /// Returns the role as it is sent to the server, e.g. "assistant".
+    pub fn as_str(&self) -> &str {
+        match self {
+            OllamaRole::System => "system",
+            OllamaRole::User => "user",
+            OllamaRole::Assistant => "assistant",
+            OllamaRole::Tool => "tool",
+            OllamaRole::Control => "control",
+            OllamaRole::Other(role) => role,
+        }
+    }
+}
+
+/// Parses a role name, ignoring case; unknown names become `Other`.
+impl From<&str> for OllamaRole {
+    fn from(role: &str) -> Self {
+        match role.to_lowercase().as_str() {
+            "system" => OllamaRole::System,
+            "user" => OllamaRole::User,
+            "assistant" => OllamaRole::Assistant,
+            "tool" => OllamaRole::Tool,
+            "control" => OllamaRole::Control,
+            _ => OllamaRole::Other(role.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for OllamaRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ===
+// TESTS: OllamaRole
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in [
+            OllamaRole::System,
+            OllamaRole::User,
+            OllamaRole::Assistant,
+            OllamaRole::Tool,
+            OllamaRole::Control,
+        ] {
+            assert_eq!(OllamaRole::from(role.as_str()), role);
+        }
+        assert_eq!(OllamaRole::from("Assistant"), OllamaRole::Assistant);
+        assert_eq!(
+            OllamaRole::from("assitant"),
+            OllamaRole::Other("assitant".to_string())
+        );
+        assert_eq!(
+            OllamaRole::Other("critic".to_string()).to_string(),
+            "critic"
+        );
+    }
+}
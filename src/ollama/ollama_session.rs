@@ -1,5 +1,44 @@
-use crate::{Ollama, OllamaMessage, OllamaOptions, OllamaRequest, OllamaResponse};
+use crate::{
+    Classification, ContentFilter, FilteredStream, JsonAnswer, JsonAttempt, JsonRetryError,
+    LanguageCode, Ollama, OllamaAbortHandle, OllamaCheckpoint, OllamaHistory, OllamaMessage,
+    OllamaOptions, OllamaRequest, OllamaRequestReport, OllamaResponse, OllamaRole,
+    OllamaSessionHooks, OllamaStreamError, OllamaTools, OllieConfig, OptionPresets, ProviderKind,
+    ProxyConfig, SharedContentFilter, TlsConfig, TokenBreakdown, Transcript, extract_json,
+    validate_json_schema,
+};
+use serde_json::{Value as JsonValue, json};
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{Instant, sleep_until};
+
+/// The history returned by sessions that have no messages yet.
+static EMPTY_HISTORY: OllamaHistory = OllamaHistory::new();
+
+/// The message sent to ask the model to carry on after hitting the length limit.
+const CONTINUE_PROMPT: &str = "Continue exactly where you left off, without repeating anything.";
+
+/// The done reason of a response whose generation a content filter halted.
+pub const CONTENT_FILTER_DONE_REASON: &str = "content_filter";
+
+/// The wait before the first resume of an interrupted stream, doubled for each
+/// further resume.
+const RESUME_BACKOFF: Duration = Duration::from_millis(250);
+
+/// The number of requests `classify` makes before giving up on an invalid label.
+const CLASSIFY_ATTEMPTS: u32 = 3;
+
+/// The instruction sent to name a conversation.
+const TITLE_PROMPT: &str = "Write a short title for the conversation below, of at most six \
+words, naming its topic. Do not use quotes or end punctuation. Answer with the title only.";
+
+/// The number of user and assistant messages, from the start, a title is based on.
+const TITLE_MESSAGES: usize = 4;
+
+/// The longest title `generate_title` returns, in characters.
+const MAX_TITLE_CHARS: usize = 60;
 
 // ===
 // STRUCT: OllamaSession
@@ -9,10 +48,24 @@ use std::error::Error;
 ///
 /// This struct manages the state of a conversation with an Ollama model,
 /// keeping track of the message history for context in future exchanges.
+/// The history is frozen into shared segments after every turn, so `fork`
+/// and `checkpoint` are cheap even for long conversations.
+#[derive(Clone, Debug)]
 pub struct OllamaSession {
     ollama: Ollama,
     request: OllamaRequest,
     options: OllamaOptions,
+    presets: OptionPresets,
+    preset: Option<OllamaOptions>,
+    responses: Vec<(usize, OllamaResponse)>,
+    max_continuations: u32,
+    max_resumes: u32,
+    max_history_tokens: u32,
+    pinned: BTreeSet<usize>,
+    abort: OllamaAbortHandle,
+    hooks: OllamaSessionHooks,
+    content_filter: Option<SharedContentFilter>,
+    steering: Option<String>,
 }
 
 impl OllamaSession {
@@ -57,6 +110,17 @@ impl OllamaSession {
             ollama,
             request,
             options: OllamaOptions::new(),
+            presets: OptionPresets::new(),
+            preset: None,
+            responses: Vec::new(),
+            max_continuations: 0,
+            max_resumes: 0,
+            max_history_tokens: 0,
+            pinned: BTreeSet::new(),
+            abort: OllamaAbortHandle::new(),
+            hooks: OllamaSessionHooks::new(),
+            content_filter: None,
+            steering: None,
         }
     }
 
@@ -80,7 +144,68 @@ impl OllamaSession {
             ollama,
             request,
             options: OllamaOptions::new(),
+            presets: OptionPresets::new(),
+            preset: None,
+            responses: Vec::new(),
+            max_continuations: 0,
+            max_resumes: 0,
+            max_history_tokens: 0,
+            pinned: BTreeSet::new(),
+            abort: OllamaAbortHandle::new(),
+            hooks: OllamaSessionHooks::new(),
+            content_filter: None,
+            steering: None,
+        }
+    }
+
+    /// Creates a new chat session from a preset defined in a configuration file.
+    ///
+    /// The session uses the preset's provider endpoint (or the default local server),
+    /// model and options, and starts with the preset's system prompt, if any. The options
+    /// of every preset in the configuration are registered for `apply_preset`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration holding the preset.
+    /// * `preset` - The name of the preset.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OllamaSession)` - The configured session.
+    /// * `Err(Box<dyn Error>)` - If the preset is unknown, is not an Ollama preset,
+    ///   or has an invalid endpoint.
+    pub fn from_config(config: &OllieConfig, preset: &str) -> Result<Self, Box<dyn Error>> {
+        let resolved = config.resolve(preset)?;
+        if resolved.provider.kind != ProviderKind::Ollama {
+            return Err(format!("preset '{preset}' does not use an Ollama provider").into());
+        }
+
+        let mut session = match &resolved.provider.endpoint {
+            Some(endpoint) => {
+                endpoint
+                    .parse::<SocketAddr>()
+                    .map_err(|err| format!("invalid Ollama endpoint '{endpoint}': {err}"))?;
+                Self::remote(resolved.model, endpoint)
+            }
+            None => Self::local(resolved.model),
+        };
+
+        if let Some(options) = resolved.options {
+            session.options = options.clone();
+        }
+
+        // Make every preset's options available to `apply_preset`.
+        for (name, preset) in &config.presets {
+            if let Some(options) = &preset.options {
+                session.register_preset(name, options.clone());
+            }
+        }
+
+        if let Some(prompt) = resolved.system_prompt {
+            session.system(prompt);
         }
+
+        Ok(session)
     }
 
     /// Adds an assistant message to the conversation.
@@ -92,12 +217,120 @@ impl OllamaSession {
     ///
     /// * `content` - The content of the assistant message.
     pub fn assistant(&mut self, content: &str) {
-        let message = OllamaMessage::new()
-            .set_role("assistant")
-            .set_content(content)
-            .to_json();
+        let mut message = OllamaMessage::new();
+        message.set_role(OllamaRole::Assistant).set_content(content);
+        self.hooks.run_assistant(&mut message);
 
-        self.request.add_message(message);
+        self.request.add_message(message.to_json());
+    }
+
+    /// Returns the model used by this session.
+    pub fn model(&self) -> Option<&str> {
+        self.request.model().map(|model| model.as_str())
+    }
+
+    /// Returns the conversation history as a list of JSON messages.
+    pub fn messages(&self) -> &OllamaHistory {
+        self.request.messages().unwrap_or(&EMPTY_HISTORY)
+    }
+
+    /// Creates an independent copy of the session that shares its history so far.
+    ///
+    /// Messages added to either session afterwards are not seen by the other, and
+    /// the copy has its own abort handle.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaSession` with the same model, options, tools and history.
+    pub fn fork(&mut self) -> Self {
+        self.freeze_history();
+        let mut fork = self.clone();
+        fork.abort = OllamaAbortHandle::new();
+        fork
+    }
+
+    /// Like `fork`, but without the hooks or content filter, for requests the library makes on its
+    /// own behalf rather than turns of the conversation.
+    pub(crate) fn internal_fork(&mut self) -> Self {
+        let mut fork = self.fork();
+        fork.hooks = OllamaSessionHooks::new();
+        fork.content_filter = None;
+        fork
+    }
+
+    /// Captures the current history and pinned messages so they can be restored later.
+    ///
+    /// # Returns
+    ///
+    /// An `OllamaCheckpoint` sharing its messages with the session.
+    pub fn checkpoint(&mut self) -> OllamaCheckpoint {
+        self.freeze_history();
+        OllamaCheckpoint::new(self.messages().clone(), self.pinned.clone())
+    }
+
+    /// Replaces the history and pinned messages with those of a checkpoint.
+    ///
+    /// A bare `OllamaHistory` can be restored too; its system messages are pinned.
+    ///
+    /// # Arguments
+    ///
+    /// * `checkpoint` - The checkpoint to continue from.
+    pub fn restore(&mut self, checkpoint: impl Into<OllamaCheckpoint>) {
+        let (history, pinned) = checkpoint.into().into_parts();
+        let len = history.len();
+        self.responses.retain(|(index, _)| *index < len);
+        self.pinned = pinned;
+        self.request.set_messages(history);
+    }
+
+    /// Returns the final responses received by `update`, each paired with the
+    /// index of the history message it produced.
+    pub(crate) fn responses(&self) -> &[(usize, OllamaResponse)] {
+        &self.responses
+    }
+
+    /// Returns the token counts of the conversation, per message and per role.
+    ///
+    /// Assistant messages generated by `update` use the server's `eval_count`;
+    /// other messages are estimated from their length.
+    ///
+    /// # Returns
+    ///
+    /// A `TokenBreakdown` of the current history.
+    pub fn token_breakdown(&self) -> TokenBreakdown {
+        let mut breakdown = TokenBreakdown::from_messages(self.messages().iter());
+        for (index, response) in &self.responses {
+            if let Some(tokens) = response.eval_count() {
+                breakdown.set_exact(*index, *tokens);
+            }
+        }
+        breakdown
+    }
+
+    /// Returns a report of the request the next `update` would send.
+    ///
+    /// The report reflects what `update` does before sending: the history is trimmed
+    /// to the history token limit and the session's options are applied. The session
+    /// itself is left unchanged. Print it to see the model, options, message count,
+    /// estimated tokens per section, tools and the serialized payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(OllamaRequestReport)` - The report of the next request.
+    /// * `Err(serde_json::Error)` - If the options cannot be serialized, as `update` would fail.
+    pub fn explain_next_request(&self) -> Result<OllamaRequestReport, serde_json::Error> {
+        let mut next = self.clone();
+        let evicted = next.trim_history();
+        next.request.set_options(next.options.to_json()?);
+        next.request.set_stream(true);
+
+        let mut report = OllamaRequestReport::new(
+            &next.request,
+            &next.token_breakdown(),
+            next.context_window_size(),
+        );
+        report.evicted = evicted;
+        Ok(report)
     }
 
     /// Gets the context window size for the model.
@@ -125,6 +358,50 @@ impl OllamaSession {
         &mut self.options
     }
 
+    /// Returns the option presets available to `apply_preset`.
+    pub fn presets(&self) -> &OptionPresets {
+        &self.presets
+    }
+
+    /// Registers a named option preset for this session.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name used to apply the preset.
+    /// * `options` - The options the preset sets.
+    pub fn register_preset(&mut self, name: &str, options: OllamaOptions) {
+        self.presets.register(name, options);
+    }
+
+    /// Applies a named option preset to the session options.
+    ///
+    /// Options set by the preset replace the current values; other options are kept,
+    /// so presets can be switched between turns without losing e.g. the context size.
+    /// The options set by the previously applied preset are cleared first, so none of
+    /// them linger after a switch.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of a built-in or registered preset.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the preset was applied.
+    /// * `Err(Box<dyn Error>)` - If no preset has the given name.
+    pub fn apply_preset(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        let preset = self
+            .presets
+            .get(name)
+            .ok_or_else(|| format!("unknown option preset '{name}'"))?
+            .clone();
+        if let Some(previous) = &self.preset {
+            self.options.unset(previous);
+        }
+        self.options.merge(&preset);
+        self.preset = Some(preset);
+        Ok(())
+    }
+
     /// Adds a user message to the conversation.
     ///
     /// User messages represent queries or statements from the user
@@ -134,63 +411,1704 @@ impl OllamaSession {
     ///
     /// * `content` - The content of the user message.
     pub fn user(&mut self, content: &str) {
-        let message = OllamaMessage::new()
-            .set_role("user")
-            .set_content(content)
-            .to_json();
+        let mut message = OllamaMessage::new();
+        message.set_role(OllamaRole::User).set_content(content);
+        self.hooks.run_user(&mut message);
 
-        self.request.add_message(message);
+        self.request.add_message(message.to_json());
     }
 
     /// Adds a system message to the conversation.
     ///
     /// System messages provide instructions or context to the model
-    /// about how it should behave throughout the conversation.
+    /// about how it should behave throughout the conversation. They are
+    /// pinned, so history trimming never evicts them; see `unpin`.
     ///
     /// # Arguments
     ///
     /// * `content` - The content of the system message.
     pub fn system(&mut self, content: &str) {
         let message = OllamaMessage::new()
-            .set_role("system")
+            .set_role(OllamaRole::System)
             .set_content(content)
             .to_json();
 
+        self.pinned.insert(self.messages().len());
         self.request.add_message(message);
     }
 
-    /// Sends the current conversation to the model and processes the response.
+    /// Replaces a message of the history in place, keeping its position and pin.
     ///
-    /// This method sends the accumulated messages to the Ollama model, processes the
-    /// streaming response, and returns the final response object.
+    /// # Arguments
+    ///
+    /// * `index` - The position of the message.
+    /// * `message` - The new message.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a message was replaced, `false` if `index` is out of range.
+    pub(crate) fn replace_message(&mut self, index: usize, message: JsonValue) -> bool {
+        self.request
+            .messages_mut()
+            .and_then(|history| history.replace(index, message))
+            .is_some()
+    }
+
+    /// Adds a tool result message to the conversation.
+    ///
+    /// Tool messages carry the output of a tool the model asked to call
+    /// and are included in the conversation history.
     ///
     /// # Arguments
     ///
-    /// * `callback` - A function that will be called with each chunk of the response
-    ///    as it is received. Use this for handling streaming responses.
+    /// * `content` - The tool output, typically serialized JSON.
+    pub fn tool(&mut self, content: &str) {
+        let message = OllamaMessage::new()
+            .set_role(OllamaRole::Tool)
+            .set_content(content)
+            .to_json();
+
+        self.request.add_message(message);
+    }
+
+    /// Adds a tool result message naming the tool that produced it.
+    ///
+    /// The name lets the model match each result to its call when it asked for
+    /// several tools at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_name` - The name of the called tool.
+    /// * `content` - The tool output, typically serialized JSON.
+    pub fn tool_result(&mut self, tool_name: &str, content: &str) {
+        let message = OllamaMessage::new()
+            .set_role(OllamaRole::Tool)
+            .set_tool_name(tool_name)
+            .set_content(content)
+            .to_json();
+
+        self.request.add_message(message);
+    }
+
+    /// Sets the tools the model may call during this session.
+    ///
+    /// # Arguments
+    ///
+    /// * `tools` - The `OllamaTools` collection of function declarations.
+    pub fn set_tools(&mut self, tools: &OllamaTools) {
+        self.request.set_tools(tools);
+    }
+
+    /// Limits the estimated size of the history sent with each request.
+    ///
+    /// Before every `update`, the oldest messages that are not pinned are evicted
+    /// until the history fits in `max_tokens`, as counted by `token_breakdown`.
+    /// System messages are pinned automatically; pin few-shot examples with `pin`.
+    /// The latest message is always kept, and tool results are evicted together
+    /// with the call that produced them. Tool declarations are not part of the
+    /// history and are always sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_tokens` - The token budget of the history; 0 disables trimming.
+    pub fn set_history_token_limit(&mut self, max_tokens: u32) {
+        self.max_history_tokens = max_tokens;
+    }
+
+    /// Pins a message so history trimming never evicts it.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the message in the history.
+    pub fn pin(&mut self, index: usize) {
+        if index < self.messages().len() {
+            self.pinned.insert(index);
+        }
+    }
+
+    /// Unpins a message, including an automatically pinned system message.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the message in the history.
+    pub fn unpin(&mut self, index: usize) {
+        self.pinned.remove(&index);
+    }
+
+    /// Returns true if the message at `index` is pinned.
+    pub fn is_pinned(&self, index: usize) -> bool {
+        self.pinned.contains(&index)
+    }
+
+    /// Evicts the oldest unpinned messages until the history fits the token limit.
+    ///
+    /// Called automatically by `update`; see `set_history_token_limit`. Pins and
+    /// recorded responses move with the messages they refer to.
     ///
     /// # Returns
     ///
-    /// * `Result<OllamaResponse, Box<dyn Error>>` - The complete response from the model if successful,
-    ///   or an error if something went wrong.
-    pub async fn update<F>(&mut self, mut callback: F) -> Result<OllamaResponse, Box<dyn Error>>
+    /// The number of messages evicted.
+    pub fn trim_history(&mut self) -> usize {
+        if self.max_history_tokens == 0 {
+            return 0;
+        }
+
+        let breakdown = self.token_breakdown();
+        let tokens = breakdown.messages();
+        let messages = self.messages();
+        let is_tool = |index: usize| messages[index]["role"] == "tool";
+
+        let mut total = breakdown.total();
+        let mut evicted = BTreeSet::new();
+        let mut index = 0;
+        while total > self.max_history_tokens && index + 1 < tokens.len() {
+            if !self.pinned.contains(&index) && !evicted.contains(&index) {
+                evicted.insert(index);
+                total -= tokens[index].tokens;
+
+                // Drop the results of an evicted tool call with it.
+                let mut next = index + 1;
+                while next + 1 < tokens.len() && is_tool(next) && !self.pinned.contains(&next) {
+                    evicted.insert(next);
+                    total -= tokens[next].tokens;
+                    next += 1;
+                }
+            }
+            index += 1;
+        }
+
+        if evicted.is_empty() {
+            return 0;
+        }
+
+        // Map each kept message to its new position.
+        let mut new_index = vec![None; messages.len()];
+        let mut kept = Vec::new();
+        for (index, message) in messages.iter().enumerate() {
+            if !evicted.contains(&index) {
+                new_index[index] = Some(kept.len());
+                kept.push(message.clone());
+            }
+        }
+
+        self.pinned = self.pinned.iter().filter_map(|i| new_index[*i]).collect();
+        for (index, _) in &mut self.responses {
+            *index = new_index[*index].unwrap_or(usize::MAX);
+        }
+        self.responses.retain(|(index, _)| *index != usize::MAX);
+        self.request.set_messages(OllamaHistory::from(kept));
+        self.freeze_history();
+
+        evicted.len()
+    }
+
+    /// Enables automatic continuation of responses cut off by the length limit.
+    ///
+    /// When a response finishes because it reached `num_predict` or the context limit,
+    /// `update` asks the model to continue and stitches the continuation onto the
+    /// truncated text, up to `max_continuations` times. The combined response is
+    /// flagged with `OllamaResponse::is_continued`, and the history keeps it as a
+    /// single assistant message.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_continuations` - The maximum number of continuation turns; 0 disables the feature.
+    pub fn set_auto_continue(&mut self, max_continuations: u32) {
+        self.max_continuations = max_continuations;
+    }
+
+    /// Enables automatic resumption of responses whose stream fails part way.
+    ///
+    /// When the server restarts or the connection resets mid-stream, `update` re-sends
+    /// the conversation with the partial text as a final assistant message, which the
+    /// model continues, up to `max_resumes` times, waiting `RESUME_BACKOFF` before the
+    /// first resume and twice as long before each further one. The chunks passed to
+    /// the callback continue where the failed stream stopped. If the stream still
+    /// fails, or a resume cannot connect, the error is an `OllamaStreamError`
+    /// holding all the text generated so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_resumes` - The maximum number of resumed requests; 0 disables the feature.
+    pub fn set_auto_resume(&mut self, max_resumes: u32) {
+        self.max_resumes = max_resumes;
+    }
+
+    /// Sets how requests reach the server; see `Ollama::set_proxy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy setting.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the proxy was set.
+    /// * `Err(Box<dyn Error>)` - If the proxy URL is invalid.
+    pub fn set_proxy(&mut self, proxy: &ProxyConfig) -> Result<(), Box<dyn Error>> {
+        self.ollama.set_proxy(proxy)?;
+        Ok(())
+    }
+
+    /// Connects to the server over HTTPS; see `Ollama::set_tls`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls` - The certificate settings.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the settings were applied.
+    /// * `Err(Box<dyn Error>)` - If the HTTP client cannot be created.
+    pub fn set_tls(&mut self, tls: &TlsConfig) -> Result<(), Box<dyn Error>> {
+        self.ollama.set_tls(tls)?;
+        Ok(())
+    }
+
+    /// Answers requests locally with their JSON payload instead of sending them.
+    ///
+    /// Each `update` then returns the request that would have been sent, including
+    /// the options, tools and trimmed history, as the assistant's text; see
+    /// `Ollama::set_dry_run`. The reply is not added to the history, and dry runs
+    /// are not written to the audit log.
+    ///
+    /// # Arguments
+    ///
+    /// * `dry_run` - Whether to answer requests locally.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.ollama.set_dry_run(dry_run);
+    }
+
+    /// Returns a handle that aborts this session from another task.
+    ///
+    /// `update` borrows the session mutably, so a stop button needs a handle taken
+    /// beforehand to call `OllamaAbortHandle::abort` while a response streams.
+    pub fn abort_handle(&self) -> OllamaAbortHandle {
+        self.abort.clone()
+    }
+
+    /// Cancels any update in flight and marks the session as aborted.
+    ///
+    /// An update in flight stops reading, which cancels generation, and returns the
+    /// text received so far flagged with `OllamaResponse::is_truncated`; that text
+    /// is added to the history as the assistant's answer, so the conversation stays
+    /// well formed. While the session is aborted, `update` returns an error without
+    /// sending anything, which also stops loops such as `Agent::run`, until
+    /// `clear_abort` is called.
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+
+    /// Returns `true` if the session was aborted and not cleared since.
+    pub fn is_aborted(&self) -> bool {
+        self.abort.is_aborted()
+    }
+
+    /// Clears the aborted mark so the session can be updated again.
+    pub fn clear_abort(&mut self) {
+        self.abort.clear();
+    }
+
+    /// Adds a hook called with each user message before it is added to the history.
+    ///
+    /// The hook may change the message, e.g. to redact personal data or add
+    /// context, and the changed message is what the model sees.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The function called with the message.
+    pub fn on_user<F>(&mut self, hook: F)
     where
-        F: FnMut(&str),
+        F: Fn(&mut OllamaMessage) + Send + Sync + 'static,
     {
-        // Apply options to the request
-        self.request.set_options(&self.options.to_json());
-        self.request.set_stream(true);
-        let response = self
-            .ollama
-            .chat(&self.request, |response| {
-                // Extract the response content and pass it to the callback, if available.
-                if let Some(content) = response.text() {
-                    callback(content);
-                }
-            })
-            .await?;
+        self.hooks.add_user(Arc::new(hook));
+    }
 
-        self.request.add_response(&response);
-        Ok(response)
+    /// Adds a hook called with each assistant message before it is added to the history.
+    ///
+    /// It runs for messages added with `assistant` and for the responses of
+    /// `update`. A response is returned as the model sent it; only the history
+    /// holds the changed message.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The function called with the message.
+    pub fn on_assistant<F>(&mut self, hook: F)
+    where
+        F: Fn(&mut OllamaMessage) + Send + Sync + 'static,
+    {
+        self.hooks.add_assistant(Arc::new(hook));
+    }
+
+    /// Adds a hook called once each turn is recorded, e.g. to log it or update a UI.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The function called with the response added to the history.
+    pub fn on_turn_complete<F>(&mut self, hook: F)
+    where
+        F: Fn(&OllamaResponse) + Send + Sync + 'static,
+    {
+        self.hooks.add_turn_complete(Arc::new(hook));
+    }
+
+    /// Returns the hooks registered on this session.
+    pub fn hooks(&self) -> &OllamaSessionHooks {
+        &self.hooks
+    }
+
+    /// Removes all hooks.
+    pub fn clear_hooks(&mut self) {
+        self.hooks = OllamaSessionHooks::new();
+    }
+
+    /// Attaches application metadata, e.g. a request, user or trace id, to every request.
+    ///
+    /// The metadata is not sent to the model. It is copied to each response, so
+    /// the turn completion hooks can read it, and to the audit log records.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The metadata name, e.g. "request_id".
+    /// * `value` - The metadata value.
+    pub fn set_metadata(&mut self, key: &str, value: &str) {
+        self.request.set_metadata(key, value);
+    }
+
+    /// Removes an entry of application metadata.
+    pub fn remove_metadata(&mut self, key: &str) {
+        self.request.remove_metadata(key);
+    }
+
+    /// Returns the application metadata attached to the session's requests.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        self.request.metadata()
+    }
+
+    /// Sets a filter that moderates the streamed output before it reaches the callback.
+    ///
+    /// The response and the history hold the filtered text. If the filter halts
+    /// generation, the response is truncated with the done reason
+    /// `CONTENT_FILTER_DONE_REASON`. Forks share the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter, e.g. a `PatternFilter`.
+    pub fn set_content_filter<F>(&mut self, filter: F)
+    where
+        F: ContentFilter + 'static,
+    {
+        self.content_filter = Some(Arc::new(Mutex::new(filter)));
+    }
+
+    /// Removes the content filter.
+    pub fn clear_content_filter(&mut self) {
+        self.content_filter = None;
+    }
+
+    /// Sets a system message sent at the end of each request but never added to
+    /// the history, e.g. an agent's reminder to call a tool.
+    pub(crate) fn set_steering(&mut self, steering: Option<String>) {
+        self.steering = steering;
+    }
+
+    /// Sends the current conversation to the model and processes the response.
+    ///
+    /// This method sends the accumulated messages to the Ollama model, processes the
+    /// streaming response, and returns the final response object.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function that will be called with each chunk of the response
+    ///   as it is received. Use this for handling streaming responses.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OllamaResponse, Box<dyn Error>>` - The complete response from the model if successful,
+    ///   or an error if something went wrong. If the stream fails part way, the error is an
+    ///   `OllamaStreamError` holding the partial response. If the session is aborted, the
+    ///   response is truncated; see `abort`.
+    pub async fn update<F>(&mut self, mut callback: F) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        self.check_not_aborted()?;
+        self.trim_history();
+        let mut response = self.send(&mut callback).await?;
+        let mut text = response.text().unwrap_or_default().to_string();
+        let mut continuations = 0;
+
+        while continuations < self.max_continuations && response.done_reason() == Some("length") {
+            // Ask for the rest with the truncated answer in context, then drop both
+            // turns again so the history holds the answer as one message.
+            self.request.add_response(&response);
+            self.request
+                .add_message(json!({ "role": "user", "content": CONTINUE_PROMPT }));
+            let next = self.send(&mut callback).await;
+            self.request.pop_message();
+            self.request.pop_message();
+
+            response = next?;
+            text.push_str(response.text().unwrap_or_default());
+            continuations += 1;
+        }
+
+        if continuations > 0 {
+            response.set_text(&text);
+            response.set_continuations(continuations);
+        }
+
+        // An abort before the first chunk leaves nothing to record.
+        if !response.is_truncated() || response.text().is_some_and(|text| !text.is_empty()) {
+            self.record_response(&response);
+        }
+        Ok(response)
+    }
+
+    /// Like `update`, but stops reading the response once `deadline` has elapsed.
+    ///
+    /// At the deadline the HTTP request is cancelled and the text generated so far
+    /// is returned, flagged with `OllamaResponse::is_truncated`. A truncated response
+    /// with text is added to the history like a complete one. Automatic continuation
+    /// and resumption do not apply. Aborting the session stops reading the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The time budget for the whole request, from now.
+    /// * `callback` - A function that will be called with each chunk of the response
+    ///   as it is received.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OllamaResponse, Box<dyn Error>>` - The complete or truncated response,
+    ///   or an error if the request failed before the deadline.
+    pub async fn update_with_deadline<F>(
+        &mut self,
+        deadline: Duration,
+        mut callback: F,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        self.check_not_aborted()?;
+        let deadline = Instant::now() + deadline;
+        self.trim_history();
+        self.request.set_options(self.options.to_json()?);
+        self.request.set_stream(true);
+
+        // Reading stops at the deadline, when the session is aborted or when the
+        // content filter halts generation.
+        let abort = self.abort.clone();
+        let halt = OllamaAbortHandle::new();
+        let stop = async {
+            tokio::select! {
+                _ = sleep_until(deadline) => {}
+                _ = abort.aborted() => {}
+                _ = halt.aborted() => {}
+            }
+        };
+        tokio::pin!(stop);
+
+        let steered = self.push_steering();
+        let stream = tokio::select! {
+            stream = self.ollama.chat_stream(&self.request) => Some(stream),
+            _ = &mut stop => None,
+        };
+        if steered {
+            self.request.pop_message();
+        }
+        let Some(stream) = stream else {
+            return Ok(self.truncated_response(None));
+        };
+        let mut stream = stream?;
+        let mut filtered = self.content_filter.clone().map(FilteredStream::new);
+
+        loop {
+            let read = tokio::select! {
+                read = stream.read() => Some(read),
+                _ = &mut stop => None,
+            };
+            match read {
+                Some(Ok(Some(chunk))) => {
+                    if let Some(content) = chunk.text() {
+                        match &mut filtered {
+                            Some(filtered) => {
+                                if !filtered.push(content, &mut callback) {
+                                    halt.abort();
+                                }
+                            }
+                            None => callback(content),
+                        }
+                    }
+                }
+                Some(Ok(None)) => break,
+                Some(Err(error)) => {
+                    let error = OllamaStreamError::new(stream.response(), error).into();
+                    return Err(match &mut filtered {
+                        Some(filtered) => {
+                            filtered.finish(&mut callback);
+                            filter_partial(error, filtered)
+                        }
+                        None => error,
+                    });
+                }
+                None => {
+                    // Dropping the stream closes the connection, cancelling generation.
+                    let mut response = self.truncated_response(stream.response());
+                    drop(stream);
+                    if let Some(filtered) = &mut filtered {
+                        filtered.finish(&mut callback);
+                        apply_filtered(&mut response, filtered);
+                    }
+                    if response.text().is_some_and(|text| !text.is_empty()) {
+                        self.record_response(&response);
+                    }
+                    return Ok(response);
+                }
+            }
+        }
+
+        let mut response = stream
+            .response()
+            .ok_or("no response received from the Ollama server")?;
+        if let Some(filtered) = &mut filtered {
+            filtered.finish(&mut callback);
+            apply_filtered(&mut response, filtered);
+        }
+        self.record_response(&response);
+        Ok(response)
+    }
+
+    /// Classifies a text as one of a fixed set of labels.
+    ///
+    /// The request is sent on a fork of the session, so the conversation so far is
+    /// used as context but the history is left unchanged. The output is constrained
+    /// with a JSON schema whose `label` is an enum of `labels`; models that do not
+    /// honor the schema are asked again, up to `CLASSIFY_ATTEMPTS` times in all,
+    /// with a correction naming the allowed labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to classify.
+    /// * `labels` - The allowed labels; the result uses their spelling.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Classification, Box<dyn Error>>` - The chosen label and the reported
+    ///   confidence, or an error if the request failed or no valid label was given.
+    pub async fn classify(
+        &mut self,
+        text: &str,
+        labels: &[&str],
+    ) -> Result<Classification, Box<dyn Error>> {
+        if labels.is_empty() {
+            return Err("classify needs at least one label".into());
+        }
+
+        let mut fork = self.internal_fork();
+        fork.request.set_format(Classification::schema(labels));
+        fork.user(&Classification::prompt(text, labels));
+
+        let mut problem = String::new();
+        for _ in 0..CLASSIFY_ATTEMPTS {
+            let response = fork.update(|_| {}).await?;
+            match Classification::parse(response.text().unwrap_or_default(), labels) {
+                Ok(classification) => return Ok(classification),
+                Err(error) => problem = error,
+            }
+            fork.user(&format!(
+                "{problem}. Answer again with one of: {}.",
+                labels.join(", ")
+            ));
+        }
+
+        Err(format!("no valid label after {CLASSIFY_ATTEMPTS} attempts: {problem}").into())
+    }
+
+    /// Asks for an answer matching a JSON schema, asking again while it does not.
+    ///
+    /// Like `classify`, the request is sent on a fork of the session, leaving the
+    /// history unchanged, and the output is constrained with the schema. Each
+    /// answer is parsed and validated with `validate_json_schema`; when it fails,
+    /// the problems are sent back as a user message and the model tries again.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The question.
+    /// * `schema` - The JSON schema the answer must match.
+    /// * `max_attempts` - The number of answers to try, at least 1.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<JsonAnswer, Box<dyn Error>>` - The parsed answer and every attempt
+    ///   made, or an error if a request failed. If no answer matched the schema, the
+    ///   error is a `JsonRetryError` holding every attempt.
+    pub async fn ask_json_with_retries(
+        &mut self,
+        prompt: &str,
+        schema: JsonValue,
+        max_attempts: u32,
+    ) -> Result<JsonAnswer, Box<dyn Error>> {
+        let mut fork = self.internal_fork();
+        fork.request.set_format(schema.clone());
+        fork.user(prompt);
+
+        let mut attempts = Vec::new();
+        for _ in 0..max_attempts.max(1) {
+            let response = fork.update(|_| {}).await?;
+            let answer = response.text().unwrap_or_default().to_string();
+            let problem = match extract_json(&answer) {
+                Ok(value) => match validate_json_schema(&value, &schema) {
+                    Ok(()) => {
+                        attempts.push(JsonAttempt {
+                            answer,
+                            problem: None,
+                        });
+                        return Ok(JsonAnswer { value, attempts });
+                    }
+                    Err(problems) => format!("The answer does not match the schema: {problems}"),
+                },
+                Err(error) => format!("The answer is not valid JSON: {}", error.message()),
+            };
+
+            fork.user(&format!(
+                "{}. Answer again with JSON that matches the schema.",
+                problem.trim_end_matches('.')
+            ));
+            attempts.push(JsonAttempt {
+                answer,
+                problem: Some(problem),
+            });
+        }
+
+        Err(JsonRetryError { attempts }.into())
+    }
+
+    /// Translates a text into another language.
+    ///
+    /// Like `classify`, the request is sent on a fork of the session, leaving the
+    /// history unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to translate.
+    /// * `target_lang` - The language code to translate into, e.g. "fr" or "pt-BR".
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Box<dyn Error>>` - The translation, or an error if the language
+    ///   code is not valid or the request failed.
+    pub async fn translate(
+        &mut self,
+        text: &str,
+        target_lang: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        self.translate_with_glossary(text, target_lang, &[]).await
+    }
+
+    /// Translates a text, making the model use the given translations of some terms.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to translate.
+    /// * `target_lang` - The language code to translate into, e.g. "fr" or "pt-BR".
+    /// * `glossary` - Terms of the text paired with the translations they must be given.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Box<dyn Error>>` - The translation, or an error if the language
+    ///   code is not valid or the request failed.
+    pub async fn translate_with_glossary(
+        &mut self,
+        text: &str,
+        target_lang: &str,
+        glossary: &[(&str, &str)],
+    ) -> Result<String, Box<dyn Error>> {
+        let language = LanguageCode::parse(target_lang)?;
+        self.ask(&language.translation_prompt(text, glossary)).await
+    }
+
+    /// Generates a short title for the conversation, e.g. for a chat list.
+    ///
+    /// The title is based on the first exchanges, sent as text on a fork of the
+    /// session, so the history is left unchanged. Quotes, a "Title:" label and end
+    /// punctuation are removed, and long titles are cut at a word boundary.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, Box<dyn Error>>` - The title, or an error if the conversation
+    ///   has no user or assistant messages, or the request failed.
+    pub async fn generate_title(&mut self) -> Result<String, Box<dyn Error>> {
+        let conversation = Transcript::from_messages(
+            self.messages()
+                .iter()
+                .filter(|message| matches!(message["role"].as_str(), Some("user" | "assistant")))
+                .take(TITLE_MESSAGES),
+        )
+        .to_text();
+        if conversation.trim().is_empty() {
+            return Err("there is no conversation to title yet".into());
+        }
+
+        let mut fork = self.internal_fork();
+        fork.restore(OllamaHistory::default());
+        let answer = fork
+            .ask(&format!("{TITLE_PROMPT}\n\nConversation:\n{conversation}"))
+            .await?;
+        Ok(clean_title(&answer))
+    }
+
+    /// Sends one prompt on a fork of the session and returns the trimmed answer.
+    ///
+    /// The history is left unchanged, which suits one-off tasks like translating or
+    /// summarizing that use the session only for its model and settings.
+    pub(crate) async fn ask(&mut self, prompt: &str) -> Result<String, Box<dyn Error>> {
+        let mut fork = self.internal_fork();
+        fork.user(prompt);
+        let response = fork.update(|_| {}).await?;
+        Ok(response.text().unwrap_or_default().trim().to_string())
+    }
+
+    /// Like `ask`, but constrains the answer to a JSON schema and parses it.
+    pub(crate) async fn ask_json(
+        &mut self,
+        prompt: &str,
+        schema: JsonValue,
+    ) -> Result<JsonValue, Box<dyn Error>> {
+        let mut fork = self.internal_fork();
+        fork.request.set_format(schema);
+        fork.user(prompt);
+        let response = fork.update(|_| {}).await?;
+        Ok(response.json()?)
+    }
+
+    /// Returns an error if the session was aborted.
+    fn check_not_aborted(&self) -> Result<(), Box<dyn Error>> {
+        if self.is_aborted() {
+            return Err("the session was aborted; call clear_abort to continue".into());
+        }
+        Ok(())
+    }
+
+    /// Flags a partial response as truncated, creating an empty one if no chunk arrived.
+    fn truncated_response(&self, partial: Option<OllamaResponse>) -> OllamaResponse {
+        let mut response = partial.unwrap_or_else(|| {
+            let empty = json!({
+                "model": self.model(),
+                "message": { "role": "assistant", "content": "" },
+                "done": false
+            });
+            let mut empty = OllamaResponse::from_json(empty).expect("empty response is valid");
+            empty.set_metadata(self.metadata().clone());
+            empty
+        });
+        response.set_truncated(true);
+        response
+    }
+
+    /// Adds a response to the history and ends the turn.
+    ///
+    /// The assistant hooks see the message before it is added, and the turn
+    /// completion hooks the response as it was recorded.
+    fn record_response(&mut self, response: &OllamaResponse) {
+        let mut response = response.clone();
+        if let Some(message) = response.message_mut() {
+            self.hooks.run_assistant(message);
+        }
+        // A dry run's reply is the request itself, not part of the conversation.
+        if self.ollama.dry_run() {
+            self.freeze_history();
+            self.hooks.run_turn_complete(&response);
+            return;
+        }
+        self.request.add_response(&response);
+        if response.message().is_some() {
+            self.responses
+                .push((self.messages().len() - 1, response.clone()));
+        }
+        self.freeze_history();
+        self.hooks.run_turn_complete(&response);
+    }
+
+    /// Moves the messages added since the last turn into a shared segment.
+    fn freeze_history(&mut self) {
+        if let Some(history) = self.request.messages_mut() {
+            history.freeze();
+        }
+    }
+
+    /// Sends one chat request with the steering message, if any, appended for
+    /// this request only.
+    async fn send<F>(&mut self, callback: &mut F) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        let steered = self.push_steering();
+        let result = self.send_filtered(callback).await;
+        if steered {
+            self.request.pop_message();
+        }
+        result
+    }
+
+    /// Appends the steering message to the request, returning `true` if there is one.
+    fn push_steering(&mut self) -> bool {
+        let Some(steering) = &self.steering else {
+            return false;
+        };
+        self.request
+            .add_message(json!({ "role": "system", "content": steering }));
+        true
+    }
+
+    /// Sends one chat request, passing the streamed text through the content filter.
+    async fn send_filtered<F>(&mut self, callback: &mut F) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        let halt = OllamaAbortHandle::new();
+        let Some(filter) = self.content_filter.clone() else {
+            return self.send_unfiltered(callback, &halt).await;
+        };
+
+        let mut filtered = FilteredStream::new(filter);
+        let result = {
+            let mut output = |chunk: &str| {
+                if !filtered.push(chunk, callback) {
+                    halt.abort();
+                }
+            };
+            self.send_unfiltered(&mut output, &halt).await
+        };
+        filtered.finish(callback);
+        match result {
+            Ok(mut response) => {
+                apply_filtered(&mut response, &filtered);
+                Ok(response)
+            }
+            Err(error) => Err(filter_partial(error, &filtered)),
+        }
+    }
+
+    /// Sends one chat request with the current history and options.
+    ///
+    /// A stream that fails part way is resumed up to `max_resumes` times, with the
+    /// partial text as an assistant prefix for the model to continue. If the session
+    /// is aborted or `halt` is triggered, the text received so far is returned as a
+    /// truncated response.
+    async fn send_unfiltered<F>(
+        &mut self,
+        callback: &mut F,
+        halt: &OllamaAbortHandle,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        // Apply options to the request
+        self.request.set_options(self.options.to_json()?);
+        self.request.set_stream(true);
+
+        let abort = self.abort.clone();
+        let mut prefix = String::new();
+        let mut resumes = 0;
+        loop {
+            // Scoped so no error, which is not `Send`, is held across the backoff.
+            prefix = {
+                let mut received = String::new();
+                let result = tokio::select! {
+                    result = self.ollama.chat(&self.request, |response| {
+                        // Extract the response content and pass it to the callback, if available.
+                        if let Some(content) = response.text() {
+                            received.push_str(content);
+                            callback(content);
+                        }
+                    }) => Some(result),
+                    _ = abort.aborted() => None,
+                    _ = halt.aborted() => None,
+                };
+                if !prefix.is_empty() {
+                    self.request.pop_message();
+                }
+
+                // Dropping the request closes the connection, cancelling generation.
+                let Some(result) = result else {
+                    prefix.push_str(&received);
+                    let mut response = self.truncated_response(None);
+                    response.set_text(&prefix);
+                    return Ok(response);
+                };
+
+                let error = match result {
+                    Ok(mut response) => {
+                        if !prefix.is_empty() {
+                            prefix.push_str(response.text().unwrap_or_default());
+                            response.set_text(&prefix);
+                        }
+                        return Ok(response);
+                    }
+                    Err(error) => error,
+                };
+
+                // A resume that fails to connect still returns the text so far.
+                let mut interrupted = match error.downcast::<OllamaStreamError>() {
+                    Ok(interrupted) => interrupted,
+                    Err(error) => {
+                        if prefix.is_empty() {
+                            return Err(error);
+                        }
+                        Box::new(OllamaStreamError::new(None, error))
+                    }
+                };
+                interrupted.prepend_text(&prefix);
+                if resumes >= self.max_resumes || interrupted.partial_text().is_empty() {
+                    return Err(interrupted);
+                }
+
+                interrupted.partial_text().to_string()
+            };
+            let backoff = tokio::select! {
+                _ = tokio::time::sleep(RESUME_BACKOFF * 2u32.pow(resumes)) => true,
+                _ = abort.aborted() => false,
+                _ = halt.aborted() => false,
+            };
+            if !backoff {
+                let mut response = self.truncated_response(None);
+                response.set_text(&prefix);
+                return Ok(response);
+            }
+            resumes += 1;
+            self.request
+                .add_message(json!({ "role": "assistant", "content": prefix }));
+        }
+    }
+}
+
+/// Replaces the text of a response with what the content filter let through.
+fn apply_filtered(response: &mut OllamaResponse, filtered: &FilteredStream) {
+    response.set_text(filtered.text());
+    if filtered.is_halted() {
+        response.set_truncated(true);
+        response.set_done_reason(CONTENT_FILTER_DONE_REASON);
+    }
+}
+
+/// Replaces the partial text of an interrupted stream with what the content
+/// filter let through, so the raw text does not reach the caller with the error.
+fn filter_partial(error: Box<dyn Error>, filtered: &FilteredStream) -> Box<dyn Error> {
+    match error.downcast::<OllamaStreamError>() {
+        Ok(mut interrupted) => {
+            if let Some(partial) = interrupted.partial_mut() {
+                apply_filtered(partial, filtered);
+            }
+            interrupted
+        }
+        Err(error) => error,
+    }
+}
+
+/// Reduces a model's answer to a bare title of at most `MAX_TITLE_CHARS` characters.
+fn clean_title(answer: &str) -> String {
+    let line = answer
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let line = line.trim_matches('*');
+    let line = line.strip_prefix("Title:").unwrap_or(line);
+    let quote = |c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '*' | '`');
+    let title = line
+        .trim_start_matches(quote)
+        .trim_end_matches(|c: char| quote(c) || matches!(c, '.' | '!' | ':' | ';' | ','));
+
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    let cut: String = title.chars().take(MAX_TITLE_CHARS + 1).collect();
+    match cut.rfind(char::is_whitespace) {
+        Some(end) => cut[..end]
+            .trim_end_matches([',', ':', ';', '-', ' '])
+            .to_string(),
+        None => cut.chars().take(MAX_TITLE_CHARS).collect(),
+    }
+}
+
+// ===
+// TESTS: OllamaSession
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{
+        MockServer, chat_body, interrupted_chat_body, ndjson, stalled_chat_body,
+    };
+    use serde_json::json;
+
+    fn truncated_body(text: &str) -> String {
+        ndjson(&[
+            json!({
+                "model": "mock",
+                "message": { "role": "assistant", "content": text },
+                "done": false
+            }),
+            json!({
+                "model": "mock",
+                "message": { "role": "assistant", "content": "" },
+                "done": true,
+                "done_reason": "length"
+            }),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_auto_continue_stitches_output() {
+        let server = MockServer::start(vec![
+            truncated_body("The quick brown"),
+            truncated_body(" fox jumps"),
+            chat_body(&[" over the dog."]),
+        ])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.set_auto_continue(3);
+        session.user("Say the pangram.");
+
+        let mut streamed = String::new();
+        let response = session
+            .update(|chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+
+        let full = "The quick brown fox jumps over the dog.";
+        assert_eq!(response.text(), Some(full));
+        assert_eq!(streamed, full);
+        assert!(response.is_continued());
+        assert_eq!(response.continuations(), 2);
+
+        // The continuation request carries the truncated answer and the continue prompt.
+        let second = &server.requests()[1]["messages"];
+        assert_eq!(second[1]["content"], "The quick brown");
+        assert_eq!(second[2]["content"], CONTINUE_PROMPT);
+
+        // The history keeps the stitched answer as a single message.
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(session.messages()[1]["content"], full);
+    }
+
+    #[tokio::test]
+    async fn test_auto_resume_after_interrupted_stream() {
+        let server = MockServer::start(vec![
+            interrupted_chat_body(&["Hello", ", wor"]),
+            chat_body(&["ld!"]),
+        ])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.set_auto_resume(1);
+        session.user("Greet the world.");
+
+        let mut streamed = String::new();
+        let response = session
+            .update(|chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+        assert_eq!(response.text(), Some("Hello, world!"));
+        assert_eq!(streamed, "Hello, world!");
+
+        // The resumed request ends with the partial answer for the model to continue.
+        let second = &server.requests()[1]["messages"];
+        assert_eq!(
+            second[1],
+            json!({ "role": "assistant", "content": "Hello, wor" })
+        );
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(session.messages()[1]["content"], "Hello, world!");
+    }
+
+    #[tokio::test]
+    async fn test_failed_resume_keeps_partial_text() {
+        // The resumed request gets an empty body, so it fails without a stream.
+        let server = MockServer::start(vec![interrupted_chat_body(&["Hello", ", wor"])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.set_auto_resume(1);
+        session.user("Greet the world.");
+
+        let error = session.update(|_| {}).await.unwrap_err();
+        let error = error.downcast_ref::<OllamaStreamError>().unwrap();
+        assert_eq!(error.partial_text(), "Hello, wor");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_forwards_seed() {
+        let server = MockServer::start(vec![chat_body(&["Same answer."])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.apply_preset("deterministic").unwrap();
+        session.user("Pick a number.");
+        session.update(|_| {}).await.unwrap();
+
+        let options = &server.requests()[0]["options"];
+        assert_eq!(options["seed"], 42);
+        assert_eq!(options["temperature"], 0.0);
+    }
+
+    #[test]
+    fn test_apply_preset_clears_the_previous_preset() {
+        let mut session = OllamaSession::remote("mock", "127.0.0.1:9");
+        session.set_context_window_size(8192);
+        session.apply_preset("deterministic").unwrap();
+        assert_eq!(session.options().seed(), Some(42));
+
+        session.apply_preset("creative").unwrap();
+        let options = session.options();
+        assert_eq!(options.seed(), None);
+        assert_eq!(options.temperature(), Some(1.1));
+        assert_eq!(options.num_ctx(), Some(8192));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_keeps_replies_out_of_the_history() {
+        let mut session = OllamaSession::remote("mock", "127.0.0.1:9");
+        session.set_dry_run(true);
+        session.user("Hi.");
+        let response = session.update(|_| {}).await.unwrap();
+        let payload: JsonValue = serde_json::from_str(response.text().unwrap()).unwrap();
+        assert_eq!(payload["messages"][0]["content"], "Hi.");
+
+        assert_eq!(session.messages().len(), 1);
+        assert_eq!(session.messages()[0]["role"], "user");
+    }
+
+    #[tokio::test]
+    async fn test_update_rejects_non_finite_options() {
+        let server = MockServer::start(vec![chat_body(&["Unreachable."])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.options().set_temperature(f32::NAN);
+        session.user("Hi.");
+        assert!(session.update(|_| {}).await.is_err());
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_stream_returns_partial_text() {
+        let server = MockServer::start(vec![interrupted_chat_body(&["Hello", ", wor"])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Greet the world.");
+
+        let error = session.update(|_| {}).await.err().unwrap();
+        let interrupted = error.downcast_ref::<OllamaStreamError>().unwrap();
+        assert_eq!(interrupted.partial_text(), "Hello, wor");
+        assert_eq!(session.messages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_with_deadline_returns_partial_text() {
+        let server = MockServer::start(vec![stalled_chat_body(&["Hello", ", wor"])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Greet the world.");
+
+        let mut streamed = String::new();
+        let response = session
+            .update_with_deadline(Duration::from_millis(200), |chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+
+        assert!(response.is_truncated());
+        assert_eq!(response.text(), Some("Hello, wor"));
+        assert_eq!(streamed, "Hello, wor");
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(session.messages()[1]["content"], "Hello, wor");
+    }
+
+    #[tokio::test]
+    async fn test_update_with_deadline_completes_in_time() {
+        let server = MockServer::start(vec![chat_body(&["Hello!"])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Greet the world.");
+
+        let response = session
+            .update_with_deadline(Duration::from_secs(10), |_| {})
+            .await
+            .unwrap();
+        assert!(!response.is_truncated());
+        assert_eq!(response.text(), Some("Hello!"));
+        assert_eq!(session.messages().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_abort_stops_update_and_keeps_partial_text() {
+        let server = MockServer::start(vec![
+            stalled_chat_body(&["Once upon", " a time"]),
+            chat_body(&["A fork."]),
+            chat_body(&["The end."]),
+        ])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Tell me a story.");
+        let handle = session.abort_handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            handle.abort();
+        });
+
+        let mut streamed = String::new();
+        let response = session
+            .update(|chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+        assert!(response.is_truncated());
+        assert_eq!(response.text(), Some("Once upon a time"));
+        assert_eq!(streamed, "Once upon a time");
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(session.messages()[1]["content"], "Once upon a time");
+
+        assert!(session.is_aborted());
+        session.user("Go on.");
+        assert!(session.update(|_| {}).await.is_err());
+        assert!(session.fork().update(|_| {}).await.is_ok());
+
+        session.clear_abort();
+        assert!(!session.is_aborted());
+        let response = session
+            .update_with_deadline(Duration::from_secs(10), |_| {})
+            .await
+            .unwrap();
+        assert_eq!(response.text(), Some("The end."));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_output_without_auto_continue() {
+        let server = MockServer::start(vec![truncated_body("The quick")]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Say the pangram.");
+
+        let response = session.update(|_| {}).await.unwrap();
+        assert_eq!(response.text(), Some("The quick"));
+        assert!(!response.is_continued());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fork_and_restore() {
+        let server = MockServer::start(vec![chat_body(&["Hi!"]), chat_body(&["Bye!"])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Hello");
+        session.update(|_| {}).await.unwrap();
+        let checkpoint = session.checkpoint();
+
+        let mut fork = session.fork();
+        fork.user("Goodbye");
+        fork.update(|_| {}).await.unwrap();
+        assert_eq!(fork.messages().len(), 4);
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(server.requests()[1]["messages"][1]["content"], "Hi!");
+
+        session.user("Something else");
+        session.restore(checkpoint);
+        assert_eq!(session.messages().len(), 2);
+        assert_eq!(session.messages()[1]["content"], "Hi!");
+    }
+
+    #[test]
+    fn test_restore_resets_pinned_messages() {
+        let mut session = OllamaSession::remote("mock", "127.0.0.1:9");
+        session.user("An example.");
+        session.pin(0);
+        session.system("Be brief.");
+        let checkpoint = session.checkpoint();
+        assert_eq!(checkpoint.pinned(), &BTreeSet::from([0, 1]));
+
+        session.unpin(0);
+        session.unpin(1);
+        session.restore(checkpoint);
+        assert!(session.is_pinned(0) && session.is_pinned(1));
+
+        let mut history = OllamaHistory::new();
+        history.push(json!({ "role": "user", "content": "Hi." }));
+        history.push(json!({ "role": "system", "content": "Be brief." }));
+        session.restore(history);
+        assert!(!session.is_pinned(0) && session.is_pinned(1));
+    }
+
+    #[tokio::test]
+    async fn test_classify_retries_invalid_label() {
+        let server = MockServer::start(vec![
+            chat_body(&[r#"{"label": "angry"}"#]),
+            chat_body(&[r#"{"label": "Negative", "confidence": 0.7}"#]),
+        ])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let labels = ["positive", "negative", "neutral"];
+        let classification = session
+            .classify("The food was cold.", &labels)
+            .await
+            .unwrap();
+
+        assert_eq!(classification.label, "negative");
+        assert_eq!(classification.confidence, Some(0.7));
+        assert_eq!(server.requests().len(), 2);
+        assert_eq!(
+            server.requests()[0]["format"]["properties"]["label"]["enum"],
+            json!(labels)
+        );
+        assert_eq!(
+            server.requests()[1]["messages"].as_array().unwrap().len(),
+            3
+        );
+        assert!(session.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ask_json_with_retries() {
+        let server = MockServer::start(vec![
+            chat_body(&["Sure! {\"city\": \"Paris\""]),
+            chat_body(&[r#"{"city": "Paris"}"#]),
+            chat_body(&[r#"{"city": "Paris", "days": 3}"#]),
+        ])
+        .await;
+        let schema = json!({
+            "type": "object",
+            "properties": { "city": { "type": "string" }, "days": { "type": "integer" } },
+            "required": ["city", "days"]
+        });
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let answer = session
+            .ask_json_with_retries("Plan a trip.", schema.clone(), 3)
+            .await
+            .unwrap();
+
+        assert_eq!(answer.value, json!({ "city": "Paris", "days": 3 }));
+        assert_eq!(answer.attempts.len(), 3);
+        assert!(
+            answer.attempts[0]
+                .problem
+                .as_ref()
+                .unwrap()
+                .contains("not valid JSON")
+        );
+        assert!(
+            answer.attempts[1]
+                .problem
+                .as_ref()
+                .unwrap()
+                .contains("missing required property 'days'")
+        );
+        assert_eq!(answer.attempts[2].problem, None);
+
+        let requests = server.requests();
+        assert_eq!(requests[0]["format"], schema);
+        let correction = &requests[2]["messages"][4]["content"];
+        let correction = correction.as_str().unwrap();
+        assert!(correction.contains("does not match the schema"));
+        assert!(!correction.contains(".."), "{correction}");
+        assert!(session.messages().is_empty());
+
+        let server = MockServer::start(vec![chat_body(&["no"])]).await;
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let error = session
+            .ask_json_with_retries("Plan a trip.", schema, 1)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("after 1 attempts"), "{error}");
+        let error = error.downcast::<JsonRetryError>().unwrap();
+        assert_eq!(error.attempts.len(), 1);
+        assert_eq!(error.attempts[0].answer, "no");
+    }
+
+    #[tokio::test]
+    async fn test_translate_with_glossary() {
+        let server = MockServer::start(vec![chat_body(&[" Öffne die Datei. \n"])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let translation = session
+            .translate_with_glossary("Open the file.", "de", &[("file", "Datei")])
+            .await
+            .unwrap();
+
+        assert_eq!(translation, "Öffne die Datei.");
+        let prompt = server.requests()[0]["messages"][0]["content"].clone();
+        assert!(prompt.as_str().unwrap().contains("- file => Datei"));
+        assert!(session.messages().is_empty());
+
+        assert!(session.translate("Hello", "klingon").await.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_breakdown_uses_eval_count() {
+        let server = MockServer::start(vec![chat_body(&["Paris."])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.system("Be brief.");
+        session.user("What is the capital of France?");
+        session.update(|_| {}).await.unwrap();
+
+        let breakdown = session.token_breakdown();
+        assert_eq!(breakdown.messages().len(), 3);
+        assert!(breakdown.messages()[0].estimated);
+        assert_eq!(breakdown.messages()[2].tokens, 10);
+        assert!(!breakdown.messages()[2].estimated);
+        assert_eq!(breakdown.by_role()["assistant"], 10);
+    }
+
+    #[tokio::test]
+    async fn test_explain_next_request_matches_what_update_sends() {
+        let server = MockServer::start(vec![chat_body(&["Four."])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.system("Answer with one word.");
+        for turn in 0..10 {
+            session.user(&format!("Old question number {turn}."));
+            session.assistant("An old answer.");
+        }
+        session.user("What is two plus two?");
+        session.set_history_token_limit(60);
+        session.options().set_temperature(0.0);
+
+        let report = session.explain_next_request().unwrap();
+        assert_eq!(session.messages().len(), 22);
+        assert_eq!(report.model.as_deref(), Some("mock"));
+        assert_eq!(report.messages + report.evicted, 22);
+        assert!(report.evicted > 0);
+        assert_eq!(report.options["temperature"], 0.0);
+        assert!(
+            report
+                .to_string()
+                .contains("evicted to fit the history limit")
+        );
+
+        session.update(|_| {}).await.unwrap();
+        assert_eq!(server.requests()[0], report.payload);
+    }
+
+    #[tokio::test]
+    async fn test_trimming_keeps_pinned_messages() {
+        let server = MockServer::start(vec![chat_body(&["Four."])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.system("Answer with one word.");
+        session.user("Example: what is one plus one?");
+        session.assistant("Two.");
+        session.pin(1);
+        session.pin(2);
+        for turn in 0..10 {
+            session.user(&format!(
+                "Old question number {turn}, which no longer matters."
+            ));
+            session.assistant("An old answer that no longer matters either.");
+        }
+        session.user("What is two plus two?");
+        session.set_history_token_limit(60);
+
+        session.update(|_| {}).await.unwrap();
+
+        let sent = server.requests()[0]["messages"].as_array().unwrap().clone();
+        assert_eq!(sent[0]["content"], "Answer with one word.");
+        assert_eq!(sent[1]["content"], "Example: what is one plus one?");
+        assert_eq!(sent[2]["content"], "Two.");
+        assert_eq!(sent.last().unwrap()["content"], "What is two plus two?");
+        assert!(sent.len() < 25);
+        assert!(TokenBreakdown::from_messages(&sent).total() <= 60);
+
+        // The pins moved with their messages, and the answer was recorded after them.
+        assert!(session.is_pinned(0) && session.is_pinned(1) && session.is_pinned(2));
+        assert_eq!(session.messages().len(), sent.len() + 1);
+    }
+
+    #[tokio::test]
+    async fn test_hooks_change_messages_and_see_turns() {
+        let server = MockServer::start(vec![
+            chat_body(&["Your card is ", "1234."]),
+            chat_body(&["{\"label\": \"billing\"}"]),
+        ])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        fn redact(message: &mut OllamaMessage) {
+            let content = message
+                .content()
+                .unwrap_or_default()
+                .replace("1234", "****");
+            message.set_content(&content);
+        }
+        session.on_user(redact);
+        session.on_assistant(redact);
+        let turns = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = turns.clone();
+        session.on_turn_complete(move |response| {
+            let text = response.text().unwrap_or_default().to_string();
+            recorded.lock().unwrap().push(text);
+        });
+
+        session.user("My card is 1234.");
+        let response = session.update(|_| {}).await.unwrap();
+
+        // The model sees and the history keeps the redacted messages, while the
+        // response is returned unchanged.
+        assert_eq!(
+            server.requests()[0]["messages"][0]["content"],
+            "My card is ****."
+        );
+        assert_eq!(response.text(), Some("Your card is 1234."));
+        assert_eq!(session.messages()[1]["content"], "Your card is ****.");
+        assert_eq!(*turns.lock().unwrap(), ["Your card is ****."]);
+
+        // Requests the session makes on its own behalf do not run the hooks.
+        session
+            .classify("Card 1234", &["billing", "other"])
+            .await
+            .unwrap();
+        assert_eq!(turns.lock().unwrap().len(), 1);
+        let prompt = server.requests()[1]["messages"][2]["content"].to_string();
+        assert!(prompt.contains("1234"), "{prompt}");
+    }
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(
+            clean_title("\"Rust Lifetimes Explained.\""),
+            "Rust Lifetimes Explained"
+        );
+        assert_eq!(clean_title("\n**Title:** Paris trip"), "Paris trip");
+        assert_eq!(clean_title("# Title: 'Baking bread'\nMore"), "Baking bread");
+
+        let long =
+            "Planning a week long hiking trip through the Scottish Highlands in early spring";
+        let title = clean_title(long);
+        assert_eq!(
+            title,
+            "Planning a week long hiking trip through the Scottish"
+        );
+        assert!(title.chars().count() <= MAX_TITLE_CHARS);
+    }
+
+    #[tokio::test]
+    async fn test_generate_title_from_first_exchanges() {
+        let server = MockServer::start(vec![chat_body(&["\"Sourdough ", "Starter Tips\"."])]).await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        assert!(session.generate_title().await.is_err());
+
+        session.system("Be brief.");
+        session.user("How do I feed a sourdough starter?");
+        session.assistant("Equal parts flour and water, daily.");
+        for turn in 0..3 {
+            session.user(&format!("Later question {turn}"));
+            session.assistant("Later answer");
+        }
+
+        let title = session.generate_title().await.unwrap();
+        assert_eq!(title, "Sourdough Starter Tips");
+        assert_eq!(session.messages().len(), 9);
+
+        let sent = server.requests()[0]["messages"].as_array().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        let prompt = sent[0]["content"].as_str().unwrap();
+        assert!(prompt.starts_with(TITLE_PROMPT));
+        assert!(prompt.contains("feed a sourdough starter") && prompt.contains("Later question 0"));
+        assert!(!prompt.contains("Be brief") && !prompt.contains("Later question 1"));
+    }
+
+    #[cfg(feature = "pattern-filter")]
+    #[tokio::test]
+    async fn test_content_filter_masks_and_halts() {
+        use crate::{FilterMode, PatternFilter};
+
+        let server = MockServer::start(vec![
+            chat_body(&["Well, sh", "it happens."]),
+            chat_body(&["Sure: pass", "word=hunter2 ", "and more."]),
+        ])
+        .await;
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let mut filter = PatternFilter::profanity(FilterMode::Mask);
+        filter.add_pattern(r"\bpassword=\S+").unwrap();
+        session.set_content_filter(filter);
+
+        session.user("Hi");
+        let mut streamed = String::new();
+        let response = session
+            .update(|chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+        assert_eq!(streamed, "Well, **** happens.");
+        assert_eq!(response.text(), Some("Well, **** happens."));
+        assert_eq!(session.messages()[1]["content"], "Well, **** happens.");
+
+        let mut filter = PatternFilter::new(FilterMode::Halt);
+        filter.add_pattern(r"\bpassword=\S+").unwrap();
+        session.set_content_filter(filter);
+        session.user("What is the password?");
+        let mut streamed = String::new();
+        let response = session
+            .update(|chunk| streamed.push_str(chunk))
+            .await
+            .unwrap();
+        assert_eq!(streamed, "Sure: ");
+        assert!(response.is_truncated());
+        assert_eq!(response.done_reason(), Some(CONTENT_FILTER_DONE_REASON));
+        assert_eq!(session.messages()[3]["content"], "Sure: ");
+    }
+
+    #[cfg(feature = "pattern-filter")]
+    #[tokio::test]
+    async fn test_content_filter_applies_to_interrupted_streams() {
+        use crate::{FilterMode, PatternFilter};
+
+        let server = MockServer::start(vec![
+            interrupted_chat_body(&["Well, sh", "it happens ", "and"]),
+            interrupted_chat_body(&["Oh sh", "it."]),
+        ])
+        .await;
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.set_content_filter(PatternFilter::profanity(FilterMode::Mask));
+
+        session.user("Hi");
+        let mut streamed = String::new();
+        let error = session
+            .update(|chunk| streamed.push_str(chunk))
+            .await
+            .unwrap_err();
+        let error = error.downcast_ref::<OllamaStreamError>().unwrap();
+        assert_eq!(error.partial_text(), "Well, **** happens and");
+        assert_eq!(streamed, "Well, **** happens and");
+
+        let error = session
+            .update_with_deadline(Duration::from_secs(5), |_| {})
+            .await
+            .unwrap_err();
+        let error = error.downcast_ref::<OllamaStreamError>().unwrap();
+        assert_eq!(error.partial_text(), "Oh ****.");
+    }
+
+    #[tokio::test]
+    async fn test_metadata_reaches_responses_and_hooks() {
+        let server = MockServer::start(vec![chat_body(&["Hi!"])]).await;
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.set_metadata("request_id", "req-1");
+        session.set_metadata("user_id", "u-9");
+        session.remove_metadata("user_id");
+
+        let seen = Arc::new(std::sync::Mutex::new(None));
+        let recorded = seen.clone();
+        session.on_turn_complete(move |response| {
+            *recorded.lock().unwrap() = response.metadata().get("request_id").cloned();
+        });
+
+        session.user("Hello");
+        let response = session.update(|_| {}).await.unwrap();
+        assert_eq!(response.metadata().len(), 1);
+        assert_eq!(seen.lock().unwrap().as_deref(), Some("req-1"));
+        assert!(server.requests()[0].get("metadata").is_none());
+        assert_eq!(session.metadata()["request_id"], "req-1");
     }
 }
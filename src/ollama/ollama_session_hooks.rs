@@ -0,0 +1,79 @@
+use crate::{OllamaMessage, OllamaResponse};
+use std::fmt;
+use std::sync::Arc;
+
+/// A hook called with a message before it is added to the history.
+pub type OllamaMessageHook = Arc<dyn Fn(&mut OllamaMessage) + Send + Sync>;
+
+/// A hook called with the response of each completed turn.
+pub type OllamaTurnHook = Arc<dyn Fn(&OllamaResponse) + Send + Sync>;
+
+// ===
+// STRUCT: OllamaSessionHooks
+// ===
+
+/// The lifecycle hooks registered on an `OllamaSession`.
+///
+/// Hooks run in the order they were added. They are shared by clones and forks
+/// of the session, but not by the forks it makes internally, e.g. for `classify`.
+#[derive(Clone, Default)]
+pub struct OllamaSessionHooks {
+    user: Vec<OllamaMessageHook>,
+    assistant: Vec<OllamaMessageHook>,
+    turn_complete: Vec<OllamaTurnHook>,
+}
+
+impl OllamaSessionHooks {
+    /// Creates an empty set of hooks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a hook called with each user message before it is added.
+    pub fn add_user(&mut self, hook: OllamaMessageHook) -> &mut Self {
+        self.user.push(hook);
+        self
+    }
+
+    /// Adds a hook called with each assistant message before it is added.
+    pub fn add_assistant(&mut self, hook: OllamaMessageHook) -> &mut Self {
+        self.assistant.push(hook);
+        self
+    }
+
+    /// Adds a hook called with the response of each completed turn.
+    pub fn add_turn_complete(&mut self, hook: OllamaTurnHook) -> &mut Self {
+        self.turn_complete.push(hook);
+        self
+    }
+
+    /// Returns `true` if no hooks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.user.is_empty() && self.assistant.is_empty() && self.turn_complete.is_empty()
+    }
+
+    /// Runs the user message hooks.
+    pub(crate) fn run_user(&self, message: &mut OllamaMessage) {
+        self.user.iter().for_each(|hook| hook(message));
+    }
+
+    /// Runs the assistant message hooks.
+    pub(crate) fn run_assistant(&self, message: &mut OllamaMessage) {
+        self.assistant.iter().for_each(|hook| hook(message));
+    }
+
+    /// Runs the turn completion hooks.
+    pub(crate) fn run_turn_complete(&self, response: &OllamaResponse) {
+        self.turn_complete.iter().for_each(|hook| hook(response));
+    }
+}
+
+impl fmt::Debug for OllamaSessionHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OllamaSessionHooks")
+            .field("user", &self.user.len())
+            .field("assistant", &self.assistant.len())
+            .field("turn_complete", &self.turn_complete.len())
+            .finish()
+    }
+}
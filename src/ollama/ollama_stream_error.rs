@@ -0,0 +1,117 @@
+use crate::OllamaResponse;
+use std::error::Error;
+use std::fmt;
+
+// ===
+// STRUCT: OllamaStreamError
+// ===
+
+/// A response stream that failed part way, e.g. because the server restarted or
+/// the connection was reset.
+///
+/// Returned, boxed, by `Ollama::generate`, `Ollama::chat` and the session `update`
+/// methods, so the text generated before the failure is not lost. Recover it with
+/// `downcast_ref`:
+///
+/// ```no_run
+/// # async fn example(session: &mut ollie_rs::OllamaSession) {
+/// use ollie_rs::OllamaStreamError;
+///
+/// if let Err(error) = session.update(|_| {}).await
+///     && let Some(interrupted) = error.downcast_ref::<OllamaStreamError>()
+/// {
+///     println!("partial answer: {}", interrupted.partial_text());
+/// }
+/// # }
+/// ```
+pub struct OllamaStreamError {
+    partial: Option<OllamaResponse>,
+    source: Box<dyn Error>,
+}
+
+impl OllamaStreamError {
+    /// Creates an error from the response accumulated so far and the error that ended the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `partial` - The response built from the chunks read before the failure, if any.
+    /// * `source` - The error that ended the stream.
+    ///
+    /// # Returns
+    ///
+    /// A new `OllamaStreamError`.
+    pub fn new(partial: Option<OllamaResponse>, source: Box<dyn Error>) -> Self {
+        Self { partial, source }
+    }
+
+    /// Returns the response accumulated before the failure, if any chunk was read.
+    pub fn partial(&self) -> Option<&OllamaResponse> {
+        self.partial.as_ref()
+    }
+
+    /// Consumes the error and returns the partial response, if any.
+    pub fn into_partial(self) -> Option<OllamaResponse> {
+        self.partial
+    }
+
+    /// Returns the text generated before the failure, or an empty string.
+    pub fn partial_text(&self) -> &str {
+        self.partial
+            .as_ref()
+            .and_then(|response| response.text())
+            .unwrap_or_default()
+    }
+
+    /// Returns the partial response for changes, e.g. by a content filter.
+    #[cfg(feature = "transport")]
+    pub(crate) fn partial_mut(&mut self) -> Option<&mut OllamaResponse> {
+        self.partial.as_mut()
+    }
+
+    /// Prepends text generated by earlier attempts to the partial response.
+    #[cfg(feature = "transport")]
+    pub(crate) fn prepend_text(&mut self, prefix: &str) {
+        if prefix.is_empty() {
+            return;
+        }
+
+        let text = format!("{prefix}{}", self.partial_text());
+        match &mut self.partial {
+            Some(partial) => partial.set_text(&text),
+            None => {
+                let message =
+                    serde_json::json!({ "message": { "role": "assistant", "content": text } });
+                self.partial = OllamaResponse::from_json(message).ok();
+            }
+        }
+    }
+}
+
+// ===
+// TRAIT: OllamaStreamError (fmt::Debug)
+// ===
+
+impl fmt::Debug for OllamaStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OllamaStreamError")
+            .field("partial_text", &self.partial_text())
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+// ===
+// TRAIT: OllamaStreamError (fmt::Display)
+// ===
+
+impl fmt::Display for OllamaStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "response stream interrupted: {}", self.source)
+    }
+}
+
+impl Error for OllamaStreamError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{OllamaAbortHandle, OllamaHistory, OllamaResponse, OllamaSession};
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+
+// ===
+// STRUCT: SharedSession
+// ===
+
+/// An `OllamaSession` that can be used from several tasks at once, e.g. web
+/// handlers or the event loop of a GUI.
+///
+/// Clones share the same session. Each method locks it for as long as it runs,
+/// so turns never interleave; `update` waits for a generation already running,
+/// while `try_update` fails at once. `abort` does not need the lock, so it can
+/// stop a generation from another task.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::{OllamaSession, SharedSession};
+///
+/// let shared = SharedSession::new(OllamaSession::new("gemma3:1b"));
+///
+/// let handler = shared.clone();
+/// tokio::spawn(async move {
+///     match handler.try_prompt("Hello!", |_| {}).await {
+///         Ok(response) => println!("{:?}", response.text()),
+///         Err(err) => println!("busy: {err}"),
+///     }
+/// });
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedSession {
+    inner: Arc<Mutex<OllamaSession>>,
+    abort: OllamaAbortHandle,
+}
+
+impl SharedSession {
+    /// Wraps a session for shared use.
+    pub fn new(session: OllamaSession) -> Self {
+        Self {
+            abort: session.abort_handle(),
+            inner: Arc::new(Mutex::new(session)),
+        }
+    }
+
+    /// Waits for the session and locks it, for any method not wrapped here.
+    pub async fn lock(&self) -> MutexGuard<'_, OllamaSession> {
+        self.inner.lock().await
+    }
+
+    /// Returns `true` while another task holds the session, e.g. during a generation.
+    pub fn is_busy(&self) -> bool {
+        self.inner.try_lock().is_err()
+    }
+
+    /// Adds a user message once the session is free.
+    pub async fn user(&self, content: &str) {
+        self.lock().await.user(content);
+    }
+
+    /// Adds a system message once the session is free.
+    pub async fn system(&self, content: &str) {
+        self.lock().await.system(content);
+    }
+
+    /// Adds an assistant message once the session is free.
+    pub async fn assistant(&self, content: &str) {
+        self.lock().await.assistant(content);
+    }
+
+    /// Returns a snapshot of the conversation history.
+    pub async fn messages(&self) -> OllamaHistory {
+        self.lock().await.checkpoint().into_parts().0
+    }
+
+    /// Waits for the session to be free, then sends the conversation to the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function called with each chunk of the response.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OllamaResponse, Box<dyn Error>>` - The response, as returned by
+    ///   `OllamaSession::update`.
+    pub async fn update<F>(&self, callback: F) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        self.lock().await.update(callback).await
+    }
+
+    /// Sends the conversation to the model, or fails if the session is busy.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function called with each chunk of the response.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OllamaResponse, Box<dyn Error>>` - The response, or an error at once
+    ///   if a generation is already running.
+    pub async fn try_update<F>(&self, callback: F) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        self.try_lock()?.update(callback).await
+    }
+
+    /// Adds a user message and sends the conversation, without another task's
+    /// turn in between.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The user message.
+    /// * `callback` - A function called with each chunk of the response.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OllamaResponse, Box<dyn Error>>` - The response to the message.
+    pub async fn prompt<F>(
+        &self,
+        content: &str,
+        callback: F,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        let mut session = self.lock().await;
+        session.user(content);
+        session.update(callback).await
+    }
+
+    /// Like `prompt`, but fails at once, without adding the message, if the
+    /// session is busy.
+    pub async fn try_prompt<F>(
+        &self,
+        content: &str,
+        callback: F,
+    ) -> Result<OllamaResponse, Box<dyn Error>>
+    where
+        F: FnMut(&str),
+    {
+        let mut session = self.try_lock()?;
+        session.user(content);
+        session.update(callback).await
+    }
+
+    /// Returns a handle that aborts the session's generations.
+    pub fn abort_handle(&self) -> OllamaAbortHandle {
+        self.abort.clone()
+    }
+
+    /// Stops the running generation, if any; see `OllamaSession::abort`.
+    ///
+    /// Later updates fail until `clear_abort` is called.
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+
+    /// Clears the aborted mark once the session is free.
+    pub async fn clear_abort(&self) {
+        self.lock().await.clear_abort();
+    }
+
+    /// Locks the session if it is free.
+    fn try_lock(&self) -> Result<MutexGuard<'_, OllamaSession>, Box<dyn Error>> {
+        self.inner
+            .try_lock()
+            .map_err(|_| "the session is busy with another generation".into())
+    }
+}
+
+impl From<OllamaSession> for SharedSession {
+    fn from(session: OllamaSession) -> Self {
+        Self::new(session)
+    }
+}
+
+// ===
+// TESTS: SharedSession
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body, stalled_chat_body};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_try_update_fails_while_busy() {
+        let server = MockServer::start(vec![
+            stalled_chat_body(&["Once upon", " a time"]),
+            chat_body(&["The end."]),
+        ])
+        .await;
+        let shared = SharedSession::new(OllamaSession::remote("mock", &server.addr()));
+
+        let story = shared.clone();
+        let task = tokio::spawn(async move {
+            let response = story.prompt("Tell me a story.", |_| {}).await.unwrap();
+            response.text().map(str::to_string)
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(shared.is_busy());
+        let error = shared.try_prompt("Hello?", |_| {}).await.unwrap_err();
+        assert!(error.to_string().contains("busy"), "{error}");
+
+        shared.abort();
+        assert_eq!(task.await.unwrap().as_deref(), Some("Once upon a time"));
+        assert!(!shared.is_busy());
+        assert_eq!(shared.messages().await.len(), 2);
+
+        shared.clear_abort().await;
+        shared.user("Go on.").await;
+        let response = shared.try_update(|_| {}).await.unwrap();
+        assert_eq!(response.text(), Some("The end."));
+        assert_eq!(shared.lock().await.messages().len(), 4);
+    }
+}
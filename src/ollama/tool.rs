@@ -1,3 +1,30 @@
+use std::ops::Index;
+
+/// Returns the array held by `value`, first turning a non-array into one.
+///
+/// `null` becomes an empty array; any other value becomes the only element.
+fn ensure_array(value: &mut serde_json::Value) -> &mut Vec<serde_json::Value> {
+    if !value.is_array() {
+        let element = value.take();
+        *value = serde_json::Value::Array(if element.is_null() {
+            vec![]
+        } else {
+            vec![element]
+        });
+    }
+    value.as_array_mut().expect("value was just made an array")
+}
+
+/// Returns the object held by `value`, first replacing a non-object with an empty one.
+fn ensure_object(value: &mut serde_json::Value) -> &mut serde_json::Map<String, serde_json::Value> {
+    if !value.is_object() {
+        *value = serde_json::Value::Object(serde_json::Map::new());
+    }
+    value
+        .as_object_mut()
+        .expect("value was just made an object")
+}
+
 //============================================================================
 // OllamaToolCall
 //============================================================================
@@ -17,6 +44,7 @@
 ///   }
 /// }
 /// ```
+#[derive(Debug, Clone, PartialEq)]
 pub struct OllamaToolCall {
     value: serde_json::Value,
 }
@@ -101,6 +129,7 @@ impl From<&serde_json::Value> for OllamaToolCall {
 ///   }
 /// ]
 /// ```
+#[derive(Debug, Clone, PartialEq)]
 pub struct OllamaToolCalls {
     array: serde_json::Value,
 }
@@ -173,8 +202,20 @@ impl OllamaToolCalls {
         }
     }
 
+    /// Returns an iterator over the tool calls in the collection.
+    ///
+    /// ## Returns
+    ///
+    /// An iterator yielding each tool call in order.
+    pub fn iter(&self) -> ToolCallIter<'_> {
+        self.calls().iter().map(|value| OllamaToolCall::from(value))
+    }
+
     /// Adds a tool call to the collection.
     ///
+    /// If the collection wraps a JSON value that is not an array, the value is
+    /// first wrapped in an array.
+    ///
     /// ## Arguments
     ///
     /// * `tool_call` - The OllamaToolCall to add to the collection
@@ -183,12 +224,55 @@ impl OllamaToolCalls {
     ///
     /// A mutable reference to self for method chaining.
     pub fn push_tool_call(&mut self, tool_call: OllamaToolCall) -> &mut Self {
-        self.array
-            .as_array_mut()
-            .unwrap()
-            .push(tool_call.value.clone());
+        ensure_array(&mut self.array).push(tool_call.value);
         self
     }
+
+    /// Returns the tool calls as a slice of JSON values.
+    fn calls(&self) -> &[serde_json::Value] {
+        self.array.as_array().map_or(&[], Vec::as_slice)
+    }
+}
+
+/// An iterator over the tool calls of an `OllamaToolCalls` collection.
+pub type ToolCallIter<'a> = std::iter::Map<
+    std::slice::Iter<'a, serde_json::Value>,
+    fn(&serde_json::Value) -> OllamaToolCall,
+>;
+
+impl<'a> IntoIterator for &'a OllamaToolCalls {
+    type Item = OllamaToolCall;
+    type IntoIter = ToolCallIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<OllamaToolCall> for OllamaToolCalls {
+    fn from_iter<I: IntoIterator<Item = OllamaToolCall>>(iter: I) -> Self {
+        let calls = iter.into_iter().map(|call| call.value).collect();
+        Self {
+            array: serde_json::Value::Array(calls),
+        }
+    }
+}
+
+impl Extend<OllamaToolCall> for OllamaToolCalls {
+    fn extend<I: IntoIterator<Item = OllamaToolCall>>(&mut self, iter: I) {
+        for tool_call in iter {
+            self.push_tool_call(tool_call);
+        }
+    }
+}
+
+/// Indexes the raw JSON of a tool call; use `tool_call` for a checked lookup.
+impl Index<usize> for OllamaToolCalls {
+    type Output = serde_json::Value;
+
+    fn index(&self, index: usize) -> &serde_json::Value {
+        &self.calls()[index]
+    }
 }
 
 impl From<&serde_json::Value> for OllamaToolCalls {
@@ -207,6 +291,12 @@ impl From<&serde_json::Value> for OllamaToolCalls {
     }
 }
 
+impl Default for OllamaToolCalls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 //============================================================================
 // OllamaFunctionParameters
 //============================================================================
@@ -215,6 +305,7 @@ impl From<&serde_json::Value> for OllamaToolCalls {
 /// This struct provides a builder pattern for defining the parameter schema
 /// for functions that can be called by the Ollama model. It follows JSON Schema
 /// conventions for defining parameters with types, descriptions, and required flags.
+#[derive(Debug, Clone, PartialEq)]
 pub struct OllamaFunctionParameters {
     object: serde_json::Value,
 }
@@ -248,6 +339,10 @@ impl OllamaFunctionParameters {
 
     /// Adds a parameter to the function parameters definition.
     ///
+    /// A schema built with `From<&Value>` that lacks an object `properties` or an
+    /// array `required` gets them added; a schema that is not an object at all is
+    /// replaced.
+    ///
     /// ## Arguments
     ///
     /// * `name` - The parameter name as it will appear in the JSON schema
@@ -270,21 +365,52 @@ impl OllamaFunctionParameters {
             "description": description,
         });
 
+        let object = ensure_object(&mut self.object);
+        object
+            .entry("type")
+            .or_insert_with(|| serde_json::json!("object"));
+
         // Add the new parameter to properties
-        self.object["properties"][name] = details;
+        let properties = object
+            .entry("properties")
+            .or_insert_with(|| serde_json::json!({}));
+        ensure_object(properties).insert(name.to_string(), details);
 
         // If parameter is required, add it to the required array
         if required {
-            self.object["required"]
-                .as_array_mut()
-                .unwrap()
-                .push(serde_json::json!(name));
+            let required = object
+                .entry("required")
+                .or_insert_with(|| serde_json::json!([]));
+            ensure_array(required).push(serde_json::json!(name));
         }
 
         self
     }
 }
 
+impl Default for OllamaFunctionParameters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&serde_json::Value> for OllamaFunctionParameters {
+    /// Creates function parameters from an existing JSON schema object.
+    ///
+    /// ## Arguments
+    ///
+    /// * `value` - A JSON schema describing the function's parameters
+    ///
+    /// ## Returns
+    ///
+    /// A new OllamaFunctionParameters containing a clone of the provided schema.
+    fn from(value: &serde_json::Value) -> Self {
+        Self {
+            object: value.clone(),
+        }
+    }
+}
+
 //============================================================================
 // OllamaFunction
 //============================================================================
@@ -292,6 +418,7 @@ impl OllamaFunctionParameters {
 ///
 /// This struct defines a function with a name, description, and parameters
 /// that conforms to the Ollama API's function calling specification.
+#[derive(Debug, Clone, PartialEq)]
 pub struct OllamaFunction {
     object: serde_json::Value,
 }
@@ -324,7 +451,7 @@ impl OllamaFunction {
     /// ## Arguments
     ///
     /// * `parameters` - The OllamaFunctionParameters that define the schema
-    ///    for the function's parameters
+    ///   for the function's parameters
     ///
     /// ## Returns
     ///
@@ -344,6 +471,7 @@ impl OllamaFunction {
 /// to Ollama API endpoints to enable function calling capabilities.
 /// It handles the proper formatting of the functions collection and provides
 /// methods for adding functions to the collection.
+#[derive(Debug, Clone, PartialEq)]
 pub struct OllamaTools {
     array: serde_json::Value,
 }
@@ -371,6 +499,9 @@ impl OllamaTools {
 
     /// Adds a function to the tools collection.
     ///
+    /// If the collection wraps a JSON value that is not an array, the value is
+    /// first wrapped in an array.
+    ///
     /// ## Arguments
     ///
     /// * `function` - The OllamaFunction to add to the collection
@@ -379,9 +510,98 @@ impl OllamaTools {
     ///
     /// A mutable reference to self for method chaining.
     pub fn push_function(&mut self, function: OllamaFunction) -> &mut Self {
-        self.array.as_array_mut().unwrap().push(function.object);
+        ensure_array(&mut self.array).push(function.object);
         self
     }
+
+    /// Returns the number of functions in the collection.
+    ///
+    /// ## Returns
+    ///
+    /// An integer count of the functions in the array.
+    pub fn len(&self) -> usize {
+        self.functions().len()
+    }
+
+    /// Checks if the collection is empty.
+    ///
+    /// ## Returns
+    ///
+    /// A boolean indicating whether the collection has no functions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the JSON definitions of the functions.
+    ///
+    /// ## Returns
+    ///
+    /// An iterator yielding each function definition in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, serde_json::Value> {
+        self.functions().iter()
+    }
+
+    /// Returns the function definitions as a slice of JSON values.
+    fn functions(&self) -> &[serde_json::Value] {
+        self.array.as_array().map_or(&[], Vec::as_slice)
+    }
+}
+
+impl From<&serde_json::Value> for OllamaTools {
+    /// Creates a tools collection from a JSON array of function definitions.
+    ///
+    /// ## Arguments
+    ///
+    /// * `value` - A JSON array, or a single function definition to wrap in one
+    ///
+    /// ## Returns
+    ///
+    /// A new OllamaTools containing a clone of the provided definitions.
+    fn from(value: &serde_json::Value) -> Self {
+        let mut array = value.clone();
+        ensure_array(&mut array);
+        Self { array }
+    }
+}
+
+impl<'a> IntoIterator for &'a OllamaTools {
+    type Item = &'a serde_json::Value;
+    type IntoIter = std::slice::Iter<'a, serde_json::Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<OllamaFunction> for OllamaTools {
+    fn from_iter<I: IntoIterator<Item = OllamaFunction>>(iter: I) -> Self {
+        let functions = iter.into_iter().map(|function| function.object).collect();
+        Self {
+            array: serde_json::Value::Array(functions),
+        }
+    }
+}
+
+impl Extend<OllamaFunction> for OllamaTools {
+    fn extend<I: IntoIterator<Item = OllamaFunction>>(&mut self, iter: I) {
+        for function in iter {
+            self.push_function(function);
+        }
+    }
+}
+
+impl Index<usize> for OllamaTools {
+    type Output = serde_json::Value;
+
+    fn index(&self, index: usize) -> &serde_json::Value {
+        &self.functions()[index]
+    }
+}
+
+impl Default for OllamaTools {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 //============================================================================
@@ -780,4 +1000,60 @@ mod tests {
         assert!(json_str.contains("echo"));
         assert!(json_str.contains("Hello world"));
     }
+
+    /// Tests that pushing onto collections built from malformed JSON repairs
+    /// the JSON instead of panicking.
+    #[test]
+    fn test_push_onto_non_array_values() {
+        let mut tools = OllamaTools::from(&serde_json::json!(null));
+        tools.push_function(OllamaFunction::new("a", "First"));
+        assert_eq!(tools.len(), 1);
+
+        let single = serde_json::json!({ "type": "function", "function": { "name": "a" } });
+        let mut tools = OllamaTools::from(&single);
+        tools.push_function(OllamaFunction::new("b", "Second"));
+        assert_eq!(tools[0], single);
+        assert_eq!(tools[1]["function"]["name"], "b");
+
+        let mut tool_calls = OllamaToolCalls::from(&serde_json::json!({ "function": {} }));
+        tool_calls.push_tool_call(OllamaToolCall::from(&serde_json::json!({ "function": {} })));
+        assert_eq!(tool_calls.len(), 2);
+
+        let mut params = OllamaFunctionParameters::from(&serde_json::json!({ "type": "object" }));
+        params.push_parameter("city", "string", "The city", true);
+        assert_eq!(params.object["properties"]["city"]["type"], "string");
+        assert_eq!(params.object["required"], serde_json::json!(["city"]));
+
+        let mut params = OllamaFunctionParameters::from(&serde_json::json!("not a schema"));
+        params.push_parameter("city", "string", "The city", false);
+        assert_eq!(params.object["type"], "object");
+        assert_eq!(
+            params.object["properties"]["city"]["description"],
+            "The city"
+        );
+    }
+
+    /// Tests iterating, indexing and collecting tool calls and tools.
+    #[test]
+    fn test_collection_traits() {
+        let tool_calls: OllamaToolCalls = ["get_weather", "search"]
+            .iter()
+            .map(|name| OllamaToolCall::from(&serde_json::json!({ "function": { "name": name } })))
+            .collect();
+
+        let mut names = Vec::new();
+        for call in &tool_calls {
+            names.push(call.name().unwrap().to_string());
+        }
+        assert_eq!(names, ["get_weather", "search"]);
+        assert_eq!(tool_calls[1]["function"]["name"], "search");
+
+        let mut tools: OllamaTools = [OllamaFunction::new("a", "First")].into_iter().collect();
+        tools.extend([OllamaFunction::new("b", "Second")]);
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[1]["function"]["name"], "b");
+        for function in &tools {
+            assert_eq!(function["type"], "function");
+        }
+    }
 }
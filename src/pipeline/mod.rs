@@ -0,0 +1,4 @@
+#[doc(hidden)]
+pub mod summarization;
+#[doc(inline)]
+pub use summarization::*;
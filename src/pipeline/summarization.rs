@@ -0,0 +1,201 @@
+use crate::{OllamaSession, TextChunker};
+use std::error::Error;
+
+/// The instruction sent with each chunk of the document.
+const CHUNK_PROMPT: &str = "Summarize this part of a longer document. Keep the key facts, \
+names and numbers, and leave out nothing important.";
+
+/// The instruction sent with a group of chunk summaries to merge.
+const COMBINE_PROMPT: &str = "Combine these summaries of consecutive parts of a document into \
+one summary, keeping the key facts and their order.";
+
+/// The instruction sent with the text of the final pass.
+const FINAL_PROMPT: &str = "Write a concise, well-structured summary of the following document.";
+
+// ===
+// ENUM: SummaryProgress
+// ===
+
+/// A step of `summarize`, reported before its request is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryProgress {
+    /// Summarizing chunk `index` of `total` chunks of the document.
+    Chunk { index: usize, total: usize },
+    /// Merging group `index` of `total` groups of summaries in reduce round `round`.
+    Combine {
+        round: usize,
+        index: usize,
+        total: usize,
+    },
+    /// Writing the final summary.
+    Final,
+}
+
+/// Summarizes a document of any length with map-reduce.
+///
+/// The document is split with `chunker` and each chunk is summarized (map).
+/// While the summaries do not fit in one chunk together, they are grouped and
+/// each group is merged into one summary (reduce). A final pass then writes the
+/// summary of the whole document. A document that fits in one chunk only gets
+/// the final pass.
+///
+/// Every request is sent on a fork of `session`, so its model, options and
+/// system prompt apply while its history is left unchanged.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::{OllamaSession, TextChunker, pipeline};
+///
+/// let document = std::fs::read_to_string("report.txt")?;
+/// let mut session = OllamaSession::new("gemma3:1b");
+/// let summary = pipeline::summarize(&document, &TextChunker::new(2000), &mut session, |step| {
+///     println!("{step:?}");
+/// })
+/// .await?;
+/// println!("{summary}");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Arguments
+/// * `document` - The text to summarize
+/// * `chunker` - Splits the document and groups the summaries
+/// * `session` - The session the requests are forked from
+/// * `progress` - Called before each request with the step it makes
+///
+/// # Returns
+/// * The summary, or the error of the first request that failed
+pub async fn summarize<F>(
+    document: &str,
+    chunker: &TextChunker,
+    session: &mut OllamaSession,
+    mut progress: F,
+) -> Result<String, Box<dyn Error>>
+where
+    F: FnMut(SummaryProgress),
+{
+    let mut parts = chunker.chunks(document);
+    if parts.is_empty() {
+        return Ok(String::new());
+    }
+
+    if parts.len() > 1 {
+        let total = parts.len();
+        let mut summaries = Vec::with_capacity(total);
+        for (index, chunk) in parts.iter().enumerate() {
+            progress(SummaryProgress::Chunk { index, total });
+            summaries.push(complete(session, CHUNK_PROMPT, chunk).await?);
+        }
+        parts = summaries;
+    }
+
+    let mut round = 0;
+    while parts.len() > 1 {
+        let groups = chunker.chunks(&parts.join("\n\n"));
+        // Stop once the summaries fit together, or when merging no longer shrinks
+        // them; the final pass then gets everything that is left.
+        if groups.len() <= 1 || groups.len() >= parts.len() {
+            break;
+        }
+
+        round += 1;
+        let total = groups.len();
+        let mut merged = Vec::with_capacity(total);
+        for (index, group) in groups.iter().enumerate() {
+            progress(SummaryProgress::Combine {
+                round,
+                index,
+                total,
+            });
+            merged.push(complete(session, COMBINE_PROMPT, group).await?);
+        }
+        parts = merged;
+    }
+
+    progress(SummaryProgress::Final);
+    complete(session, FINAL_PROMPT, &parts.join("\n\n")).await
+}
+
+/// Sends one instruction with its text on a fork of the session.
+async fn complete(
+    session: &mut OllamaSession,
+    instruction: &str,
+    text: &str,
+) -> Result<String, Box<dyn Error>> {
+    session.ask(&format!("{instruction}\n\n{text}")).await
+}
+
+// ===
+// TESTS: summarize
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body};
+
+    #[tokio::test]
+    async fn test_summarize_map_reduce() {
+        let server = MockServer::start(vec![
+            chat_body(&["One."]),
+            chat_body(&["Two."]),
+            chat_body(&["Three."]),
+            chat_body(&["Final summary."]),
+        ])
+        .await;
+        let document = "The first paragraph of the document.\n\n\
+                        The second paragraph of the document.\n\n\
+                        The third paragraph of the document.";
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let mut steps = Vec::new();
+        let summary = summarize(document, &TextChunker::new(10), &mut session, |step| {
+            steps.push(step)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(summary, "Final summary.");
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0], SummaryProgress::Chunk { index: 0, total: 3 });
+        assert_eq!(steps[3], SummaryProgress::Final);
+
+        let requests = server.requests();
+        let last = requests[3]["messages"][0]["content"].as_str().unwrap();
+        assert!(last.starts_with(FINAL_PROMPT));
+        assert!(last.contains("One.\n\nTwo.\n\nThree."));
+        assert!(session.messages().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_summarize_reduces_until_it_fits() {
+        let server = MockServer::start(vec![
+            chat_body(&["Summary one ok."]),
+            chat_body(&["Summary two ok."]),
+            chat_body(&["Summary six ok."]),
+            chat_body(&["Merged."]),
+            chat_body(&["Merged."]),
+            chat_body(&["Done."]),
+        ])
+        .await;
+        let document = "The first paragraph of the document.\n\n\
+                        The second paragraph of the document.\n\n\
+                        The third paragraph of the document.";
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let mut steps = Vec::new();
+        let summary = summarize(document, &TextChunker::new(10), &mut session, |step| {
+            steps.push(step)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(summary, "Done.");
+        assert!(steps.contains(&SummaryProgress::Combine {
+            round: 1,
+            index: 1,
+            total: 2
+        }));
+        assert_eq!(server.requests().len(), 6);
+    }
+}
@@ -0,0 +1,29 @@
+//! The types and traits most programs need, in one import.
+//!
+//! ```
+//! use ollie_rs::prelude::*;
+//!
+//! let mut request = OllamaRequest::new();
+//! request.set_model("gemma3:1b");
+//! ```
+//!
+//! The clients, sessions and streams need the `transport` feature. Everything
+//! here is also exported from the crate root.
+
+pub use crate::{
+    ContentFilter, FilterVerdict, GeminiContent, GeminiFunctionCall, GeminiFunctionDeclaration,
+    GeminiFunctionResponse, GeminiGenerationConfig, GeminiPart, GeminiPromptSystem,
+    GeminiPromptUser, GeminiRequest, GeminiResponse, GeminiRole, GeminiToolDeclaration, ImageData,
+    OllamaFunction, OllamaHistory, OllamaMessage, OllamaOptions, OllamaRequest, OllamaResponse,
+    OllamaRole, OllamaTools, OllieConfig, Tool, ToolRegistry, Transcript, messages,
+};
+
+#[cfg(feature = "pattern-filter")]
+pub use crate::{FilterMode, PatternFilter};
+
+#[cfg(feature = "transport")]
+pub use crate::{
+    Agent, Dialogue, Gemini, GeminiApiVersion, GeminiBuilder, GeminiResponseStream,
+    GeminiTransport, Ollama, OllamaAbortHandle, OllamaResponseStream, OllamaSession, SharedSession,
+    print_chunk,
+};
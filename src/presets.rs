@@ -0,0 +1,119 @@
+use crate::{GeminiGenerationConfig, OllamaOptions};
+use std::collections::HashMap;
+
+// ===
+// STRUCT: OptionPresets
+// ===
+
+/// A registry of named sets of sampling options.
+///
+/// Presets are stored as [`OllamaOptions`] and can be converted to a
+/// [`GeminiGenerationConfig`], so the same preset drives either API. A new
+/// registry contains the built-in presets:
+///
+/// * `creative` - High temperature and wide sampling for brainstorming and prose.
+/// * `deterministic` - Zero temperature and a fixed seed for repeatable answers.
+/// * `code` - Low temperature with focused sampling for code generation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionPresets {
+    presets: HashMap<String, OllamaOptions>,
+}
+
+impl OptionPresets {
+    /// Creates a registry containing the built-in presets.
+    pub fn new() -> Self {
+        let mut presets = Self::empty();
+
+        let mut creative = OllamaOptions::new();
+        creative.set_temperature(1.1).set_top_p(0.95).set_top_k(80);
+        presets.register("creative", creative);
+
+        presets.register("deterministic", OllamaOptions::deterministic());
+
+        let mut code = OllamaOptions::new();
+        code.set_temperature(0.2).set_top_p(0.9).set_top_k(20);
+        presets.register("code", code);
+
+        presets
+    }
+
+    /// Creates a registry without any presets.
+    pub fn empty() -> Self {
+        Self {
+            presets: HashMap::new(),
+        }
+    }
+
+    /// Registers a preset, replacing any existing preset with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name used to apply the preset.
+    /// * `options` - The options the preset sets; unset options are left unchanged when applied.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn register(&mut self, name: &str, options: OllamaOptions) -> &mut Self {
+        self.presets.insert(name.to_string(), options);
+        self
+    }
+
+    /// Returns the options of the preset with the given name.
+    pub fn get(&self, name: &str) -> Option<&OllamaOptions> {
+        self.presets.get(name)
+    }
+
+    /// Returns the preset with the given name as a Gemini generation config.
+    pub fn gemini(&self, name: &str) -> Option<GeminiGenerationConfig> {
+        self.get(name).map(GeminiGenerationConfig::from)
+    }
+
+    /// Returns the names of all registered presets, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.presets.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+// ===
+// TRAIT: Default for OptionPresets
+// ===
+
+impl Default for OptionPresets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ===
+// TESTS: OptionPresets
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_presets() {
+        let presets = OptionPresets::new();
+        assert_eq!(presets.names(), ["code", "creative", "deterministic"]);
+        assert_eq!(
+            presets.get("deterministic").unwrap().temperature(),
+            Some(0.0)
+        );
+        assert_eq!(presets.gemini("code").unwrap().top_k, Some(20));
+        assert!(OptionPresets::empty().get("code").is_none());
+    }
+
+    #[test]
+    fn test_register_replaces() {
+        let mut presets = OptionPresets::new();
+        let mut code = OllamaOptions::new();
+        code.set_temperature(0.0);
+        presets.register("code", code);
+        assert_eq!(presets.get("code").unwrap().temperature(), Some(0.0));
+        assert_eq!(presets.get("code").unwrap().top_k(), None);
+    }
+}
@@ -0,0 +1,8 @@
+#[doc(hidden)]
+pub mod query_rewrite;
+#[doc(inline)]
+pub use query_rewrite::*;
+#[doc(hidden)]
+pub mod rerank;
+#[doc(inline)]
+pub use rerank::*;
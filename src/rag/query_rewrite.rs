@@ -0,0 +1,99 @@
+use crate::OllamaSession;
+use std::error::Error;
+
+/// The instruction sent to rewrite a query for search.
+const REWRITE_PROMPT: &str = "Rewrite the question below as a search query for a document \
+retrieval system. Spell out abbreviations, add likely synonyms and drop filler words. \
+Answer with the query only, on one line.";
+
+/// The instruction sent to write a hypothetical document.
+const HYDE_PROMPT: &str = "Write a short passage, as it might appear in a reference document, \
+that answers the question below. State the answer directly, without mentioning the \
+question. Answer with the passage only.";
+
+/// Rewrites a user query into one that retrieves better.
+///
+/// Conversational questions often match documents poorly; the rewrite states the
+/// topic with the terms a relevant document would likely use.
+///
+/// The request is sent on a fork of `session`, whose history is left unchanged,
+/// so a chat session can be passed to resolve references like "it" from context.
+///
+/// # Arguments
+/// * `session` - The session the request is forked from
+/// * `query` - The query as the user typed it
+///
+/// # Returns
+/// * The rewritten query, or the original query if the model answered with nothing
+pub async fn rewrite_query(
+    session: &mut OllamaSession,
+    query: &str,
+) -> Result<String, Box<dyn Error>> {
+    let answer = session
+        .ask(&format!("{REWRITE_PROMPT}\n\nQuestion: {query}"))
+        .await?;
+
+    let rewrite = answer
+        .lines()
+        .map(|line| line.trim())
+        .map(|line| line.strip_prefix("Query:").unwrap_or(line))
+        .map(|line| line.trim().trim_matches('"').trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or(query);
+    Ok(rewrite.to_string())
+}
+
+/// Writes a hypothetical document answering a query (HyDE).
+///
+/// Embedding the passage instead of the query finds documents that look like an
+/// answer, which often beats matching on the question alone. The passage may be
+/// wrong in its details; it is only used for retrieval.
+///
+/// # Arguments
+/// * `session` - The session the request is forked from
+/// * `query` - The query to answer
+///
+/// # Returns
+/// * The passage to embed
+pub async fn hyde(session: &mut OllamaSession, query: &str) -> Result<String, Box<dyn Error>> {
+    session
+        .ask(&format!("{HYDE_PROMPT}\n\nQuestion: {query}"))
+        .await
+}
+
+// ===
+// TESTS: rewrite_query
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body};
+
+    #[tokio::test]
+    async fn test_rewrite_query_and_hyde() {
+        let server = MockServer::start(vec![
+            chat_body(&["\nQuery: \"rust async runtime comparison tokio\"\n"]),
+            chat_body(&["Tokio is the most widely used async runtime for Rust."]),
+        ])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let rewrite = rewrite_query(&mut session, "which one's best for async?")
+            .await
+            .unwrap();
+        assert_eq!(rewrite, "rust async runtime comparison tokio");
+
+        let passage = hyde(&mut session, "What is Tokio?").await.unwrap();
+        assert!(passage.starts_with("Tokio is"));
+
+        let prompt = server.requests()[1]["messages"][0]["content"].clone();
+        assert!(
+            prompt
+                .as_str()
+                .unwrap()
+                .ends_with("Question: What is Tokio?")
+        );
+        assert!(session.messages().is_empty());
+    }
+}
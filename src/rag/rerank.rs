@@ -0,0 +1,185 @@
+use crate::OllamaSession;
+use serde_json::{Value as JsonValue, json};
+use std::error::Error;
+
+/// The instruction sent to score retrieved passages.
+const RERANK_PROMPT: &str = "Rate how relevant each numbered passage below is to the \
+question, from 0 (unrelated) to 10 (answers it directly). Answer with JSON like \
+{\"scores\": [{\"passage\": 1, \"score\": 7}]}, with one entry per passage.";
+
+// ===
+// STRUCT: RankedChunk
+// ===
+
+/// A retrieved chunk with the relevance score a model gave it.
+///
+/// Returned by `rerank`, most relevant first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedChunk {
+    /// The position of the chunk in the candidates passed to `rerank`.
+    pub index: usize,
+    /// The chunk text.
+    pub text: String,
+    /// The relevance score, from 0.0 to 1.0.
+    pub score: f64,
+}
+
+/// Reorders retrieved chunks by how relevant a model judges them to a query.
+///
+/// All candidates are scored in one request, so keep the list to what the model's
+/// context can hold. Candidates the model leaves out score 0.0; ties keep their
+/// retrieval order.
+///
+/// The request is sent on a fork of `session`, whose history is left unchanged.
+///
+/// # Arguments
+/// * `session` - The session the request is forked from
+/// * `query` - The query the chunks were retrieved for
+/// * `candidates` - The retrieved chunks, in retrieval order
+///
+/// # Returns
+/// * The chunks with their scores, most relevant first
+pub async fn rerank(
+    session: &mut OllamaSession,
+    query: &str,
+    candidates: &[&str],
+) -> Result<Vec<RankedChunk>, Box<dyn Error>> {
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let answer = session
+        .ask_json(&rerank_prompt(query, candidates), rerank_schema())
+        .await?;
+    Ok(rank(candidates, &answer))
+}
+
+/// Builds the prompt listing the candidates, numbered from 1.
+fn rerank_prompt(query: &str, candidates: &[&str]) -> String {
+    let passages: Vec<String> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, text)| format!("[{}] {}", index + 1, text.trim()))
+        .collect();
+    format!(
+        "{RERANK_PROMPT}\n\nQuestion: {query}\n\nPassages:\n{}",
+        passages.join("\n\n")
+    )
+}
+
+/// Builds the JSON schema for the list of passage scores.
+fn rerank_schema() -> JsonValue {
+    json!({
+        "type": "object",
+        "properties": {
+            "scores": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "passage": { "type": "integer" },
+                        "score": { "type": "number", "minimum": 0, "maximum": 10 }
+                    },
+                    "required": ["passage", "score"]
+                }
+            }
+        },
+        "required": ["scores"]
+    })
+}
+
+/// Pairs the candidates with the scores in a model's answer and sorts them.
+///
+/// Passage numbers out of range are ignored, and scores are clamped to 0-10
+/// before being scaled to 0.0-1.0.
+fn rank(candidates: &[&str], answer: &JsonValue) -> Vec<RankedChunk> {
+    let mut scores = vec![0.0; candidates.len()];
+    let entries = answer["scores"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    for entry in entries {
+        let (Some(passage), Some(score)) = (entry["passage"].as_u64(), entry["score"].as_f64())
+        else {
+            continue;
+        };
+        let Some(slot) = (passage as usize)
+            .checked_sub(1)
+            .and_then(|i| scores.get_mut(i))
+        else {
+            continue;
+        };
+        *slot = score.clamp(0.0, 10.0) / 10.0;
+    }
+
+    let mut ranked: Vec<RankedChunk> = candidates
+        .iter()
+        .zip(scores)
+        .enumerate()
+        .map(|(index, (text, score))| RankedChunk {
+            index,
+            text: text.to_string(),
+            score,
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked
+}
+
+// ===
+// TESTS: rerank
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body};
+
+    #[test]
+    fn test_rank_scales_and_skips_bad_entries() {
+        let answer = json!({ "scores": [
+            { "passage": 2, "score": 9 },
+            { "passage": 7, "score": 10 },
+            { "passage": 3, "score": 14 },
+            { "passage": 1 }
+        ]});
+        let ranked = rank(&["a", "b", "c", "d"], &answer);
+
+        let order: Vec<usize> = ranked.iter().map(|chunk| chunk.index).collect();
+        assert_eq!(order, vec![2, 1, 0, 3]);
+        assert_eq!(ranked[0].score, 1.0);
+        assert_eq!(ranked[1].score, 0.9);
+        assert_eq!(ranked[1].text, "b");
+        assert_eq!(ranked[2].score, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_rerank() {
+        let server = MockServer::start(vec![chat_body(&[
+            r#"{"scores": [{"passage": 1, "score": 2}, {"passage": 2, "score": 8}]}"#,
+        ])])
+        .await;
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        let ranked = rerank(
+            &mut session,
+            "What is Tokio?",
+            &["Serde serializes data.", "Tokio is an async runtime."],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ranked[0].text, "Tokio is an async runtime.");
+        assert_eq!(ranked[0].score, 0.8);
+        assert_eq!(ranked[1].index, 0);
+
+        let request = &server.requests()[0];
+        assert_eq!(request["format"]["required"][0], "scores");
+        let prompt = request["messages"][0]["content"].as_str().unwrap();
+        assert!(prompt.contains("[2] Tokio is an async runtime."));
+        assert!(session.messages().is_empty());
+
+        let empty = rerank(&mut session, "What is Tokio?", &[]).await.unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,4 @@
+#[doc(hidden)]
+pub mod injection_detector;
+#[doc(inline)]
+pub use injection_detector::*;
@@ -0,0 +1,160 @@
+/// Enforces stop sequences on streamed text on the client side.
+///
+/// Some models ignore server-side stop sequences, so streamed text is passed
+/// through this filter before it reaches the caller. Text that could be the
+/// beginning of a stop sequence is held back until it is known not to be one,
+/// and everything from the first stop sequence onwards is discarded.
+#[derive(Debug, Clone, Default)]
+pub struct StopSequenceFilter {
+    stops: Vec<String>,
+    pending: String,
+    stopped: bool,
+}
+
+impl StopSequenceFilter {
+    /// Creates a filter for the given stop sequences; empty sequences are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `stops` - The sequences that end generation.
+    ///
+    /// # Returns
+    ///
+    /// A new `StopSequenceFilter`.
+    pub fn new<S: AsRef<str>>(stops: &[S]) -> Self {
+        Self {
+            stops: stops
+                .iter()
+                .map(|stop| stop.as_ref().to_string())
+                .filter(|stop| !stop.is_empty())
+                .collect(),
+            pending: String::new(),
+            stopped: false,
+        }
+    }
+
+    /// Returns `true` if the filter has no stop sequences.
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty()
+    }
+
+    /// Returns `true` once a stop sequence has been observed.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Feeds a chunk of streamed text through the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The next chunk of generated text.
+    ///
+    /// # Returns
+    ///
+    /// The text that can safely be passed on to the caller.
+    pub fn push(&mut self, text: &str) -> String {
+        if self.stopped {
+            return String::new();
+        }
+
+        if self.stops.is_empty() {
+            return text.to_string();
+        }
+
+        self.pending.push_str(text);
+
+        // Cut the text at the earliest stop sequence, if one is present.
+        let earliest = self
+            .stops
+            .iter()
+            .filter_map(|stop| self.pending.find(stop.as_str()))
+            .min();
+
+        if let Some(position) = earliest {
+            self.stopped = true;
+            let mut output = std::mem::take(&mut self.pending);
+            output.truncate(position);
+            return output;
+        }
+
+        // Hold back the longest suffix that could still grow into a stop sequence.
+        let held = self
+            .stops
+            .iter()
+            .map(|stop| partial_suffix_len(&self.pending, stop))
+            .max()
+            .unwrap_or(0);
+
+        let split = self.pending.len() - held;
+        let output = self.pending[..split].to_string();
+        self.pending.drain(..split);
+        output
+    }
+
+    /// Flushes any held back text at the end of the stream.
+    ///
+    /// # Returns
+    ///
+    /// The remaining text, which did not turn out to be a stop sequence.
+    pub fn finish(&mut self) -> String {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Returns the length of the longest suffix of `text` that is a proper prefix of `stop`.
+fn partial_suffix_len(text: &str, stop: &str) -> usize {
+    (1..stop.len())
+        .rev()
+        .filter(|&len| stop.is_char_boundary(len) && len <= text.len())
+        .find(|&len| text.is_char_boundary(text.len() - len) && text.ends_with(&stop[..len]))
+        .unwrap_or(0)
+}
+
+// ===
+// TESTS: StopSequenceFilter
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(filter: &mut StopSequenceFilter, chunks: &[&str]) -> String {
+        let mut output: String = chunks.iter().map(|chunk| filter.push(chunk)).collect();
+        output.push_str(&filter.finish());
+        output
+    }
+
+    #[test]
+    fn test_stop_split_across_chunks() {
+        let mut filter = StopSequenceFilter::new(&["\nUser:"]);
+        assert_eq!(filter.push("Hello\nUs"), "Hello");
+        assert!(!filter.is_stopped());
+        assert_eq!(filter.push("er: next"), "");
+        assert!(filter.is_stopped());
+        assert_eq!(filter.push("more"), "");
+        assert_eq!(filter.finish(), "");
+    }
+
+    #[test]
+    fn test_held_back_text_is_released() {
+        let mut filter = StopSequenceFilter::new(&["END"]);
+        assert_eq!(run(&mut filter, &["the E", "ND", "ING"]), "the ");
+
+        let mut filter = StopSequenceFilter::new(&["END"]);
+        assert_eq!(run(&mut filter, &["the E", "nd"]), "the End");
+        assert!(!filter.is_stopped());
+    }
+
+    #[test]
+    fn test_earliest_stop_wins() {
+        let mut filter = StopSequenceFilter::new(&["world", "lo"]);
+        assert_eq!(run(&mut filter, &["hello world"]), "hel");
+    }
+
+    #[test]
+    fn test_no_stops_passes_through() {
+        let mut filter = StopSequenceFilter::new::<&str>(&[]);
+        assert!(filter.is_empty());
+        assert_eq!(run(&mut filter, &["a", "b"]), "ab");
+    }
+}
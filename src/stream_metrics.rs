@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+// ===
+// STRUCT: StreamMetrics
+// ===
+
+/// Client-side latency measurements of one streamed response.
+///
+/// Unlike the durations reported by the server, these are wall times measured
+/// from just before the request was sent, so they include queueing, network and
+/// client overhead.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamMetrics {
+    time_to_first_token: Option<Duration>,
+    total_time: Duration,
+    chunk_intervals: Vec<Duration>,
+}
+
+impl StreamMetrics {
+    /// Returns the time until the first chunk with text arrived, if any did.
+    pub fn time_to_first_token(&self) -> Option<Duration> {
+        self.time_to_first_token
+    }
+
+    /// Returns the time until the stream ended.
+    pub fn total_time(&self) -> Duration {
+        self.total_time
+    }
+
+    /// Returns the time between each pair of consecutive chunks, in order.
+    pub fn chunk_intervals(&self) -> &[Duration] {
+        &self.chunk_intervals
+    }
+
+    /// Returns a percentile of the inter-chunk latency, using the nearest-rank method.
+    ///
+    /// # Arguments
+    ///
+    /// * `percentile` - The percentile, from 0 to 100, e.g. 50 for the median or 99 for the tail.
+    ///
+    /// # Returns
+    ///
+    /// The latency, or `None` if fewer than two chunks arrived.
+    pub fn inter_chunk_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut intervals = self.chunk_intervals.clone();
+        intervals.sort_unstable();
+
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * intervals.len() as f64).ceil() as usize;
+        intervals.get(rank.saturating_sub(1)).copied()
+    }
+}
+
+// ===
+// STRUCT: StreamTimer
+// ===
+
+/// Records chunk arrival times while a response is streamed.
+#[derive(Debug, Clone)]
+pub struct StreamTimer {
+    start: Instant,
+    last_chunk: Option<Instant>,
+    end: Option<Instant>,
+    metrics: StreamMetrics,
+}
+
+impl StreamTimer {
+    /// Starts timing; call this just before sending the request.
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            last_chunk: None,
+            end: None,
+            metrics: StreamMetrics::default(),
+        }
+    }
+
+    /// Records the arrival of a chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `has_text` - Whether the chunk carries generated text, for the time to first token.
+    pub fn record_chunk(&mut self, has_text: bool) {
+        let now = Instant::now();
+        if let Some(last_chunk) = self.last_chunk {
+            self.metrics.chunk_intervals.push(now - last_chunk);
+        }
+        if has_text && self.metrics.time_to_first_token.is_none() {
+            self.metrics.time_to_first_token = Some(now - self.start);
+        }
+        self.last_chunk = Some(now);
+    }
+
+    /// Records the end of the stream. Later calls have no effect.
+    pub fn finish(&mut self) {
+        self.end.get_or_insert_with(Instant::now);
+    }
+
+    /// Returns the metrics recorded so far.
+    ///
+    /// The total time runs until `finish` was called, or until now if it was not.
+    pub fn metrics(&self) -> StreamMetrics {
+        let end = self.end.unwrap_or_else(Instant::now);
+        StreamMetrics {
+            total_time: end - self.start,
+            ..self.metrics.clone()
+        }
+    }
+}
+
+impl Default for StreamTimer {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+// ===
+// TESTS: StreamMetrics
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inter_chunk_percentile() {
+        let metrics = StreamMetrics {
+            chunk_intervals: [40, 10, 30, 20].map(Duration::from_millis).to_vec(),
+            ..StreamMetrics::default()
+        };
+
+        assert_eq!(
+            metrics.inter_chunk_percentile(0.0),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(
+            metrics.inter_chunk_percentile(50.0),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(
+            metrics.inter_chunk_percentile(99.0),
+            Some(Duration::from_millis(40))
+        );
+        assert_eq!(StreamMetrics::default().inter_chunk_percentile(50.0), None);
+    }
+
+    #[test]
+    fn test_timer_records_chunks() {
+        let mut timer = StreamTimer::start();
+        timer.record_chunk(false);
+        timer.record_chunk(true);
+        timer.record_chunk(true);
+        timer.finish();
+
+        let metrics = timer.metrics();
+        assert_eq!(metrics.chunk_intervals().len(), 2);
+        assert!(metrics.time_to_first_token().unwrap() <= metrics.total_time());
+        assert_eq!(timer.metrics(), metrics);
+    }
+}
@@ -0,0 +1,229 @@
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+// ===
+// STRUCT: StreamTee
+// ===
+
+/// Fans the chunks of one generation stream out to several consumers.
+///
+/// Pass `callback` to the generation call, e.g. `OllamaSession::update`, and call
+/// `finish` once it returns. Every consumer, such as a live UI, a transcript
+/// logger and a token counter, receives each chunk in order from its own
+/// `StreamTeeReceiver` or `spawn`ed task.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::{OllamaSession, StreamTee};
+///
+/// let tee = StreamTee::new();
+/// let transcript = tee.subscribe();
+/// let printer = tee.spawn(|chunk| print!("{chunk}"));
+///
+/// let mut session = OllamaSession::new("gemma3:1b");
+/// session.user("Tell me a story.");
+/// session.update(tee.callback()).await?;
+/// tee.finish();
+///
+/// printer.await?;
+/// let text = transcript.collect().await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamTee {
+    // `None` marks the end of the stream, so clones of the callback still alive
+    // do not keep the consumers waiting.
+    sender: broadcast::Sender<Option<String>>,
+}
+
+impl StreamTee {
+    /// The default number of chunks a consumer may fall behind before missing some.
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Creates a tee with the default capacity.
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Creates a tee buffering up to `capacity` chunks for its slowest consumer.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The number of chunks buffered. Values below 1 are treated as 1.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Adds a consumer.
+    ///
+    /// A consumer only receives the chunks sent after it subscribed, so subscribe
+    /// before the stream starts.
+    pub fn subscribe(&self) -> StreamTeeReceiver {
+        StreamTeeReceiver {
+            receiver: self.sender.subscribe(),
+            missed: 0,
+            finished: false,
+        }
+    }
+
+    /// Adds a consumer that calls `consumer` with each chunk on a tokio task.
+    ///
+    /// # Returns
+    ///
+    /// The task, which completes once the stream is finished.
+    pub fn spawn<F>(&self, mut consumer: F) -> JoinHandle<()>
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        let mut receiver = self.subscribe();
+        tokio::spawn(async move {
+            while let Some(chunk) = receiver.recv().await {
+                consumer(&chunk);
+            }
+        })
+    }
+
+    /// Returns the number of consumers.
+    pub fn consumer_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Sends a chunk to every consumer.
+    pub fn send(&self, chunk: &str) {
+        // Sending only fails when there are no consumers, which is not an error.
+        let _ = self.sender.send(Some(chunk.to_string()));
+    }
+
+    /// Returns a callback for a generation call that sends each chunk it receives.
+    pub fn callback(&self) -> impl FnMut(&str) + Send + 'static {
+        let tee = self.clone();
+        move |chunk| tee.send(chunk)
+    }
+
+    /// Ends the stream; each consumer receives `None` after the last chunk.
+    pub fn finish(&self) {
+        let _ = self.sender.send(None);
+    }
+}
+
+impl Default for StreamTee {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ===
+// STRUCT: StreamTeeReceiver
+// ===
+
+/// One consumer of a `StreamTee`.
+#[derive(Debug)]
+pub struct StreamTeeReceiver {
+    receiver: broadcast::Receiver<Option<String>>,
+    missed: u64,
+    finished: bool,
+}
+
+impl StreamTeeReceiver {
+    /// Waits for the next chunk.
+    ///
+    /// A consumer that falls more than the tee's capacity behind skips the oldest
+    /// chunks; `missed` counts them.
+    ///
+    /// # Returns
+    ///
+    /// The chunk, or `None` once the stream is finished or the tee is dropped.
+    pub async fn recv(&mut self) -> Option<String> {
+        while !self.finished {
+            match self.receiver.recv().await {
+                Ok(Some(chunk)) => return Some(chunk),
+                Ok(None) | Err(RecvError::Closed) => self.finished = true,
+                Err(RecvError::Lagged(skipped)) => self.missed += skipped,
+            }
+        }
+        None
+    }
+
+    /// Returns the number of chunks skipped because this consumer fell behind.
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    /// Waits for the rest of the stream and returns its text.
+    pub async fn collect(mut self) -> String {
+        let mut text = String::new();
+        while let Some(chunk) = self.recv().await {
+            text.push_str(&chunk);
+        }
+        text
+    }
+}
+
+// ===
+// TESTS: StreamTee
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaSession;
+    use crate::mock_server::{MockServer, chat_body};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_fans_out_a_session_stream() {
+        let server = MockServer::start(vec![chat_body(&["Once ", "upon ", "a time."])]).await;
+
+        let tee = StreamTee::new();
+        let ui = tee.subscribe();
+        let transcript = tee.subscribe();
+        let characters = Arc::new(AtomicUsize::new(0));
+        let counted = characters.clone();
+        let counter = tee.spawn(move |chunk| {
+            counted.fetch_add(chunk.chars().count(), Ordering::Relaxed);
+        });
+        assert_eq!(tee.consumer_count(), 3);
+
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Tell me a story.");
+        session.update(tee.callback()).await.unwrap();
+        tee.finish();
+
+        counter.await.unwrap();
+        assert_eq!(characters.load(Ordering::Relaxed), 17);
+        assert_eq!(ui.collect().await, "Once upon a time.");
+        assert_eq!(transcript.collect().await, "Once upon a time.");
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_misses_oldest_chunks() {
+        let tee = StreamTee::with_capacity(2);
+        let mut slow = tee.subscribe();
+        for chunk in ["a", "b", "c", "d"] {
+            tee.send(chunk);
+        }
+        drop(tee);
+
+        assert_eq!(slow.recv().await.as_deref(), Some("c"));
+        assert_eq!(slow.recv().await.as_deref(), Some("d"));
+        assert_eq!(slow.recv().await, None);
+        assert_eq!(slow.missed(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_zero_capacity_is_treated_as_one() {
+        let tee = StreamTee::with_capacity(0);
+        let mut consumer = tee.subscribe();
+        tee.send("a");
+        tee.send("b");
+        drop(tee);
+
+        assert_eq!(consumer.recv().await.as_deref(), Some("b"));
+        assert_eq!(consumer.recv().await, None);
+        assert_eq!(consumer.missed(), 1);
+    }
+}
@@ -0,0 +1,301 @@
+// ===
+// STRUCT: CodeBlock
+// ===
+
+/// A fenced code block found in Markdown text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The language tag after the opening fence, e.g. "rust", if any.
+    pub language: Option<String>,
+    /// The code between the fences, without the fence lines.
+    pub code: String,
+}
+
+// ===
+// STRUCT: Markdown
+// ===
+
+/// Utilities for post-processing Markdown produced by a model.
+pub struct Markdown;
+
+impl Markdown {
+    /// Returns the fenced code blocks in the input, in order.
+    ///
+    /// A block that is still open at the end of the input, e.g. because generation
+    /// was cut off, runs to the end of the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The Markdown text
+    ///
+    /// # Returns
+    ///
+    /// The code blocks, with their language tags.
+    pub fn code_blocks(input: &str) -> Vec<CodeBlock> {
+        let mut blocks = Vec::new();
+        let mut current: Option<CodeBlock> = None;
+
+        for line in input.lines() {
+            let Some(tag) = fence(line) else {
+                if let Some(block) = &mut current {
+                    block.code.push_str(line);
+                    block.code.push('\n');
+                }
+                continue;
+            };
+
+            match current.take() {
+                Some(block) => blocks.push(block),
+                None => {
+                    current = Some(CodeBlock {
+                        language: (!tag.is_empty()).then(|| tag.to_string()),
+                        code: String::new(),
+                    });
+                }
+            }
+        }
+
+        blocks.extend(current);
+        blocks
+    }
+
+    /// Returns the code of the first block in the given language.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The Markdown text
+    /// * `language` - The language tag to look for, in any case, e.g. "python"
+    ///
+    /// # Returns
+    ///
+    /// The code of the first matching block, or None if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ollie_rs::text::Markdown;
+    ///
+    /// let answer = "Run this:\n```sh\nls -la\n```\nor in Python:\n```python\nprint(1)\n```";
+    /// assert_eq!(Markdown::code_block(answer, "python"), Some("print(1)\n".to_string()));
+    /// assert_eq!(Markdown::code_block(answer, "rust"), None);
+    /// ```
+    pub fn code_block(input: &str, language: &str) -> Option<String> {
+        Self::code_blocks(input)
+            .into_iter()
+            .find(|block| {
+                block
+                    .language
+                    .as_deref()
+                    .is_some_and(|tag| tag.eq_ignore_ascii_case(language))
+            })
+            .map(|block| block.code)
+    }
+
+    /// Removes the fence lines of code blocks, keeping the code itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The Markdown text
+    ///
+    /// # Returns
+    ///
+    /// The text without code fences.
+    pub fn strip_code_fences(input: &str) -> String {
+        let kept: Vec<&str> = input.lines().filter(|line| fence(line).is_none()).collect();
+        kept.join("\n")
+    }
+
+    /// Converts Markdown into plain text.
+    ///
+    /// Headings, block quotes, emphasis, inline code markers, rules and code fences
+    /// are removed, links and images are replaced by their text, and list bullets
+    /// are normalized to "- ". Code blocks are kept verbatim.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The Markdown text
+    ///
+    /// # Returns
+    ///
+    /// The plain text.
+    pub fn to_plain_text(input: &str) -> String {
+        let mut lines = Vec::new();
+        let mut in_code = false;
+
+        for line in input.lines() {
+            if fence(line).is_some() {
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                lines.push(line.to_string());
+                continue;
+            }
+
+            let trimmed = line.trim_start();
+            if is_rule(trimmed) {
+                continue;
+            }
+
+            let mut text = trimmed.trim_start_matches('#');
+            if text.len() != trimmed.len() {
+                text = text.trim_start();
+            }
+            while let Some(rest) = text.strip_prefix('>') {
+                text = rest.trim_start();
+            }
+
+            let indent = &line[..line.len() - trimmed.len()];
+            let (bullet, text) = match text.split_once(' ') {
+                Some(("-" | "*" | "+", rest)) => ("- ", rest),
+                _ => ("", text),
+            };
+            lines.push(format!("{indent}{bullet}{}", inline_to_plain(text)));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Returns the language tag if the line opens or closes a code fence.
+pub(super) fn fence(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    trimmed
+        .strip_prefix("```")
+        .or_else(|| trimmed.strip_prefix("~~~"))
+        .map(|tag| tag.trim_start_matches(['`', '~']).trim())
+}
+
+/// Returns true if the line is a horizontal rule, e.g. "---" or "***".
+pub(super) fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .into_iter()
+            .any(|marker| compact.chars().all(|c| c == marker))
+}
+
+/// Removes inline emphasis and code markers, and replaces links by their text.
+fn inline_to_plain(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        // Inline code is kept literally, without its backticks.
+        if c == '`'
+            && let Some(end) = chars[i + 1..].iter().position(|&next| next == '`')
+        {
+            plain.extend(&chars[i + 1..i + 1 + end]);
+            i += end + 2;
+            continue;
+        }
+
+        // Links and images: [text](url) and ![alt](url) become their text.
+        let label_start = if c == '!' && chars.get(i + 1) == Some(&'[') {
+            i + 1
+        } else {
+            i
+        };
+        if chars[label_start] == '['
+            && let Some(close) = chars[label_start..].iter().position(|&next| next == ']')
+            && chars.get(label_start + close + 1) == Some(&'(')
+            && let Some(paren) = chars[label_start + close..]
+                .iter()
+                .position(|&next| next == ')')
+        {
+            let label: String = chars[label_start + 1..label_start + close].iter().collect();
+            plain.push_str(&inline_to_plain(&label));
+            i = label_start + close + paren + 1;
+            continue;
+        }
+
+        // Emphasis markers sit against a word on one side only, unlike the `*`
+        // in "2 * 3" or the `_` in "snake_case".
+        if c == '*' || c == '_' {
+            let mut run = 1;
+            while chars.get(i + run) == Some(&c) {
+                run += 1;
+            }
+            let before = i.checked_sub(1).map(|j| chars[j]);
+            let after = chars.get(i + run).copied();
+            let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+            if is_word(before) != is_word(after) {
+                i += run;
+                continue;
+            }
+            plain.extend(&chars[i..i + run]);
+            i += run;
+            continue;
+        }
+
+        plain.push(c);
+        i += 1;
+    }
+
+    plain
+}
+
+// ===
+// TESTS: Markdown
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWER: &str = "\
+## Setup
+
+Install it with **cargo**:
+
+```sh
+cargo add ollie-rs
+```
+
+Then see [the docs](https://docs.rs/ollie-rs) and `main.rs`:
+
+```rust
+fn main() {}
+```
+
+---
+* It uses snake_case and 2 * 3 = 6.
+> Have _fun_!";
+
+    #[test]
+    fn test_code_blocks() {
+        let blocks = Markdown::code_blocks(ANSWER);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].language.as_deref(), Some("sh"));
+        assert_eq!(blocks[0].code, "cargo add ollie-rs\n");
+        assert_eq!(
+            Markdown::code_block(ANSWER, "Rust"),
+            Some("fn main() {}\n".to_string())
+        );
+
+        let truncated = Markdown::code_blocks("```\nunfinished");
+        assert_eq!(truncated[0].language, None);
+        assert_eq!(truncated[0].code, "unfinished\n");
+    }
+
+    #[test]
+    fn test_strip_code_fences() {
+        assert_eq!(
+            Markdown::strip_code_fences("Code:\n```json\n{}\n```"),
+            "Code:\n{}"
+        );
+    }
+
+    #[test]
+    fn test_to_plain_text() {
+        assert_eq!(
+            Markdown::to_plain_text(ANSWER),
+            "Setup\n\nInstall it with cargo:\n\ncargo add ollie-rs\n\n\
+             Then see the docs and main.rs:\n\nfn main() {}\n\n\
+             - It uses snake_case and 2 * 3 = 6.\nHave fun!"
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use super::markdown::{fence, is_rule};
+
+// ===
+// ENUM: MarkdownEvent
+// ===
+
+/// A complete line of streamed Markdown, classified by what it renders as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkdownEvent {
+    /// A heading, with its level from 1 to 6 and its text.
+    Heading { level: usize, text: String },
+    /// A list item, with the indentation of its marker and its text.
+    ListItem {
+        ordered: bool,
+        indent: usize,
+        text: String,
+    },
+    /// A block quote line, without the `>` markers.
+    Quote(String),
+    /// A horizontal rule.
+    Rule,
+    /// A line of ordinary text, or the continuation of a list item.
+    Text(String),
+    /// An empty line.
+    BlankLine,
+    /// The opening fence of a code block, with its language tag if any.
+    CodeStart { language: Option<String> },
+    /// A line of code, with the language of its block for syntax highlighting.
+    Code {
+        language: Option<String>,
+        line: String,
+    },
+    /// The closing fence of a code block.
+    CodeEnd,
+}
+
+// ===
+// STRUCT: MarkdownStream
+// ===
+
+/// Turns streamed Markdown deltas into render events, one per complete line.
+///
+/// The stream tracks whether a code block is open, and in which language, so a
+/// terminal UI can highlight code lines as they arrive instead of waiting for
+/// the closing fence. The incomplete last line is available from `pending` with
+/// the same context, for showing it live.
+///
+/// ```no_run
+/// # async fn example(session: &mut ollie_rs::OllamaSession) -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::{MarkdownEvent, MarkdownStream};
+///
+/// let mut markdown = MarkdownStream::new();
+/// let mut render = |event: MarkdownEvent| match event {
+///     MarkdownEvent::Code { language, line } => println!("[{language:?}] {line}"),
+///     other => println!("{other:?}"),
+/// };
+/// session
+///     .update(|delta| markdown.push(delta).into_iter().for_each(&mut render))
+///     .await?;
+/// markdown.finish().into_iter().for_each(&mut render);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkdownStream {
+    line: String,
+    code: Option<Option<String>>,
+    in_list: bool,
+    heading: Option<String>,
+}
+
+impl MarkdownStream {
+    /// Creates a stream outside any block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` while a code block is open.
+    pub fn in_code_block(&self) -> bool {
+        self.code.is_some()
+    }
+
+    /// Returns the language of the open code block, if it has one.
+    pub fn code_language(&self) -> Option<&str> {
+        self.code.as_ref().and_then(|language| language.as_deref())
+    }
+
+    /// Returns `true` while the lines belong to a list.
+    pub fn in_list(&self) -> bool {
+        self.in_list
+    }
+
+    /// Returns the text of the last heading, i.e. the current section.
+    pub fn heading(&self) -> Option<&str> {
+        self.heading.as_deref()
+    }
+
+    /// Returns the incomplete line received so far.
+    pub fn pending(&self) -> &str {
+        &self.line
+    }
+
+    /// Adds a streamed delta.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The next chunk of streamed Markdown.
+    ///
+    /// # Returns
+    ///
+    /// The events of the lines the delta completed, in order.
+    pub fn push(&mut self, delta: &str) -> Vec<MarkdownEvent> {
+        let mut events = Vec::new();
+        for piece in delta.split_inclusive('\n') {
+            self.line.push_str(piece);
+            if self.line.ends_with('\n') {
+                let line = std::mem::take(&mut self.line);
+                events.push(self.classify(line.trim_end_matches(['\n', '\r'])));
+            }
+        }
+        events
+    }
+
+    /// Ends the stream, classifying the last line if it has no newline.
+    ///
+    /// A code block still open, e.g. because generation was cut off, is closed
+    /// with a `CodeEnd` event.
+    ///
+    /// # Returns
+    ///
+    /// The remaining events.
+    pub fn finish(&mut self) -> Vec<MarkdownEvent> {
+        let mut events = Vec::new();
+        if !self.line.is_empty() {
+            let line = std::mem::take(&mut self.line);
+            events.push(self.classify(&line));
+        }
+        if self.code.take().is_some() {
+            events.push(MarkdownEvent::CodeEnd);
+        }
+        self.in_list = false;
+        events
+    }
+
+    /// Classifies a complete line and updates the block state.
+    fn classify(&mut self, line: &str) -> MarkdownEvent {
+        if let Some(tag) = fence(line) {
+            return match self.code.take() {
+                Some(_) => MarkdownEvent::CodeEnd,
+                None => {
+                    let language = (!tag.is_empty()).then(|| tag.to_string());
+                    self.code = Some(language.clone());
+                    self.in_list = false;
+                    MarkdownEvent::CodeStart { language }
+                }
+            };
+        }
+        if let Some(language) = &self.code {
+            return MarkdownEvent::Code {
+                language: language.clone(),
+                line: line.to_string(),
+            };
+        }
+
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if trimmed.is_empty() {
+            return MarkdownEvent::BlankLine;
+        }
+
+        // Checked first, since "* * *" is a rule rather than a list item.
+        if is_rule(trimmed) {
+            self.in_list = false;
+            return MarkdownEvent::Rule;
+        }
+        if let Some((ordered, text)) = list_item(trimmed) {
+            self.in_list = true;
+            return MarkdownEvent::ListItem {
+                ordered,
+                indent,
+                text: text.to_string(),
+            };
+        }
+
+        // An indented line continues the list item above it.
+        if indent == 0 {
+            self.in_list = false;
+        }
+
+        let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+        if indent == 0 && (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            let text = trimmed[hashes..].trim().to_string();
+            self.heading = Some(text.clone());
+            return MarkdownEvent::Heading {
+                level: hashes,
+                text,
+            };
+        }
+
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            let mut text = quote.trim_start();
+            while let Some(rest) = text.strip_prefix('>') {
+                text = rest.trim_start();
+            }
+            return MarkdownEvent::Quote(text.to_string());
+        }
+
+        MarkdownEvent::Text(line.to_string())
+    }
+}
+
+/// Returns whether a line is an ordered list item, and its text, if it is one.
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some((false, text));
+        }
+    }
+
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = &line[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some((true, &rest[2..]));
+    }
+    None
+}
+
+// ===
+// TESTS: MarkdownStream
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_across_deltas() {
+        let mut markdown = MarkdownStream::new();
+        let deltas = [
+            "## Set",
+            "up\n\n1. Install:\n  ",
+            "with cargo\n* done\n",
+            "> note\n---\n```r",
+            "ust\nfn main() {}\n",
+            "```\nBye",
+        ];
+
+        let mut events = Vec::new();
+        for delta in deltas {
+            events.extend(markdown.push(delta));
+            if delta.ends_with("{}\n") {
+                assert!(markdown.in_code_block());
+                assert_eq!(markdown.code_language(), Some("rust"));
+            }
+        }
+        assert_eq!(markdown.pending(), "Bye");
+        events.extend(markdown.finish());
+
+        let text = |text: &str| text.to_string();
+        assert_eq!(
+            events,
+            [
+                MarkdownEvent::Heading {
+                    level: 2,
+                    text: text("Setup")
+                },
+                MarkdownEvent::BlankLine,
+                MarkdownEvent::ListItem {
+                    ordered: true,
+                    indent: 0,
+                    text: text("Install:")
+                },
+                MarkdownEvent::Text(text("  with cargo")),
+                MarkdownEvent::ListItem {
+                    ordered: false,
+                    indent: 0,
+                    text: text("done")
+                },
+                MarkdownEvent::Quote(text("note")),
+                MarkdownEvent::Rule,
+                MarkdownEvent::CodeStart {
+                    language: Some(text("rust"))
+                },
+                MarkdownEvent::Code {
+                    language: Some(text("rust")),
+                    line: text("fn main() {}")
+                },
+                MarkdownEvent::CodeEnd,
+                MarkdownEvent::Text(text("Bye")),
+            ]
+        );
+        assert_eq!(markdown.heading(), Some("Setup"));
+        assert!(!markdown.in_list());
+    }
+
+    #[test]
+    fn test_list_state_and_cut_off_code() {
+        let mut markdown = MarkdownStream::new();
+        markdown.push("- one\n  more\n");
+        assert!(markdown.in_list());
+        assert_eq!(markdown.push("* * *\n"), [MarkdownEvent::Rule]);
+        markdown.push("- two\n");
+        markdown.push("after\n```\nlet x");
+        assert!(!markdown.in_list());
+        assert_eq!(markdown.code_language(), None);
+        assert!(markdown.in_code_block());
+
+        assert_eq!(
+            markdown.finish(),
+            [
+                MarkdownEvent::Code {
+                    language: None,
+                    line: "let x".to_string()
+                },
+                MarkdownEvent::CodeEnd,
+            ]
+        );
+        assert!(!markdown.in_code_block());
+    }
+}
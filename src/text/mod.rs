@@ -0,0 +1,19 @@
+#[doc(hidden)]
+pub mod markdown;
+#[doc(inline)]
+pub use markdown::*;
+
+#[doc(hidden)]
+pub mod markdown_stream;
+#[doc(inline)]
+pub use markdown_stream::*;
+
+#[doc(hidden)]
+pub mod text_chunker;
+#[doc(inline)]
+pub use text_chunker::*;
+
+#[doc(hidden)]
+pub mod text_coalescer;
+#[doc(inline)]
+pub use text_coalescer::*;
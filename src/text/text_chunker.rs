@@ -0,0 +1,147 @@
+use crate::estimate_tokens;
+
+// ===
+// STRUCT: TextChunker
+// ===
+
+/// Splits long text into pieces that fit a token budget.
+///
+/// Paragraphs, separated by blank lines, are packed together until the next one
+/// would exceed the budget. A paragraph that is too long on its own is split
+/// between words. Token counts are estimated with `estimate_tokens`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChunker {
+    max_tokens: u32,
+}
+
+impl TextChunker {
+    /// Creates a chunker.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_tokens` - The estimated size limit of a chunk, at least 1
+    pub fn new(max_tokens: u32) -> Self {
+        Self {
+            max_tokens: max_tokens.max(1),
+        }
+    }
+
+    /// Returns the estimated size limit of a chunk.
+    pub fn max_tokens(&self) -> u32 {
+        self.max_tokens
+    }
+
+    /// Splits a text into chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to split
+    ///
+    /// # Returns
+    ///
+    /// The chunks in order, with paragraphs joined by blank lines. A single word
+    /// longer than the limit becomes a chunk of its own.
+    pub fn chunks(&self, text: &str) -> Vec<String> {
+        let mut chunks = Vec::new();
+        let mut current = String::new();
+
+        for paragraph in paragraphs(text) {
+            if estimate_tokens(&paragraph) > self.max_tokens {
+                push_chunk(&mut chunks, &mut current);
+                self.split_words(&paragraph, &mut chunks);
+                continue;
+            }
+
+            let joined = if current.is_empty() {
+                paragraph.clone()
+            } else {
+                format!("{current}\n\n{paragraph}")
+            };
+            if estimate_tokens(&joined) > self.max_tokens {
+                push_chunk(&mut chunks, &mut current);
+                current = paragraph;
+            } else {
+                current = joined;
+            }
+        }
+
+        push_chunk(&mut chunks, &mut current);
+        chunks
+    }
+
+    /// Splits a paragraph that exceeds the limit between words.
+    fn split_words(&self, paragraph: &str, chunks: &mut Vec<String>) {
+        let mut current = String::new();
+        for word in paragraph.split_whitespace() {
+            let joined = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{current} {word}")
+            };
+            if estimate_tokens(&joined) > self.max_tokens && !current.is_empty() {
+                push_chunk(chunks, &mut current);
+                current = word.to_string();
+            } else {
+                current = joined;
+            }
+        }
+        push_chunk(chunks, &mut current);
+    }
+}
+
+/// Returns the non-empty paragraphs of a text, trimmed.
+fn paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join("\n"));
+    }
+    paragraphs
+}
+
+/// Moves a non-empty chunk into the list.
+fn push_chunk(chunks: &mut Vec<String>, current: &mut String) {
+    if !current.is_empty() {
+        chunks.push(std::mem::take(current));
+    }
+}
+
+// ===
+// TESTS: TextChunker
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_pack_paragraphs() {
+        let text = "One two three.\n\nFour five six.\n\n\n\nSeven eight nine.";
+        let chunker = TextChunker::new(8);
+        assert_eq!(
+            chunker.chunks(text),
+            ["One two three.\n\nFour five six.", "Seven eight nine."]
+        );
+        assert_eq!(TextChunker::new(100).chunks(text).len(), 1);
+        assert!(chunker.chunks(" \n\n ").is_empty());
+    }
+
+    #[test]
+    fn test_chunks_split_long_paragraph() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta";
+        let chunks = TextChunker::new(4).chunks(text);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| estimate_tokens(chunk) <= 4));
+        assert_eq!(chunks.join(" "), text);
+    }
+}
@@ -0,0 +1,180 @@
+/// The delimiters ending a sentence: a terminator followed by whitespace.
+const SENTENCE_DELIMITERS: &[&str] = &[
+    ". ", "! ", "? ", ".\n", "!\n", "?\n", ".\t", "!\t", "?\t", "\n\n",
+];
+
+/// The delimiter ending a paragraph: a blank line.
+const PARAGRAPH_DELIMITERS: &[&str] = &["\n\n"];
+
+// ===
+// STRUCT: TextCoalescer
+// ===
+
+/// Buffers streamed text deltas and releases them as complete sentences or
+/// paragraphs.
+///
+/// Consumers such as text-to-speech, translation or markdown rendering work
+/// poorly on fragments that end mid-word. Each piece released ends with the
+/// delimiter that completed it, so the pieces joined are exactly the streamed
+/// text.
+///
+/// ```no_run
+/// # async fn example(session: &mut ollie_rs::OllamaSession) -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::TextCoalescer;
+///
+/// let mut sentences = TextCoalescer::sentences();
+/// session
+///     .update(|delta| {
+///         for sentence in sentences.push(delta) {
+///             println!("speak: {sentence}");
+///         }
+///     })
+///     .await?;
+/// if let Some(rest) = sentences.finish() {
+///     println!("speak: {rest}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextCoalescer {
+    delimiters: Vec<String>,
+    buffer: String,
+}
+
+impl TextCoalescer {
+    /// Creates a coalescer releasing a piece after each of the given delimiters.
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiters` - The strings ending a piece; empty strings are ignored.
+    ///
+    /// # Returns
+    ///
+    /// A new `TextCoalescer`.
+    pub fn new<S: AsRef<str>>(delimiters: &[S]) -> Self {
+        Self {
+            delimiters: delimiters
+                .iter()
+                .map(|delimiter| delimiter.as_ref().to_string())
+                .filter(|delimiter| !delimiter.is_empty())
+                .collect(),
+            buffer: String::new(),
+        }
+    }
+
+    /// Creates a coalescer releasing complete sentences.
+    ///
+    /// A sentence ends at `.`, `!` or `?` followed by whitespace, or at a blank
+    /// line. Numbers such as "3.14" are not split.
+    pub fn sentences() -> Self {
+        Self::new(SENTENCE_DELIMITERS)
+    }
+
+    /// Creates a coalescer releasing complete paragraphs, which end at a blank line.
+    pub fn paragraphs() -> Self {
+        Self::new(PARAGRAPH_DELIMITERS)
+    }
+
+    /// Returns the text held back until its piece is complete.
+    pub fn pending(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Adds a streamed delta.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The next chunk of streamed text.
+    ///
+    /// # Returns
+    ///
+    /// The pieces the delta completed, in order; often none.
+    pub fn push(&mut self, delta: &str) -> Vec<String> {
+        // A delimiter may straddle the previous delta, so search from just before it.
+        let longest = self.delimiters.iter().map(String::len).max().unwrap_or(0);
+        let mut from = self.buffer.len().saturating_sub(longest.saturating_sub(1));
+        self.buffer.push_str(delta);
+
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        loop {
+            while !self.buffer.is_char_boundary(from) {
+                from -= 1;
+            }
+            let end = self
+                .delimiters
+                .iter()
+                .filter_map(|delimiter| {
+                    let found = self.buffer[from..].find(delimiter.as_str())?;
+                    Some(from + found + delimiter.len())
+                })
+                .min();
+            let Some(end) = end else {
+                break;
+            };
+            pieces.push(self.buffer[start..end].to_string());
+            start = end;
+            from = end;
+        }
+
+        self.buffer.drain(..start);
+        pieces
+    }
+
+    /// Releases the text still held back, e.g. a last sentence without a delimiter.
+    ///
+    /// # Returns
+    ///
+    /// The remaining text, or `None` if there is none.
+    pub fn finish(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.buffer))
+    }
+}
+
+// ===
+// TESTS: TextCoalescer
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coalesce(coalescer: &mut TextCoalescer, deltas: &[&str]) -> Vec<String> {
+        let mut pieces: Vec<String> = deltas
+            .iter()
+            .flat_map(|delta| coalescer.push(delta))
+            .collect();
+        pieces.extend(coalescer.finish());
+        pieces
+    }
+
+    #[test]
+    fn test_sentences() {
+        let mut sentences = TextCoalescer::sentences();
+        let deltas = ["Pi is 3", ".14", ". Is", " it? Y", "es!", "\nDone"];
+        assert_eq!(
+            coalesce(&mut sentences, &deltas),
+            ["Pi is 3.14. ", "Is it? ", "Yes!\n", "Done"]
+        );
+        assert_eq!(sentences.pending(), "");
+        assert_eq!(sentences.finish(), None);
+    }
+
+    #[test]
+    fn test_delimiter_split_across_deltas() {
+        let mut paragraphs = TextCoalescer::paragraphs();
+        assert!(paragraphs.push("# Title\n").is_empty());
+        assert_eq!(paragraphs.push("\nBody é"), ["# Title\n\n"]);
+        assert_eq!(paragraphs.pending(), "Body é");
+
+        let mut custom = TextCoalescer::new(&["<br>", ""]);
+        assert_eq!(
+            coalesce(&mut custom, &["a<b", "r>b<", "br><br>"]),
+            ["a<br>", "b<br>", "<br>"]
+        );
+    }
+}
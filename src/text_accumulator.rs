@@ -0,0 +1,158 @@
+use std::fmt;
+
+/// The minimum capacity of each segment, so short chunks share a segment.
+const SEGMENT_CAPACITY: usize = 4096;
+
+// ===
+// STRUCT: TextAccumulator
+// ===
+
+/// Collects streamed text in fixed segments instead of one growing `String`.
+///
+/// Each segment is allocated once and never grows, so appending tens of thousands
+/// of small chunks never moves text that was already received. The final text is
+/// assembled with a single allocation of the exact size.
+#[derive(Debug, Clone, Default)]
+pub struct TextAccumulator {
+    segments: Vec<String>,
+    len: usize,
+}
+
+impl TextAccumulator {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty accumulator whose first segment holds `capacity` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The expected length of the text, e.g. from `num_predict`.
+    ///
+    /// # Returns
+    ///
+    /// A new `TextAccumulator` with the first segment preallocated.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            segments: vec![String::with_capacity(capacity)],
+            len: 0,
+        }
+    }
+
+    /// Appends a chunk of text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The chunk to append.
+    pub fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        match self.segments.last_mut() {
+            Some(segment) if segment.capacity() - segment.len() >= text.len() => {
+                segment.push_str(text);
+            }
+            _ => {
+                let mut segment = String::with_capacity(text.len().max(SEGMENT_CAPACITY));
+                segment.push_str(text);
+                self.segments.push(segment);
+            }
+        }
+        self.len += text.len();
+    }
+
+    /// Returns the length of the accumulated text in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no text has been accumulated.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the accumulated text as a sequence of segments, in order.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().map(String::as_str)
+    }
+
+    /// Removes all text, keeping the first segment's allocation.
+    pub fn clear(&mut self) {
+        self.segments.truncate(1);
+        if let Some(segment) = self.segments.first_mut() {
+            segment.clear();
+        }
+        self.len = 0;
+    }
+
+    /// Copies the accumulated text into a single `String`.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.len);
+        self.segments().for_each(|segment| text.push_str(segment));
+        text
+    }
+
+    /// Converts the accumulator into a single `String`, without copying if it has one segment.
+    pub fn into_string(mut self) -> String {
+        if self.segments.len() == 1 {
+            self.segments.pop().unwrap_or_default()
+        } else {
+            self.text()
+        }
+    }
+}
+
+// ===
+// TRAIT: Display for TextAccumulator
+// ===
+
+impl fmt::Display for TextAccumulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.segments().try_for_each(|segment| f.write_str(segment))
+    }
+}
+
+// ===
+// TESTS: TextAccumulator
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_never_grow() {
+        let mut text = TextAccumulator::new();
+        let chunk = "x".repeat(1000);
+        for _ in 0..10 {
+            text.push(&chunk);
+        }
+
+        assert_eq!(text.len(), 10_000);
+        assert_eq!(text.segments().count(), 3);
+        assert!(
+            text.segments
+                .iter()
+                .all(|s| s.capacity() == SEGMENT_CAPACITY)
+        );
+        assert_eq!(text.text(), chunk.repeat(10));
+        assert_eq!(text.to_string(), text.text());
+    }
+
+    #[test]
+    fn test_with_capacity_and_clear() {
+        let mut text = TextAccumulator::with_capacity(16);
+        text.push("Hello, ");
+        text.push("");
+        text.push("world!");
+        assert_eq!(text.segments().collect::<Vec<_>>(), ["Hello, world!"]);
+
+        text.clear();
+        assert!(text.is_empty());
+        text.push("again");
+        assert_eq!(text.into_string(), "again");
+        assert_eq!(TextAccumulator::new().into_string(), "");
+    }
+}
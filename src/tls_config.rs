@@ -0,0 +1,180 @@
+use std::error::Error;
+use std::path::Path;
+
+// ===
+// STRUCT: TlsConfig
+// ===
+
+/// How a client verifies the certificates of HTTPS servers.
+///
+/// By default clients trust the system's root certificates. Add the root
+/// certificate of a private CA to reach, say, a self-hosted Ollama server behind
+/// an HTTPS reverse proxy with its own certificate.
+///
+/// An `Ollama` client is addressed by IP, so its server's certificate must list
+/// that IP address unless a server name is set with `set_server_name`; the client
+/// then verifies the certificate against the name while still connecting to the
+/// address.
+///
+/// ```no_run
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::{Ollama, TlsConfig};
+///
+/// let mut tls = TlsConfig::new();
+/// tls.add_root_certificate_file("certs/internal-ca.pem")?
+///     .set_server_name("ollama.internal");
+///
+/// let mut ollama = Ollama::new("10.0.0.5:443");
+/// ollama.set_tls(&tls)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    root_certificates: Vec<reqwest::Certificate>,
+    server_name: Option<String>,
+    accept_invalid_certs: bool,
+}
+
+impl TlsConfig {
+    /// Creates a configuration that trusts the system's root certificates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts a root certificate in addition to the system's.
+    ///
+    /// # Arguments
+    ///
+    /// * `pem` - The certificate, PEM encoded.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&mut Self)` - The configuration, for method chaining.
+    /// * `Err(Box<dyn Error>)` - If the certificate cannot be parsed.
+    pub fn add_root_certificate_pem(&mut self, pem: &[u8]) -> Result<&mut Self, Box<dyn Error>> {
+        let certificate = reqwest::Certificate::from_pem(pem)
+            .map_err(|err| format!("invalid root certificate: {err}"))?;
+        self.root_certificates.push(certificate);
+        Ok(self)
+    }
+
+    /// Trusts the root certificate stored in a PEM file in addition to the system's.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The PEM file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&mut Self)` - The configuration, for method chaining.
+    /// * `Err(Box<dyn Error>)` - If the file cannot be read or parsed.
+    pub fn add_root_certificate_file(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<&mut Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let pem =
+            std::fs::read(path).map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+        self.add_root_certificate_pem(&pem)
+    }
+
+    /// Returns the number of root certificates trusted in addition to the system's.
+    pub fn root_certificate_count(&self) -> usize {
+        self.root_certificates.len()
+    }
+
+    /// Sets the host name the server's certificate is issued for, e.g.
+    /// "ollama.internal", for clients addressed by IP.
+    ///
+    /// Requests name the host in their URL, and an `Ollama` client resolves it
+    /// to its own address. Behind a proxy, the proxy must resolve the name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The host name to verify the certificate against.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_server_name(&mut self, name: &str) -> &mut Self {
+        self.server_name = Some(name.to_string());
+        self
+    }
+
+    /// Returns the host name the server's certificate is verified against, if set.
+    pub fn server_name(&self) -> Option<&str> {
+        self.server_name.as_deref()
+    }
+
+    /// Accepts any server certificate, including expired, self-signed and
+    /// mismatched ones.
+    ///
+    /// This makes the connection open to interception, so only use it in
+    /// development. A client built with it prints a warning to stderr.
+    ///
+    /// # Arguments
+    ///
+    /// * `accept` - Whether to skip certificate verification.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_danger_accept_invalid_certs(&mut self, accept: bool) -> &mut Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Returns `true` if certificate verification is disabled.
+    pub fn danger_accept_invalid_certs(&self) -> bool {
+        self.accept_invalid_certs
+    }
+
+    /// Applies the configuration to an HTTP client under construction.
+    pub(crate) fn apply(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        if self.accept_invalid_certs {
+            eprintln!(
+                "warning: ollie-rs: TLS certificate verification is disabled; \
+                 connections can be intercepted, so never use this in production"
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        builder
+    }
+}
+
+// ===
+// TESTS: TlsConfig
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_certificates() {
+        let mut tls = TlsConfig::new();
+        let error = tls
+            .add_root_certificate_pem(b"not a certificate")
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with("invalid root certificate"),
+            "{error}"
+        );
+
+        let error = tls
+            .add_root_certificate_file("/nonexistent/ca.pem")
+            .unwrap_err();
+        assert!(error.to_string().contains("/nonexistent/ca.pem"), "{error}");
+        assert_eq!(tls.root_certificate_count(), 0);
+
+        tls.set_server_name("ollama.internal");
+        assert_eq!(tls.server_name(), Some("ollama.internal"));
+        tls.set_danger_accept_invalid_certs(true);
+        assert!(tls.danger_accept_invalid_certs());
+        assert!(tls.apply(reqwest::Client::builder()).build().is_ok());
+    }
+}
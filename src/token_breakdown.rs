@@ -0,0 +1,172 @@
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The tokens a chat template adds around each message, e.g. role markers.
+const MESSAGE_OVERHEAD: u32 = 4;
+
+/// Estimates the number of tokens in a text, at about four characters per token.
+///
+/// This is a rough, model-independent heuristic; use the counts reported by the
+/// server where they are available.
+pub fn estimate_tokens(text: &str) -> u32 {
+    u32::try_from(text.chars().count().div_ceil(4)).unwrap_or(u32::MAX)
+}
+
+// ===
+// STRUCT: MessageTokens
+// ===
+
+/// The token count of one message in a conversation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageTokens {
+    /// The position of the message in the history.
+    pub index: usize,
+    /// The role of the message, e.g. "user".
+    pub role: String,
+    /// The number of tokens of the message.
+    pub tokens: u32,
+    /// Whether `tokens` is an estimate rather than a count reported by the server.
+    pub estimated: bool,
+}
+
+// ===
+// STRUCT: TokenBreakdown
+// ===
+
+/// Per-message and per-role token counts of a conversation.
+///
+/// Returned by `OllamaSession::token_breakdown` to show where the context goes.
+/// Assistant messages generated in the session use the server's `eval_count`;
+/// all other messages are estimated with `estimate_tokens`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenBreakdown {
+    messages: Vec<MessageTokens>,
+}
+
+impl TokenBreakdown {
+    /// Estimates the tokens of each message of a chat history.
+    ///
+    /// # Arguments
+    /// * `messages` - The messages, as sent to the chat endpoint
+    ///
+    /// # Returns
+    /// * A breakdown with an estimate for every message
+    pub fn from_messages<'a>(messages: impl IntoIterator<Item = &'a JsonValue>) -> Self {
+        let messages = messages
+            .into_iter()
+            .enumerate()
+            .map(|(index, message)| MessageTokens {
+                index,
+                role: message["role"].as_str().unwrap_or("unknown").to_string(),
+                tokens: estimate_message(message),
+                estimated: true,
+            })
+            .collect();
+
+        Self { messages }
+    }
+
+    /// Replaces the estimate of a message with a count reported by the server.
+    ///
+    /// # Arguments
+    /// * `index` - The position of the message in the history
+    /// * `tokens` - The exact token count
+    pub fn set_exact(&mut self, index: usize, tokens: u32) {
+        if let Some(message) = self.messages.get_mut(index) {
+            message.tokens = tokens;
+            message.estimated = false;
+        }
+    }
+
+    /// Returns the token counts of the messages, in history order.
+    pub fn messages(&self) -> &[MessageTokens] {
+        &self.messages
+    }
+
+    /// Returns the total number of tokens of the conversation.
+    pub fn total(&self) -> u32 {
+        self.messages.iter().map(|message| message.tokens).sum()
+    }
+
+    /// Returns the total number of tokens per role.
+    pub fn by_role(&self) -> BTreeMap<String, u32> {
+        let mut roles = BTreeMap::new();
+        for message in &self.messages {
+            *roles.entry(message.role.clone()).or_insert(0) += message.tokens;
+        }
+        roles
+    }
+
+    /// Returns the `count` messages with the most tokens, largest first.
+    pub fn largest(&self, count: usize) -> Vec<&MessageTokens> {
+        let mut messages: Vec<&MessageTokens> = self.messages.iter().collect();
+        messages.sort_by_key(|message| std::cmp::Reverse(message.tokens));
+        messages.truncate(count);
+        messages
+    }
+}
+
+/// Estimates the tokens of a message's content and tool calls, plus the template overhead.
+fn estimate_message(message: &JsonValue) -> u32 {
+    let content = message["content"].as_str().map_or(0, estimate_tokens);
+    let tool_calls = message
+        .get("tool_calls")
+        .map_or(0, |calls| estimate_tokens(&calls.to_string()));
+
+    MESSAGE_OVERHEAD + content + tool_calls
+}
+
+// ===
+// TRAIT: TokenBreakdown (fmt::Display)
+// ===
+
+impl fmt::Display for TokenBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total();
+        write!(
+            f,
+            "total: {total} tokens in {} messages",
+            self.messages.len()
+        )?;
+        for (role, tokens) in self.by_role() {
+            let share = f64::from(tokens) * 100.0 / f64::from(total.max(1));
+            write!(f, "\n{role}: {tokens} tokens ({share:.0}%)")?;
+        }
+        Ok(())
+    }
+}
+
+// ===
+// TESTS: TokenBreakdown
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_breakdown() {
+        let messages = [
+            json!({ "role": "system", "content": "Be brief." }),
+            json!({ "role": "user", "content": "What is the capital of France?" }),
+            json!({ "role": "assistant", "content": "Paris." }),
+        ];
+
+        let mut breakdown = TokenBreakdown::from_messages(&messages);
+        assert_eq!(breakdown.messages()[0].tokens, 4 + 3);
+        assert_eq!(breakdown.messages()[1].tokens, 4 + 8);
+
+        breakdown.set_exact(2, 3);
+        assert!(!breakdown.messages()[2].estimated);
+        assert_eq!(breakdown.total(), 7 + 12 + 3);
+        assert_eq!(breakdown.by_role()["user"], 12);
+        assert_eq!(breakdown.largest(1)[0].index, 1);
+        assert_eq!(
+            breakdown.to_string(),
+            "total: 22 tokens in 3 messages\nassistant: 3 tokens (14%)\n\
+             system: 7 tokens (32%)\nuser: 12 tokens (55%)"
+        );
+    }
+}
@@ -0,0 +1,490 @@
+//! A small collection of ready-made tools for agent demos and prototypes.
+//!
+//! Every constructor returns a [`Tool`] that can be added to a [`ToolRegistry`];
+//! [`registry`] bundles the tools that are safe to enable without extra configuration.
+//! All tools apply conservative limits: the shell tool only runs allow-listed programs
+//! without a shell, file access is confined to a root directory, and all outputs are
+//! capped in size.
+
+use crate::{Tool, ToolRegistry, ToolResult};
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The maximum number of bytes any built-in tool returns to the model.
+pub const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// The maximum time a shell command may run before it is killed.
+pub const SHELL_TIMEOUT: Duration = Duration::from_secs(30);
+
+// ===
+// PUBLIC: builtin
+// ===
+
+/// Creates a registry with the built-in tools that need no allow-list: file
+/// read/write confined to `root`, HTTP GET and the current time.
+///
+/// # Arguments
+///
+/// * `root` - The directory the file tools are restricted to.
+///
+/// # Returns
+///
+/// A new `ToolRegistry` containing the built-in tools.
+pub fn registry(root: impl AsRef<Path>) -> ToolRegistry {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(read_file(root.as_ref()))
+        .register(write_file(root.as_ref()))
+        .register(http_get(MAX_OUTPUT_BYTES))
+        .register(current_time());
+    registry
+}
+
+/// Creates a `run_command` tool that executes allow-listed programs.
+///
+/// Commands are spawned directly (no shell interpretation, so pipes, globs and
+/// redirections are not available), run inside `working_dir`, are killed after
+/// [`SHELL_TIMEOUT`] and have their output truncated to [`MAX_OUTPUT_BYTES`].
+///
+/// # Arguments
+///
+/// * `allowed` - The program names the model is allowed to run (e.g. `["ls", "cat"]`).
+/// * `working_dir` - The directory commands are run in.
+///
+/// # Returns
+///
+/// The `run_command` tool.
+pub fn shell(allowed: &[&str], working_dir: impl AsRef<Path>) -> Tool {
+    let allowed: Arc<Vec<String>> = Arc::new(allowed.iter().map(|s| s.to_string()).collect());
+    let working_dir = Arc::new(working_dir.as_ref().to_path_buf());
+    let description = format!(
+        "Runs a program with arguments and returns its exit code, stdout and stderr. Allowed programs: {}.",
+        allowed.join(", ")
+    );
+
+    let parameters = json!({
+        "type": "object",
+        "properties": {
+            "command": { "type": "string", "description": "The program to run." },
+            "args": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "The arguments passed to the program."
+            }
+        },
+        "required": ["command"]
+    });
+
+    Tool::new("run_command", &description, parameters, move |args| {
+        let allowed = allowed.clone();
+        let working_dir = working_dir.clone();
+        async move { run_command(&allowed, &working_dir, args).await }
+    })
+}
+
+/// Creates an `http_get` tool that fetches a URL and returns its body as text.
+///
+/// # Arguments
+///
+/// * `max_bytes` - The maximum number of body bytes returned; longer bodies are truncated.
+///
+/// # Returns
+///
+/// The `http_get` tool.
+pub fn http_get(max_bytes: usize) -> Tool {
+    let client = reqwest::Client::new();
+    let parameters = json!({
+        "type": "object",
+        "properties": {
+            "url": { "type": "string", "description": "The http or https URL to fetch." }
+        },
+        "required": ["url"]
+    });
+
+    Tool::new(
+        "http_get",
+        "Fetches a web page or API endpoint with an HTTP GET request and returns the response body.",
+        parameters,
+        move |args| {
+            let client = client.clone();
+            async move { fetch(&client, max_bytes, args).await }
+        },
+    )
+}
+
+/// Creates a `read_file` tool that reads text files below `root`.
+///
+/// # Arguments
+///
+/// * `root` - The directory the tool is restricted to.
+///
+/// # Returns
+///
+/// The `read_file` tool.
+pub fn read_file(root: impl AsRef<Path>) -> Tool {
+    let root = Arc::new(root.as_ref().to_path_buf());
+    let parameters = json!({
+        "type": "object",
+        "properties": {
+            "path": { "type": "string", "description": "The file path, relative to the workspace root." }
+        },
+        "required": ["path"]
+    });
+
+    Tool::new(
+        "read_file",
+        "Reads a text file from the workspace and returns its contents.",
+        parameters,
+        move |args| {
+            let root = root.clone();
+            async move {
+                let path = resolve(&root, string_arg(&args, "path")?, true)?;
+                let bytes = tokio::fs::read(&path).await?;
+                let (text, truncated) = truncate(&bytes, MAX_OUTPUT_BYTES);
+                Ok(json!({ "content": text, "truncated": truncated }))
+            }
+        },
+    )
+}
+
+/// Creates a `write_file` tool that writes text files below `root`.
+///
+/// Missing parent directories are created; existing files are overwritten.
+///
+/// # Arguments
+///
+/// * `root` - The directory the tool is restricted to.
+///
+/// # Returns
+///
+/// The `write_file` tool.
+pub fn write_file(root: impl AsRef<Path>) -> Tool {
+    let root = Arc::new(root.as_ref().to_path_buf());
+    let parameters = json!({
+        "type": "object",
+        "properties": {
+            "path": { "type": "string", "description": "The file path, relative to the workspace root." },
+            "content": { "type": "string", "description": "The text to write to the file." }
+        },
+        "required": ["path", "content"]
+    });
+
+    Tool::new(
+        "write_file",
+        "Writes text to a file in the workspace, replacing any existing content.",
+        parameters,
+        move |args| {
+            let root = root.clone();
+            async move {
+                let content = string_arg(&args, "content")?;
+                let path = resolve(&root, string_arg(&args, "path")?, false)?;
+
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+
+                tokio::fs::write(&path, content).await?;
+                Ok(json!({ "bytes_written": content.len() }))
+            }
+        },
+    )
+}
+
+/// Creates a `current_time` tool returning the current UTC time.
+///
+/// # Returns
+///
+/// The `current_time` tool.
+pub fn current_time() -> Tool {
+    let parameters = json!({ "type": "object", "properties": {}, "required": [] });
+
+    Tool::new(
+        "current_time",
+        "Returns the current date and time in UTC.",
+        parameters,
+        |_args| async move {
+            let seconds = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            Ok(json!({ "utc": format_utc(seconds), "unix_seconds": seconds }))
+        },
+    )
+}
+
+// ===
+// PRIVATE: builtin
+// ===
+
+async fn run_command(allowed: &[String], working_dir: &Path, args: JsonValue) -> ToolResult {
+    let command = string_arg(&args, "command")?;
+
+    if !allowed.iter().any(|allowed| allowed == command) {
+        return Err(format!("command '{command}' is not allowed").into());
+    }
+
+    let command_args: Vec<String> = match args.get("args") {
+        Some(JsonValue::Null) | None => Vec::new(),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|err| format!("invalid argument 'args': {err}"))?,
+    };
+
+    let child = tokio::process::Command::new(command)
+        .args(&command_args)
+        .current_dir(working_dir)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    let output = tokio::time::timeout(SHELL_TIMEOUT, child)
+        .await
+        .map_err(|_| format!("command '{command}' timed out"))??;
+
+    let (stdout, stdout_truncated) = truncate(&output.stdout, MAX_OUTPUT_BYTES);
+    let (stderr, stderr_truncated) = truncate(&output.stderr, MAX_OUTPUT_BYTES);
+
+    Ok(json!({
+        "exit_code": output.status.code(),
+        "stdout": stdout,
+        "stderr": stderr,
+        "truncated": stdout_truncated || stderr_truncated,
+    }))
+}
+
+async fn fetch(client: &reqwest::Client, max_bytes: usize, args: JsonValue) -> ToolResult {
+    let url = string_arg(&args, "url")?;
+
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("unsupported URL '{url}', expected http or https").into());
+    }
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| err.without_url())?;
+    let status = response.status().as_u16();
+
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await.map_err(|err| err.without_url())? {
+        body.extend_from_slice(&chunk);
+        if body.len() > max_bytes {
+            truncated = true;
+            break;
+        }
+    }
+
+    let (text, cut) = truncate(&body, max_bytes);
+    Ok(json!({ "status": status, "body": text, "truncated": truncated || cut }))
+}
+
+/// Returns a required string argument from the call arguments.
+fn string_arg<'a>(args: &'a JsonValue, name: &str) -> Result<&'a str, String> {
+    args.get(name)
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| format!("missing string argument '{name}'"))
+}
+
+/// Resolves `relative` inside `root`, rejecting paths that escape it.
+///
+/// When `must_exist` is true the file itself is canonicalized (following symlinks).
+/// Otherwise the file must not be a symlink, and its deepest existing ancestor is
+/// canonicalized, so directories created later cannot lead through a symlink.
+fn resolve(root: &Path, relative: &str, must_exist: bool) -> Result<PathBuf, String> {
+    let relative = Path::new(relative);
+    let escapes = relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+
+    if escapes {
+        return Err(format!(
+            "path '{}' must be relative to the workspace root",
+            relative.display()
+        ));
+    }
+
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("invalid workspace root: {err}"))?;
+    let path = root.join(relative);
+
+    let checked = if must_exist {
+        path.canonicalize()
+            .map_err(|err| format!("cannot open '{}': {err}", relative.display()))?
+    } else {
+        if path
+            .symlink_metadata()
+            .is_ok_and(|metadata| metadata.file_type().is_symlink())
+        {
+            return Err(format!("path '{}' is a symlink", relative.display()));
+        }
+        // The root exists, so some ancestor always does.
+        path.ancestors()
+            .find_map(|ancestor| ancestor.canonicalize().ok())
+            .unwrap_or_else(|| root.clone())
+    };
+
+    if !checked.starts_with(&root) {
+        return Err(format!(
+            "path '{}' is outside the workspace root",
+            relative.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// Converts bytes to text, truncating to at most `max_bytes` on a character boundary.
+fn truncate(bytes: &[u8], max_bytes: usize) -> (String, bool) {
+    let text = String::from_utf8_lossy(bytes);
+
+    if text.len() <= max_bytes {
+        return (text.into_owned(), false);
+    }
+
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    (text[..end].to_string(), true)
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+fn format_utc(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let secs_of_day = seconds % 86_400;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3_600,
+        (secs_of_day % 3_600) / 60,
+        secs_of_day % 60
+    )
+}
+
+// ===
+// TESTS: builtin
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("ollie-builtin-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate(b"hello", 10), ("hello".to_string(), false));
+        assert_eq!(truncate(b"hello", 3), ("hel".to_string(), true));
+        // "é" is two bytes, so truncating inside it backs off to the boundary.
+        assert_eq!(truncate("aé".as_bytes(), 2), ("a".to_string(), true));
+    }
+
+    #[test]
+    fn test_resolve_rejects_escapes() {
+        let root = temp_root("resolve");
+        assert!(resolve(&root, "../etc/passwd", false).is_err());
+        assert!(resolve(&root, "/etc/passwd", false).is_err());
+        assert!(resolve(&root, "notes/today.txt", false).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_rejects_symlinks() {
+        let root = temp_root("symlinks");
+        let outside = temp_root("symlinks-outside");
+        let _ = std::fs::remove_file(root.join("link"));
+        let _ = std::fs::remove_file(root.join("file.txt"));
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("x.txt"), root.join("file.txt")).unwrap();
+
+        // Directories that do not exist yet are checked through the symlink.
+        let error = resolve(&root, "link/new/x.txt", false).unwrap_err();
+        assert!(error.contains("outside the workspace root"), "{error}");
+        let error = resolve(&root, "file.txt", false).unwrap_err();
+        assert!(error.contains("is a symlink"), "{error}");
+        assert!(!outside.join("new").exists());
+    }
+
+    #[tokio::test]
+    async fn test_write_then_read_file() {
+        let root = temp_root("files");
+        let registry = registry(&root);
+
+        let written = registry
+            .call("write_file", json!({ "path": "a/b.txt", "content": "hi" }))
+            .await
+            .unwrap();
+        assert_eq!(written["bytes_written"], 2);
+
+        let read = registry
+            .call("read_file", json!({ "path": "a/b.txt" }))
+            .await
+            .unwrap();
+        assert_eq!(read["content"], "hi");
+        assert_eq!(read["truncated"], false);
+
+        assert!(
+            registry
+                .call("read_file", json!({ "path": "../b.txt" }))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shell_allow_list() {
+        let root = temp_root("shell");
+        let tool = shell(&["echo"], &root);
+
+        let output = tool
+            .call(json!({ "command": "echo", "args": ["hello"] }))
+            .await
+            .unwrap();
+        assert_eq!(output["exit_code"], 0);
+        assert_eq!(output["stdout"], "hello\n");
+
+        let denied = tool
+            .call(json!({ "command": "rm", "args": ["-rf", "."] }))
+            .await;
+        assert!(denied.unwrap_err().to_string().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_http_get_rejects_non_http() {
+        let tool = http_get(1024);
+        let result = tool.call(json!({ "url": "file:///etc/passwd" })).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_current_time() {
+        let output = current_time().call(json!({})).await.unwrap();
+        assert!(output["utc"].as_str().unwrap().ends_with('Z'));
+        assert!(output["unix_seconds"].as_u64().unwrap() > 1_700_000_000);
+    }
+}
@@ -0,0 +1,23 @@
+#[doc(hidden)]
+pub mod schema_validation;
+#[doc(inline)]
+pub use schema_validation::*;
+
+#[doc(hidden)]
+pub mod tool_error;
+#[doc(inline)]
+pub use tool_error::*;
+
+#[doc(hidden)]
+pub mod tool_registry;
+#[doc(inline)]
+pub use tool_registry::*;
+
+#[doc(hidden)]
+pub mod tool_stats;
+#[doc(inline)]
+pub use tool_stats::*;
+
+#[doc(hidden)]
+#[cfg(feature = "builtin-tools")]
+pub mod builtin;
@@ -0,0 +1,232 @@
+use serde_json::Value as JsonValue;
+
+/// Checks a JSON value against a JSON schema, such as a tool's parameters.
+///
+/// The keywords tool declarations use are supported: `type` (a name or a list of
+/// names), `enum`, `const`, `properties`, `required`, `additionalProperties: false`,
+/// `items`, `minItems`/`maxItems`, `minLength`/`maxLength`, `minimum`/`maximum`
+/// and `anyOf`/`oneOf`. Other keywords are ignored, so an unusual schema accepts
+/// more than it declares rather than rejecting valid values.
+///
+/// # Arguments
+/// * `value` - The value to check
+/// * `schema` - The schema it should match
+///
+/// # Returns
+/// * An error listing every problem found, each prefixed with its JSON path
+pub fn validate_json_schema(value: &JsonValue, schema: &JsonValue) -> Result<(), String> {
+    let mut problems = Vec::new();
+    check(value, schema, "$", &mut problems);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("; "))
+    }
+}
+
+/// Adds the problems of a value at `path` to `problems`.
+fn check(value: &JsonValue, schema: &JsonValue, path: &str, problems: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            JsonValue::String(name) => vec![name.as_str()],
+            JsonValue::Array(names) => names.iter().filter_map(JsonValue::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !names.is_empty() && !names.iter().any(|name| has_type(value, name)) {
+            problems.push(format!(
+                "{path}: expected {}, got {}",
+                names.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(JsonValue::as_array)
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(JsonValue::to_string).collect();
+        problems.push(format!("{path}: must be one of {}", allowed.join(", ")));
+    }
+    if let Some(constant) = schema.get("const")
+        && constant != value
+    {
+        problems.push(format!("{path}: must be {constant}"));
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(options) = schema.get(keyword).and_then(JsonValue::as_array)
+            && !options
+                .iter()
+                .any(|option| validate_json_schema(value, option).is_ok())
+        {
+            problems.push(format!("{path}: matches none of the allowed schemas"));
+        }
+    }
+
+    match value {
+        JsonValue::Object(object) => {
+            let properties = schema.get("properties").and_then(JsonValue::as_object);
+            for name in schema
+                .get("required")
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(JsonValue::as_str)
+            {
+                if !object.contains_key(name) {
+                    problems.push(format!("{path}: missing required property '{name}'"));
+                }
+            }
+            for (name, field) in object {
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(field_schema) => {
+                        check(field, field_schema, &format!("{path}.{name}"), problems)
+                    }
+                    None if schema.get("additionalProperties") == Some(&JsonValue::Bool(false)) => {
+                        problems.push(format!("{path}: unexpected property '{name}'"));
+                    }
+                    None => {}
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            check_bounds(
+                items.len() as f64,
+                schema,
+                "minItems",
+                "maxItems",
+                path,
+                problems,
+            );
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(item, item_schema, &format!("{path}[{index}]"), problems);
+                }
+            }
+        }
+        JsonValue::String(text) => {
+            let length = text.chars().count() as f64;
+            check_bounds(length, schema, "minLength", "maxLength", path, problems);
+        }
+        JsonValue::Number(number) => {
+            if let Some(number) = number.as_f64() {
+                check_bounds(number, schema, "minimum", "maximum", path, problems);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Adds a problem if `actual` is outside the schema's `min`/`max` keywords.
+fn check_bounds(
+    actual: f64,
+    schema: &serde_json::Map<String, JsonValue>,
+    min: &str,
+    max: &str,
+    path: &str,
+    problems: &mut Vec<String>,
+) {
+    if let Some(limit) = schema.get(min).and_then(JsonValue::as_f64)
+        && actual < limit
+    {
+        problems.push(format!("{path}: {min} is {limit}, got {actual}"));
+    }
+    if let Some(limit) = schema.get(max).and_then(JsonValue::as_f64)
+        && actual > limit
+    {
+        problems.push(format!("{path}: {max} is {limit}, got {actual}"));
+    }
+}
+
+/// Returns `true` if a value is of the JSON schema type `name`.
+///
+/// Numbers without a fractional part, such as `3.0`, count as integers.
+fn has_type(value: &JsonValue, name: &str) -> bool {
+    match name {
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+/// Returns the JSON schema type name of a value.
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+// ===
+// TESTS: validate_json_schema
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn weather_schema() -> JsonValue {
+        json!({
+            "type": "object",
+            "properties": {
+                "city": { "type": "string", "minLength": 1 },
+                "unit": { "type": "string", "enum": ["celsius", "fahrenheit"] },
+                "days": { "type": "integer", "minimum": 1, "maximum": 7 },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["city"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn test_valid_arguments() {
+        let args = json!({ "city": "Paris", "unit": "celsius", "days": 3, "tags": ["a"] });
+        assert_eq!(validate_json_schema(&args, &weather_schema()), Ok(()));
+        assert_eq!(validate_json_schema(&json!(1.5), &json!({})), Ok(()));
+
+        let args = json!({ "city": "Paris", "days": 3.0 });
+        assert_eq!(validate_json_schema(&args, &weather_schema()), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_every_problem_with_its_path() {
+        let args = json!({ "unit": "kelvin", "days": 2.5, "tags": ["a", 1], "extra": true });
+        let problems = validate_json_schema(&args, &weather_schema()).unwrap_err();
+        assert_eq!(
+            problems,
+            "$: missing required property 'city'; \
+             $.days: expected integer, got number; \
+             $: unexpected property 'extra'; \
+             $.tags[1]: expected string, got number; \
+             $.unit: must be one of \"celsius\", \"fahrenheit\""
+        );
+    }
+
+    #[test]
+    fn test_bounds_and_alternatives() {
+        let schema = weather_schema();
+        let problems = validate_json_schema(&json!({ "city": "", "days": 9 }), &schema);
+        assert_eq!(
+            problems.unwrap_err(),
+            "$.city: minLength is 1, got 0; $.days: maximum is 7, got 9"
+        );
+
+        let schema = json!({ "anyOf": [{ "type": "string" }, { "type": "null" }] });
+        assert!(validate_json_schema(&json!(null), &schema).is_ok());
+        assert!(validate_json_schema(&json!(3), &schema).is_err());
+        assert!(validate_json_schema(&json!("x"), &json!({ "type": ["string", "null"] })).is_ok());
+    }
+}
@@ -0,0 +1,174 @@
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+
+// ===
+// ENUM: ToolErrorKind
+// ===
+
+/// What went wrong when a tool was called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolErrorKind {
+    /// No tool with the called name is registered.
+    NotFound,
+    /// The arguments do not match the tool's parameter schema.
+    InvalidArgs,
+    /// The handler panicked.
+    HandlerPanic,
+    /// The handler did not finish within the registry's timeout.
+    Timeout,
+    /// The handler returned an error.
+    Failed,
+}
+
+impl ToolErrorKind {
+    /// Returns the kind as sent to the model, e.g. "invalid_args".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not_found",
+            Self::InvalidArgs => "invalid_args",
+            Self::HandlerPanic => "handler_panic",
+            Self::Timeout => "timeout",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Returns a suggestion for the model on how to carry on.
+    fn hint(&self) -> &'static str {
+        match self {
+            Self::NotFound => "Call one of the available tools instead.",
+            Self::InvalidArgs => {
+                "Fix the arguments to match the tool's parameters and call it again."
+            }
+            Self::HandlerPanic | Self::Failed => {
+                "The tool could not complete this call; try other arguments or continue without it."
+            }
+            Self::Timeout => {
+                "The tool took too long; try a smaller request or continue without it."
+            }
+        }
+    }
+}
+
+// ===
+// STRUCT: ToolError
+// ===
+
+/// The error of a tool call dispatched by a `ToolRegistry`.
+///
+/// `ToolOutput` sends it back to the model as a structured result, with the
+/// kind of failure and a hint, so the model can correct the call or carry on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolError {
+    /// The name of the called tool.
+    pub name: String,
+    /// What went wrong.
+    pub kind: ToolErrorKind,
+    /// The details, e.g. the schema problems or the handler's error message.
+    pub detail: String,
+}
+
+impl ToolError {
+    /// Creates a tool error.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the called tool.
+    /// * `kind` - What went wrong.
+    /// * `detail` - The details.
+    pub fn new(name: &str, kind: ToolErrorKind, detail: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            detail: detail.to_string(),
+        }
+    }
+
+    /// Converts the error of a handler, keeping a `ToolError` as it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the called tool.
+    /// * `error` - The error returned by the handler.
+    ///
+    /// # Returns
+    ///
+    /// The `ToolError`, of kind `Failed` unless the handler returned a `ToolError`.
+    pub fn from_error(name: &str, error: Box<dyn Error + Send + Sync>) -> Self {
+        match error.downcast::<ToolError>() {
+            Ok(error) => *error,
+            Err(error) => Self::new(name, ToolErrorKind::Failed, &error.to_string()),
+        }
+    }
+
+    /// Creates the error of a handler that panicked, from the panic payload.
+    pub(crate) fn from_panic(name: &str, payload: Box<dyn Any + Send>) -> Self {
+        let detail = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Self::new(name, ToolErrorKind::HandlerPanic, &detail)
+    }
+
+    /// Returns the tool result telling the model what went wrong, e.g.
+    /// `{"error": "...", "kind": "invalid_args", "hint": "..."}`.
+    pub fn to_json(&self) -> JsonValue {
+        json!({
+            "error": self.to_string(),
+            "kind": self.kind.as_str(),
+            "hint": self.kind.hint(),
+        })
+    }
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.name;
+        let detail = &self.detail;
+        match self.kind {
+            ToolErrorKind::NotFound if detail.is_empty() => write!(f, "unknown tool '{name}'"),
+            ToolErrorKind::NotFound => write!(f, "unknown tool '{name}'; {detail}"),
+            ToolErrorKind::InvalidArgs => write!(f, "arguments failed validation: {detail}"),
+            ToolErrorKind::HandlerPanic if detail.is_empty() => write!(f, "tool '{name}' panicked"),
+            ToolErrorKind::HandlerPanic => write!(f, "tool '{name}' panicked: {detail}"),
+            ToolErrorKind::Timeout => write!(f, "tool '{name}' timed out: {detail}"),
+            ToolErrorKind::Failed => f.write_str(detail),
+        }
+    }
+}
+
+impl Error for ToolError {}
+
+// ===
+// TESTS: ToolError
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_and_json() {
+        let error = ToolError::new("add", ToolErrorKind::InvalidArgs, "$.a: expected number");
+        assert_eq!(
+            error.to_json(),
+            json!({
+                "error": "arguments failed validation: $.a: expected number",
+                "kind": "invalid_args",
+                "hint": "Fix the arguments to match the tool's parameters and call it again."
+            })
+        );
+
+        let boxed: Box<dyn Error + Send + Sync> = Box::new(error.clone());
+        assert_eq!(ToolError::from_error("other", boxed), error);
+        let failed = ToolError::from_error("add", "overflow".into());
+        assert_eq!(failed.kind, ToolErrorKind::Failed);
+        assert_eq!(failed.to_string(), "overflow");
+
+        let panic = ToolError::from_panic("add", Box::new("boom"));
+        assert_eq!(panic.to_string(), "tool 'add' panicked: boom");
+    }
+}
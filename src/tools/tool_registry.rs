@@ -0,0 +1,900 @@
+use crate::tool_stats::ToolStatsRecorder;
+use crate::{GeminiFunctionCall, GeminiFunctionDeclaration, GeminiFunctionResponse};
+use crate::{GeminiFunctionResponseDetails, GeminiToolDeclaration};
+use crate::{
+    OllamaFunction, OllamaFunctionParameters, OllamaMessage, OllamaRole, OllamaToolCall,
+    OllamaTools,
+};
+use crate::{ToolError, ToolErrorKind, ToolStats, validate_json_schema};
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+#[cfg(feature = "transport")]
+use std::time::Duration;
+use std::time::Instant;
+
+/// The result returned by a tool handler.
+pub type ToolResult = Result<JsonValue, Box<dyn Error + Send + Sync>>;
+
+/// The boxed future returned by a tool handler.
+pub type ToolFuture = Pin<Box<dyn Future<Output = ToolResult> + Send>>;
+
+/// A type-erased, shareable tool handler.
+pub type ToolHandler = Arc<dyn Fn(JsonValue) -> ToolFuture + Send + Sync>;
+
+// ===
+// STRUCT: Tool
+// ===
+
+/// A provider-agnostic tool: a declaration (name, description and JSON schema
+/// for its parameters) paired with the async handler that executes it.
+///
+/// Tools are usually collected in a [`ToolRegistry`], which converts them into
+/// the Ollama or Gemini declaration formats and dispatches tool calls back to
+/// the handlers.
+#[derive(Clone)]
+pub struct Tool {
+    name: String,
+    description: String,
+    parameters: JsonValue,
+    handler: ToolHandler,
+    #[cfg(feature = "transport")]
+    timeout: Option<Duration>,
+}
+
+impl Tool {
+    /// Creates a new tool from its declaration and handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the model uses to call the tool.
+    /// * `description` - A description of what the tool does.
+    /// * `parameters` - A JSON schema object describing the tool's arguments.
+    /// * `handler` - An async function receiving the call arguments as JSON and
+    ///   returning any serializable value, which is converted to JSON.
+    ///
+    /// # Returns
+    ///
+    /// A new `Tool` instance.
+    pub fn new<F, Fut, T>(name: &str, description: &str, parameters: JsonValue, handler: F) -> Self
+    where
+        F: Fn(JsonValue) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, Box<dyn Error + Send + Sync>>> + Send + 'static,
+        T: Serialize,
+    {
+        let handler = Arc::new(handler);
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            parameters,
+            handler: Arc::new(move |args| {
+                let future = handler(args);
+                Box::pin(async move { Ok(serde_json::to_value(future.await?)?) })
+            }),
+            #[cfg(feature = "transport")]
+            timeout: None,
+        }
+    }
+
+    /// Builds the JSON schema of a single parameter type.
+    ///
+    /// Sub-schemas are inlined and the `$schema`/`title` keys are removed, so the
+    /// result can be embedded directly in a tool's `properties` object.
+    ///
+    /// # Returns
+    ///
+    /// The JSON schema describing `T`.
+    pub fn schema_of<T: JsonSchema>() -> JsonValue {
+        let settings = SchemaSettings::draft07().with(|settings| {
+            settings.inline_subschemas = true;
+            settings.meta_schema = None;
+        });
+
+        let schema = settings.into_generator().into_root_schema_for::<T>();
+        let mut value = serde_json::to_value(schema).unwrap_or_default();
+
+        if let Some(object) = value.as_object_mut() {
+            object.remove("title");
+            object.remove("definitions");
+        }
+
+        value
+    }
+
+    /// Returns the name of the tool.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the description of the tool.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the JSON schema of the tool's parameters.
+    pub fn parameters(&self) -> &JsonValue {
+        &self.parameters
+    }
+
+    /// Sets how long a call to this tool may run when dispatched by a `ToolRegistry`,
+    /// overriding the registry's default timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The longest the handler may run.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    #[cfg(feature = "transport")]
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns how long a call to this tool may run, if it has its own limit.
+    #[cfg(feature = "transport")]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Checks call arguments against the tool's parameter schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The call arguments as a JSON object.
+    ///
+    /// # Returns
+    ///
+    /// An error describing every argument that does not match the schema.
+    pub fn validate_arguments(&self, args: &JsonValue) -> Result<(), String> {
+        validate_json_schema(args, &self.parameters)
+    }
+
+    /// Executes the tool's handler with the given arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The call arguments as a JSON object.
+    ///
+    /// # Returns
+    ///
+    /// The JSON result produced by the handler, or the handler's error.
+    pub async fn call(&self, args: JsonValue) -> ToolResult {
+        (self.handler)(args).await
+    }
+
+    /// Converts the tool into an Ollama function declaration.
+    pub fn to_ollama_function(&self) -> OllamaFunction {
+        let mut function = OllamaFunction::new(&self.name, &self.description);
+        function.set_parameters(OllamaFunctionParameters::from(&self.parameters));
+        function
+    }
+
+    /// Converts the tool into a Gemini function declaration.
+    pub fn to_gemini_function(&self) -> GeminiFunctionDeclaration {
+        GeminiFunctionDeclaration {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            parameters: self.parameters.clone(),
+        }
+    }
+}
+
+// ===
+// STRUCT: ToolOutput
+// ===
+
+/// The result of a tool call, paired with the name of the tool that produced it.
+///
+/// Builds the tool result message each provider expects: a "tool" message with
+/// the tool name for Ollama, or a `functionResponse` part for Gemini. A failed
+/// call is sent as the `ToolError::to_json` of its error.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolOutput {
+    name: String,
+    result: Result<JsonValue, ToolError>,
+}
+
+impl ToolOutput {
+    /// Creates the output of a call to the named tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the called tool.
+    /// * `result` - The tool's result or error.
+    ///
+    /// # Returns
+    ///
+    /// A new `ToolOutput`, holding the error as a `ToolError`.
+    pub fn new(name: &str, result: ToolResult) -> Self {
+        Self {
+            name: name.to_string(),
+            result: result.map_err(|err| ToolError::from_error(name, err)),
+        }
+    }
+
+    /// Returns the name of the called tool.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the tool's result, or its error.
+    pub fn result(&self) -> &Result<JsonValue, ToolError> {
+        &self.result
+    }
+
+    /// Returns the result as JSON text, or the error's `ToolError::to_json` for a failed call.
+    pub fn content(&self) -> String {
+        match &self.result {
+            Ok(value) => value.to_string(),
+            Err(err) => err.to_json().to_string(),
+        }
+    }
+
+    /// Builds the Ollama "tool" message carrying the result.
+    pub fn to_ollama_message(&self) -> OllamaMessage {
+        let mut message = OllamaMessage::new();
+        message
+            .set_role(OllamaRole::Tool)
+            .set_tool_name(&self.name)
+            .set_content(&self.content());
+        message
+    }
+
+    /// Builds the Gemini `functionResponse` part carrying the result.
+    pub fn to_gemini_response(&self) -> GeminiFunctionResponse {
+        match &self.result {
+            Ok(value) => GeminiFunctionResponse::new(&self.name, value.clone()),
+            Err(err) => GeminiFunctionResponse {
+                function_response: GeminiFunctionResponseDetails {
+                    name: self.name.clone(),
+                    response: err.to_json(),
+                },
+            },
+        }
+    }
+}
+
+// ===
+// ENUM: ToolResultTruncation
+// ===
+
+/// How a tool result longer than the registry's result limit is shortened.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToolResultTruncation {
+    /// Keeps the start of the result.
+    #[default]
+    Head,
+    /// Keeps the end of the result, e.g. for logs.
+    Tail,
+    /// Asks the model to summarize the result. An agent falls back to `Head` if the
+    /// summary fails or is still too long.
+    Summarize,
+}
+
+// ===
+// STRUCT: ToolRegistry
+// ===
+
+/// A collection of tools that can be declared to a model and dispatched by name.
+///
+/// The registry records usage statistics for every call it dispatches. Clones
+/// share them, so a copy kept before handing the registry to an `Agent` sees the
+/// agent's calls.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: Vec<Tool>,
+    result_limit: Option<(usize, ToolResultTruncation)>,
+    #[cfg(feature = "transport")]
+    timeout: Option<Duration>,
+    stats: ToolStatsRecorder,
+}
+
+impl ToolRegistry {
+    /// Creates a new, empty tool registry.
+    pub fn new() -> Self {
+        Self {
+            tools: Vec::new(),
+            result_limit: None,
+            #[cfg(feature = "transport")]
+            timeout: None,
+            stats: ToolStatsRecorder::default(),
+        }
+    }
+
+    /// Registers a tool, replacing any existing tool with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool` - The tool to register.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn register(&mut self, tool: Tool) -> &mut Self {
+        match self.tools.iter_mut().find(|t| t.name == tool.name) {
+            Some(existing) => *existing = tool,
+            None => self.tools.push(tool),
+        }
+        self
+    }
+
+    /// Returns the tool with the given name, if registered.
+    pub fn get(&self, name: &str) -> Option<&Tool> {
+        self.tools.iter().find(|tool| tool.name == name)
+    }
+
+    /// Returns the registered tools in registration order.
+    pub fn tools(&self) -> &[Tool] {
+        &self.tools
+    }
+
+    /// Returns the number of registered tools.
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    /// Returns `true` if no tools are registered.
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// Limits the size of the tool results sent back to a model.
+    ///
+    /// A tool that returns a large blob would otherwise fill the context window.
+    /// Results are not limited by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_chars` - The largest result, in characters, passed on unchanged.
+    /// * `truncation` - How longer results are shortened.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    pub fn set_result_limit(
+        &mut self,
+        max_chars: usize,
+        truncation: ToolResultTruncation,
+    ) -> &mut Self {
+        self.result_limit = Some((max_chars, truncation));
+        self
+    }
+
+    /// Returns the result size limit in characters and its truncation strategy.
+    pub fn result_limit(&self) -> Option<(usize, ToolResultTruncation)> {
+        self.result_limit
+    }
+
+    /// Sets how long a tool call may run before its handler is cancelled and the
+    /// call fails with `ToolErrorKind::Timeout`.
+    ///
+    /// This is the default for tools without a timeout of their own, set with
+    /// `Tool::set_timeout`. Calls are not limited by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The longest a handler may run.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to self for method chaining.
+    #[cfg(feature = "transport")]
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the default timeout of tool calls, if limited.
+    #[cfg(feature = "transport")]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Shortens a tool result to the result limit by keeping its head or tail.
+    ///
+    /// A marker stating how many characters were dropped takes the place of the
+    /// removed text. `Summarize` needs a model, so it keeps the head here.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The tool result as sent to the model.
+    ///
+    /// # Returns
+    ///
+    /// The content, unchanged if it fits or no limit is set.
+    pub fn truncate_result(&self, content: &str) -> String {
+        let Some((max_chars, truncation)) = self.result_limit else {
+            return content.to_string();
+        };
+        let length = content.chars().count();
+        if length <= max_chars {
+            return content.to_string();
+        }
+
+        let dropped = length - max_chars;
+        let marker = format!("[... truncated {dropped} characters ...]");
+        match truncation {
+            ToolResultTruncation::Tail => {
+                let tail: String = content.chars().skip(dropped).collect();
+                format!("{marker}{tail}")
+            }
+            ToolResultTruncation::Head | ToolResultTruncation::Summarize => {
+                let head: String = content.chars().take(max_chars).collect();
+                format!("{head}{marker}")
+            }
+        }
+    }
+
+    /// Builds the Ollama `tools` declaration for every registered tool.
+    pub fn to_ollama_tools(&self) -> OllamaTools {
+        let mut tools = OllamaTools::new();
+        for tool in &self.tools {
+            tools.push_function(tool.to_ollama_function());
+        }
+        tools
+    }
+
+    /// Builds the Gemini tool declaration for every registered tool.
+    pub fn to_gemini_tool(&self) -> GeminiToolDeclaration {
+        let mut declaration = GeminiToolDeclaration::new();
+        for tool in &self.tools {
+            declaration.add_function(tool.to_gemini_function());
+        }
+        declaration
+    }
+
+    /// Calls the tool with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tool to call.
+    /// * `args` - The call arguments as a JSON object.
+    ///
+    /// # Returns
+    ///
+    /// The tool's JSON result, or a `ToolError` if the tool is unknown, or its
+    /// handler fails, panics or times out.
+    pub async fn call(&self, name: &str, args: JsonValue) -> ToolResult {
+        let start = Instant::now();
+        let sample = args.clone();
+        let result = match self.get(name) {
+            Some(tool) => self.run(tool, args).await,
+            None => Err(ToolError::new(
+                name,
+                ToolErrorKind::NotFound,
+                &format!("available tools: {}", self.names().join(", ")),
+            )),
+        };
+        self.stats
+            .record_call(name, &sample, start.elapsed(), result.is_ok());
+        Ok(result?)
+    }
+
+    /// Runs a tool's handler, turning a panic or timeout into a `ToolError`. The
+    /// tool's own timeout takes precedence over the registry's.
+    async fn run(&self, tool: &Tool, args: JsonValue) -> Result<JsonValue, ToolError> {
+        let call = CatchUnwind((tool.handler)(args));
+
+        #[cfg(feature = "transport")]
+        let outcome = match tool.timeout.or(self.timeout) {
+            Some(timeout) => tokio::time::timeout(timeout, call).await.map_err(|_| {
+                let detail = format!("no result after {timeout:?}");
+                ToolError::new(&tool.name, ToolErrorKind::Timeout, &detail)
+            })?,
+            None => call.await,
+        };
+        #[cfg(not(feature = "transport"))]
+        let outcome = call.await;
+
+        match outcome {
+            Ok(result) => result.map_err(|err| ToolError::from_error(&tool.name, err)),
+            Err(payload) => Err(ToolError::from_panic(&tool.name, payload)),
+        }
+    }
+
+    /// Returns the names of the registered tools.
+    fn names(&self) -> Vec<&str> {
+        self.tools.iter().map(|tool| tool.name.as_str()).collect()
+    }
+
+    /// Returns the usage statistics of every tool called so far, by name.
+    ///
+    /// Calls to unknown tools are included, which shows when a model invents
+    /// tool names.
+    pub fn stats(&self) -> BTreeMap<String, ToolStats> {
+        self.stats.snapshot()
+    }
+
+    /// Clears the usage statistics.
+    pub fn reset_stats(&self) {
+        self.stats.reset();
+    }
+
+    /// Checks the arguments of an Ollama tool call against the called tool's schema.
+    ///
+    /// Calls to unknown tools pass, since `dispatch` reports them.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The tool call emitted by the model.
+    ///
+    /// # Returns
+    ///
+    /// An error describing every argument that does not match the schema.
+    pub fn validate(&self, tool_call: &OllamaToolCall) -> Result<(), String> {
+        let Some(tool) = tool_call.name().and_then(|name| self.get(name)) else {
+            return Ok(());
+        };
+        let args = tool_call.arguments().cloned().unwrap_or(JsonValue::Null);
+        let result = tool.validate_arguments(&args);
+        if result.is_err() {
+            self.stats.record_invalid(tool.name());
+        }
+        result
+    }
+
+    /// Dispatches an Ollama tool call and pairs the result with the tool name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The tool call emitted by the model.
+    ///
+    /// # Returns
+    ///
+    /// The output, ready to be sent back with `ToolOutput::to_ollama_message`. Arguments
+    /// that do not match the tool's schema fail with `ToolErrorKind::InvalidArgs`
+    /// without running the tool.
+    pub async fn respond(&self, tool_call: &OllamaToolCall) -> ToolOutput {
+        let name = tool_call.name().unwrap_or_default();
+        if let Err(problems) = self.validate(tool_call) {
+            let error = ToolError::new(name, ToolErrorKind::InvalidArgs, &problems);
+            return ToolOutput::new(name, Err(error.into()));
+        }
+        ToolOutput::new(name, self.dispatch(tool_call).await)
+    }
+
+    /// Dispatches a Gemini function call and pairs the result with the tool name.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_call` - The function call emitted by the model.
+    ///
+    /// # Returns
+    ///
+    /// The output, ready to be sent back with `ToolOutput::to_gemini_response`. Arguments
+    /// that do not match the tool's schema fail with `ToolErrorKind::InvalidArgs`
+    /// without running the tool.
+    pub async fn respond_gemini(&self, function_call: &GeminiFunctionCall) -> ToolOutput {
+        let name = function_call.name();
+        if let Some(tool) = self.get(name)
+            && let Err(problems) = tool.validate_arguments(function_call.args())
+        {
+            self.stats.record_invalid(name);
+            let error = ToolError::new(name, ToolErrorKind::InvalidArgs, &problems);
+            return ToolOutput::new(name, Err(error.into()));
+        }
+        ToolOutput::new(name, self.call(name, function_call.args().clone()).await)
+    }
+
+    /// Dispatches an Ollama tool call to the matching registered tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The tool call emitted by the model.
+    ///
+    /// # Returns
+    ///
+    /// The tool's JSON result, or an error if the call is malformed or the tool fails.
+    pub async fn dispatch(&self, tool_call: &OllamaToolCall) -> ToolResult {
+        let name = tool_call.name().ok_or("tool call has no function name")?;
+        let args = tool_call.arguments().cloned().unwrap_or(JsonValue::Null);
+        self.call(name, args).await
+    }
+}
+
+/// A future that returns a panic of the future it wraps as an error.
+struct CatchUnwind<F>(F);
+
+impl<F: Future + Unpin> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Box<dyn std::any::Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &mut self.0;
+        match catch_unwind(AssertUnwindSafe(|| Pin::new(inner).poll(cx))) {
+            Ok(poll) => poll.map(Ok),
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+// ===
+// TESTS: ToolRegistry
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn echo_tool() -> Tool {
+        Tool::new(
+            "echo",
+            "Echoes the given text.",
+            json!({
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"]
+            }),
+            |args| async move { Ok(args["text"].clone()) },
+        )
+    }
+
+    #[test]
+    fn test_schema_of() {
+        assert_eq!(Tool::schema_of::<String>(), json!({ "type": "string" }));
+        assert_eq!(
+            Tool::schema_of::<Vec<bool>>(),
+            json!({ "type": "array", "items": { "type": "boolean" } })
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_same_name() {
+        let mut registry = ToolRegistry::new();
+        registry.register(echo_tool()).register(echo_tool());
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get("echo").is_some());
+        assert!(registry.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_declarations() {
+        let mut registry = ToolRegistry::new();
+        registry.register(echo_tool());
+
+        let ollama = registry.to_ollama_tools();
+        assert_eq!(ollama.as_json()[0]["function"]["name"], "echo");
+        assert_eq!(
+            ollama.as_json()[0]["function"]["parameters"]["required"][0],
+            "text"
+        );
+
+        let gemini = serde_json::to_value(registry.to_gemini_tool()).unwrap();
+        assert_eq!(gemini["functionDeclarations"][0]["name"], "echo");
+    }
+
+    #[tokio::test]
+    async fn test_call_and_dispatch() {
+        let mut registry = ToolRegistry::new();
+        registry.register(echo_tool());
+
+        let result = registry.call("echo", json!({ "text": "hi" })).await;
+        assert_eq!(result.unwrap(), json!("hi"));
+
+        let tool_call = OllamaToolCall::from(&json!({
+            "function": { "name": "echo", "arguments": { "text": "there" } }
+        }));
+        assert_eq!(registry.dispatch(&tool_call).await.unwrap(), json!("there"));
+
+        assert!(registry.call("missing", json!({})).await.is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut registry = ToolRegistry::new();
+        registry.register(echo_tool());
+
+        let valid = OllamaToolCall::from(&json!({
+            "function": { "name": "echo", "arguments": { "text": "hi" } }
+        }));
+        assert_eq!(registry.validate(&valid), Ok(()));
+
+        let invalid = OllamaToolCall::from(&json!({
+            "function": { "name": "echo", "arguments": { "text": 3 } }
+        }));
+        assert_eq!(
+            registry.validate(&invalid),
+            Err("$.text: expected string, got number".to_string())
+        );
+
+        let unknown = OllamaToolCall::from(&json!({
+            "function": { "name": "missing", "arguments": {} }
+        }));
+        assert_eq!(registry.validate(&unknown), Ok(()));
+        assert_eq!(registry.stats()["echo"].invalid_arguments, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stats_are_shared_by_clones() {
+        let mut registry = ToolRegistry::new();
+        registry.register(echo_tool());
+        let copy = registry.clone();
+
+        registry
+            .call("echo", json!({ "text": "hi" }))
+            .await
+            .unwrap();
+        registry.call("echo", json!({})).await.unwrap();
+        assert!(registry.call("missing", json!({})).await.is_err());
+
+        let stats = copy.stats();
+        assert_eq!(stats["echo"].calls, 2);
+        assert_eq!(stats["echo"].failures, 0);
+        assert_eq!(stats["echo"].argument_samples[0], json!({ "text": "hi" }));
+        assert_eq!(stats["missing"].failure_rate(), 1.0);
+
+        copy.reset_stats();
+        assert!(registry.stats().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_call_errors() {
+        let mut registry = ToolRegistry::new();
+        registry.register(echo_tool());
+        registry.register(Tool::new(
+            "explode",
+            "Always panics.",
+            json!({ "type": "object" }),
+            |_| async move {
+                panic!("bad state");
+                #[allow(unreachable_code)]
+                Ok(JsonValue::Null)
+            },
+        ));
+
+        let error = registry.call("explode", json!({})).await.unwrap_err();
+        let error = ToolError::from_error("explode", error);
+        assert_eq!(error.kind, ToolErrorKind::HandlerPanic);
+        assert_eq!(error.detail, "bad state");
+        assert_eq!(registry.stats()["explode"].failures, 1);
+
+        let error = registry.call("missing", json!({})).await.unwrap_err();
+        let error = ToolError::from_error("missing", error);
+        assert_eq!(error.kind, ToolErrorKind::NotFound);
+        assert_eq!(error.detail, "available tools: echo, explode");
+
+        let tool_call = OllamaToolCall::from(&json!({
+            "function": { "name": "echo", "arguments": { "text": 3 } }
+        }));
+        let output = registry.respond(&tool_call).await;
+        let error = output.result().as_ref().unwrap_err();
+        assert_eq!(error.kind, ToolErrorKind::InvalidArgs);
+        assert_eq!(error.detail, "$.text: expected string, got number");
+        assert_eq!(registry.stats()["echo"].calls, 0);
+    }
+
+    #[cfg(feature = "transport")]
+    #[tokio::test]
+    async fn test_call_timeout() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Tool::new(
+            "sleep",
+            "Sleeps for a second.",
+            json!({ "type": "object" }),
+            |_| async move {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(JsonValue::Null)
+            },
+        ));
+        registry.set_timeout(Duration::from_millis(10));
+
+        let error = registry.call("sleep", json!({})).await.unwrap_err();
+        let error = ToolError::from_error("sleep", error);
+        assert_eq!(error.kind, ToolErrorKind::Timeout);
+        assert_eq!(
+            error.to_string(),
+            "tool 'sleep' timed out: no result after 10ms"
+        );
+
+        // A tool's own timeout overrides the registry's default.
+        let mut quick = Tool::new(
+            "quick",
+            "Sleeps briefly.",
+            json!({ "type": "object" }),
+            |_| async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(JsonValue::Null)
+            },
+        );
+        quick.set_timeout(Duration::from_secs(5));
+        registry.register(quick);
+        assert_eq!(
+            registry.call("quick", json!({})).await.unwrap(),
+            JsonValue::Null
+        );
+
+        let mut tool = registry.get("sleep").unwrap().clone();
+        tool.set_timeout(Duration::from_millis(20));
+        registry.register(tool);
+        let error = registry.call("sleep", json!({})).await.unwrap_err();
+        assert!(
+            error.to_string().ends_with("no result after 20ms"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_truncate_result() {
+        let mut registry = ToolRegistry::new();
+        assert_eq!(registry.truncate_result("abcdefgh"), "abcdefgh");
+
+        registry.set_result_limit(3, ToolResultTruncation::Head);
+        assert_eq!(registry.truncate_result("abc"), "abc");
+        assert_eq!(
+            registry.truncate_result("äbcdefgh"),
+            "äbc[... truncated 5 characters ...]"
+        );
+
+        registry.set_result_limit(3, ToolResultTruncation::Tail);
+        assert_eq!(
+            registry.truncate_result("abcdefgh"),
+            "[... truncated 5 characters ...]fgh"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_serializable_results_and_outputs() {
+        #[derive(Serialize)]
+        struct Weather {
+            city: String,
+            celsius: f64,
+        }
+
+        let mut registry = ToolRegistry::new();
+        registry.register(Tool::new(
+            "weather",
+            "Gets the weather.",
+            json!({ "type": "object" }),
+            |args| async move {
+                Ok(Weather {
+                    city: args["city"].as_str().unwrap_or_default().to_string(),
+                    celsius: 21.5,
+                })
+            },
+        ));
+
+        let tool_call = OllamaToolCall::from(&json!({
+            "function": { "name": "weather", "arguments": { "city": "Paris" } }
+        }));
+        let output = registry.respond(&tool_call).await;
+        assert_eq!(output.name(), "weather");
+        assert_eq!(
+            output.result(),
+            &Ok(json!({ "city": "Paris", "celsius": 21.5 }))
+        );
+        assert_eq!(
+            output.to_ollama_message().to_json(),
+            json!({
+                "role": "tool",
+                "content": "{\"celsius\":21.5,\"city\":\"Paris\"}",
+                "tool_name": "weather"
+            })
+        );
+
+        let function_call: GeminiFunctionCall = serde_json::from_value(json!({
+            "functionCall": { "name": "missing", "args": {} }
+        }))
+        .unwrap();
+        let output = registry.respond_gemini(&function_call).await;
+        let response = output.to_gemini_response().function_response;
+        assert_eq!(response.name, "missing");
+        assert_eq!(
+            response.response,
+            json!({
+                "error": "unknown tool 'missing'; available tools: weather",
+                "kind": "not_found",
+                "hint": "Call one of the available tools instead."
+            })
+        );
+    }
+}
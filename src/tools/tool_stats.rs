@@ -0,0 +1,133 @@
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The number of recent call arguments kept per tool.
+const MAX_ARGUMENT_SAMPLES: usize = 5;
+
+// ===
+// STRUCT: ToolStats
+// ===
+
+/// Usage statistics of one tool, as recorded by a `ToolRegistry`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolStats {
+    /// The number of times the tool was called.
+    pub calls: u64,
+    /// The number of calls that returned an error.
+    pub failures: u64,
+    /// The number of calls rejected because their arguments did not match the schema.
+    pub invalid_arguments: u64,
+    /// The time spent in the tool's handler, over all calls.
+    pub total_latency: Duration,
+    /// The longest time a single call took.
+    pub max_latency: Duration,
+    /// The arguments of the most recent calls, oldest first.
+    pub argument_samples: VecDeque<JsonValue>,
+}
+
+impl ToolStats {
+    /// Returns the fraction of calls that failed, from 0.0 to 1.0.
+    pub fn failure_rate(&self) -> f64 {
+        if self.calls == 0 {
+            return 0.0;
+        }
+        self.failures as f64 / self.calls as f64
+    }
+
+    /// Returns the mean time a call took.
+    pub fn average_latency(&self) -> Duration {
+        if self.calls == 0 {
+            return Duration::ZERO;
+        }
+        self.total_latency / self.calls as u32
+    }
+}
+
+// ===
+// STRUCT: ToolStatsRecorder
+// ===
+
+/// The shared statistics of a registry and its clones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ToolStatsRecorder {
+    stats: Arc<Mutex<BTreeMap<String, ToolStats>>>,
+}
+
+impl ToolStatsRecorder {
+    /// Records a finished call.
+    pub(crate) fn record_call(&self, name: &str, args: &JsonValue, latency: Duration, ok: bool) {
+        self.update(name, |stats| {
+            stats.calls += 1;
+            if !ok {
+                stats.failures += 1;
+            }
+            stats.total_latency += latency;
+            stats.max_latency = stats.max_latency.max(latency);
+            if stats.argument_samples.len() == MAX_ARGUMENT_SAMPLES {
+                stats.argument_samples.pop_front();
+            }
+            stats.argument_samples.push_back(args.clone());
+        });
+    }
+
+    /// Records a call rejected by argument validation.
+    pub(crate) fn record_invalid(&self, name: &str) {
+        self.update(name, |stats| stats.invalid_arguments += 1);
+    }
+
+    /// Returns a snapshot of the statistics, by tool name.
+    pub(crate) fn snapshot(&self) -> BTreeMap<String, ToolStats> {
+        self.lock().clone()
+    }
+
+    /// Clears every statistic.
+    pub(crate) fn reset(&self) {
+        self.lock().clear();
+    }
+
+    fn update(&self, name: &str, change: impl FnOnce(&mut ToolStats)) {
+        change(self.lock().entry(name.to_string()).or_default());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, ToolStats>> {
+        self.stats
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// ===
+// TESTS: ToolStats
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_calls() {
+        let recorder = ToolStatsRecorder::default();
+        for index in 0..7 {
+            let latency = Duration::from_millis(10 * (index + 1));
+            recorder.record_call("search", &json!({ "page": index }), latency, index % 2 == 0);
+        }
+        recorder.record_invalid("search");
+
+        let stats = &recorder.snapshot()["search"];
+        assert_eq!(stats.calls, 7);
+        assert_eq!(stats.failures, 3);
+        assert_eq!(stats.invalid_arguments, 1);
+        assert!((stats.failure_rate() - 3.0 / 7.0).abs() < 1e-9);
+        assert_eq!(stats.average_latency(), Duration::from_millis(40));
+        assert_eq!(stats.max_latency, Duration::from_millis(70));
+        assert_eq!(stats.argument_samples.len(), MAX_ARGUMENT_SAMPLES);
+        assert_eq!(stats.argument_samples[0], json!({ "page": 2 }));
+
+        recorder.reset();
+        assert!(recorder.snapshot().is_empty());
+        assert_eq!(ToolStats::default().failure_rate(), 0.0);
+    }
+}
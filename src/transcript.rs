@@ -0,0 +1,647 @@
+use crate::OllamaMessage;
+#[cfg(feature = "transport")]
+use crate::OllamaSession;
+use serde_json::{Value as JsonValue, json};
+use std::collections::VecDeque;
+use std::fmt;
+
+// ===
+// STRUCT: TranscriptToolCall
+// ===
+
+/// A tool call requested by the model, as recorded in a transcript.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptToolCall {
+    /// The name of the called tool.
+    pub name: String,
+    /// The arguments passed to the tool.
+    pub arguments: JsonValue,
+}
+
+// ===
+// STRUCT: TranscriptEntry
+// ===
+
+/// A single message of a transcript.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TranscriptEntry {
+    /// The role of the author, e.g. "user" or "assistant".
+    pub role: String,
+    /// The text of the message.
+    pub content: String,
+    /// The tool calls requested in this message.
+    pub tool_calls: Vec<TranscriptToolCall>,
+    /// When the message was created, as reported by the server.
+    pub timestamp: Option<String>,
+    /// The number of prompt tokens evaluated to produce this message.
+    pub prompt_tokens: Option<u32>,
+    /// The number of tokens generated for this message.
+    pub completion_tokens: Option<u32>,
+}
+
+impl TranscriptEntry {
+    /// Creates a new entry with the given role and content.
+    pub fn new(role: &str, content: &str) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates an entry from an Ollama chat message in JSON form.
+    pub fn from_message(message: &JsonValue) -> Self {
+        let Ok(message) = OllamaMessage::from_json(message.clone()) else {
+            return Self::default();
+        };
+
+        let mut tool_calls = Vec::new();
+        if let Some(calls) = message.tool_calls() {
+            for index in 0..calls.len() {
+                if let Some(call) = calls.tool_call(index) {
+                    tool_calls.push(TranscriptToolCall {
+                        name: call.name().unwrap_or_default().to_string(),
+                        arguments: call.arguments().cloned().unwrap_or(JsonValue::Null),
+                    });
+                }
+            }
+        }
+
+        Self {
+            role: message
+                .role()
+                .map(|role| role.to_string())
+                .unwrap_or_default(),
+            content: message.content().unwrap_or_default().to_string(),
+            tool_calls,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the role with its first letter capitalized, used as a header.
+    fn role_header(&self) -> String {
+        let mut chars = self.role.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => "Unknown".to_string(),
+        }
+    }
+
+    /// Returns the timestamp and token stats as a single line, if any are known.
+    fn details(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(timestamp) = &self.timestamp {
+            parts.push(timestamp.clone());
+        }
+        if let Some(prompt) = self.prompt_tokens {
+            parts.push(format!("{prompt} prompt tokens"));
+        }
+        if let Some(completion) = self.completion_tokens {
+            parts.push(format!("{completion} completion tokens"));
+        }
+
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+// ===
+// ENUM: TrainingStyle
+// ===
+
+/// The chat layouts a transcript can be exported to for fine-tuning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrainingStyle {
+    /// `{"messages": [{"role": ..., "content": ...}]}`, as used by OpenAI.
+    #[default]
+    OpenAi,
+    /// `{"conversations": [{"from": ..., "value": ...}]}`, as used by ShareGPT.
+    ShareGpt,
+}
+
+/// How `Transcript::to_training_jsonl` writes a conversation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrainingFormat {
+    style: TrainingStyle,
+    include_system: bool,
+    include_tools: bool,
+}
+
+impl TrainingFormat {
+    /// Creates a format in the given style that keeps every message.
+    pub fn new(style: TrainingStyle) -> Self {
+        Self {
+            style,
+            include_system: true,
+            include_tools: true,
+        }
+    }
+
+    /// Creates a format in the OpenAI chat style.
+    pub fn openai() -> Self {
+        Self::new(TrainingStyle::OpenAi)
+    }
+
+    /// Creates a format in the ShareGPT style.
+    pub fn share_gpt() -> Self {
+        Self::new(TrainingStyle::ShareGpt)
+    }
+
+    /// Returns the layout of the records.
+    pub fn style(&self) -> TrainingStyle {
+        self.style
+    }
+
+    /// Sets whether system messages are exported.
+    pub fn set_include_system(&mut self, include: bool) -> &mut Self {
+        self.include_system = include;
+        self
+    }
+
+    /// Sets whether tool calls and tool results are exported.
+    ///
+    /// Without them, assistant messages that only called tools are dropped, so the
+    /// record holds the plain conversation.
+    pub fn set_include_tools(&mut self, include: bool) -> &mut Self {
+        self.include_tools = include;
+        self
+    }
+}
+
+impl Default for TrainingFormat {
+    fn default() -> Self {
+        Self::openai()
+    }
+}
+
+// ===
+// STRUCT: Transcript
+// ===
+
+/// A structured record of a conversation that can be rendered for logging or sharing.
+///
+/// A transcript is usually built from an [`OllamaSession`] with [`Transcript::from_session`],
+/// which also picks up the timestamps and token counts of the responses received by the session.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Transcript {
+    title: Option<String>,
+    model: Option<String>,
+    entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    /// Creates a new, empty transcript.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a transcript from the history of a session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session whose messages and response stats are recorded.
+    ///
+    /// # Returns
+    ///
+    /// A `Transcript` with one entry per history message.
+    #[cfg(feature = "transport")]
+    pub fn from_session(session: &OllamaSession) -> Self {
+        let mut transcript = Self::from_messages(session.messages().iter());
+        transcript.model = session.model().map(str::to_string);
+
+        for (index, response) in session.responses() {
+            if let Some(entry) = transcript.entries.get_mut(*index) {
+                entry.timestamp = response.created_at().map(str::to_string);
+                entry.prompt_tokens = response.prompt_eval_count().copied();
+                entry.completion_tokens = response.eval_count().copied();
+            }
+        }
+
+        transcript
+    }
+
+    /// Builds a transcript from a list of Ollama chat messages in JSON form.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The messages to record, e.g. a slice or an `OllamaHistory` iterator.
+    ///
+    /// # Returns
+    ///
+    /// A `Transcript` with one entry per message.
+    pub fn from_messages<'a>(messages: impl IntoIterator<Item = &'a JsonValue>) -> Self {
+        Self {
+            entries: messages
+                .into_iter()
+                .map(TranscriptEntry::from_message)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the title of the transcript, if set.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Sets the title rendered at the top of the transcript.
+    pub fn set_title(&mut self, title: &str) -> &mut Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Returns the model the conversation was held with, if known.
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Sets the model the conversation was held with.
+    pub fn set_model(&mut self, model: &str) -> &mut Self {
+        self.model = Some(model.to_string());
+        self
+    }
+
+    /// Returns the entries of the transcript.
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    /// Appends an entry to the transcript.
+    pub fn push(&mut self, entry: TranscriptEntry) -> &mut Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Returns the total number of prompt and completion tokens recorded.
+    pub fn total_tokens(&self) -> u32 {
+        self.entries
+            .iter()
+            .map(|e| e.prompt_tokens.unwrap_or(0) + e.completion_tokens.unwrap_or(0))
+            .sum()
+    }
+
+    /// Renders the transcript as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title.as_deref().unwrap_or("Conversation"));
+        if let Some(model) = &self.model {
+            out.push_str(&format!("\n*Model: {model}*\n"));
+        }
+
+        for entry in &self.entries {
+            out.push_str(&format!("\n## {}\n", entry.role_header()));
+            if let Some(details) = entry.details() {
+                out.push_str(&format!("\n*{details}*\n"));
+            }
+            if !entry.content.is_empty() {
+                out.push_str(&format!("\n{}\n", entry.content));
+            }
+            for call in &entry.tool_calls {
+                out.push_str(&format!(
+                    "\n**Tool call:** `{}`\n\n```json\n{}\n```\n",
+                    call.name,
+                    serde_json::to_string_pretty(&call.arguments).unwrap_or_default()
+                ));
+            }
+        }
+
+        let total = self.total_tokens();
+        if total > 0 {
+            out.push_str(&format!("\n---\n\n**Total tokens:** {total}\n"));
+        }
+
+        out
+    }
+
+    /// Renders the transcript as a standalone HTML fragment.
+    pub fn to_html(&self) -> String {
+        let title = escape_html(self.title.as_deref().unwrap_or("Conversation"));
+        let mut out = format!("<article class=\"transcript\">\n<h1>{title}</h1>\n");
+        if let Some(model) = &self.model {
+            out.push_str(&format!(
+                "<p class=\"model\">Model: {}</p>\n",
+                escape_html(model)
+            ));
+        }
+
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "<section class=\"message {}\">\n<h2>{}</h2>\n",
+                escape_html(&entry.role),
+                escape_html(&entry.role_header())
+            ));
+            if let Some(details) = entry.details() {
+                out.push_str(&format!(
+                    "<p class=\"details\">{}</p>\n",
+                    escape_html(&details)
+                ));
+            }
+            for paragraph in entry.content.split("\n\n").filter(|p| !p.trim().is_empty()) {
+                out.push_str(&format!(
+                    "<p>{}</p>\n",
+                    escape_html(paragraph).replace('\n', "<br>")
+                ));
+            }
+            for call in &entry.tool_calls {
+                out.push_str(&format!(
+                    "<div class=\"tool-call\"><strong>Tool call:</strong> <code>{}</code>\n<pre>{}</pre></div>\n",
+                    escape_html(&call.name),
+                    escape_html(&serde_json::to_string_pretty(&call.arguments).unwrap_or_default())
+                ));
+            }
+            out.push_str("</section>\n");
+        }
+
+        let total = self.total_tokens();
+        if total > 0 {
+            out.push_str(&format!("<footer>Total tokens: {total}</footer>\n"));
+        }
+
+        out.push_str("</article>\n");
+        out
+    }
+
+    /// Renders the transcript as plain text.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if let Some(title) = &self.title {
+            out.push_str(&format!("{title}\n\n"));
+        }
+        if let Some(model) = &self.model {
+            out.push_str(&format!("Model: {model}\n\n"));
+        }
+
+        for entry in &self.entries {
+            out.push_str(&format!("[{}]", entry.role_header()));
+            if let Some(details) = entry.details() {
+                out.push_str(&format!(" ({details})"));
+            }
+            out.push('\n');
+            if !entry.content.is_empty() {
+                out.push_str(&format!("{}\n", entry.content));
+            }
+            for call in &entry.tool_calls {
+                out.push_str(&format!(
+                    "-> tool call: {}({})\n",
+                    call.name, call.arguments
+                ));
+            }
+            out.push('\n');
+        }
+
+        let total = self.total_tokens();
+        if total > 0 {
+            out.push_str(&format!("Total tokens: {total}\n"));
+        }
+
+        out
+    }
+
+    /// Exports the conversation as one line of chat-format JSONL for fine-tuning.
+    ///
+    /// Lines from several transcripts can be concatenated into a dataset file.
+    /// Tool calls are given ids in order, and each tool result answers the oldest
+    /// call still unanswered.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The layout of the record and the messages it keeps.
+    ///
+    /// # Returns
+    ///
+    /// The record followed by a newline, or an empty string if no message is kept.
+    pub fn to_training_jsonl(&self, format: &TrainingFormat) -> String {
+        let record = match format.style {
+            TrainingStyle::OpenAi => self.openai_messages(format),
+            TrainingStyle::ShareGpt => self.share_gpt_turns(format),
+        };
+        let Some(record) = record else {
+            return String::new();
+        };
+        format!("{record}\n")
+    }
+
+    /// Returns the entries a training record keeps.
+    fn training_entries<'a>(
+        &'a self,
+        format: &'a TrainingFormat,
+    ) -> impl Iterator<Item = &'a TranscriptEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| match entry.role.as_str() {
+                "system" => format.include_system,
+                "tool" => format.include_tools,
+                "assistant" => format.include_tools || !entry.content.is_empty(),
+                _ => true,
+            })
+    }
+
+    /// Builds an OpenAI style record, or `None` if it has no messages.
+    fn openai_messages(&self, format: &TrainingFormat) -> Option<JsonValue> {
+        let mut messages = Vec::new();
+        let mut pending = VecDeque::new();
+        let mut next_id = 0;
+        for entry in self.training_entries(format) {
+            let mut message = json!({ "role": entry.role, "content": entry.content });
+            if entry.role == "tool" {
+                if let Some(id) = pending.pop_front() {
+                    message["tool_call_id"] = json!(id);
+                }
+            } else if format.include_tools && !entry.tool_calls.is_empty() {
+                let calls: Vec<JsonValue> = entry
+                    .tool_calls
+                    .iter()
+                    .map(|call| {
+                        let id = format!("call_{next_id}");
+                        next_id += 1;
+                        pending.push_back(id.clone());
+                        json!({
+                            "id": id,
+                            "type": "function",
+                            "function": { "name": call.name, "arguments": call.arguments.to_string() }
+                        })
+                    })
+                    .collect();
+                message["tool_calls"] = json!(calls);
+            }
+            messages.push(message);
+        }
+        (!messages.is_empty()).then(|| json!({ "messages": messages }))
+    }
+
+    /// Builds a ShareGPT style record, or `None` if it has no turns.
+    fn share_gpt_turns(&self, format: &TrainingFormat) -> Option<JsonValue> {
+        let mut turns = Vec::new();
+        for entry in self.training_entries(format) {
+            let from = match entry.role.as_str() {
+                "user" => "human",
+                "assistant" => "gpt",
+                "tool" => "observation",
+                other => other,
+            };
+            if !entry.content.is_empty() || entry.tool_calls.is_empty() || !format.include_tools {
+                turns.push(json!({ "from": from, "value": entry.content }));
+            }
+            if format.include_tools {
+                for call in &entry.tool_calls {
+                    let value = json!({ "name": call.name, "arguments": call.arguments });
+                    turns.push(json!({ "from": "function_call", "value": value.to_string() }));
+                }
+            }
+        }
+        (!turns.is_empty()).then(|| json!({ "conversations": turns }))
+    }
+}
+
+// ===
+// TRAIT: Display for Transcript
+// ===
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_text())
+    }
+}
+
+/// Escapes the characters that are significant in HTML text and attributes.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// ===
+// TESTS: Transcript
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, chat_body};
+    use serde_json::json;
+
+    fn sample() -> Transcript {
+        let mut transcript = Transcript::from_messages(&[
+            json!({ "role": "user", "content": "What is <b>2 + 2</b>?" }),
+            json!({
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{ "function": { "name": "add", "arguments": { "a": 2, "b": 2 } } }]
+            }),
+            json!({ "role": "tool", "content": "4" }),
+        ]);
+
+        let mut answer = TranscriptEntry::new("assistant", "It is 4.");
+        answer.timestamp = Some("2025-01-01T00:00:00Z".to_string());
+        answer.prompt_tokens = Some(12);
+        answer.completion_tokens = Some(3);
+        transcript.push(answer).set_title("Math").set_model("mock");
+        transcript
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = sample().to_markdown();
+        assert!(markdown.starts_with("# Math\n\n*Model: mock*\n"));
+        assert!(markdown.contains("## User\n\nWhat is <b>2 + 2</b>?\n"));
+        assert!(markdown.contains("**Tool call:** `add`"));
+        assert!(
+            markdown.contains("*2025-01-01T00:00:00Z · 12 prompt tokens · 3 completion tokens*")
+        );
+        assert!(markdown.ends_with("**Total tokens:** 15\n"));
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let html = sample().to_html();
+        assert!(html.contains("<h1>Math</h1>"));
+        assert!(html.contains("<p>What is &lt;b&gt;2 + 2&lt;/b&gt;?</p>"));
+        assert!(html.contains("<section class=\"message tool\">"));
+        assert!(html.contains("<footer>Total tokens: 15</footer>"));
+    }
+
+    #[test]
+    fn test_text() {
+        let text = sample().to_string();
+        assert!(text.contains("[Assistant]\n-> tool call: add({\"a\":2,\"b\":2})\n"));
+        assert!(text.contains("[Tool]\n4\n"));
+        assert!(text.ends_with("Total tokens: 15\n"));
+    }
+
+    #[tokio::test]
+    async fn test_from_session_records_stats() {
+        let server = MockServer::start(vec![chat_body(&["Hi!"])]).await;
+        let mut session = OllamaSession::remote("mock", &server.addr());
+        session.user("Hello");
+        session.update(|_| {}).await.unwrap();
+
+        let transcript = Transcript::from_session(&session);
+        assert_eq!(transcript.model(), Some("mock"));
+        assert_eq!(transcript.entries().len(), 2);
+        assert_eq!(transcript.entries()[1].content, "Hi!");
+        assert_eq!(transcript.entries()[1].completion_tokens, Some(10));
+        assert_eq!(transcript.total_tokens(), 30);
+    }
+
+    #[test]
+    fn test_training_jsonl_openai() {
+        let mut transcript = Transcript::from_messages(&[json!({
+            "role": "system",
+            "content": "Be exact."
+        })]);
+        for entry in sample().entries() {
+            transcript.push(entry.clone());
+        }
+
+        let line = transcript.to_training_jsonl(&TrainingFormat::openai());
+        assert!(line.ends_with('\n') && line.lines().count() == 1);
+        let record: JsonValue = serde_json::from_str(&line).unwrap();
+        let messages = record["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 5);
+        assert_eq!(
+            messages[0],
+            json!({ "role": "system", "content": "Be exact." })
+        );
+        let call = &messages[2]["tool_calls"][0];
+        assert_eq!(call["id"], "call_0");
+        assert_eq!(call["function"]["name"], "add");
+        assert_eq!(call["function"]["arguments"], "{\"a\":2,\"b\":2}");
+        assert_eq!(messages[3]["tool_call_id"], "call_0");
+        assert_eq!(messages[4]["content"], "It is 4.");
+
+        let mut plain = TrainingFormat::openai();
+        plain.set_include_system(false).set_include_tools(false);
+        let record: JsonValue =
+            serde_json::from_str(&transcript.to_training_jsonl(&plain)).unwrap();
+        let roles: Vec<&str> = record["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, ["user", "assistant"]);
+        assert_eq!(Transcript::new().to_training_jsonl(&plain), "");
+    }
+
+    #[test]
+    fn test_training_jsonl_share_gpt() {
+        let line = sample().to_training_jsonl(&TrainingFormat::share_gpt());
+        let record: JsonValue = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            record["conversations"],
+            json!([
+                { "from": "human", "value": "What is <b>2 + 2</b>?" },
+                { "from": "function_call", "value": "{\"arguments\":{\"a\":2,\"b\":2},\"name\":\"add\"}" },
+                { "from": "observation", "value": "4" },
+                { "from": "gpt", "value": "It is 4." },
+            ])
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// The ISO 639-1 codes of the languages `LanguageCode` accepts, with their names.
+const LANGUAGES: [(&str, &str); 40] = [
+    ("ar", "Arabic"),
+    ("bg", "Bulgarian"),
+    ("bn", "Bengali"),
+    ("ca", "Catalan"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("et", "Estonian"),
+    ("fa", "Persian"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hr", "Croatian"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("lt", "Lithuanian"),
+    ("lv", "Latvian"),
+    ("ms", "Malay"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sk", "Slovak"),
+    ("sl", "Slovenian"),
+    ("sr", "Serbian"),
+    ("sv", "Swedish"),
+    ("sw", "Swahili"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("zh", "Chinese"),
+];
+
+/// The prompt template of a translation; `{language}`, `{glossary}` and `{text}`
+/// are replaced when it is rendered.
+const TRANSLATION_TEMPLATE: &str = "Translate the text below into {language}. \
+Keep the meaning, tone and formatting, and answer with the translation only.{glossary}\n\n\
+Text:\n{text}";
+
+// ===
+// STRUCT: LanguageCode
+// ===
+
+/// A validated language code, e.g. "fr" or "pt-BR".
+///
+/// The language is an ISO 639-1 code from a fixed list of widely supported
+/// languages. It may be followed by a region ("pt-BR", "es-419") or a script
+/// ("zh-Hant") subtag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageCode {
+    code: String,
+    name: &'static str,
+}
+
+impl LanguageCode {
+    /// Parses and validates a language code, ignoring case.
+    ///
+    /// # Arguments
+    /// * `code` - The code, with "-" or "_" before a subtag
+    ///
+    /// # Returns
+    /// * The code in its canonical form, or a description of what is wrong with it
+    pub fn parse(code: &str) -> Result<Self, String> {
+        let mut parts = code.trim().split(['-', '_']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let subtag = parts.next();
+        if parts.next().is_some() {
+            return Err(format!("\"{code}\" has more than one subtag"));
+        }
+
+        let name = LANGUAGES
+            .iter()
+            .find(|(known, _)| *known == language)
+            .map(|(_, name)| *name)
+            .ok_or_else(|| format!("\"{code}\" is not a supported language code"))?;
+
+        let code = match subtag {
+            None => language,
+            Some(region) if is_region(region) => format!("{language}-{}", region.to_uppercase()),
+            Some(script)
+                if script.len() == 4 && script.chars().all(|c| c.is_ascii_alphabetic()) =>
+            {
+                let mut script = script.to_ascii_lowercase();
+                script[..1].make_ascii_uppercase();
+                format!("{language}-{script}")
+            }
+            Some(subtag) => return Err(format!("\"{subtag}\" is not a region or script")),
+        };
+
+        Ok(Self { code, name })
+    }
+
+    /// Returns the canonical code, e.g. "pt-BR".
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns the English name of the language, without the subtag.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Renders the prompt asking a model to translate a text into this language.
+    ///
+    /// # Arguments
+    /// * `text` - The text to translate
+    /// * `glossary` - Terms and the translations they must be given; may be empty
+    ///
+    /// # Returns
+    /// * The prompt to send as a user message
+    pub fn translation_prompt(&self, text: &str, glossary: &[(&str, &str)]) -> String {
+        let glossary = if glossary.is_empty() {
+            String::new()
+        } else {
+            let terms: Vec<String> = glossary
+                .iter()
+                .map(|(term, translation)| format!("- {term} => {translation}"))
+                .collect();
+            format!(
+                "\nAlways translate these terms as given:\n{}",
+                terms.join("\n")
+            )
+        };
+
+        let language = if self.code.contains('-') {
+            format!("{} ({})", self.name, self.code)
+        } else {
+            self.name.to_string()
+        };
+
+        TRANSLATION_TEMPLATE
+            .replace("{language}", &language)
+            .replace("{glossary}", &glossary)
+            .replace("{text}", text)
+    }
+}
+
+/// Returns true for a two-letter or three-digit region subtag.
+fn is_region(subtag: &str) -> bool {
+    (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+        || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+}
+
+// ===
+// TRAIT: LanguageCode (FromStr)
+// ===
+
+impl FromStr for LanguageCode {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Self::parse(code)
+    }
+}
+
+// ===
+// TRAIT: LanguageCode (fmt::Display)
+// ===
+
+impl fmt::Display for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
+// ===
+// TESTS: LanguageCode
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_language_code() {
+        assert_eq!(LanguageCode::parse("FR").unwrap().code(), "fr");
+        assert_eq!(LanguageCode::parse("pt_br").unwrap().code(), "pt-BR");
+        assert_eq!(LanguageCode::parse("es-419").unwrap().name(), "Spanish");
+        assert_eq!("zh-hant".parse::<LanguageCode>().unwrap().code(), "zh-Hant");
+
+        assert!(LanguageCode::parse("xx").is_err());
+        assert!(LanguageCode::parse("french").is_err());
+        assert!(LanguageCode::parse("en-1").is_err());
+        assert!(LanguageCode::parse("en-US-x").is_err());
+    }
+
+    #[test]
+    fn test_translation_prompt() {
+        let german = LanguageCode::parse("de").unwrap();
+        let prompt = german.translation_prompt("Open the file.", &[("file", "Datei")]);
+        assert!(prompt.starts_with("Translate the text below into German."));
+        assert!(prompt.contains("- file => Datei"));
+        assert!(prompt.ends_with("Text:\nOpen the file."));
+
+        let brazilian = LanguageCode::parse("pt-BR").unwrap();
+        assert!(
+            brazilian
+                .translation_prompt("Hi", &[])
+                .contains("into Portuguese (pt-BR). Keep")
+        );
+    }
+}
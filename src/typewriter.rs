@@ -0,0 +1,251 @@
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+// ===
+// STRUCT: Typewriter
+// ===
+
+/// Re-emits streamed text word by word at a steady rate, for chat UIs.
+///
+/// Models stream text in bursts. The typewriter smooths them out: each word is
+/// passed to the output after a delay matching its length at the configured
+/// rate. Once the stream is finished, the words still waiting are sped up so
+/// they are all shown within the catch-up time.
+///
+/// ```no_run
+/// # async fn example(session: &mut ollie_rs::OllamaSession) -> Result<(), Box<dyn std::error::Error>> {
+/// use ollie_rs::Typewriter;
+///
+/// let typewriter = Typewriter::new(60.0).start(|word| print!("{word}"));
+/// session.update(typewriter.callback()).await?;
+/// typewriter.finish().await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typewriter {
+    chars_per_second: f64,
+    catch_up: Duration,
+}
+
+impl Typewriter {
+    /// The default time to show the remaining text in once the stream is finished.
+    pub const DEFAULT_CATCH_UP: Duration = Duration::from_millis(500);
+
+    /// Creates a typewriter.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars_per_second` - The rate text is shown at while streaming.
+    ///
+    /// # Returns
+    ///
+    /// A new `Typewriter` with the default catch-up time.
+    pub fn new(chars_per_second: f64) -> Self {
+        Self {
+            chars_per_second: chars_per_second.max(f64::MIN_POSITIVE),
+            catch_up: Self::DEFAULT_CATCH_UP,
+        }
+    }
+
+    /// Sets the longest time the remaining text takes to show once the stream is finished.
+    ///
+    /// Text is never shown slower than the typewriter's rate, so a short
+    /// remainder may finish sooner.
+    pub fn set_catch_up(&mut self, catch_up: Duration) -> &mut Self {
+        self.catch_up = catch_up;
+        self
+    }
+
+    /// Starts showing text on a tokio task.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - Called with each word and the whitespace after it.
+    ///
+    /// # Returns
+    ///
+    /// A handle to send the streamed text to.
+    pub fn start<F>(&self, mut output: F) -> TypewriterHandle
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Option<String>>();
+        let mut rate = self.chars_per_second;
+        let catch_up = self.catch_up;
+
+        let task = tokio::spawn(async move {
+            let mut buffer = String::new();
+            let mut finished = false;
+            let mut catching_up = false;
+            loop {
+                while let Ok(message) = receiver.try_recv() {
+                    match message {
+                        Some(text) => buffer.push_str(&text),
+                        None => finished = true,
+                    }
+                }
+
+                // Speed up once, when the end of the stream is first seen.
+                if finished && !catching_up {
+                    catching_up = true;
+                    let remaining = buffer.chars().count() as f64;
+                    let seconds = catch_up.as_secs_f64();
+                    rate = if seconds > 0.0 {
+                        rate.max(remaining / seconds)
+                    } else {
+                        f64::INFINITY
+                    };
+                }
+
+                let Some(end) = next_word(&buffer, finished) else {
+                    if finished {
+                        break;
+                    }
+                    // Wait for the rest of the word, or the end of the stream.
+                    match receiver.recv().await {
+                        Some(Some(text)) => buffer.push_str(&text),
+                        Some(None) | None => finished = true,
+                    }
+                    continue;
+                };
+
+                let word: String = buffer.drain(..end).collect();
+                output(&word);
+                let delay = word.chars().count() as f64 / rate;
+                if delay > 0.0 {
+                    tokio::time::sleep(Duration::from_secs_f64(delay)).await;
+                }
+            }
+        });
+
+        TypewriterHandle { sender, task }
+    }
+}
+
+/// Returns the end of the first word and the whitespace after it.
+///
+/// A word at the end of the buffer may still grow, so it is only complete once
+/// the stream is finished.
+fn next_word(buffer: &str, finished: bool) -> Option<usize> {
+    if buffer.is_empty() {
+        return None;
+    }
+    let leading = buffer.len() - buffer.trim_start().len();
+    let word_end = buffer[leading..]
+        .find(char::is_whitespace)
+        .map(|position| leading + position);
+    match word_end {
+        Some(position) => {
+            let rest = &buffer[position..];
+            let space_end = position + rest.len() - rest.trim_start().len();
+            // Whitespace at the end may be followed by more whitespace.
+            (space_end < buffer.len() || finished).then_some(space_end)
+        }
+        None if finished || leading == buffer.len() => Some(buffer.len()),
+        None => None,
+    }
+}
+
+// ===
+// STRUCT: TypewriterHandle
+// ===
+
+/// A running `Typewriter`.
+#[derive(Debug)]
+pub struct TypewriterHandle {
+    sender: mpsc::UnboundedSender<Option<String>>,
+    task: JoinHandle<()>,
+}
+
+impl TypewriterHandle {
+    /// Adds streamed text to show.
+    pub fn push(&self, text: &str) {
+        if !text.is_empty() {
+            let _ = self.sender.send(Some(text.to_string()));
+        }
+    }
+
+    /// Returns a callback for a generation call that adds each chunk it receives.
+    pub fn callback(&self) -> impl FnMut(&str) + Send + 'static {
+        let sender = self.sender.clone();
+        move |text| {
+            if !text.is_empty() {
+                let _ = sender.send(Some(text.to_string()));
+            }
+        }
+    }
+
+    /// Marks the stream as finished and waits until all of its text is shown.
+    pub async fn finish(self) {
+        let _ = self.sender.send(None);
+        let _ = self.task.await;
+    }
+}
+
+// ===
+// TESTS: Typewriter
+// ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    fn recorder() -> (Arc<Mutex<Vec<String>>>, impl FnMut(&str) + Send + 'static) {
+        let words = Arc::new(Mutex::new(Vec::new()));
+        let recorded = words.clone();
+        (words, move |word: &str| {
+            recorded.lock().unwrap().push(word.to_string())
+        })
+    }
+
+    #[test]
+    fn test_next_word() {
+        assert_eq!(next_word("Hello  wor", false), Some(7));
+        assert_eq!(next_word("wor", false), None);
+        assert_eq!(next_word("wor", true), Some(3));
+        assert_eq!(next_word("end  ", false), None);
+        assert_eq!(next_word("end  ", true), Some(5));
+        assert_eq!(next_word("", true), None);
+    }
+
+    #[tokio::test]
+    async fn test_paces_words() {
+        let (words, output) = recorder();
+        let typewriter = Typewriter::new(200.0).start(output);
+
+        let start = Instant::now();
+        typewriter.push("Hello, wo");
+        typewriter.push("rld! How are");
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        typewriter.push(" you?");
+        typewriter.finish().await;
+
+        // "Hello, " and "world! " take 70 ms at 200 characters a second.
+        assert!(start.elapsed() >= Duration::from_millis(60));
+        assert_eq!(
+            *words.lock().unwrap(),
+            ["Hello, ", "world! ", "How ", "are ", "you?"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_catches_up_on_finish() {
+        let (words, output) = recorder();
+        let mut typewriter = Typewriter::new(10.0);
+        typewriter.set_catch_up(Duration::from_millis(100));
+        let typewriter = typewriter.start(output);
+
+        let text = "word ".repeat(40);
+        let start = Instant::now();
+        typewriter.push(&text);
+        typewriter.finish().await;
+
+        // 200 characters at 10 a second would take 20 seconds.
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(words.lock().unwrap().concat(), text);
+    }
+}
@@ -35,13 +35,8 @@ impl XmlUtil {
         let opening_tag = format!("<{}", tag_name);
         let closing_tag = format!("</{}>", tag_name);
 
-        loop {
-            // Find the opening tag
-            let start_pos = match result.find(&opening_tag) {
-                Some(pos) => pos,
-                None => break, // No more opening tags found
-            };
-
+        // Find each opening tag until no more are found
+        while let Some(start_pos) = result.find(&opening_tag) {
             // Find the end of the opening tag (could have attributes)
             let tag_end = match result[start_pos..].find('>') {
                 Some(pos) => start_pos + pos + 1,